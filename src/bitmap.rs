@@ -0,0 +1,151 @@
+// font-kit/src/bitmap.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Pre-rendered bitmap glyphs embedded in fonts (`sbix`, `CBDT`, and `EBDT` strikes).
+
+use euclid::default::{Size2D, Vector2D};
+use euclid::vec2;
+use float_ord::FloatOrd;
+
+use crate::png;
+use crate::tables::cbdt;
+use crate::tables::cblc::CblcTable;
+use crate::tables::sbix::{self, SbixTable};
+
+/// The table that a bitmap strike comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitmapSource {
+    /// Apple's `sbix` table, which holds PNG images.
+    Sbix,
+    /// The `CBLC`/`CBDT` tables, which hold PNG color images.
+    Cbdt,
+    /// The `EBLC`/`EBDT` tables, which hold monochrome or grayscale images.
+    Ebdt,
+}
+
+/// A set of bitmap glyphs rendered for one pixels-per-em size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitmapStrike {
+    /// The size, in pixels per em, that the glyphs in this strike were rendered at.
+    pub pixels_per_em: u16,
+    /// The table that the strike comes from.
+    pub source: BitmapSource,
+    /// The index of the strike within its table.
+    pub(crate) index: usize,
+}
+
+impl BitmapStrike {
+    /// Returns true if the glyphs of this strike have to be scaled to be drawn at the given size,
+    /// in pixels per em.
+    ///
+    /// Sizes are rounded to whole pixels first, so a 12-pixel strike needs no scaling at 12.4
+    /// pixels per em.
+    #[inline]
+    pub fn needs_scaling(&self, pixels_per_em: f32) -> bool {
+        self.pixels_per_em as f32 != pixels_per_em.round()
+    }
+}
+
+/// The format of the image data of a bitmap glyph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitmapFormat {
+    /// The raw contents of a PNG file.
+    Png,
+    /// One bit per pixel, most significant bit first. Each row is padded to a whole byte.
+    Mono,
+    /// One byte of coverage per pixel, with no padding between rows.
+    Gray,
+}
+
+/// A bitmap glyph loaded from a strike.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitmapGlyph {
+    /// The format of `data`.
+    pub format: BitmapFormat,
+    /// The image data.
+    pub data: Vec<u8>,
+    /// The size of the image, in pixels.
+    pub size: Size2D<u32>,
+    /// The offset from the glyph origin (on the baseline) to the top left corner of the image,
+    /// in pixels, with y pointing down.
+    pub offset: Vector2D<i32>,
+}
+
+/// Loads a glyph from the `sbix` strike at `strike_index`.
+///
+/// Only PNG images are supported; glyphs in other formats are treated as missing.
+pub(crate) fn load_sbix_glyph(
+    sbix_data: &[u8],
+    num_glyphs: u32,
+    strike_index: usize,
+    glyph_id: u16,
+) -> Option<BitmapGlyph> {
+    let sbix_table = SbixTable::parse(sbix_data, num_glyphs)?;
+    let glyph = sbix_table.glyph(sbix_table.strikes.get(strike_index)?, glyph_id)?;
+    if glyph.graphic_type != sbix::GRAPHIC_TYPE_PNG {
+        return None;
+    }
+    let size = png::size(glyph.data)?;
+    Some(BitmapGlyph {
+        format: BitmapFormat::Png,
+        data: glyph.data.to_vec(),
+        size,
+        offset: vec2(
+            glyph.origin_offset_x as i32,
+            -(glyph.origin_offset_y as i32 + size.height as i32),
+        ),
+    })
+}
+
+/// Loads a glyph from the `CBLC`/`CBDT` or `EBLC`/`EBDT` strike at `strike_index`.
+pub(crate) fn load_cbdt_glyph(
+    location_data: &[u8],
+    bitmap_data: &[u8],
+    source: BitmapSource,
+    strike_index: usize,
+    glyph_id: u16,
+) -> Option<BitmapGlyph> {
+    let cblc_table = CblcTable::parse(location_data)?;
+    let bitmap_size = cblc_table.sizes.get(strike_index)?;
+    let location = cblc_table.locate(bitmap_size, glyph_id)?;
+    let (format, data, metrics) = if source == BitmapSource::Cbdt {
+        let glyph = cbdt::png_glyph(bitmap_data, &location)?;
+        (BitmapFormat::Png, glyph.data.to_vec(), glyph.metrics)
+    } else {
+        let glyph = cbdt::raw_glyph(bitmap_data, &location, bitmap_size.bit_depth)?;
+        let format = if bitmap_size.bit_depth == 1 {
+            BitmapFormat::Mono
+        } else {
+            BitmapFormat::Gray
+        };
+        (format, glyph.pixels, glyph.metrics)
+    };
+    Some(BitmapGlyph {
+        format,
+        data,
+        size: Size2D::new(metrics.width as u32, metrics.height as u32),
+        offset: vec2(metrics.bearing_x as i32, -(metrics.bearing_y as i32)),
+    })
+}
+
+/// Sorts strikes from the best match for the given size, in pixels per em, to the worst: the
+/// strike that needs no scaling, then the larger strikes from the smallest up, then the smaller
+/// strikes from the largest down. See `Loader::best_strike_for_size()`.
+pub(crate) fn sort_by_preference(strikes: &mut [BitmapStrike], pixels_per_em: f32) {
+    let pixels_per_em = pixels_per_em.round();
+    strikes.sort_by_key(|strike| {
+        let ppem = strike.pixels_per_em as f32;
+        if ppem >= pixels_per_em {
+            (false, FloatOrd(ppem))
+        } else {
+            (true, FloatOrd(-ppem))
+        }
+    });
+}
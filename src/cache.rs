@@ -0,0 +1,274 @@
+// font-kit/src/cache.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A cache of rasterized glyphs, with least-recently-used eviction.
+
+use euclid::default::{Point2D, Vector2D};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::canvas::{Canvas, Format, LcdFilter, RasterizationAdjustments, RasterizationOptions};
+use crate::error::GlyphLoadingError;
+use crate::hinting::HintingOptions;
+use crate::loader::{FontTransform, Loader};
+
+/// The default number of horizontal subpixel positions that glyphs are rasterized at.
+pub const DEFAULT_HORIZONTAL_SUBPIXEL_POSITIONS: u32 = 4;
+
+/// A glyph rasterized by a `GlyphCache`.
+#[derive(Debug)]
+pub struct CachedGlyph {
+    /// The coverage of the glyph, cropped to its raster bounds.
+    pub canvas: Canvas,
+    /// The position of the top left corner of `canvas` relative to the integer pixel that the
+    /// glyph was positioned in; that is, the glyph should be drawn at `position.floor() +
+    /// offset`.
+    pub offset: Vector2D<i32>,
+}
+
+/// Memoizes `Loader::rasterize_glyph_with_adjustments()`, evicting the least recently used glyphs
+/// once the rasterized glyphs take up more than a budget of bytes.
+///
+/// Glyphs are keyed by a font key `F`, chosen by the caller, that must uniquely identify each
+/// font rasterized with the cache, along with the glyph ID, point size, hinting and
+/// rasterization options and adjustments, canvas format, and fractional pixel position.
+/// Fractional positions are rounded down to one of a fixed number of subpixel positions, so that
+/// glyphs positioned at nearly the same offset share a cache entry.
+pub struct GlyphCache<F> {
+    entries: HashMap<GlyphKey<F>, CacheEntry>,
+    recency: BTreeMap<u64, GlyphKey<F>>,
+    next_use: u64,
+    byte_count: usize,
+    byte_budget: usize,
+    subpixel_positions: Vector2D<u32>,
+}
+
+struct CacheEntry {
+    glyph: CachedGlyph,
+    last_use: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct GlyphKey<F> {
+    font: F,
+    glyph_id: u32,
+    point_size: u32,
+    hinting: (u8, u32),
+    rasterization_options: RasterizationOptions,
+    stem_darkening: bool,
+    gamma: u32,
+    lcd_filter: LcdFilter,
+    bilevel_threshold: Option<u8>,
+    format: Format,
+    subpixel_position: (u32, u32),
+}
+
+impl<F> GlyphCache<F>
+where
+    F: Clone + Eq + Hash,
+{
+    /// Creates an empty cache that holds up to `byte_budget` bytes of rasterized glyphs, at
+    /// `DEFAULT_HORIZONTAL_SUBPIXEL_POSITIONS` horizontal positions and one vertical position
+    /// per pixel.
+    #[inline]
+    pub fn new(byte_budget: usize) -> GlyphCache<F> {
+        GlyphCache::with_subpixel_positions(byte_budget, DEFAULT_HORIZONTAL_SUBPIXEL_POSITIONS, 1)
+    }
+
+    /// Creates an empty cache that holds up to `byte_budget` bytes of rasterized glyphs, at the
+    /// given number of horizontal and vertical subpixel positions per pixel.
+    ///
+    /// Counts of zero are treated as one.
+    pub fn with_subpixel_positions(
+        byte_budget: usize,
+        horizontal: u32,
+        vertical: u32,
+    ) -> GlyphCache<F> {
+        GlyphCache {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_use: 0,
+            byte_count: 0,
+            byte_budget,
+            subpixel_positions: Vector2D::new(horizontal.max(1), vertical.max(1)),
+        }
+    }
+
+    /// Returns the glyph rasterized at the given size and position, rasterizing it if it isn't
+    /// already cached.
+    ///
+    /// `font_key` must identify `font`: the cache assumes that two fonts with the same key
+    /// rasterize identically. `position` is in pixels, with y pointing down.
+    ///
+    /// Errors are not cached. A glyph larger than the whole budget is still cached, until the
+    /// next glyph is added.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rasterize<L>(
+        &mut self,
+        font_key: F,
+        font: &L,
+        glyph_id: u32,
+        point_size: f32,
+        position: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+        format: Format,
+    ) -> Result<&CachedGlyph, GlyphLoadingError>
+    where
+        L: Loader,
+    {
+        let fraction = *position - position.floor();
+        let subpixel_position = (
+            subpixel_bucket(fraction.x, self.subpixel_positions.x),
+            subpixel_bucket(fraction.y, self.subpixel_positions.y),
+        );
+        let key = GlyphKey {
+            font: font_key,
+            glyph_id,
+            point_size: point_size.to_bits(),
+            hinting: hinting_key(hinting_options),
+            rasterization_options,
+            stem_darkening: rasterization_adjustments.stem_darkening,
+            gamma: rasterization_adjustments.gamma.to_bits(),
+            lcd_filter: rasterization_adjustments.lcd_filter,
+            bilevel_threshold: rasterization_adjustments.bilevel_threshold(rasterization_options),
+            format,
+            subpixel_position,
+        };
+
+        let last_use = self.next_use;
+        self.next_use += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            self.recency.remove(&entry.last_use);
+            self.recency.insert(last_use, key.clone());
+            entry.last_use = last_use;
+            return Ok(&self.entries[&key].glyph);
+        }
+
+        let origin = Point2D::new(
+            subpixel_position.0 as f32 / self.subpixel_positions.x as f32,
+            subpixel_position.1 as f32 / self.subpixel_positions.y as f32,
+        );
+        let transform = FontTransform::identity();
+        let raster_bounds = font.raster_bounds_with_adjustments(
+            glyph_id,
+            point_size,
+            &transform,
+            &origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )?;
+        let mut canvas = Canvas::new(&raster_bounds.size.to_u32(), format);
+        if raster_bounds.size.width > 0 && raster_bounds.size.height > 0 {
+            font.rasterize_glyph_with_adjustments(
+                &mut canvas,
+                glyph_id,
+                point_size,
+                &transform,
+                &(origin - raster_bounds.origin.to_f32().to_vector()),
+                hinting_options,
+                rasterization_options,
+                rasterization_adjustments,
+            )?;
+        }
+
+        self.byte_count += canvas.pixels.len();
+        self.evict(canvas.pixels.len());
+        self.recency.insert(last_use, key.clone());
+        let glyph = CachedGlyph {
+            canvas,
+            offset: raster_bounds.origin.to_vector(),
+        };
+        Ok(&self
+            .entries
+            .entry(key)
+            .or_insert(CacheEntry { glyph, last_use })
+            .glyph)
+    }
+
+    /// Returns the number of glyphs in the cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the cache contains no glyphs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of bytes of pixel data that the cached glyphs take up.
+    #[inline]
+    pub fn byte_count(&self) -> usize {
+        self.byte_count
+    }
+
+    /// Returns the number of bytes of pixel data that the cache holds before evicting glyphs.
+    #[inline]
+    pub fn byte_budget(&self) -> usize {
+        self.byte_budget
+    }
+
+    /// Changes the byte budget, evicting glyphs as necessary to meet it.
+    pub fn set_byte_budget(&mut self, byte_budget: usize) {
+        self.byte_budget = byte_budget;
+        self.evict(0);
+    }
+
+    /// Removes all glyphs from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.byte_count = 0;
+    }
+
+    /// Evicts the least recently used glyphs until the cache fits in its budget, counting
+    /// `reserved_bytes` that belong to a glyph about to be added as already used.
+    fn evict(&mut self, reserved_bytes: usize) {
+        while self.byte_count > self.byte_budget && self.byte_count > reserved_bytes {
+            let (&last_use, _) = match self.recency.iter().next() {
+                Some(oldest) => oldest,
+                None => break,
+            };
+            let key = self.recency.remove(&last_use).unwrap();
+            let entry = self.entries.remove(&key).unwrap();
+            self.byte_count -= entry.glyph.canvas.pixels.len();
+        }
+    }
+}
+
+impl<F> fmt::Debug for GlyphCache<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GlyphCache")
+            .field("entries", &self.entries.len()) // Do not dump the glyphs.
+            .field("byte_count", &self.byte_count)
+            .field("byte_budget", &self.byte_budget)
+            .field("subpixel_positions", &self.subpixel_positions)
+            .finish()
+    }
+}
+
+fn subpixel_bucket(fraction: f32, positions: u32) -> u32 {
+    ((fraction * positions as f32) as u32).min(positions - 1)
+}
+
+fn hinting_key(hinting_options: HintingOptions) -> (u8, u32) {
+    match hinting_options {
+        HintingOptions::None => (0, 0),
+        HintingOptions::Vertical(size) => (1, size.to_bits()),
+        HintingOptions::VerticalSubpixel(size) => (2, size.to_bits()),
+        HintingOptions::Full(size) => (3, size.to_bits()),
+        HintingOptions::Autohint(size) => (4, size.to_bits()),
+    }
+}
@@ -10,25 +10,31 @@
 
 //! An in-memory bitmap surface for glyph rasterization.
 
-use euclid::default::{Point2D, Rect, Size2D};
-use euclid::point2;
+use euclid::default::{Point2D, Rect, Size2D, Transform2D};
+use euclid::{point2, vec2};
 use lazy_static::lazy_static;
 use std::cmp;
 use std::fmt;
 
+use crate::color::ColorU;
 use crate::utils;
 
 lazy_static! {
     static ref BITMAP_1BPP_TO_8BPP_LUT: [[u8; 8]; 256] = {
         let mut lut = [[0; 8]; 256];
-        for byte in 0..0x100 {
-            let mut value = [0; 8];
-            for bit in 0..8 {
+        for (byte, value) in lut.iter_mut().enumerate() {
+            for (bit, component) in value.iter_mut().enumerate() {
                 if (byte & (0x80 >> bit)) != 0 {
-                    value[bit] = 0xff;
+                    *component = 0xff;
                 }
             }
-            lut[byte] = value
+        }
+        lut
+    };
+    static ref SRGB_TO_LINEAR_LUT: [f32; 256] = {
+        let mut lut = [0.0; 256];
+        for (value, linear) in lut.iter_mut().enumerate() {
+            *linear = srgb_to_linear(value as f32 / 255.0);
         }
         lut
     };
@@ -74,6 +80,152 @@ impl Canvas {
         }
     }
 
+    /// Blends `color` onto the premultiplied R8G8B8A8, sRGB-encoded pixels `dst`, using the
+    /// coverage in this canvas as a mask.
+    ///
+    /// `dst` must be the same size as this canvas, with `dst_stride` bytes between successive
+    /// rows. The coverage of an `A8` canvas applies to all three color channels, while each
+    /// channel of an `Rgb24` canvas, as produced by subpixel antialiasing, masks the
+    /// corresponding color channel. An `Rgba32` canvas is masked by its alpha channel alone.
+    ///
+    /// `gamma` selects the color space that the blending happens in. Blending in linear light
+    /// avoids the thin, muddy edges of blending sRGB values directly.
+    pub fn blend_onto(&self, dst: &mut [u8], dst_stride: usize, color: ColorU, gamma: Gamma) {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+        assert!(dst_stride >= width * 4);
+        if height > 0 {
+            assert!(dst.len() >= dst_stride * (height - 1) + width * 4);
+        }
+
+        let src_bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        let color_alpha = color.a as f32 / 255.0;
+        let foreground = [color.r, color.g, color.b];
+        for y in 0..height {
+            let src_row =
+                &self.pixels[(y * self.stride)..(y * self.stride + width * src_bytes_per_pixel)];
+            let dst_row = &mut dst[(y * dst_stride)..(y * dst_stride + width * 4)];
+            for (src, dst) in src_row
+                .chunks(src_bytes_per_pixel)
+                .zip(dst_row.chunks_mut(4))
+            {
+                let coverage = match self.format {
+                    Format::A8 => [src[0]; 3],
+                    Format::Rgb24 => [src[0], src[1], src[2]],
+                    Format::Rgba32 => [src[3]; 3],
+                };
+                let coverage = [
+                    coverage[0] as f32 / 255.0 * color_alpha,
+                    coverage[1] as f32 / 255.0 * color_alpha,
+                    coverage[2] as f32 / 255.0 * color_alpha,
+                ];
+                let alpha = coverage.iter().cloned().fold(0.0, f32::max);
+                if alpha == 0.0 {
+                    continue;
+                }
+                let dst_alpha = dst[3] as f32 / 255.0;
+                let result_alpha = alpha + dst_alpha * (1.0 - alpha);
+                for channel in 0..3 {
+                    let mask = coverage[channel];
+                    dst[channel] = match gamma {
+                        Gamma::Srgb => {
+                            let src = foreground[channel] as f32 * mask;
+                            (src + dst[channel] as f32 * (1.0 - mask)).round() as u8
+                        }
+                        Gamma::Linear => {
+                            // Unpremultiply the destination so that it can be linearized, blend,
+                            // and then premultiply the result again.
+                            let dst_linear = if dst_alpha > 0.0 {
+                                let straight = (dst[channel] as f32 / dst_alpha).round().min(255.0);
+                                SRGB_TO_LINEAR_LUT[straight as usize] * dst_alpha
+                            } else {
+                                0.0
+                            };
+                            let src_linear = SRGB_TO_LINEAR_LUT[foreground[channel] as usize];
+                            let result = src_linear * mask + dst_linear * (1.0 - mask);
+                            let result = linear_to_srgb((result / result_alpha).min(1.0));
+                            (result * result_alpha * 255.0).round() as u8
+                        }
+                    };
+                }
+                dst[3] = (result_alpha * 255.0).round() as u8;
+            }
+        }
+    }
+
+    /// Returns a copy of this canvas converted to `format`, with a tightly packed stride.
+    ///
+    /// `A8` and `Rgb24` canvases are treated as coverage: the coverage of an `A8` canvas applies
+    /// to all three color channels, while each channel of an `Rgb24` canvas, as produced by
+    /// subpixel antialiasing, gives the coverage of the corresponding color channel. Converting
+    /// coverage to `Rgb24` or `Rgba32` fills it with `color`, or with opaque white if `color` is
+    /// `None`. `Rgba32` results are premultiplied, with the alpha of the most covered channel;
+    /// `Rgb24` results are as if composited over black. Converting to `A8` averages the channels.
+    ///
+    /// An `Rgba32` canvas is already colored, so `color` is ignored for it: converting it to
+    /// `A8` keeps its alpha channel, and converting it to `Rgb24` keeps its premultiplied color
+    /// channels.
+    pub fn convert_to(&self, format: Format, color: Option<ColorU>) -> Canvas {
+        let mut canvas = Canvas::new(&self.size, format);
+        let color = color.unwrap_or(ColorU {
+            r: 0xff,
+            g: 0xff,
+            b: 0xff,
+            a: 0xff,
+        });
+        let foreground = [color.r, color.g, color.b];
+        let width = self.size.width as usize;
+        let (src_bytes_per_pixel, dest_bytes_per_pixel) = (
+            self.format.bytes_per_pixel() as usize,
+            format.bytes_per_pixel() as usize,
+        );
+        for y in 0..self.size.height as usize {
+            let src_row_start = y * self.stride;
+            let src_row =
+                &self.pixels[src_row_start..(src_row_start + width * src_bytes_per_pixel)];
+            let dest_row_start = y * canvas.stride;
+            let dest_row =
+                &mut canvas.pixels[dest_row_start..(dest_row_start + width * dest_bytes_per_pixel)];
+            for (src, dest) in src_row
+                .chunks(src_bytes_per_pixel)
+                .zip(dest_row.chunks_mut(dest_bytes_per_pixel))
+            {
+                let coverage = match (self.format, format) {
+                    (Format::Rgba32, Format::Rgba32) => {
+                        dest.copy_from_slice(src);
+                        continue;
+                    }
+                    (Format::Rgba32, Format::Rgb24) => {
+                        dest.copy_from_slice(&src[0..3]);
+                        continue;
+                    }
+                    (Format::Rgba32, Format::A8) => {
+                        dest[0] = src[3];
+                        continue;
+                    }
+                    (Format::A8, _) => [src[0]; 3],
+                    (Format::Rgb24, _) => [src[0], src[1], src[2]],
+                };
+                match format {
+                    Format::A8 => {
+                        let sum: u32 = coverage.iter().map(|&value| value as u32).sum();
+                        dest[0] = ((sum + 1) / 3) as u8;
+                    }
+                    Format::Rgb24 | Format::Rgba32 => {
+                        for channel in 0..3 {
+                            let alpha = multiply_u8(color.a, coverage[channel]);
+                            dest[channel] = multiply_u8(foreground[channel], alpha);
+                        }
+                        if format == Format::Rgba32 {
+                            let coverage = coverage.iter().cloned().fold(0, cmp::max);
+                            dest[3] = multiply_u8(color.a, coverage);
+                        }
+                    }
+                }
+            }
+        }
+        canvas
+    }
+
     #[allow(dead_code)]
     pub(crate) fn blit_from_canvas(&mut self, src: &Canvas) {
         self.blit_from(point2(0, 0), &src.pixels, &src.size, src.stride, src.format)
@@ -161,6 +313,88 @@ impl Canvas {
         }
     }
 
+    /// Composites `color`, masked by the coverage in the A8 canvas `mask`, over this `Rgba32`
+    /// canvas. The two canvases must be the same size.
+    pub(crate) fn composite_color_with_mask(&mut self, color: ColorU, mask: &Canvas) {
+        debug_assert_eq!(self.format, Format::Rgba32);
+        debug_assert_eq!(mask.format, Format::A8);
+        debug_assert_eq!(self.size, mask.size);
+
+        let width = self.size.width as usize;
+        for y in 0..self.size.height as usize {
+            let dest_row_start = y * self.stride;
+            let dest_row = &mut self.pixels[dest_row_start..(dest_row_start + width * 4)];
+            let mask_row_start = y * mask.stride;
+            let mask_row = &mask.pixels[mask_row_start..(mask_row_start + width)];
+            for (dest, &coverage) in dest_row.chunks_mut(4).zip(mask_row.iter()) {
+                if coverage == 0 {
+                    continue;
+                }
+                let alpha = multiply_u8(color.a, coverage);
+                let src = [
+                    multiply_u8(color.r, alpha),
+                    multiply_u8(color.g, alpha),
+                    multiply_u8(color.b, alpha),
+                    alpha,
+                ];
+                composite_source_over(dest, &src);
+            }
+        }
+    }
+
+    /// Composites a premultiplied R8G8B8A8 image over this `Rgba32` canvas.
+    ///
+    /// `transform` maps image pixel coordinates to canvas pixel coordinates. The image is sampled
+    /// with bilinear filtering.
+    pub(crate) fn composite_image(
+        &mut self,
+        image_pixels: &[u8],
+        image_size: &Size2D<u32>,
+        transform: &Transform2D<f32>,
+    ) {
+        debug_assert_eq!(self.format, Format::Rgba32);
+
+        let inverse_transform = match transform.inverse() {
+            Some(inverse_transform) => inverse_transform,
+            None => return,
+        };
+        let image_rect = Rect::new(Point2D::zero(), image_size.to_f32());
+        let dest_rect = transform.transform_rect(&image_rect).round_out().to_i32();
+        let dest_rect = match dest_rect.intersection(&Rect::new(point2(0, 0), self.size.to_i32())) {
+            Some(dest_rect) => dest_rect.to_usize(),
+            None => return,
+        };
+
+        let (image_width, image_height) = (image_size.width as i32, image_size.height as i32);
+        let fetch = |x: i32, y: i32, channel: usize| -> f32 {
+            if x < 0 || y < 0 || x >= image_width || y >= image_height {
+                return 0.0;
+            }
+            image_pixels[(y as usize * image_width as usize + x as usize) * 4 + channel] as f32
+        };
+
+        for y in dest_rect.origin.y..dest_rect.max_y() {
+            for x in dest_rect.origin.x..dest_rect.max_x() {
+                let center = point2(x as f32 + 0.5, y as f32 + 0.5);
+                let position = inverse_transform.transform_point(center) - vec2(0.5, 0.5);
+                let (x0, y0) = (position.x.floor() as i32, position.y.floor() as i32);
+                let (fx, fy) = (position.x - x0 as f32, position.y - y0 as f32);
+                let mut src = [0; 4];
+                for (channel, value) in src.iter_mut().enumerate() {
+                    let top = fetch(x0, y0, channel) * (1.0 - fx) + fetch(x0 + 1, y0, channel) * fx;
+                    let bottom = fetch(x0, y0 + 1, channel) * (1.0 - fx)
+                        + fetch(x0 + 1, y0 + 1, channel) * fx;
+                    *value = (top * (1.0 - fy) + bottom * fy).round() as u8;
+                }
+                if src[3] == 0 {
+                    continue;
+                }
+                let dest_start = y * self.stride + x * 4;
+                composite_source_over(&mut self.pixels[dest_start..(dest_start + 4)], &src);
+            }
+        }
+    }
+
     fn blit_from_with<B: Blit>(
         &mut self,
         rect: &Rect<usize>,
@@ -197,9 +431,13 @@ impl fmt::Debug for Canvas {
 }
 
 /// The image format for the canvas.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Format {
     /// Premultiplied R8G8B8A8, little-endian.
+    ///
+    /// Each pixel is four bytes in the order red, green, blue, alpha, and the color channels have
+    /// already been multiplied by alpha. This matches the layout of `RGBA8` GPU textures used
+    /// with premultiplied-alpha blending.
     Rgba32,
     /// R8G8B8, little-endian.
     Rgb24,
@@ -242,16 +480,181 @@ impl Format {
 }
 
 /// The antialiasing strategy that should be used when rasterizing glyphs.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum RasterizationOptions {
     /// "Black-and-white" rendering. Each pixel is either entirely on or off.
+    ///
+    /// Hinting still applies, so stems snap to whole pixels. Which pixels are on is up to the
+    /// rasterizer, unless `RasterizationAdjustments::bilevel_threshold` sets the coverage at
+    /// which they turn on.
     Bilevel,
     /// Grayscale antialiasing. Only one channel is used.
     GrayscaleAa,
     /// Subpixel RGB antialiasing, for LCD screens.
+    ///
+    /// Each color channel of an `Rgb24` canvas receives the coverage of the corresponding
+    /// subpixel. On an `A8` canvas, the channels are averaged together.
     SubpixelAa,
 }
 
+/// Adjustments to the coverage of rasterized glyphs, which can make thin glyphs look heavier at
+/// small sizes. Pass these to `Loader::rasterize_glyph_with_adjustments()`.
+///
+/// The default adjusts nothing. To adjust one thing, use struct update syntax; e.g.
+///
+///     # use font_kit::canvas::RasterizationAdjustments;
+///     let adjustments = RasterizationAdjustments {
+///         stem_darkening: true,
+///         ..RasterizationAdjustments::default()
+///     };
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterizationAdjustments {
+    /// Whether to embolden stems slightly, which keeps thin glyphs from looking washed out at
+    /// small sizes.
+    ///
+    /// On FreeType, this enables the stem darkening of the CFF, Type 1, and CID drivers and of
+    /// the autohinter; note that FreeType only darkens glyphs that it hints. On Core Text, this
+    /// enables font smoothing. DirectWrite ignores it.
+    pub stem_darkening: bool,
+    /// The gamma curve applied to antialiased coverage values: each coverage value `c`, from 0
+    /// to 1, becomes `c^(1 / gamma)`.
+    ///
+    /// 1.0, the default, leaves coverage unchanged. Values above 1.0 darken antialiased edges,
+    /// and values below 1.0 lighten them. Bilevel rendering is unaffected.
+    pub gamma: f32,
+    /// The filter applied across subpixels with subpixel antialiasing, to reduce color fringes.
+    ///
+    /// Only FreeType uses this. Core Text and DirectWrite always apply the system's own filter.
+    pub lcd_filter: LcdFilter,
+    /// With bilevel rendering, the coverage, from 0 to 255, at which a pixel is turned on.
+    ///
+    /// `None`, the default, leaves the decision to the rasterizer's own bilevel mode. Otherwise,
+    /// the glyph is rasterized with grayscale antialiasing, hinted as `hinting_options` asks, and
+    /// each pixel whose coverage is at least the threshold becomes 255 and every other pixel 0.
+    /// Other antialiasing strategies ignore this.
+    pub bilevel_threshold: Option<u8>,
+}
+
+impl Default for RasterizationAdjustments {
+    /// Returns adjustments with no stem darkening, a gamma of 1.0, the default LCD filter, and
+    /// the rasterizer's own bilevel threshold.
+    #[inline]
+    fn default() -> RasterizationAdjustments {
+        RasterizationAdjustments {
+            stem_darkening: false,
+            gamma: 1.0,
+            lcd_filter: LcdFilter::Default,
+            bilevel_threshold: None,
+        }
+    }
+}
+
+impl RasterizationAdjustments {
+    /// Returns the bilevel threshold of these adjustments, if they ask for bilevel rendering by
+    /// thresholding grayscale coverage.
+    #[inline]
+    pub(crate) fn bilevel_threshold(
+        &self,
+        rasterization_options: RasterizationOptions,
+    ) -> Option<u8> {
+        match rasterization_options {
+            RasterizationOptions::Bilevel => self.bilevel_threshold,
+            RasterizationOptions::GrayscaleAa | RasterizationOptions::SubpixelAa => None,
+        }
+    }
+
+    /// Applies the gamma curve of these adjustments to antialiased coverage values, in place.
+    pub(crate) fn apply_gamma(
+        &self,
+        rasterization_options: RasterizationOptions,
+        coverage: &mut [u8],
+    ) {
+        if self.gamma == 1.0 || rasterization_options == RasterizationOptions::Bilevel {
+            return;
+        }
+        let exponent = 1.0 / self.gamma;
+        let mut table = [0; 256];
+        for (value, entry) in table.iter_mut().enumerate() {
+            *entry = (f32::powf(value as f32 / 255.0, exponent) * 255.0).round() as u8;
+        }
+        for value in coverage {
+            *value = table[*value as usize];
+        }
+    }
+}
+
+/// The color space in which `Canvas::blend_onto()` blends colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Gamma {
+    /// Blend sRGB-encoded values directly, as most naive compositors do.
+    Srgb,
+    /// Convert sRGB-encoded values to linear light, blend, and convert the result back.
+    Linear,
+}
+
+/// A five-tap FIR filter applied horizontally across subpixels when rasterizing with subpixel
+/// antialiasing.
+///
+/// Without filtering, subpixel-antialiased glyphs have strong color fringes. Filtering spreads
+/// each subpixel's coverage over its neighbors, trading some sharpness for less fringing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LcdFilter {
+    /// No filtering.
+    None,
+    /// FreeType's default filter, with the weights `[0x08, 0x4d, 0x56, 0x4d, 0x08]`.
+    Default,
+    /// FreeType's light filter, with the weights `[0x00, 0x55, 0x56, 0x55, 0x00]`. This is
+    /// sharper than the default filter but leaves more fringing.
+    Light,
+    /// Custom weights, in units of 1/256. They should sum to 256 (0x100) so that filtering
+    /// preserves the overall coverage.
+    Custom([u8; 5]),
+}
+
+impl LcdFilter {
+    /// Returns the weights of this filter, in units of 1/256, or `None` if this is
+    /// `LcdFilter::None`.
+    pub fn weights(self) -> Option<[u8; 5]> {
+        match self {
+            LcdFilter::None => None,
+            LcdFilter::Default => Some([0x08, 0x4d, 0x56, 0x4d, 0x08]),
+            LcdFilter::Light => Some([0x00, 0x55, 0x56, 0x55, 0x00]),
+            LcdFilter::Custom(weights) => Some(weights),
+        }
+    }
+
+    /// Filters one row or column of subpixel coverage values, `src`, into `dest`.
+    ///
+    /// The filter spreads coverage two subpixels in each direction, so `dest` must be six
+    /// subpixels (two pixels' worth) longer than `src`: `dest[i + 3]` is centered on `src[i]`.
+    #[allow(dead_code)]
+    pub(crate) fn filter(self, src: &[u8], dest: &mut [u8]) {
+        debug_assert_eq!(dest.len(), src.len() + 6);
+        let weights = match self.weights() {
+            Some(weights) => weights,
+            None => {
+                dest.iter_mut().for_each(|value| *value = 0);
+                dest[3..(src.len() + 3)].copy_from_slice(src);
+                return;
+            }
+        };
+        for (dest_index, value) in dest.iter_mut().enumerate() {
+            let mut sum = 0;
+            for (tap, &weight) in weights.iter().enumerate() {
+                // The subpixel under this tap is `dest_index - 3 + tap - 2`.
+                let src_index = match (dest_index + tap).checked_sub(5) {
+                    Some(src_index) => src_index,
+                    None => continue,
+                };
+                if let Some(&coverage) = src.get(src_index) {
+                    sum += weight as u32 * coverage as u32;
+                }
+            }
+            *value = cmp::min((sum + 0x80) >> 8, 0xff) as u8;
+        }
+    }
+}
+
 trait Blit {
     fn blit(dest: &mut [u8], src: &[u8]);
 }
@@ -301,3 +704,36 @@ impl Blit for BlitRgba32ToRgb24 {
         }
     }
 }
+
+/// Multiplies two values in the range 0-255, treating 255 as 1.0.
+#[inline]
+fn multiply_u8(a: u8, b: u8) -> u8 {
+    ((a as u32 * b as u32 + 127) / 255) as u8
+}
+
+/// Converts an sRGB-encoded value, from 0 to 1, to linear light.
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        f32::powf((value + 0.055) / 1.055, 2.4)
+    }
+}
+
+/// Converts a linear-light value, from 0 to 1, to sRGB encoding.
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * f32::powf(value, 1.0 / 2.4) - 0.055
+    }
+}
+
+/// Composites a premultiplied RGBA pixel over another with the Porter-Duff "over" operator.
+#[inline]
+fn composite_source_over(dest: &mut [u8], src: &[u8; 4]) {
+    let inverse_alpha = 255 - src[3];
+    for (dest, &src) in dest.iter_mut().zip(src.iter()) {
+        *dest = src.saturating_add(multiply_u8(*dest, inverse_alpha));
+    }
+}
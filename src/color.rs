@@ -0,0 +1,287 @@
+// font-kit/src/color.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Color glyph data, such as the layers of a `COLR` glyph and the `CPAL` palettes they use.
+
+use euclid::default::{Point2D, Transform2D};
+
+/// A color with 8 bits per channel, not premultiplied by alpha.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ColorU {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+    /// The alpha channel.
+    pub a: u8,
+}
+
+impl ColorU {
+    /// Creates a color from its red, green, blue, and alpha channels.
+    #[inline]
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> ColorU {
+        ColorU { r, g, b, a }
+    }
+}
+
+/// The color glyph technologies that a font carries, which call for different rendering
+/// support.
+///
+/// See `Loader::color_format()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ColorFormat {
+    /// The font's `COLR` table has version 0 glyphs: layers of solid colors.
+    pub colr_v0: bool,
+    /// The font's `COLR` table has version 1 glyphs, which are painted with gradients,
+    /// transforms, and compositing as well as solid colors.
+    pub colr_v1: bool,
+    /// The font has an `SVG ` table of SVG documents.
+    pub svg: bool,
+    /// The font has an `sbix` table of bitmaps, usually PNG images.
+    pub sbix: bool,
+    /// The font has `CBDT` and `CBLC` tables of color bitmaps.
+    pub cbdt: bool,
+}
+
+impl ColorFormat {
+    /// Returns true if the font carries no color glyphs at all.
+    #[inline]
+    pub fn is_monochrome(&self) -> bool {
+        *self == ColorFormat::default()
+    }
+}
+
+/// A single layer of a color glyph.
+///
+/// To draw a color glyph, fill the outline of each layer's glyph with the layer's color, from the
+/// first layer (bottommost) to the last (topmost).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorLayer {
+    /// The ID of the glyph whose outline makes up this layer.
+    pub glyph_id: u32,
+    /// The color to fill the layer with, resolved from the requested `CPAL` palette.
+    ///
+    /// `None` means that the layer should use the current text (foreground) color.
+    pub color: Option<ColorU>,
+}
+
+/// A color palette from a font's `CPAL` table, which the layers of its `COLR` glyphs take their
+/// colors from.
+///
+/// See `Loader::color_palettes()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColorPalette {
+    /// The colors of the palette, indexed by palette entry.
+    pub colors: Vec<ColorU>,
+    /// True if the font marks the palette as suitable for text on light backgrounds.
+    pub usable_with_light_background: bool,
+    /// True if the font marks the palette as suitable for text on dark backgrounds.
+    pub usable_with_dark_background: bool,
+    /// The English name of the palette, from the `name` table, if the font labels it.
+    pub name: Option<String>,
+    /// The English names of the palette's entries, from the `name` table, indexed like `colors`.
+    ///
+    /// Entry labels are shared by every palette of the font, and say what the entry is used for
+    /// (e.g. "Outline") rather than which color it is.
+    pub entry_names: Vec<Option<String>>,
+}
+
+/// A node of the paint graph of a `COLR` version 1 glyph.
+///
+/// Coordinates are in font units with y pointing up, like glyph outlines. Colors are resolved from
+/// the requested `CPAL` palette; a color of `None` stands for the current text (foreground)
+/// color. Variable paints are given their default values, with no variations applied.
+///
+/// See `Loader::colrv1_paint()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    /// Paints drawn on top of one another, bottommost first.
+    Layers(Vec<Paint>),
+    /// A solid color.
+    Solid {
+        /// The color, or `None` for the foreground color.
+        color: Option<ColorU>,
+        /// The opacity to multiply the color's alpha by, from 0 to 1.
+        alpha: f32,
+    },
+    /// A gradient along the line from `p0` to `p1`, rotated so that its lines of equal color run
+    /// parallel to the line from `p0` to `p2`.
+    LinearGradient {
+        /// The colors of the gradient, with stops from 0 at `p0` to 1 at `p1`.
+        color_line: ColorLine,
+        /// The start point.
+        p0: Point2D<f32>,
+        /// The end point.
+        p1: Point2D<f32>,
+        /// The rotation point.
+        p2: Point2D<f32>,
+    },
+    /// A gradient between two circles.
+    RadialGradient {
+        /// The colors of the gradient, with stops from 0 at the first circle to 1 at the second.
+        color_line: ColorLine,
+        /// The center of the first circle.
+        c0: Point2D<f32>,
+        /// The radius of the first circle.
+        r0: f32,
+        /// The center of the second circle.
+        c1: Point2D<f32>,
+        /// The radius of the second circle.
+        r1: f32,
+    },
+    /// A gradient around a center point.
+    SweepGradient {
+        /// The colors of the gradient, with stops from 0 at `start_angle` to 1 at `end_angle`.
+        color_line: ColorLine,
+        /// The center of the gradient.
+        center: Point2D<f32>,
+        /// The angle where the gradient starts, in degrees counterclockwise from the positive x
+        /// axis.
+        start_angle: f32,
+        /// The angle where the gradient ends, in degrees counterclockwise from the positive x
+        /// axis.
+        end_angle: f32,
+    },
+    /// A paint clipped to the outline of a glyph.
+    Glyph {
+        /// The ID of the glyph whose outline clips the paint.
+        glyph_id: u32,
+        /// The paint to fill the outline with.
+        paint: Box<Paint>,
+    },
+    /// The paint graph of another `COLR` version 1 glyph, reused.
+    ColrGlyph {
+        /// The ID of the glyph whose paint graph this is.
+        glyph_id: u32,
+        /// The paint graph.
+        paint: Box<Paint>,
+    },
+    /// A paint drawn with an affine transform. Translations, scales, rotations, and skews,
+    /// about a center or not, are all given as transforms.
+    Transform {
+        /// The transform, applied to the coordinates of the paint.
+        transform: Transform2D<f32>,
+        /// The paint to transform.
+        paint: Box<Paint>,
+    },
+    /// A paint composited onto another.
+    Composite {
+        /// The paint to composite.
+        source: Box<Paint>,
+        /// How to composite `source` onto `backdrop`.
+        mode: CompositeMode,
+        /// The paint to composite onto.
+        backdrop: Box<Paint>,
+    },
+}
+
+/// The colors of a gradient.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorLine {
+    /// How the gradient continues outside of the stops.
+    pub extend: Extend,
+    /// The color stops, in the order that the font lists them.
+    pub stops: Vec<ColorStop>,
+}
+
+/// A color at a position along a gradient.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    /// The position of the stop along the gradient.
+    pub offset: f32,
+    /// The color, or `None` for the foreground color.
+    pub color: Option<ColorU>,
+    /// The opacity to multiply the color's alpha by, from 0 to 1.
+    pub alpha: f32,
+}
+
+/// How a gradient continues outside of its first and last color stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Extend {
+    /// The colors of the first and last stops continue.
+    Pad,
+    /// The gradient repeats.
+    Repeat,
+    /// The gradient repeats, reversed every other time.
+    Reflect,
+}
+
+/// How a `Paint::Composite` composites its source onto its backdrop: a Porter-Duff operator or a
+/// blend mode, as in the W3C Compositing and Blending specification.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompositeMode {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl CompositeMode {
+    pub(crate) fn from_value(value: u8) -> Option<CompositeMode> {
+        const MODES: [CompositeMode; 28] = [
+            CompositeMode::Clear,
+            CompositeMode::Source,
+            CompositeMode::Destination,
+            CompositeMode::SourceOver,
+            CompositeMode::DestinationOver,
+            CompositeMode::SourceIn,
+            CompositeMode::DestinationIn,
+            CompositeMode::SourceOut,
+            CompositeMode::DestinationOut,
+            CompositeMode::SourceAtop,
+            CompositeMode::DestinationAtop,
+            CompositeMode::Xor,
+            CompositeMode::Plus,
+            CompositeMode::Screen,
+            CompositeMode::Overlay,
+            CompositeMode::Darken,
+            CompositeMode::Lighten,
+            CompositeMode::ColorDodge,
+            CompositeMode::ColorBurn,
+            CompositeMode::HardLight,
+            CompositeMode::SoftLight,
+            CompositeMode::Difference,
+            CompositeMode::Exclusion,
+            CompositeMode::Multiply,
+            CompositeMode::Hue,
+            CompositeMode::Saturation,
+            CompositeMode::Color,
+            CompositeMode::Luminosity,
+        ];
+        MODES.get(value as usize).copied()
+    }
+}
@@ -0,0 +1,124 @@
+// font-kit/src/coverage.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The set of Unicode codepoints that a font can display.
+
+use std::iter;
+use std::ops::RangeInclusive;
+
+/// The set of Unicode codepoints that a font maps to glyphs, as returned by
+/// `Loader::codepoint_coverage()`.
+///
+/// The set is stored as sorted, non-overlapping, non-adjacent ranges, so membership queries are
+/// binary searches. Surrogate codepoints (U+D800 to U+DFFF) are never included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodepointCoverage {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl CodepointCoverage {
+    /// Creates a coverage set from arbitrary ranges of codepoints, which may overlap and needn't
+    /// be sorted.
+    pub fn from_ranges<I>(ranges: I) -> CodepointCoverage
+    where
+        I: IntoIterator<Item = RangeInclusive<u32>>,
+    {
+        let mut sorted: Vec<RangeInclusive<u32>> = ranges
+            .into_iter()
+            .map(|range| *range.start()..=(*range.end()).min(MAX_CODEPOINT))
+            .filter(|range| !range.is_empty())
+            .collect();
+        sorted.sort_by_key(|range| *range.start());
+
+        let mut merged: Vec<RangeInclusive<u32>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            // Cut surrogates out of every range so that none can bridge the gap they leave.
+            for range in without_surrogates(range) {
+                match merged.last_mut() {
+                    Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                        if range.end() > last.end() {
+                            *last = *last.start()..=*range.end();
+                        }
+                    }
+                    _ => merged.push(range),
+                }
+            }
+        }
+        CodepointCoverage { ranges: merged }
+    }
+
+    /// Returns the covered codepoints as sorted, non-overlapping ranges.
+    #[inline]
+    pub fn ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.ranges
+    }
+
+    /// Returns true if no codepoints are covered.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the number of codepoints covered.
+    pub fn len(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|range| (range.end() - range.start()) as usize + 1)
+            .sum()
+    }
+
+    /// Returns true if the given character is covered.
+    #[inline]
+    pub fn contains(&self, character: char) -> bool {
+        self.contains_codepoint(character as u32)
+    }
+
+    /// Returns true if the given codepoint is covered.
+    pub fn contains_codepoint(&self, codepoint: u32) -> bool {
+        self.range_containing(codepoint).is_some()
+    }
+
+    /// Returns true if every codepoint in the given range is covered, ignoring surrogates.
+    ///
+    /// For example, `contains_range(0x4e00..=0x9fff)` answers whether a font covers all of the
+    /// CJK Unified Ideographs block.
+    pub fn contains_range(&self, range: RangeInclusive<u32>) -> bool {
+        without_surrogates(range).all(|range| match self.range_containing(*range.start()) {
+            Some(covered) => covered.end() >= range.end(),
+            None => false,
+        })
+    }
+
+    fn range_containing(&self, codepoint: u32) -> Option<&RangeInclusive<u32>> {
+        // Find the last range that starts at or before the codepoint.
+        let index = self
+            .ranges
+            .partition_point(|range| *range.start() <= codepoint);
+        let range = self.ranges.get(index.checked_sub(1)?)?;
+        if codepoint <= *range.end() {
+            Some(range)
+        } else {
+            None
+        }
+    }
+}
+
+const MAX_CODEPOINT: u32 = 0x10ffff;
+const SURROGATES: RangeInclusive<u32> = 0xd800..=0xdfff;
+
+/// Splits the range into the (at most two) pieces that lie outside the surrogate block.
+fn without_surrogates(range: RangeInclusive<u32>) -> impl Iterator<Item = RangeInclusive<u32>> {
+    let (start, end) = (*range.start(), *range.end());
+    let below = start..=end.min(*SURROGATES.start() - 1);
+    let above = start.max(*SURROGATES.end() + 1)..=end;
+    iter::once(below)
+        .chain(iter::once(above))
+        .filter(|range| !range.is_empty())
+}
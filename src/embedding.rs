@@ -0,0 +1,81 @@
+// font-kit/src/embedding.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The licensing restrictions that a font places on embedding it in documents.
+
+const RESTRICTED_LICENSE: u16 = 0x0002;
+const PREVIEW_AND_PRINT: u16 = 0x0004;
+const EDITABLE: u16 = 0x0008;
+const NO_SUBSETTING: u16 = 0x0100;
+const BITMAP_ONLY: u16 = 0x0200;
+
+/// What a font's license permits when embedding it in documents, decoded from the `fsType` field
+/// of its `OS/2` table.
+///
+/// Fonts without an `OS/2` table carry no restrictions, as if `fsType` were 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EmbeddingRestrictions {
+    /// How a document that embeds the font may be used.
+    pub permission: EmbeddingPermission,
+    /// Whether the font must be embedded whole rather than subset.
+    pub no_subsetting: bool,
+    /// Whether only the font's bitmaps may be embedded, and not its outlines.
+    pub bitmap_only: bool,
+}
+
+/// How a document that embeds a font may be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmbeddingPermission {
+    /// The font may be embedded and permanently installed on the remote system.
+    Installable,
+    /// The font must not be embedded without permission from its legal owner.
+    RestrictedLicense,
+    /// The font may be embedded, but documents that embed it must be opened read-only.
+    PreviewAndPrint,
+    /// The font may be embedded in documents that can be edited, and installed temporarily while
+    /// they are.
+    Editable,
+}
+
+impl Default for EmbeddingPermission {
+    #[inline]
+    fn default() -> EmbeddingPermission {
+        EmbeddingPermission::Installable
+    }
+}
+
+impl EmbeddingRestrictions {
+    /// Decodes the `fsType` field of an `OS/2` table.
+    ///
+    /// Older fonts may set several of the mutually exclusive permission bits; as the OpenType
+    /// specification directs, the least restrictive one applies.
+    pub fn from_fs_type(fs_type: u16) -> EmbeddingRestrictions {
+        let permission = if fs_type & EDITABLE != 0 {
+            EmbeddingPermission::Editable
+        } else if fs_type & PREVIEW_AND_PRINT != 0 {
+            EmbeddingPermission::PreviewAndPrint
+        } else if fs_type & RESTRICTED_LICENSE != 0 {
+            EmbeddingPermission::RestrictedLicense
+        } else {
+            EmbeddingPermission::Installable
+        };
+        EmbeddingRestrictions {
+            permission,
+            no_subsetting: fs_type & NO_SUBSETTING != 0,
+            bitmap_only: fs_type & BITMAP_ONLY != 0,
+        }
+    }
+
+    /// Returns true if the font may be embedded in a document at all without further permission.
+    #[inline]
+    pub fn can_embed(&self) -> bool {
+        self.permission != EmbeddingPermission::RestrictedLicense
+    }
+}
@@ -38,7 +38,7 @@ pub enum FontLoadingError {
     /// Attempted to load an invalid index in a TrueType or OpenType font collection.
     ///
     /// For example, if a `.ttc` file has 2 fonts in it, and you ask for the 5th one, you'll get
-    /// this error.
+    /// this error. Asking for a PostScript name that no font in the data has gives it too.
     NoSuchFontInCollection,
     /// Attempted to load a malformed or corrupted font.
     Parse,
@@ -67,26 +67,43 @@ impl From<io::Error> for FontLoadingError {
 }
 
 /// Reasons why a font might fail to load a glyph.
-#[derive(Clone, Copy, PartialEq, Debug)]
+///
+/// More variants may be added in the future, so matches on this type need a wildcard arm.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum GlyphLoadingError {
     /// The font didn't contain a glyph with that ID.
     NoSuchGlyph,
-    /// A platform function returned an error.
-    PlatformError,
+    /// The font's data for the glyph, or a table that the glyph depends on, is corrupt.
+    MalformedFont,
+    /// The font's glyph data is missing, or in a format that this operation doesn't support.
+    UnsupportedFormat,
+    /// A platform function returned an error, described by the message.
+    ///
+    /// This covers failures unrelated to the font data, such as running out of memory.
+    PlatformError(String),
+    /// The font has no color glyph data (e.g. `COLR` and `CPAL` tables).
+    NoColorData,
+    /// The font has no vertical metrics (`vhea` and `vmtx` tables).
+    NoVerticalMetrics,
 }
 
 impl Error for GlyphLoadingError {}
 
 impl_display! { GlyphLoadingError, {
         NoSuchGlyph => "no such glyph",
-        PlatformError => "platform error",
+        MalformedFont => "malformed font",
+        UnsupportedFormat => "unsupported glyph format",
+        PlatformError(message) => format!("platform error: {}", message),
+        NoColorData => "no color data",
+        NoVerticalMetrics => "no vertical metrics",
     }
 }
 
 #[cfg(target_family = "windows")]
 impl From<winapi::um::winnt::HRESULT> for GlyphLoadingError {
-    fn from(_err: winapi::um::winnt::HRESULT) -> GlyphLoadingError {
-        GlyphLoadingError::PlatformError
+    fn from(err: winapi::um::winnt::HRESULT) -> GlyphLoadingError {
+        GlyphLoadingError::PlatformError(format!("HRESULT {:#010x}", err))
     }
 }
 
@@ -11,18 +11,21 @@
 //! Encapsulates the information needed to locate and open the fonts in a family.
 
 use crate::handle::Handle;
+use crate::properties::Properties;
 
 /// Encapsulates the information needed to locate and open the fonts in a family.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct FamilyHandle {
     pub(crate) fonts: Vec<Handle>,
+    /// The properties of each font, if the source already knew them.
+    pub(crate) properties: Option<Vec<Properties>>,
 }
 
 impl FamilyHandle {
     /// Creates an empty set of family handles.
     #[inline]
     pub fn new() -> FamilyHandle {
-        FamilyHandle { fonts: vec![] }
+        FamilyHandle::default()
     }
 
     /// Creates a set of font family handles.
@@ -33,13 +36,28 @@ impl FamilyHandle {
     {
         FamilyHandle {
             fonts: fonts.collect::<Vec<Handle>>(),
+            properties: None,
+        }
+    }
+
+    /// Creates a set of font family handles whose properties are already known, so that matching
+    /// within the family doesn't have to load the fonts.
+    pub(crate) fn from_font_handles_and_properties<I>(fonts: I) -> FamilyHandle
+    where
+        I: Iterator<Item = (Handle, Properties)>,
+    {
+        let (fonts, properties) = fonts.unzip();
+        FamilyHandle {
+            fonts,
+            properties: Some(properties),
         }
     }
 
     /// Adds a new handle to this set.
     #[inline]
     pub fn push(&mut self, font: Handle) {
-        self.fonts.push(font)
+        self.fonts.push(font);
+        self.properties = None;
     }
 
     /// Returns true if and only if this set has no fonts in it.
@@ -35,3 +35,43 @@ pub enum FamilyName {
     /// expressive representations of characters.
     Fantasy,
 }
+
+/// The families that the generic families should resolve to, in place of the platform defaults,
+/// as browsers let users choose a default font for each generic family.
+///
+/// Each list is tried in order, and the platform default is used only if none of its families is
+/// installed. See `Source::select_family_by_generic_name_with_config()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenericFamilyConfig {
+    /// The families to try for `FamilyName::Serif`.
+    pub serif: Vec<String>,
+    /// The families to try for `FamilyName::SansSerif`.
+    pub sans_serif: Vec<String>,
+    /// The families to try for `FamilyName::Monospace`.
+    pub monospace: Vec<String>,
+    /// The families to try for `FamilyName::Cursive`.
+    pub cursive: Vec<String>,
+    /// The families to try for `FamilyName::Fantasy`.
+    pub fantasy: Vec<String>,
+}
+
+impl GenericFamilyConfig {
+    /// Creates a configuration that leaves every generic family to the platform default.
+    #[inline]
+    pub fn new() -> GenericFamilyConfig {
+        GenericFamilyConfig::default()
+    }
+
+    /// Returns the families to try for the given generic family, in order. Specific families
+    /// have none.
+    pub fn families(&self, family_name: &FamilyName) -> &[String] {
+        match *family_name {
+            FamilyName::Title(_) => &[],
+            FamilyName::Serif => &self.serif,
+            FamilyName::SansSerif => &self.sans_serif,
+            FamilyName::Monospace => &self.monospace,
+            FamilyName::Cursive => &self.cursive,
+            FamilyName::Fantasy => &self.fantasy,
+        }
+    }
+}
@@ -8,13 +8,21 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! The type of a font file: either a single font or a TrueType/OpenType collection.
+//! The type of a font file: a single font, a TrueType/OpenType collection, or a web font.
 
-/// The type of a font file: either a single font or a TrueType/OpenType collection.
+/// The type of a font file: a single font, a TrueType/OpenType collection, or a web font.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FileType {
-    /// The font file represents a single font (`.ttf`, `.otf`, `.woff`, etc.)
+    /// The font file represents a single font (`.ttf`, `.otf`, etc.)
     Single,
     /// The font file represents a collection of fonts (`.ttc`, `.otc`, etc.)
     Collection(u32),
+    /// The font file is a single font compressed in the WOFF 1.0 format (`.woff`).
+    ///
+    /// Loaders decompress these fonts transparently.
+    Woff,
+    /// The font file is a single font compressed in the WOFF 2.0 format (`.woff2`).
+    ///
+    /// Loaders decompress these fonts transparently if the `woff2` feature is enabled.
+    Woff2,
 }
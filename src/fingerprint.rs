@@ -0,0 +1,133 @@
+// font-kit/src/fingerprint.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Cheap fingerprints of font files, computed from a few tables rather than by loading the font.
+
+use byteorder::{BigEndian, ReadBytesExt};
+use flate2::Crc;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use crate::error::FontLoadingError;
+use crate::gzip;
+use crate::names;
+use crate::sfnt::{SFNT_HEADER_SIZE, SFNT_TABLE_RECORD_SIZE};
+use crate::tables::name::{self, NameTable};
+use crate::tables::{self, head};
+use crate::woff;
+
+const COLLECTION_TAG: u32 = tables::tag(b"ttcf");
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Computes the fingerprint of the font at `font_index` in the given font file. See
+/// `Loader::fingerprint_file()`.
+///
+/// Only the table directory and the `head` and `name` tables are read, unless the font is
+/// compressed (WOFF, WOFF2, or gzip), in which case the whole file has to be decompressed.
+pub(crate) fn fingerprint<R>(reader: &mut R, font_index: u32) -> Result<u64, FontLoadingError>
+where
+    R: Read + Seek,
+{
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut signature = [0; 4];
+    reader.read_exact(&mut signature)?;
+    if gzip::is_compressed(&signature) || woff::web_font_type(&signature).is_some() {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut font_data = vec![];
+        reader.read_to_end(&mut font_data)?;
+        let font_data = woff::to_sfnt(gzip::decompress(Arc::new(font_data))?)?;
+        return fingerprint_sfnt(&mut Cursor::new(&font_data[..]), font_index, file_size);
+    }
+    fingerprint_sfnt(reader, font_index, file_size)
+}
+
+fn fingerprint_sfnt<R>(
+    reader: &mut R,
+    font_index: u32,
+    file_size: u64,
+) -> Result<u64, FontLoadingError>
+where
+    R: Read + Seek,
+{
+    let data_size = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let offset_table_pos = if reader.read_u32::<BigEndian>()? == COLLECTION_TAG {
+        let _version = reader.read_u32::<BigEndian>()?;
+        if font_index >= reader.read_u32::<BigEndian>()? {
+            return Err(FontLoadingError::NoSuchFontInCollection);
+        }
+        reader.seek(SeekFrom::Current(font_index as i64 * 4))?;
+        reader.read_u32::<BigEndian>()? as u64
+    } else if font_index != 0 {
+        return Err(FontLoadingError::NoSuchFontInCollection);
+    } else {
+        0
+    };
+
+    reader.seek(SeekFrom::Start(offset_table_pos + 4))?;
+    let num_tables = reader.read_u16::<BigEndian>()?;
+    reader.seek(SeekFrom::Start(offset_table_pos + SFNT_HEADER_SIZE as u64))?;
+    let mut directory = vec![0; num_tables as usize * SFNT_TABLE_RECORD_SIZE];
+    reader.read_exact(&mut directory)?;
+    let head_data =
+        read_table(reader, &directory, data_size, head::TAG)?.ok_or(FontLoadingError::Parse)?;
+    let postscript_name = read_table(reader, &directory, data_size, name::TAG)?
+        .and_then(|data| NameTable::parse(&data))
+        .and_then(|table| table.english_name(names::POSTSCRIPT_NAME))
+        .unwrap_or_default();
+
+    let mut head_crc = Crc::new();
+    head_crc.update(&head_data);
+
+    // FNV-1a, since the fingerprint has to be the same in every process and on every platform.
+    let mut hash = FNV_OFFSET_BASIS;
+    let fields = [
+        &head_crc.sum().to_be_bytes()[..],
+        postscript_name.as_bytes(),
+        &[0],
+        &file_size.to_be_bytes(),
+        &font_index.to_be_bytes(),
+    ];
+    for byte in fields.iter().flat_map(|field| field.iter()) {
+        hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+    }
+    Ok(hash)
+}
+
+/// Reads the table with the given tag, if the table directory has one.
+fn read_table<R>(
+    reader: &mut R,
+    directory: &[u8],
+    data_size: u64,
+    tag: u32,
+) -> Result<Option<Vec<u8>>, FontLoadingError>
+where
+    R: Read + Seek,
+{
+    for mut record in directory.chunks_exact(SFNT_TABLE_RECORD_SIZE) {
+        if record.read_u32::<BigEndian>()? != tag {
+            continue;
+        }
+        let _checksum = record.read_u32::<BigEndian>()?;
+        let offset = record.read_u32::<BigEndian>()?;
+        let length = record.read_u32::<BigEndian>()?;
+        if offset as u64 + length as u64 > data_size {
+            return Err(FontLoadingError::Parse);
+        }
+        reader.seek(SeekFrom::Start(offset as u64))?;
+        let mut data = vec![0; length as usize];
+        reader.read_exact(&mut data)?;
+        return Ok(Some(data));
+    }
+    Ok(None)
+}
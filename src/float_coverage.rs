@@ -0,0 +1,216 @@
+// font-kit/src/float_coverage.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rasterization of glyph outlines into floating-point coverage, by accumulating the signed area
+//! that each edge covers in each pixel.
+
+use euclid::default::{Point2D, Size2D, Transform2D};
+use lyon_path::builder::{Build, FlatPathBuilder};
+use lyon_path::math::Point;
+
+use crate::error::GlyphLoadingError;
+use crate::hinting::HintingOptions;
+use crate::loader::{FontTransform, Loader};
+
+/// The maximum distance, in pixels, between a curve and the line segments approximating it.
+const FLATTENING_TOLERANCE: f32 = 0.05;
+
+/// Rasterizes a glyph into a buffer of `size.width * size.height` coverage values in [0, 1],
+/// row by row. See `Loader::rasterize_glyph_f32()`.
+pub(crate) fn rasterize_glyph_f32<L>(
+    font: &L,
+    size: &Size2D<u32>,
+    glyph_id: u32,
+    point_size: f32,
+    transform: &FontTransform,
+    origin: &Point2D<f32>,
+    hinting_options: HintingOptions,
+) -> Result<Vec<f32>, GlyphLoadingError>
+where
+    L: Loader,
+{
+    // Outlines arrive in font units with y pointing up. Scale them to pixels, flip them to point
+    // y down like the buffer, then apply the transform and the origin.
+    let scale = point_size / font.metrics().units_per_em as f32;
+    let to_pixels =
+        Transform2D::create_scale(scale, -scale).post_transform(&Transform2D::column_major(
+            transform.scale_x,
+            transform.skew_x,
+            origin.x,
+            transform.skew_y,
+            transform.scale_y,
+            origin.y,
+        ));
+    let mut builder = LineBuilder::new(to_pixels).flattened(FLATTENING_TOLERANCE / scale);
+    font.outline(glyph_id, hinting_options, &mut builder)?;
+    let lines = builder.build();
+
+    let (width, height) = (size.width as usize, size.height as usize);
+    let mut accumulator = Accumulator::new(width, height);
+    for &(from, to) in &lines {
+        accumulator.add_line(from, to);
+    }
+    Ok(accumulator.into_coverage())
+}
+
+/// A buffer of the signed area that edges contribute to each pixel, which sums, from left to
+/// right, to the winding number of the outline weighted by coverage.
+///
+/// Each row sums to zero, so the buffer is accumulated as one long run; area that falls to the
+/// right of the last column lands at the start of the next row, where it cancels out.
+struct Accumulator {
+    areas: Vec<f32>,
+    width: usize,
+    height: usize,
+}
+
+impl Accumulator {
+    fn new(width: usize, height: usize) -> Accumulator {
+        Accumulator {
+            areas: vec![0.0; width * height + 2],
+            width,
+            height,
+        }
+    }
+
+    fn add_line(&mut self, from: Point, to: Point) {
+        if from.y == to.y {
+            return;
+        }
+        let (direction, top, bottom) = if from.y < to.y {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+        let dx_dy = (bottom.x - top.x) / (bottom.y - top.y);
+
+        // Area left of the first column counts toward the first column, where the sum starts.
+        let max_x = self.width as f32;
+        let mut x = top.x - top.y.min(0.0) * dx_dy;
+        let first_row = top.y.max(0.0) as usize;
+        let end_row = (bottom.y.ceil().max(0.0) as usize).min(self.height);
+        for row in first_row..end_row {
+            let row_start = row * self.width;
+            let dy = (row as f32 + 1.0).min(bottom.y) - (row as f32).max(top.y);
+            let next_x = x + dx_dy * dy;
+            let area = dy * direction;
+            let (x0, x1) = if x < next_x { (x, next_x) } else { (next_x, x) };
+            let (x0, x1) = (x0.clamp(0.0, max_x), x1.clamp(0.0, max_x));
+            let (x0_floor, x1_ceil) = (x0.floor(), x1.ceil());
+            let (x0_index, x1_index) = (x0_floor as usize, x1_ceil as usize);
+
+            if x1_index <= x0_index + 1 {
+                // The edge stays within one pixel of the row.
+                let x_mid = 0.5 * (x0 + x1) - x0_floor;
+                self.areas[row_start + x0_index] += area - area * x_mid;
+                self.areas[row_start + x0_index + 1] += area * x_mid;
+            } else {
+                // The edge crosses several pixels, covering a triangle of the first and last and
+                // a trapezoid of the ones in between.
+                let inverse_width = 1.0 / (x1 - x0);
+                let x0_fraction = x0 - x0_floor;
+                let x1_fraction = x1 - x1_ceil + 1.0;
+                let first_area = 0.5 * inverse_width * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+                let last_area = 0.5 * inverse_width * x1_fraction * x1_fraction;
+                self.areas[row_start + x0_index] += area * first_area;
+                if x1_index == x0_index + 2 {
+                    self.areas[row_start + x0_index + 1] += area * (1.0 - first_area - last_area);
+                } else {
+                    let second_area = inverse_width * (1.5 - x0_fraction);
+                    self.areas[row_start + x0_index + 1] += area * (second_area - first_area);
+                    for x_index in (x0_index + 2)..(x1_index - 1) {
+                        self.areas[row_start + x_index] += area * inverse_width;
+                    }
+                    let before_last_area =
+                        second_area + (x1_index - x0_index - 3) as f32 * inverse_width;
+                    self.areas[row_start + x1_index - 1] +=
+                        area * (1.0 - before_last_area - last_area);
+                }
+                self.areas[row_start + x1_index] += area * last_area;
+            }
+            x = next_x;
+        }
+    }
+
+    /// Sums the areas into coverage, treating overlapping contours as the nonzero rule does.
+    fn into_coverage(mut self) -> Vec<f32> {
+        self.areas.truncate(self.width * self.height);
+        let mut sum = 0.0;
+        for value in &mut self.areas {
+            sum += *value;
+            *value = sum.abs().min(1.0);
+        }
+        self.areas
+    }
+}
+
+/// Collects the line segments of a flattened outline, transformed from font units to pixels.
+struct LineBuilder {
+    lines: Vec<(Point, Point)>,
+    transform: Transform2D<f32>,
+    first_position: Point,
+    current_position: Point,
+}
+
+impl LineBuilder {
+    fn new(transform: Transform2D<f32>) -> LineBuilder {
+        LineBuilder {
+            lines: vec![],
+            transform,
+            first_position: Point::zero(),
+            current_position: Point::zero(),
+        }
+    }
+}
+
+impl FlatPathBuilder for LineBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.close();
+        self.first_position = to;
+        self.current_position = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let line = (
+            self.transform.transform_point(self.current_position),
+            self.transform.transform_point(to),
+        );
+        self.lines.push(line);
+        self.current_position = to;
+    }
+
+    fn close(&mut self) {
+        // Contours are always filled, so close them even if the outline didn't.
+        if self.current_position != self.first_position {
+            let first_position = self.first_position;
+            self.line_to(first_position);
+        }
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
+}
+
+impl Build for LineBuilder {
+    type PathType = Vec<(Point, Point)>;
+
+    fn build(mut self) -> Vec<(Point, Point)> {
+        self.close();
+        self.lines
+    }
+
+    fn build_and_reset(&mut self) -> Vec<(Point, Point)> {
+        self.close();
+        self.first_position = Point::zero();
+        self.current_position = Point::zero();
+        self.lines.split_off(0)
+    }
+}
@@ -0,0 +1,38 @@
+// font-kit/src/gzip.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decompression of gzip-compressed font files, such as the `.pcf.gz` bitmap fonts that some Unix
+//! systems ship.
+
+use flate2::read::MultiGzDecoder;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::error::FontLoadingError;
+
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Returns true if the data is gzip-compressed.
+#[inline]
+pub(crate) fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Returns the font data decompressed if it's gzip-compressed, or as is otherwise.
+pub(crate) fn decompress(font_data: Arc<Vec<u8>>) -> Result<Arc<Vec<u8>>, FontLoadingError> {
+    if !is_compressed(&font_data) {
+        return Ok(font_data);
+    }
+    let mut decompressed = vec![];
+    MultiGzDecoder::new(&font_data[..])
+        .read_to_end(&mut decompressed)
+        .map_err(|_| FontLoadingError::Parse)?;
+    Ok(Arc::new(decompressed))
+}
@@ -25,7 +25,11 @@ use crate::font::Font;
 /// This is either the path to the font or the raw in-memory font data.
 ///
 /// To open the font referenced by a handle, use a loader.
+///
+/// With the `serde` Cargo feature, handles implement `Serialize` and `Deserialize`, so they can be
+/// saved and restored later.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Handle {
     /// A font on disk referenced by a path.
     Path {
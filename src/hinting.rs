@@ -38,8 +38,17 @@ pub enum HintingOptions {
     /// is used for grid fitting.
     ///
     /// This corresponds to what GDI in non-ClearType modes and FreeType in its normal hinting mode
-    /// do.
+    /// do. Like the other modes, it uses the font's own hinting instructions where the loader
+    /// can.
     Full(f32),
+
+    /// Hinting is performed in both horizontal and vertical directions by FreeType's autohinter,
+    /// ignoring any hinting instructions in the font. The specified point size is used for grid
+    /// fitting.
+    ///
+    /// This helps with fonts whose instructions render worse than the autohinter does. Only
+    /// FreeType supports it; see `Loader::supports_hinting_options()`.
+    Autohint(f32),
 }
 
 impl HintingOptions {
@@ -50,7 +59,8 @@ impl HintingOptions {
             HintingOptions::None => None,
             HintingOptions::Vertical(size)
             | HintingOptions::VerticalSubpixel(size)
-            | HintingOptions::Full(size) => Some(size),
+            | HintingOptions::Full(size)
+            | HintingOptions::Autohint(size) => Some(size),
         }
     }
 }
@@ -0,0 +1,185 @@
+// font-kit/src/layout.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Information about the OpenType layout features (`GSUB` and `GPOS`) that a font supports.
+
+use std::collections::BTreeMap;
+
+use crate::tables::layout::LayoutTable;
+
+/// An OpenType script tag, such as `latn` or `cyrl`, as declared in a font's `GSUB` and `GPOS`
+/// tables.
+///
+/// These differ from the ISO 15924 tags of `Script`: they are lowercase, and some scripts have
+/// more than one (e.g. `deva` and `dev2`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScriptTag(pub u32);
+
+/// An OpenType language system tag, such as `TRK ` or `ROM `, as declared in a font's `GSUB`
+/// and `GPOS` tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LangTag(pub u32);
+
+impl ScriptTag {
+    /// The tag that fonts use for features that apply regardless of script (`DFLT`).
+    pub const DEFAULT: ScriptTag = ScriptTag::new(b"DFLT");
+
+    /// Creates a script tag from its four characters, such as `b"latn"`.
+    #[inline]
+    pub const fn new(tag: &[u8; 4]) -> ScriptTag {
+        ScriptTag(u32::from_be_bytes(*tag))
+    }
+}
+
+impl LangTag {
+    /// Creates a language system tag from its four characters, such as `b"TRK "`.
+    #[inline]
+    pub const fn new(tag: &[u8; 4]) -> LangTag {
+        LangTag(u32::from_be_bytes(*tag))
+    }
+}
+
+/// The kind of a glyph, as the `GlyphClassDef` of the `GDEF` table defines it.
+///
+/// See `Loader::glyph_class()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphClass {
+    /// The font doesn't say, because it has no `GDEF` table or assigns the glyph no class.
+    Unknown,
+    /// A single character, spacing glyph.
+    Base,
+    /// A multiple character, spacing glyph.
+    Ligature,
+    /// A non-spacing combining glyph.
+    Mark,
+    /// A part of a single character, spacing glyph.
+    Component,
+}
+
+impl GlyphClass {
+    pub(crate) fn from_class_value(class_value: u16) -> GlyphClass {
+        match class_value {
+            1 => GlyphClass::Base,
+            2 => GlyphClass::Ligature,
+            3 => GlyphClass::Mark,
+            4 => GlyphClass::Component,
+            _ => GlyphClass::Unknown,
+        }
+    }
+}
+
+/// The OpenType layout features of a font, grouped by script and language system.
+///
+/// See `Loader::layout_features()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LayoutFeatures {
+    /// The scripts that the font's `GSUB` and `GPOS` tables declare, sorted by tag.
+    pub scripts: Vec<ScriptFeatures>,
+}
+
+/// The layout features of one script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptFeatures {
+    /// The four-character OpenType script tag (e.g. `latn` or `DFLT`), packed big-endian.
+    pub script_tag: u32,
+    /// The language systems of the script, with the default language system, if any, first and
+    /// the rest sorted by tag.
+    pub language_systems: Vec<LanguageSystemFeatures>,
+}
+
+/// The layout features of one language system of a script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageSystemFeatures {
+    /// The four-character OpenType language system tag (e.g. `TRK `), packed big-endian, or
+    /// `None` for the default language system of the script.
+    pub language_tag: Option<u32>,
+    /// The distinct features of the language system, sorted by tag.
+    pub features: Vec<LayoutFeature>,
+}
+
+/// A layout feature, and the tables it was found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutFeature {
+    /// The four-character feature tag (e.g. `smcp` or `ss01`), packed big-endian.
+    pub tag: u32,
+    /// True if the `GSUB` table has this feature for the language system.
+    pub in_gsub: bool,
+    /// True if the `GPOS` table has this feature for the language system.
+    pub in_gpos: bool,
+}
+
+impl LayoutFeatures {
+    /// Returns true if the font declares no layout features at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Returns the distinct tags of every feature of every script and language system, sorted.
+    pub fn feature_tags(&self) -> Vec<u32> {
+        let mut tags: Vec<u32> = self
+            .scripts
+            .iter()
+            .flat_map(|script| script.language_systems.iter())
+            .flat_map(|language_system| language_system.features.iter())
+            .map(|feature| feature.tag)
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+}
+
+/// Merges the language systems of the given `GSUB` and `GPOS` table data.
+pub(crate) fn layout_features(gsub: Option<&[u8]>, gpos: Option<&[u8]>) -> LayoutFeatures {
+    let mut scripts: BTreeMap<u32, BTreeMap<Option<u32>, BTreeMap<u32, LayoutFeature>>> =
+        BTreeMap::new();
+    for (data, is_gsub) in [(gsub, true), (gpos, false)] {
+        let table = match data.and_then(LayoutTable::parse) {
+            None => continue,
+            Some(table) => table,
+        };
+        for (script_tag, language_tag, feature_tags) in table.language_systems() {
+            let features = scripts
+                .entry(script_tag)
+                .or_default()
+                .entry(language_tag)
+                .or_default();
+            for tag in feature_tags {
+                let feature = features.entry(tag).or_insert(LayoutFeature {
+                    tag,
+                    in_gsub: false,
+                    in_gpos: false,
+                });
+                if is_gsub {
+                    feature.in_gsub = true
+                } else {
+                    feature.in_gpos = true
+                }
+            }
+        }
+    }
+
+    LayoutFeatures {
+        scripts: scripts
+            .into_iter()
+            .map(|(script_tag, language_systems)| ScriptFeatures {
+                script_tag,
+                language_systems: language_systems
+                    .into_iter()
+                    .map(|(language_tag, features)| LanguageSystemFeatures {
+                        language_tag,
+                        features: features.into_values().collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
@@ -86,7 +86,8 @@
 //!
 //! `font-kit` is capable of doing the following:
 //!
-//! * Loading fonts from files or memory.
+//! * Loading fonts from files or memory, including WOFF web fonts (and WOFF2 web fonts with the
+//!   optional `woff2` Cargo feature, which uses the pure-Rust `brotli-decompressor` crate).
 //!
 //! * Determining whether files on disk or in memory represent fonts.
 //!
@@ -94,8 +95,9 @@
 //!
 //! * Querying various metadata about fonts.
 //!
-//! * Doing simple glyph-to-character mapping. (For more complex use cases, a shaper is required;
-//!   proper shaping is beyond the scope of `font-kit`.)
+//! * Doing simple glyph-to-character mapping. (For more complex use cases, a shaper is required.
+//!   The optional `harfbuzz` Cargo feature adds shaping via a copy of HarfBuzz that the
+//!   `harfbuzz-sys` crate builds and bundles.)
 //!
 //! * Reading unhinted or hinted vector outlines from glyphs.
 //!
@@ -122,10 +124,12 @@
 #![warn(missing_debug_implementations)]
 #![warn(missing_copy_implementations)]
 
-#[macro_use]
-extern crate log;
-
+pub mod bitmap;
+pub mod cache;
 pub mod canvas;
+pub mod color;
+pub mod coverage;
+pub mod embedding;
 pub mod error;
 pub mod family;
 pub mod family_handle;
@@ -134,15 +138,39 @@ pub mod file_type;
 pub mod font;
 pub mod handle;
 pub mod hinting;
+pub mod layout;
 pub mod loader;
 pub mod loaders;
+pub mod math;
 pub mod metrics;
+pub mod names;
+pub mod outline;
+pub mod outline_format;
+pub mod panose;
 pub mod properties;
+pub mod script;
+#[cfg(feature = "harfbuzz")]
+pub mod shaping;
+pub mod signature;
 pub mod source;
 pub mod sources;
+pub mod subset;
+pub mod svg_path;
+pub mod synthesis;
+pub mod variations;
 
 #[cfg(test)]
-pub mod test;
+mod test;
 
+mod fingerprint;
+mod float_coverage;
+mod gzip;
 mod matching;
+mod png;
+mod sdf;
+mod sfnt;
+mod tables;
 mod utils;
+mod woff;
+#[cfg(feature = "woff2")]
+mod woff2;
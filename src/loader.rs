@@ -11,24 +11,83 @@
 //! Provides a common interface to the platform-specific API that loads, parses, and rasterizes
 //! fonts.
 
-use euclid::default::{Point2D, Rect, Transform2D, Vector2D};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use flate2::read::GzDecoder;
 use log::warn;
-use lyon_path::builder::PathBuilder;
+use lyon_path::builder::{FlatPathBuilder, PathBuilder};
+use lyon_path::geom;
+use lyon_path::math::{Angle, Point, Vector};
+use std::io::{Cursor, Read};
+use std::marker::PhantomData;
+use std::ops::Deref;
 use std::sync::Arc;
 
-use crate::canvas::{Canvas, RasterizationOptions};
+use crate::bitmap::{self, BitmapGlyph, BitmapSource, BitmapStrike};
+use crate::canvas::{Canvas, Format, RasterizationAdjustments, RasterizationOptions};
+use crate::color::{ColorFormat, ColorLayer, ColorPalette, ColorU, Paint};
+use crate::coverage::CodepointCoverage;
+use crate::embedding::EmbeddingRestrictions;
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
+use crate::fingerprint;
+use crate::float_coverage;
 use crate::handle::Handle;
 use crate::hinting::HintingOptions;
-use crate::metrics::Metrics;
+use crate::layout::{self, GlyphClass, LangTag, LayoutFeatures, ScriptTag};
+use crate::math::{GlyphVariant, MathConstants, StretchDirection};
+use crate::metrics::{GlyphInfo, HorizontalMetrics, MaxpLimits, Metrics};
+use crate::names::{self, NameRecord};
+use crate::outline::{Outline, OutlineBuilder};
+use crate::outline_format::OutlineFormat;
+use crate::panose::Panose;
+use crate::png;
 use crate::properties::Properties;
+use crate::sdf;
+use crate::sfnt;
+#[cfg(feature = "harfbuzz")]
+use crate::shaping::{self, Direction, Feature, Script, ShapedGlyph};
+use crate::signature::SignatureInfo;
+use crate::subset::{self, FontSubset};
+use crate::svg_path::SvgPathBuilder;
+use crate::synthesis::Synthesis;
+use crate::tables;
+use crate::tables::base;
+use crate::tables::cbdt;
+use crate::tables::cblc::{self, CblcTable};
+use crate::tables::cff;
+use crate::tables::cmap::{self, ReverseCmap};
+use crate::tables::colr::{self, ColrTable};
+use crate::tables::cpal::{self, CpalTable};
+use crate::tables::dsig;
+use crate::tables::fvar::{self, FvarTable};
+use crate::tables::gdef::{self, CaretValue};
+use crate::tables::glyf;
+use crate::tables::gpos;
+use crate::tables::gsub;
+use crate::tables::head;
+use crate::tables::hhea;
+use crate::tables::hmtx;
+use crate::tables::kern;
+use crate::tables::math::{self, MathTable};
+use crate::tables::maxp;
+use crate::tables::name::{self, NameTable};
+use crate::tables::os_2;
+use crate::tables::post;
+use crate::tables::sbix::{self, SbixTable};
+use crate::tables::svg;
+use crate::tables::vhea::{self, VheaTable};
+use crate::tables::vmtx;
+use crate::tables::vorg;
+use crate::variations::{NamedInstance, VariationAxis};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::utils;
+
 /// The transform that glyphs will be transformed by.
 #[derive(Debug, Clone, Copy)]
 pub struct FontTransform {
@@ -91,6 +150,10 @@ pub trait Loader: Clone + Sized {
     }
 
     /// Creates a font from a native API handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid, live font object of the underlying platform API.
     unsafe fn from_native_font(native_font: Self::NativeFont) -> Self;
 
     /// Loads the font pointed to by a handle.
@@ -128,7 +191,114 @@ pub trait Loader: Clone + Sized {
         <Self as Loader>::analyze_file(&mut File::open(path)?)
     }
 
-    /// Returns the wrapped native font handle.
+    /// Returns a fingerprint of the font at `font_index` in the given file, for deduplicating
+    /// fonts and keying caches, without loading the font.
+    ///
+    /// The fingerprint combines the CRC-32 of the `head` table, the PostScript name, the size of
+    /// the file, and the font index. It is the same for identical files in every process and on
+    /// every platform, and differs if the font changes in any way that reaches the `head` table,
+    /// which holds the checksum of the whole font. Only the table directory and the `head` and
+    /// `name` tables are read, unless the file is compressed (WOFF, WOFF2, or gzip).
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fingerprint_file(file: &mut File, font_index: u32) -> Result<u64, FontLoadingError> {
+        fingerprint::fingerprint(file, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in the file at the given path, without
+    /// loading the font.
+    ///
+    /// See `fingerprint_file()`.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fingerprint_path<P>(path: P, font_index: u32) -> Result<u64, FontLoadingError>
+    where
+        P: AsRef<Path>,
+    {
+        <Self as Loader>::fingerprint_file(&mut File::open(path)?, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in a blob of raw font data, without
+    /// loading the font.
+    ///
+    /// This agrees with `fingerprint_file()` on the contents of the file. See that function.
+    fn fingerprint_bytes(font_data: &[u8], font_index: u32) -> Result<u64, FontLoadingError> {
+        fingerprint::fingerprint(&mut Cursor::new(font_data), font_index)
+    }
+
+    /// Returns the number of fonts in a blob of raw font data: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    fn count_in_collection_bytes(font_data: Arc<Vec<u8>>) -> Result<u32, FontLoadingError> {
+        match <Self as Loader>::analyze_bytes(font_data)? {
+            FileType::Collection(font_count) => Ok(font_count),
+            FileType::Single | FileType::Woff | FileType::Woff2 => Ok(1),
+        }
+    }
+
+    /// Returns the number of fonts in the file at a path: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn count_in_collection<P>(path: P) -> Result<u32, FontLoadingError>
+    where
+        P: AsRef<Path>,
+    {
+        match <Self as Loader>::analyze_path(path)? {
+            FileType::Collection(font_count) => Ok(font_count),
+            FileType::Single | FileType::Woff | FileType::Woff2 => Ok(1),
+        }
+    }
+
+    /// Returns an iterator that loads each font in a blob of raw font data in turn.
+    ///
+    /// A single font yields just itself; a collection (`.ttc`/`.otc`/etc.) yields each of its
+    /// faces, in order.
+    fn all_in_collection_bytes(
+        font_data: Arc<Vec<u8>>,
+    ) -> Result<CollectionFonts<Self>, FontLoadingError> {
+        let font_count = <Self as Loader>::count_in_collection_bytes(font_data.clone())?;
+        Ok(CollectionFonts {
+            font_data,
+            next_font_index: 0,
+            font_count,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Loads the font with the given PostScript name from a blob of raw font data.
+    ///
+    /// The faces of a collection are loaded in turn until one's PostScript name matches; a single
+    /// font is returned if its own does. Returns `FontLoadingError::NoSuchFontInCollection` if no
+    /// face matches.
+    fn from_bytes_by_postscript_name(
+        font_data: Arc<Vec<u8>>,
+        postscript_name: &str,
+    ) -> Result<Self, FontLoadingError> {
+        for font in <Self as Loader>::all_in_collection_bytes(font_data)? {
+            let font = font?;
+            if font.postscript_name().as_deref() == Some(postscript_name) {
+                return Ok(font);
+            }
+        }
+        Err(FontLoadingError::NoSuchFontInCollection)
+    }
+
+    /// Returns an iterator that loads each font in the file at a path in turn.
+    ///
+    /// A single font yields just itself; a collection (`.ttc`/`.otc`/etc.) yields each of its
+    /// faces, in order.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn all_in_collection<P>(path: P) -> Result<CollectionFonts<Self>, FontLoadingError>
+    where
+        P: AsRef<Path>,
+    {
+        let font_data = Arc::new(utils::slurp_file(&mut File::open(path)?)?);
+        <Self as Loader>::all_in_collection_bytes(font_data)
+    }
+
+    /// Returns the wrapped native font handle: the exact face that this font was loaded as, for
+    /// calling platform APIs that font-kit doesn't wrap.
+    ///
+    /// The returned handle holds its own reference to the native object; see the documentation
+    /// of each loader for who releases it.
     fn native_font(&self) -> Self::NativeFont;
 
     /// Returns the PostScript name of the font. This should be globally unique.
@@ -141,16 +311,51 @@ pub trait Loader: Clone + Sized {
     fn family_name(&self) -> String;
 
     /// Returns true if and only if the font is monospace (fixed-width).
+    ///
+    /// Fonts don't always declare their pitch correctly, so the advances of `i`, `m`, and `W`
+    /// decide when the font has at least two of them. Otherwise the font is monospace if the
+    /// native font API, the `isFixedPitch` field of the `post` table, or the PANOSE proportion
+    /// says so.
     fn is_monospace(&self) -> bool;
 
     /// Returns the values of various font properties, corresponding to those defined in CSS.
+    ///
+    /// For variable fonts, the weight, stretch, and style reflect the current variation
+    /// coordinates of the `wght`, `wdth`, `slnt`, and `ital` axes.
     fn properties(&self) -> Properties;
 
+    /// Returns the slant of the font in degrees counterclockwise from vertical, from the
+    /// `italicAngle` field of its `post` table.
+    ///
+    /// Faces that lean to the right, as most italic and oblique faces do, have negative angles.
+    /// Fonts without a `post` table are reported as upright, with an angle of 0.
+    fn italic_angle(&self) -> f32 {
+        self.load_font_table(post::TAG)
+            .and_then(|data| post::italic_angle(&data))
+            .unwrap_or(0.0)
+    }
+
     /// Returns the number of glyphs in the font.
     ///
     /// Glyph IDs range from 0 inclusive to this value exclusive.
     fn glyph_count(&self) -> u32;
 
+    /// Returns an iterator over every glyph of the font, in order of glyph ID, with its metrics.
+    ///
+    /// The metrics of each glyph are loaded as the iterator reaches it, so stopping early is
+    /// cheap. Glyph names come from the `post` table, which is read up front.
+    fn glyphs(&self) -> Glyphs<'_, Self> {
+        Glyphs {
+            font: self,
+            names: self
+                .load_font_table(post::TAG)
+                .and_then(|data| post::glyph_names(&data))
+                .unwrap_or_default(),
+            next_glyph_id: 0,
+            glyph_count: self.glyph_count(),
+        }
+    }
+
     /// Returns the usual glyph ID for a Unicode character.
     ///
     /// Be careful with this function; typographically correct character-to-glyph mapping must be
@@ -158,11 +363,59 @@ pub trait Loader: Clone + Sized {
     /// use cases like "what does character X look like on its own".
     fn glyph_for_char(&self, character: char) -> Option<u32>;
 
+    /// Returns the glyph ID for a Unicode character followed by a variation selector, such as
+    /// U+FE0E or U+FE0F for the text and emoji presentations or U+E0100 and up for ideographic
+    /// variants.
+    ///
+    /// The variation sequences come from the format 14 subtable of the `cmap`. If the font has no
+    /// specific glyph for the sequence, this returns the usual glyph for the character, like
+    /// `glyph_for_char()`.
+    fn glyph_for_char_with_variation(
+        &self,
+        character: char,
+        variation_selector: char,
+    ) -> Option<u32> {
+        self.load_font_table(cmap::TAG)
+            .and_then(|cmap| {
+                cmap::variation_glyph(&cmap, character as u32, variation_selector as u32)
+            })
+            .or_else(|| self.glyph_for_char(character))
+    }
+
     /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// Fonts whose `post` table is format 3 carry no glyph names. For these, names that identify
+    /// a character by convention are resolved through the `cmap` instead: the names of the
+    /// standard Macintosh character set, such as `A` or `eacute`, and the Adobe Glyph List's
+    /// `uniXXXX` and `uXXXX` forms.
+    ///
+    /// The default implementation supports only these synthesized names.
     #[inline]
-    fn glyph_by_name(&self, _name: &str) -> Option<u32> {
-        warn!("unimplemented");
-        None
+    fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        glyph_by_synthesized_name(self, name)
+    }
+
+    /// Returns the name of a glyph, the reverse of `glyph_by_name()`.
+    ///
+    /// Names come from the `post` table: format 1 tables give the names of the standard Macintosh
+    /// character set, and format 2 tables list their own. Glyphs of fonts with format 3 tables
+    /// get names synthesized from the character that they map to, which `glyph_by_name()`
+    /// resolves back to them: the standard Macintosh name if there is one and `uniXXXX` or
+    /// `uXXXXX` otherwise, with `.notdef` for glyph 0. Glyphs that map to no character have no
+    /// name then.
+    ///
+    /// The default implementation supports only `post` table names and these synthesized ones.
+    fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        if glyph_id >= self.glyph_count() {
+            return None;
+        }
+        match self
+            .load_font_table(post::TAG)
+            .and_then(|data| post::glyph_names(&data))
+        {
+            Some(names) => names.into_iter().nth(glyph_id as usize),
+            None => synthesized_glyph_name(self, glyph_id),
+        }
     }
 
     /// Sends the vector path for a glyph to a path builder.
@@ -170,6 +423,16 @@ pub trait Loader: Clone + Sized {
     /// If `hinting_mode` is not None, this function performs grid-fitting as requested before
     /// sending the hinding outlines to the builder.
     ///
+    /// The outlines of variable fonts, whether TrueType or CFF2, reflect the variations set with
+    /// `set_variations()`.
+    ///
+    /// Contours are sent in the direction that the font stores them, in font units with y
+    /// pointing up. The two outline formats disagree: the outer contours of TrueType (`glyf`)
+    /// glyphs run clockwise and their holes counterclockwise, while CFF and CFF2 glyphs run the
+    /// other way around. Either way, holes run opposite to the contours that enclose them, so
+    /// the nonzero fill rule renders both correctly; only code that depends on the direction
+    /// itself, such as an outline stroker, needs to check `outline_format()`.
+    ///
     /// TODO(pcwalton): What should we do for bitmap glyphs?
     fn outline<B>(
         &self,
@@ -180,20 +443,189 @@ pub trait Loader: Clone + Sized {
     where
         B: PathBuilder;
 
+    /// Returns the vector path for a glyph, in font units, as a `lyon_path` path.
+    ///
+    /// This is a shortcut for sending the outline to the builder of a new path; see `outline()`.
+    fn outline_path(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<lyon_path::Path, GlyphLoadingError> {
+        let mut path_builder = lyon_path::Path::builder();
+        self.outline(glyph_id, hinting_mode, &mut path_builder)?;
+        Ok(path_builder.build())
+    }
+
+    /// Returns the vector outline of a glyph, in font units, as an owned `Outline`.
+    ///
+    /// This is the outline that `outline()` would send to a path builder, collected so that it
+    /// can be cached or transformed; `Outline::copy_to()` sends it on to a path builder later.
+    fn glyph_outline(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<Outline, GlyphLoadingError> {
+        let mut outline_builder = OutlineBuilder::new();
+        self.outline(glyph_id, hinting_mode, &mut outline_builder)?;
+        Ok(outline_builder.into_outline())
+    }
+
+    /// Sends the vector path for a glyph to a path builder, after transforming it from font units
+    /// by `transform`.
+    ///
+    /// This is useful for scaling outlines to pixels, rotating them, or skewing them to make a
+    /// synthetic oblique. Any hinting happens first, at the point size that `hinting_mode`
+    /// specifies; to hint for the size the outline will be drawn at, that should be the size that
+    /// `transform` scales to.
+    fn outline_transformed<B>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        transform: &Transform2D<f32>,
+        path_builder: &mut B,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        B: PathBuilder,
+    {
+        let mut path_builder = TransformingPathBuilder {
+            path_builder,
+            transform: *transform,
+            current_position: Point::zero(),
+        };
+        self.outline(glyph_id, hinting_mode, &mut path_builder)
+    }
+
+    /// Returns the unhinted outline of a glyph as the `d` attribute of an SVG `path` element.
+    ///
+    /// The outline is scaled to `point_size` and flipped so that y points down, as it does in
+    /// SVG. The glyph origin is at (0, 0), so the part of the glyph above the baseline has
+    /// negative y coordinates. See `SvgPathBuilder` to write other outlines as SVG paths.
+    fn glyph_svg_path(&self, glyph_id: u32, point_size: f32) -> Result<String, GlyphLoadingError> {
+        let scale = point_size / self.metrics().units_per_em as f32;
+        let mut path_builder = SvgPathBuilder::new();
+        self.outline_transformed(
+            glyph_id,
+            HintingOptions::None,
+            &Transform2D::create_scale(scale, -scale),
+            &mut path_builder,
+        )?;
+        Ok(path_builder.into_string())
+    }
+
     /// Returns the boundaries of a glyph in font units. The origin of the coordinate
     /// space is at the bottom left.
     fn typographic_bounds(&self, glyph_id: u32) -> Result<Rect<f32>, GlyphLoadingError>;
 
+    /// Returns the boundaries of each of the given glyphs in font units, in order.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`. The default
+    /// implementation calls `typographic_bounds()` once per glyph; loaders whose platform can
+    /// measure many glyphs in one call override it.
+    fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        glyph_ids
+            .iter()
+            .map(|&glyph_id| self.typographic_bounds(glyph_id))
+            .collect()
+    }
+
     /// Returns the distance from the origin of the glyph with the given ID to the next, in font
     /// units.
     fn advance(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError>;
 
+    /// Returns the advances of each of the given glyphs in font units, in order, as `advance()`
+    /// does.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`. The default
+    /// implementation calls `advance()` once per glyph; loaders whose platform can measure many
+    /// glyphs in one call override it.
+    fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        glyph_ids
+            .iter()
+            .map(|&glyph_id| self.advance(glyph_id))
+            .collect()
+    }
+
+    /// Returns the advance of the glyph with the given ID in font units, as recorded in the
+    /// font's `hmtx` table, independent of any point size.
+    ///
+    /// The table is read directly, so this is the integral design advance, and it doesn't reflect
+    /// variations applied with `set_variations()`. Fonts without `hhea` and `hmtx` tables fall
+    /// back to `advance()`.
+    fn advance_font_units(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        if glyph_id >= self.glyph_count() {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        let advance = self.load_font_table(hhea::TAG).and_then(|hhea| {
+            let long_metric_count = hhea::long_metric_count(&hhea)?;
+            let hmtx = self.load_font_table(hmtx::TAG)?;
+            hmtx::metric(&hmtx, long_metric_count, glyph_id)
+        });
+        match advance {
+            Some((advance, _)) => Ok(Vector2D::new(advance as f32, 0.0)),
+            None => self.advance(glyph_id),
+        }
+    }
+
+    /// Returns the advance width and left side bearing of the given glyph, in font units, as
+    /// the font's `hmtx` table records them.
+    ///
+    /// Unlike the left edge of `typographic_bounds()`, the left side bearing is read directly, so
+    /// it matches what layout engines that use `hmtx` see. Like `advance_font_units()`, this
+    /// doesn't reflect variations applied with `set_variations()`. Fonts without `hhea` and
+    /// `hmtx` tables yield `GlyphLoadingError::UnsupportedFormat`.
+    fn horizontal_metrics(&self, glyph_id: u32) -> Result<HorizontalMetrics, GlyphLoadingError> {
+        if glyph_id >= self.glyph_count() {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        let hhea = self
+            .load_font_table(hhea::TAG)
+            .ok_or(GlyphLoadingError::UnsupportedFormat)?;
+        let hmtx = self
+            .load_font_table(hmtx::TAG)
+            .ok_or(GlyphLoadingError::UnsupportedFormat)?;
+        let long_metric_count =
+            hhea::long_metric_count(&hhea).ok_or(GlyphLoadingError::MalformedFont)?;
+        let (advance_width, left_side_bearing) = hmtx::metric(&hmtx, long_metric_count, glyph_id)
+            .ok_or(GlyphLoadingError::MalformedFont)?;
+        Ok(HorizontalMetrics {
+            advance_width: advance_width as f32,
+            left_side_bearing: left_side_bearing as f32,
+        })
+    }
+
     /// Returns the amount that the given glyph should be displaced from the origin.
     fn origin(&self, glyph_id: u32) -> Result<Point2D<f32>, GlyphLoadingError>;
 
     /// Retrieves various metrics that apply to the entire font.
     fn metrics(&self) -> Metrics;
 
+    /// Returns the number of font units per em, from the `head` table.
+    ///
+    /// This is always the same as the `units_per_em` field of `metrics()`, but it's cheaper to
+    /// look up on its own, which matters when converting many advances or bounds to pixels.
+    fn units_per_em(&self) -> u32 {
+        match self
+            .load_font_table(head::TAG)
+            .and_then(|table| head::units_per_em(&table))
+        {
+            Some(units_per_em) => units_per_em as u32,
+            None => self.metrics().units_per_em,
+        }
+    }
+
+    /// Returns the limits that the font's TrueType outlines stay within, from its `maxp` table,
+    /// so that buffers for loading glyphs can be allocated up front.
+    ///
+    /// The limits are as the font records them; they aren't checked against its glyphs. Returns
+    /// `None` for fonts without TrueType outlines, whose `maxp` tables have no limits.
+    fn maxp_limits(&self) -> Option<MaxpLimits> {
+        self.load_font_table(maxp::TAG)
+            .and_then(|table| maxp::limits(&table))
+    }
+
     /// Returns a handle to this font, if possible.
     ///
     /// This is useful if you want to open the font with a different loader.
@@ -209,6 +641,15 @@ pub trait Loader: Clone + Sized {
     /// collection.
     fn copy_font_data(&self) -> Option<Arc<Vec<u8>>>;
 
+    /// Returns the raw font data without copying it, if it's available.
+    ///
+    /// Data held in memory is shared with the font, and data that the font maps from a file is
+    /// borrowed from the mapping. As with `copy_font_data()`, members of a collection return the
+    /// data for the entire collection.
+    fn font_data(&self) -> Option<FontData<'_>> {
+        self.copy_font_data().map(FontData::Memory)
+    }
+
     /// Returns true if and only if the font loader can perform hinting in the requested way.
     ///
     /// Some APIs support only rasterizing glyphs with hinting, not retriving hinted outlines. If
@@ -221,35 +662,92 @@ pub trait Loader: Clone + Sized {
         for_rasterization: bool,
     ) -> bool;
 
+    /// Returns true if the font carries hints for its outlines: TrueType instructions, in its
+    /// `fpgm` or `prep` table or in any glyph, or the stem hints of CFF charstrings.
+    ///
+    /// Unlike `supports_hinting_options()`, which says what the loader can do, this says whether
+    /// the font gives it anything to work with. Fonts without hints are grid-fitted better by an
+    /// autohinter (`HintingOptions::Autohint`) than by their own non-existent instructions.
+    fn has_hinting_instructions(&self) -> bool {
+        let has_table = |tag| {
+            self.load_font_table(tag)
+                .is_some_and(|data| !data.is_empty())
+        };
+        if has_table(glyf::FPGM_TAG) || has_table(glyf::PREP_TAG) {
+            return true;
+        }
+        if let (Some(head), Some(loca), Some(glyf)) = (
+            self.load_font_table(glyf::HEAD_TAG),
+            self.load_font_table(glyf::LOCA_TAG),
+            self.load_font_table(glyf::TAG),
+        ) {
+            return (0..self.glyph_count()).any(|glyph_id| {
+                glyf::glyph_data(&head, &loca, &glyf, glyph_id)
+                    .and_then(glyf::instructions)
+                    .is_some_and(|instructions| !instructions.is_empty())
+            });
+        }
+        self.load_font_table(cff::TAG)
+            .is_some_and(|data| cff::has_stem_hints(&data))
+    }
+
     /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
     /// rasterizer at the given `point_size`, `transform` and `origin`. `origin` is not transformed
     /// by `transform`. The origin of the coordinate space is at the top left.
+    ///
+    /// The bounds are computed from the glyph's outline or metrics without rasterizing it, so
+    /// they are cheap enough to query for every glyph of an atlas. They are conservative: canvases
+    /// of this size always hold all of what `rasterize_glyph()` draws, but may leave a pixel or
+    /// two empty around it.
+    ///
+    /// This is `raster_bounds_with_adjustments()` with the default adjustments.
+    #[inline]
     fn raster_bounds(
         &self,
         glyph_id: u32,
         point_size: f32,
         transform: &FontTransform,
         origin: &Point2D<f32>,
-        _: HintingOptions,
-        _: RasterizationOptions,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
     ) -> Result<Rect<i32>, GlyphLoadingError> {
-        let typographic_bounds = self.typographic_bounds(glyph_id)?;
-        let mut typographic_raster_bounds =
-            typographic_bounds * point_size / self.metrics().units_per_em as f32;
-        typographic_raster_bounds.origin.y =
-            -typographic_raster_bounds.origin.y - typographic_raster_bounds.size.height;
-        let transform: Transform2D<f32> = Transform2D::column_major(
-            transform.scale_x,
-            transform.skew_x,
-            origin.x,
-            transform.skew_y,
-            transform.scale_y,
-            origin.y,
-        );
-        Ok(transform
-            .transform_rect(&typographic_raster_bounds)
-            .round_out()
-            .to_i32())
+        self.raster_bounds_with_adjustments(
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            RasterizationAdjustments::default(),
+        )
+    }
+
+    /// Returns the pixel boundaries that the glyph will take up when rendered as
+    /// `rasterize_glyph_with_adjustments()` renders it.
+    ///
+    /// The default implementation transforms the glyph's typographic bounds, adding a pixel on
+    /// each side when hinting or stem darkening may move its edges.
+    #[allow(clippy::too_many_arguments)]
+    fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        typographic_raster_bounds(
+            self,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )
     }
 
     /// Rasterizes a glyph to a canvas with the given size and origin.
@@ -262,6 +760,10 @@ pub trait Loader: Clone + Sized {
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
     /// `origin` is not transformed by `transform`.
+    ///
+    /// This is `rasterize_glyph_with_adjustments()` with the default adjustments.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
     fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -271,8 +773,300 @@ pub trait Loader: Clone + Sized {
         origin: &Point2D<f32>,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_with_adjustments(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            RasterizationAdjustments::default(),
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, with the given adjustments to
+    /// its coverage.
+    ///
+    /// Bilevel rendering with a `bilevel_threshold` thresholds hinted grayscale coverage, so the
+    /// result is the same on every loader.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyph_with_adjustments(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
     ) -> Result<(), GlyphLoadingError>;
 
+    /// Returns how to make this font look like a face with the given properties, by slanting or
+    /// emboldening it, when the family lacks that face.
+    ///
+    /// This compares the properties against the font's own with `Synthesis::new()`. Pass the
+    /// result to `rasterize_glyph_with_synthesis()` to apply it.
+    #[inline]
+    fn synthesis(&self, properties: &Properties) -> Synthesis {
+        Synthesis::new(&self.properties(), properties)
+    }
+
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, but slanted and emboldened as
+    /// `synthesis` says.
+    ///
+    /// Emboldening strikes the glyph several times, up to `synthesis.embolden_offset(point_size)`
+    /// pixels to the right, so pixels need to be added to the right of the raster bounds to fit
+    /// it. The bounds should be computed with `synthesis.apply_to_font_transform(transform)`.
+    /// Coverage is combined with what the canvas already contains by taking the maximum.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyph_with_synthesis(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        synthesis: &Synthesis,
+    ) -> Result<(), GlyphLoadingError> {
+        let transform = synthesis.apply_to_font_transform(transform);
+        let offset = synthesis.embolden_offset(point_size);
+
+        // Strike at least every pixel along the way, so that the strokes widen without gaps.
+        let strike_count = offset.ceil() as u32 + 1;
+        let mut strike = Canvas::with_stride(&canvas.size, canvas.stride, canvas.format);
+        for strike_index in 0..strike_count {
+            let x_offset = if strike_count > 1 {
+                offset * strike_index as f32 / (strike_count - 1) as f32
+            } else {
+                0.0
+            };
+            strike.pixels.iter_mut().for_each(|pixel| *pixel = 0);
+            self.rasterize_glyph(
+                &mut strike,
+                glyph_id,
+                point_size,
+                &transform,
+                &Point2D::new(origin.x + x_offset, origin.y),
+                hinting_options,
+                rasterization_options,
+            )?;
+            for (dest, &src) in canvas.pixels.iter_mut().zip(strike.pixels.iter()) {
+                *dest = (*dest).max(src);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rasterizes a line of text to a canvas, laying its glyphs out from left to right.
+    ///
+    /// `origin` is the position of the start of the baseline on the canvas. Each character is
+    /// mapped to a glyph with `glyph_for_char()`, or to glyph 0 if the font has none for it, and
+    /// the pen moves by the glyph's advance plus any `kerning()` with the next glyph. Glyphs are
+    /// rasterized at their exact pen positions, so subpixel positioning is preserved, and parts
+    /// that fall outside the canvas are clipped. Coverage is combined with what the canvas already
+    /// contains by taking the maximum.
+    ///
+    /// This does no shaping: ligatures, marks, and right-to-left text need `shape()`.
+    fn rasterize_string(
+        &self,
+        canvas: &mut Canvas,
+        text: &str,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        let scale = point_size / self.metrics().units_per_em as f32;
+        let transform = FontTransform::identity();
+        let canvas_rect = Rect::new(Point2D::zero(), canvas.size.to_i32());
+        let bytes_per_pixel = canvas.format.bytes_per_pixel() as usize;
+        let mut pen = *origin;
+        let mut previous_glyph = None;
+        for character in text.chars() {
+            let glyph_id = self.glyph_for_char(character).unwrap_or(0);
+            if let Some(previous_glyph) = previous_glyph {
+                // Kerning points y up, and the canvas points it down.
+                let kerning = self.kerning(previous_glyph, glyph_id, point_size);
+                pen += Vector2D::new(kerning.x, -kerning.y);
+            }
+            previous_glyph = Some(glyph_id);
+
+            let bounds = self.raster_bounds(
+                glyph_id,
+                point_size,
+                &transform,
+                &pen,
+                hinting_options,
+                rasterization_options,
+            )?;
+            // Rasterize each glyph on its own, so that it can be merged without overwriting its
+            // neighbors where they overlap.
+            let clipped_bounds = bounds.intersection(&canvas_rect);
+            if let Some(clipped_bounds) = clipped_bounds.filter(|bounds| !bounds.is_empty()) {
+                let mut glyph_canvas = Canvas::new(&bounds.size.to_u32(), canvas.format);
+                self.rasterize_glyph(
+                    &mut glyph_canvas,
+                    glyph_id,
+                    point_size,
+                    &transform,
+                    &(pen - bounds.origin.to_f32().to_vector()),
+                    hinting_options,
+                    rasterization_options,
+                )?;
+                let row_length = clipped_bounds.size.width as usize * bytes_per_pixel;
+                for y in clipped_bounds.min_y()..clipped_bounds.max_y() {
+                    let src_start = (y - bounds.origin.y) as usize * glyph_canvas.stride
+                        + (clipped_bounds.origin.x - bounds.origin.x) as usize * bytes_per_pixel;
+                    let dest_start = y as usize * canvas.stride
+                        + clipped_bounds.origin.x as usize * bytes_per_pixel;
+                    let src = &glyph_canvas.pixels[src_start..(src_start + row_length)];
+                    let dest = &mut canvas.pixels[dest_start..(dest_start + row_length)];
+                    for (dest, &src) in dest.iter_mut().zip(src.iter()) {
+                        *dest = (*dest).max(src);
+                    }
+                }
+            }
+
+            pen.x += self.advance(glyph_id)?.x * scale;
+        }
+        Ok(())
+    }
+
+    /// Rasterizes a single-channel signed distance field of a glyph to a canvas, for rendering
+    /// text on the GPU.
+    ///
+    /// `origin` is the position of the glyph origin on the canvas, as for `rasterize_glyph()`.
+    /// Each pixel stores the distance from its center to the nearest edge of the unhinted
+    /// outline, mapped so that 128 lies on the edge, values above it lie inside the glyph, and
+    /// distances of `spread` pixels or more saturate at 0 or 255. Rasterizing every glyph with
+    /// the same `spread` makes a shader's edge threshold apply to all of them alike.
+    ///
+    /// To fit the whole field, the canvas needs `spread` pixels of room around the glyph's
+    /// raster bounds. The canvas should be `A8`; an `Rgb24` canvas gets the same distances in all
+    /// three channels.
+    fn rasterize_glyph_sdf(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        spread: f32,
+    ) -> Result<(), GlyphLoadingError> {
+        sdf::rasterize_glyph_sdf(self, canvas, glyph_id, point_size, origin, spread)
+    }
+
+    /// Rasterizes a glyph into floating-point coverage values in [0, 1], one per pixel of a
+    /// buffer of the given size, row by row with no padding.
+    ///
+    /// This takes the same arguments as `rasterize_glyph()`, but rather than asking the platform
+    /// rasterizer for 8-bit coverage, it computes the exact area of each pixel that the glyph's
+    /// outline covers, so that blending and gamma correction can happen at full precision. Only
+    /// grayscale antialiasing is available. Hinting is applied if the loader supports hinted
+    /// outlines; see `supports_hinting_options()`.
+    fn rasterize_glyph_f32(
+        &self,
+        size: &Size2D<u32>,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<Vec<f32>, GlyphLoadingError> {
+        float_coverage::rasterize_glyph_f32(
+            self,
+            size,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Rasterizes a color glyph to an `Rgba32` canvas with the given size and origin.
+    ///
+    /// If the font has embedded color bitmaps (`sbix` or `CBDT`), the strike closest in size to
+    /// the requested size (preferring larger strikes) is scaled to fit. Otherwise the glyph's
+    /// `COLR` layers are composited bottommost first, using palette 0. Layers that use the text
+    /// color, and glyphs in `COLR` fonts that have no layers, are drawn in opaque black.
+    ///
+    /// The result is composited over the existing contents of the canvas in premultiplied
+    /// R8G8B8A8 format; see `Format::Rgba32`. Returns `GlyphLoadingError::NoColorData` if the
+    /// font has no color data at all.
+    ///
+    /// If `hinting_options` is not None, the requested grid fitting is performed on the outlines
+    /// of `COLR` layers. `origin` is not transformed by `transform`.
+    ///
+    /// Panics if the canvas format isn't `Format::Rgba32`.
+    fn rasterize_color_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        assert_eq!(
+            canvas.format,
+            Format::Rgba32,
+            "Color glyphs need an RGBA canvas!"
+        );
+        if glyph_id >= self.glyph_count() || glyph_id > u16::MAX as u32 {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+
+        let pixel_transform = Transform2D::column_major(
+            transform.scale_x,
+            transform.skew_x,
+            origin.x,
+            transform.skew_y,
+            transform.scale_y,
+            origin.y,
+        );
+        let pixel_size = point_size * pixel_transform.determinant().abs().sqrt();
+        if let Some((ppem, glyph, image)) = load_color_bitmap(self, glyph_id, pixel_size) {
+            let scale = point_size / ppem;
+            let offset = glyph.offset.to_f32();
+            let image_transform = Transform2D::create_translation(offset.x, offset.y)
+                .post_scale(scale, scale)
+                .post_transform(&pixel_transform);
+            canvas.composite_image(&image.pixels, &image.size, &image_transform);
+            return Ok(());
+        }
+
+        let mut layers = self.glyph_color_layers(glyph_id, 0)?;
+        if layers.is_empty() {
+            layers.push(ColorLayer {
+                glyph_id,
+                color: None,
+            });
+        }
+        let mut mask = Canvas::new(&canvas.size, Format::A8);
+        for layer in layers {
+            for pixel in &mut mask.pixels {
+                *pixel = 0;
+            }
+            self.rasterize_glyph(
+                &mut mask,
+                layer.glyph_id,
+                point_size,
+                transform,
+                origin,
+                hinting_options,
+                RasterizationOptions::GrayscaleAa,
+            )?;
+            let color = layer.color.unwrap_or_else(|| ColorU::new(0, 0, 0, 0xff));
+            canvas.composite_color_with_mask(color, &mask);
+        }
+        Ok(())
+    }
+
     /// Get font fallback results for the given text and locale.
     ///
     /// The `locale` argument is a language tag such as `"en-US"` or `"zh-Hans-CN"`.
@@ -280,6 +1074,900 @@ pub trait Loader: Clone + Sized {
 
     /// Returns the OpenType font table with the given tag, if the table exists.
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>>;
+
+    /// Returns the tags of the tables in the font's sfnt table directory, in directory order.
+    ///
+    /// This doesn't load any of the tables. Formats without a table directory, like PCF, have no
+    /// tags.
+    fn table_tags(&self) -> Vec<u32> {
+        self.font_data()
+            .and_then(|font_data| sfnt::table_tags(&font_data, 0))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the font has a table with the given tag, without loading it.
+    fn contains_table(&self, table_tag: u32) -> bool {
+        self.table_tags().contains(&table_tag)
+    }
+
+    /// Returns the format of the font's glyph outlines, from the tables present.
+    ///
+    /// Fonts with both TrueType and CFF outlines, which are invalid, are reported as TrueType,
+    /// and fonts with bitmaps as well as outlines are reported by their outlines.
+    fn outline_format(&self) -> OutlineFormat {
+        let table_tags = self.table_tags();
+        let has_table = |tag: u32| has_table(self, &table_tags, tag);
+        if has_table(glyf::TAG) {
+            OutlineFormat::TrueType
+        } else if has_table(cff::TAG) {
+            OutlineFormat::Cff
+        } else if has_table(cff::CFF2_TAG) {
+            OutlineFormat::Cff2
+        } else if [cbdt::TAG, cbdt::EBDT_TAG, sbix::TAG]
+            .iter()
+            .any(|&tag| has_table(tag))
+        {
+            OutlineFormat::Bitmap
+        } else {
+            OutlineFormat::None
+        }
+    }
+
+    /// Returns the color glyph technologies that the font carries, from the tables present.
+    ///
+    /// Only the `COLR` table is loaded, to tell its version 0 layers from its version 1 paints.
+    /// A font with none of them is monochrome; see `ColorFormat::is_monochrome()`.
+    fn color_format(&self) -> ColorFormat {
+        let table_tags = self.table_tags();
+        let has_table = |tag: u32| has_table(self, &table_tags, tag);
+        let colr_table = if has_table(colr::TAG) {
+            self.load_font_table(colr::TAG)
+        } else {
+            None
+        };
+        let colr_table = colr_table.as_ref().and_then(|data| ColrTable::parse(data));
+        ColorFormat {
+            colr_v0: colr_table.is_some_and(|table| table.has_version_0_glyphs()),
+            colr_v1: colr_table.is_some_and(|table| table.has_version_1_glyphs()),
+            svg: has_table(svg::TAG),
+            sbix: has_table(sbix::TAG),
+            cbdt: has_table(cbdt::TAG) && has_table(cblc::TAG),
+        }
+    }
+
+    /// Returns true if the font is a variable font, that is, if it has an `fvar` table.
+    ///
+    /// This is cheaper than checking whether `variation_axes()` is empty, since the table isn't
+    /// parsed.
+    fn is_variable(&self) -> bool {
+        self.load_font_table(fvar::TAG).is_some()
+    }
+
+    /// Returns the design axes of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    fn variation_axes(&self) -> Vec<VariationAxis> {
+        let fvar_table = match self
+            .load_font_table(fvar::TAG)
+            .and_then(|data| FvarTable::parse(&data))
+        {
+            None => return vec![],
+            Some(fvar_table) => fvar_table,
+        };
+        let name_table = self
+            .load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data));
+        fvar_table
+            .axes
+            .iter()
+            .map(|axis| VariationAxis {
+                tag: axis.tag,
+                min_value: axis.min_value,
+                default_value: axis.default_value,
+                max_value: axis.max_value,
+                hidden: axis.hidden,
+                name: name_table
+                    .as_ref()
+                    .and_then(|name_table| name_table.english_name(axis.name_id))
+                    .unwrap_or_else(|| tables::tag_to_string(axis.tag)),
+            })
+            .collect()
+    }
+
+    /// Returns the named instances of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    fn named_instances(&self) -> Vec<NamedInstance> {
+        let fvar_table = match self
+            .load_font_table(fvar::TAG)
+            .and_then(|data| FvarTable::parse(&data))
+        {
+            None => return vec![],
+            Some(fvar_table) => fvar_table,
+        };
+        let name_table = self
+            .load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data));
+        let lookup_name = |name_id| {
+            name_table
+                .as_ref()
+                .and_then(|name_table| name_table.english_name(name_id))
+        };
+        fvar_table
+            .instances
+            .iter()
+            .map(|instance| NamedInstance {
+                subfamily_name: lookup_name(instance.subfamily_name_id).unwrap_or_default(),
+                coordinates: fvar_table
+                    .axes
+                    .iter()
+                    .zip(instance.coordinates.iter())
+                    .map(|(axis, &value)| (axis.tag, value))
+                    .collect(),
+                postscript_name: instance.postscript_name_id.and_then(lookup_name),
+            })
+            .collect()
+    }
+
+    /// Instantiates a variable font at the given point in its design space.
+    ///
+    /// `coords` maps axis tags to values. Values outside the range of an axis are clamped to it,
+    /// and axes that aren't mentioned are left at their default values. Subsequent outlines,
+    /// metrics, and rasterization reflect the chosen instance.
+    ///
+    /// This has no effect on fonts that aren't variable fonts.
+    fn set_variations(&mut self, coords: &[(u32, f32)]);
+
+    /// Returns the current position of a variable font in its design space, as one `(tag, value)`
+    /// pair per axis, in axis order.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    fn variations(&self) -> Vec<(u32, f32)>;
+
+    /// Returns the layers that make up the given color glyph, as declared in the font's `COLR`
+    /// table, with colors taken from the `CPAL` palette at `palette_index`.
+    ///
+    /// Layers are returned bottommost first. If the glyph isn't a color glyph, this returns an
+    /// empty vector. If the palette doesn't exist, palette 0 is used instead, as CSS does.
+    ///
+    /// Returns `GlyphLoadingError::NoColorData` if the font has no `COLR` or `CPAL` table.
+    fn glyph_color_layers(
+        &self,
+        glyph_id: u32,
+        palette_index: u16,
+    ) -> Result<Vec<ColorLayer>, GlyphLoadingError> {
+        let (colr_data, cpal_data) = match (
+            self.load_font_table(colr::TAG),
+            self.load_font_table(cpal::TAG),
+        ) {
+            (Some(colr_data), Some(cpal_data)) => (colr_data, cpal_data),
+            _ => return Err(GlyphLoadingError::NoColorData),
+        };
+        let (colr_table, cpal_table) =
+            match (ColrTable::parse(&colr_data), CpalTable::parse(&cpal_data)) {
+                (Some(colr_table), Some(cpal_table)) => (colr_table, cpal_table),
+                _ => return Err(GlyphLoadingError::NoColorData),
+            };
+        if glyph_id >= self.glyph_count() || glyph_id > u16::MAX as u32 {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+
+        let palette_index = if palette_index < cpal_table.num_palettes {
+            palette_index
+        } else {
+            0
+        };
+        let layers = match colr_table.layers(glyph_id as u16) {
+            None => {
+                warn!("malformed `COLR` table");
+                return Ok(vec![]);
+            }
+            Some(layers) => layers,
+        };
+        Ok(layers
+            .iter()
+            .map(|layer| ColorLayer {
+                glyph_id: layer.glyph_id as u32,
+                color: match layer.palette_index {
+                    colr::FOREGROUND_PALETTE_INDEX => None,
+                    entry_index => cpal_table.color(palette_index, entry_index),
+                },
+            })
+            .collect())
+    }
+
+    /// Returns the paint graph of the given `COLR` version 1 color glyph, with colors taken from
+    /// the `CPAL` palette at `palette_index`.
+    ///
+    /// Unlike `glyph_color_layers()`, this keeps the gradients, transforms, and compositing of
+    /// version 1 glyphs; `Paint::ColrGlyph` nodes that reuse other glyphs' paints are resolved.
+    /// If the palette doesn't exist, palette 0 is used instead, as CSS does.
+    ///
+    /// Returns `None` if the font has no `COLR` or `CPAL` table, if the glyph isn't a version 1
+    /// color glyph, or if its paints are malformed.
+    fn colrv1_paint(&self, glyph_id: u32, palette_index: u16) -> Option<Paint> {
+        if glyph_id > u16::MAX as u32 {
+            return None;
+        }
+        let (colr_data, cpal_data) = (
+            self.load_font_table(colr::TAG)?,
+            self.load_font_table(cpal::TAG)?,
+        );
+        let (colr_table, cpal_table) =
+            (ColrTable::parse(&colr_data)?, CpalTable::parse(&cpal_data)?);
+
+        let palette_index = if palette_index < cpal_table.num_palettes {
+            palette_index
+        } else {
+            0
+        };
+        let color = |entry_index: u16| match entry_index {
+            colr::FOREGROUND_PALETTE_INDEX => None,
+            entry_index => cpal_table.color(palette_index, entry_index),
+        };
+        colr_table.paint(glyph_id as u16, &color)
+    }
+
+    /// Returns the color palettes in the font's `CPAL` table, in order of palette index.
+    ///
+    /// Version 1 tables may also say which palettes suit light and dark backgrounds and label
+    /// palettes and their entries; the labels are looked up as English names in the `name` table.
+    /// If the font has no `CPAL` table, this returns an empty vector.
+    fn color_palettes(&self) -> Vec<ColorPalette> {
+        let cpal_data = match self.load_font_table(cpal::TAG) {
+            None => return vec![],
+            Some(cpal_data) => cpal_data,
+        };
+        let cpal_table = match CpalTable::parse(&cpal_data) {
+            None => {
+                warn!("malformed `CPAL` table");
+                return vec![];
+            }
+            Some(cpal_table) => cpal_table,
+        };
+        let name_table = self
+            .load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data));
+        let label = |name_id: Option<u16>| {
+            name_table
+                .as_ref()
+                .and_then(|name_table| name_table.english_name(name_id?))
+        };
+
+        let entry_names: Vec<_> = (0..cpal_table.num_palette_entries)
+            .map(|entry_index| label(cpal_table.palette_entry_label(entry_index)))
+            .collect();
+        (0..cpal_table.num_palettes)
+            .map(|palette_index| {
+                let (usable_with_light_background, usable_with_dark_background) =
+                    cpal_table.palette_type(palette_index);
+                ColorPalette {
+                    // Colors that are missing from a malformed table are left transparent.
+                    colors: (0..cpal_table.num_palette_entries)
+                        .map(|entry_index| {
+                            cpal_table
+                                .color(palette_index, entry_index)
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                    usable_with_light_background,
+                    usable_with_dark_background,
+                    name: label(cpal_table.palette_label(palette_index)),
+                    entry_names: entry_names.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the strikes of pre-rendered bitmap glyphs that the font contains, from its `sbix`,
+    /// `CBLC`/`CBDT`, and `EBLC`/`EBDT` tables.
+    ///
+    /// If the font has no embedded bitmaps, this returns an empty vector.
+    fn bitmap_strikes(&self) -> Vec<BitmapStrike> {
+        let mut strikes = vec![];
+        if let Some(sbix_table) = self
+            .load_font_table(sbix::TAG)
+            .and_then(|data| SbixTable::parse(&data, self.glyph_count()).map(|table| table.strikes))
+        {
+            strikes.extend(
+                sbix_table
+                    .iter()
+                    .enumerate()
+                    .map(|(index, strike)| BitmapStrike {
+                        pixels_per_em: strike.ppem,
+                        source: BitmapSource::Sbix,
+                        index,
+                    }),
+            );
+        }
+        for &(tag, source) in &[
+            (cblc::TAG, BitmapSource::Cbdt),
+            (cblc::EBLC_TAG, BitmapSource::Ebdt),
+        ] {
+            if let Some(sizes) = self
+                .load_font_table(tag)
+                .and_then(|data| CblcTable::parse(&data).map(|table| table.sizes))
+            {
+                strikes.extend(sizes.iter().enumerate().map(|(index, size)| BitmapStrike {
+                    pixels_per_em: size.ppem_y as u16,
+                    source,
+                    index,
+                }));
+            }
+        }
+        strikes
+    }
+
+    /// Returns the bitmap strike that best matches the given size, in pixels per em, or `None`
+    /// if the font has no embedded bitmaps.
+    ///
+    /// The size is rounded to whole pixels. A strike of exactly that size is preferred, then the
+    /// smallest larger strike, which can be scaled down without losing detail, then the largest
+    /// smaller strike. `BitmapStrike::needs_scaling()` says whether the glyphs of the result have
+    /// to be scaled, so that callers who want crisp bitmaps can rasterize outlines instead.
+    fn best_strike_for_size(&self, pixels_per_em: f32) -> Option<BitmapStrike> {
+        let mut strikes = self.bitmap_strikes();
+        bitmap::sort_by_preference(&mut strikes, pixels_per_em);
+        strikes.first().cloned()
+    }
+
+    /// Loads the pre-rendered bitmap of a glyph from one of the strikes returned by
+    /// `bitmap_strikes()`.
+    ///
+    /// PNG images are returned undecoded. Returns `None` if the strike has no bitmap for the
+    /// glyph, or if the bitmap is in an unsupported format.
+    fn load_bitmap_glyph(
+        &self,
+        glyph_id: u32,
+        strike: &BitmapStrike,
+    ) -> Result<Option<BitmapGlyph>, GlyphLoadingError> {
+        if glyph_id >= self.glyph_count() || glyph_id > u16::MAX as u32 {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        let glyph_id = glyph_id as u16;
+        let (location_tag, data_tag) = match strike.source {
+            BitmapSource::Sbix => {
+                return Ok(self.load_font_table(sbix::TAG).and_then(|data| {
+                    bitmap::load_sbix_glyph(&data, self.glyph_count(), strike.index, glyph_id)
+                }));
+            }
+            BitmapSource::Cbdt => (cblc::TAG, cbdt::TAG),
+            BitmapSource::Ebdt => (cblc::EBLC_TAG, cbdt::EBDT_TAG),
+        };
+        match (
+            self.load_font_table(location_tag),
+            self.load_font_table(data_tag),
+        ) {
+            (Some(location_data), Some(bitmap_data)) => Ok(bitmap::load_cbdt_glyph(
+                &location_data,
+                &bitmap_data,
+                strike.source,
+                strike.index,
+                glyph_id,
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the SVG document from the font's `SVG ` table that describes the given glyph.
+    ///
+    /// One document may describe several glyphs, in which case the glyph's element has the ID
+    /// `glyph<glyph_id>`. Compressed documents are decompressed. Returns `None` if the font has no
+    /// `SVG ` table, or if the table has no document for the glyph.
+    fn glyph_svg_document(&self, glyph_id: u32) -> Result<Option<Vec<u8>>, GlyphLoadingError> {
+        if glyph_id >= self.glyph_count() || glyph_id > u16::MAX as u32 {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        let svg_data = match self.load_font_table(svg::TAG) {
+            None => return Ok(None),
+            Some(svg_data) => svg_data,
+        };
+        let document = match svg::document(&svg_data, glyph_id as u16) {
+            None => return Ok(None),
+            Some(document) => document,
+        };
+        if !document.starts_with(&svg::GZIP_MAGIC) {
+            return Ok(Some(document.to_vec()));
+        }
+        let mut decompressed = vec![];
+        match GzDecoder::new(document).read_to_end(&mut decompressed) {
+            Ok(_) => Ok(Some(decompressed)),
+            Err(_) => {
+                warn!("malformed compressed SVG document");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the kerning adjustment between the given pair of glyphs, in pixels at the given
+    /// point size.
+    ///
+    /// This is the amount to add to the advance of `left_glyph` when it is followed by
+    /// `right_glyph`. The pair positioning lookups of the `GPOS` table's `kern` feature are used
+    /// if the font has them, and format 0 subtables of the legacy `kern` table otherwise. The y
+    /// component is nonzero only for fonts that adjust vertical advances, and points up.
+    ///
+    /// If the font doesn't kern the pair, this returns a zero vector.
+    fn kerning(&self, left_glyph: u32, right_glyph: u32, point_size: f32) -> Vector2D<f32> {
+        if left_glyph > u16::MAX as u32 || right_glyph > u16::MAX as u32 {
+            return Vector2D::zero();
+        }
+        let (left_glyph, right_glyph) = (left_glyph as u16, right_glyph as u16);
+        let kerning = match self
+            .load_font_table(gpos::TAG)
+            .and_then(|data| gpos::kerning(&data, left_glyph, right_glyph))
+        {
+            Some(value_record) => {
+                Vector2D::new(value_record.x_advance as f32, value_record.y_advance as f32)
+            }
+            None => {
+                match self
+                    .load_font_table(kern::TAG)
+                    .and_then(|data| kern::kerning(&data, left_glyph, right_glyph))
+                {
+                    Some(value) => Vector2D::new(value as f32, 0.0),
+                    None => Vector2D::zero(),
+                }
+            }
+        };
+        kerning * point_size / self.metrics().units_per_em as f32
+    }
+
+    /// Applies the ligature substitutions (`GSUB` lookup type 4) of the given OpenType features
+    /// to a run of glyphs, returning the substituted run.
+    ///
+    /// `features` holds feature tags, such as `u32::from_be_bytes(*b"dlig")`. If it's empty, the
+    /// `liga` and `clig` features are used. Lookups are applied in the order they appear in the
+    /// font, and glyphs that no ligature matches are left untouched. Other lookup types, and
+    /// lookup flags such as "ignore marks", aren't supported.
+    fn substitute_ligatures(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        let mut glyphs = glyphs.to_vec();
+        let features = if features.is_empty() {
+            &gsub::DEFAULT_LIGATURE_FEATURES[..]
+        } else {
+            features
+        };
+        if let Some(data) = self.load_font_table(gsub::TAG) {
+            gsub::substitute_ligatures(&data, &mut glyphs, features);
+        }
+        glyphs
+    }
+
+    /// Applies the contextual and chaining contextual substitutions (`GSUB` lookup types 5 and
+    /// 6) of the given OpenType features to a run of glyphs, returning the substituted run.
+    ///
+    /// `features` holds feature tags, such as `u32::from_be_bytes(*b"calt")`. If it's empty, the
+    /// `rlig`, `calt`, and `clig` features are used. Each rule, whether it matches glyphs, glyph
+    /// classes, or coverage tables, applies the single, multiple, alternate, ligature, or
+    /// contextual lookups that it names to the glyphs it matched; alternate substitutions pick
+    /// the first alternate. Lookups are applied in the order they appear in the font, and
+    /// lookups of other types that the features reference directly are skipped, so e.g. the
+    /// Arabic `init`, `medi`, and `fina` forms still have to be chosen with
+    /// `feature_affects()`. Lookup flags such as "ignore marks" aren't supported.
+    fn apply_contextual_substitutions(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        let mut glyphs = glyphs.to_vec();
+        let features = if features.is_empty() {
+            &gsub::DEFAULT_CONTEXTUAL_FEATURES[..]
+        } else {
+            features
+        };
+        if let Some(data) = self.load_font_table(gsub::TAG) {
+            gsub::substitute_contextually(&data, &mut glyphs, features);
+        }
+        glyphs
+    }
+
+    /// Returns the `(input, output)` glyph pairs that the single and alternate substitutions
+    /// (`GSUB` lookup types 1 and 3) of the given OpenType feature can produce, sorted by input
+    /// glyph.
+    ///
+    /// `feature` is a feature tag, such as `u32::from_be_bytes(*b"smcp")`. This is useful for
+    /// previewing what turning on a feature would do. Every alternate of an alternate
+    /// substitution is listed, the feature is looked up across all scripts and language systems,
+    /// and substitutions of other types, such as ligatures and contextual substitutions, aren't
+    /// included.
+    fn feature_affects(&self, feature: u32) -> Vec<(u32, u32)> {
+        match self.load_font_table(gsub::TAG) {
+            Some(data) => gsub::feature_substitutions(&data, feature),
+            None => vec![],
+        }
+    }
+
+    /// Returns the alternates of the given glyph, for glyph pickers, in the order the font lists
+    /// them.
+    ///
+    /// The alternates are those that the `aalt` ("access all alternates") feature substitutes for
+    /// the glyph, through single or alternate substitutions (`GSUB` lookup types 1 and 3). Fonts
+    /// without an `aalt` feature yield the alternates of all of their alternate substitution
+    /// lookups instead. Glyphs without alternates yield an empty vector.
+    fn glyph_alternates(&self, glyph_id: u32) -> Vec<u32> {
+        match self.load_font_table(gsub::TAG) {
+            Some(data) => gsub::glyph_alternates(&data, glyph_id),
+            None => vec![],
+        }
+    }
+
+    /// Returns whether the given glyph is a base glyph, a ligature, a mark, or a component of a
+    /// glyph, from the `GlyphClassDef` of the `GDEF` table.
+    ///
+    /// Fonts without a `GDEF` table, or that don't classify the glyph, yield
+    /// `GlyphClass::Unknown`.
+    fn glyph_class(&self, glyph_id: u32) -> GlyphClass {
+        if glyph_id > u16::MAX as u32 {
+            return GlyphClass::Unknown;
+        }
+        let class_value = self
+            .load_font_table(gdef::TAG)
+            .map_or(0, |data| gdef::glyph_class(&data, glyph_id as u16));
+        GlyphClass::from_class_value(class_value)
+    }
+
+    /// Returns the mark attachment class of the given glyph, from the `MarkAttachClassDef` of the
+    /// `GDEF` table.
+    ///
+    /// Lookups whose flags name a mark attachment type skip the marks of other classes. Glyphs
+    /// that aren't marks, or fonts that don't classify them, have class 0.
+    fn mark_attach_class(&self, glyph_id: u32) -> u16 {
+        if glyph_id > u16::MAX as u32 {
+            return 0;
+        }
+        self.load_font_table(gdef::TAG)
+            .map_or(0, |data| gdef::mark_attach_class(&data, glyph_id as u16))
+    }
+
+    /// Returns the caret positions within the given ligature glyph, in font units from its
+    /// origin, for placing a cursor between the characters that it stands for.
+    ///
+    /// These come from the `LigCaretList` of the `GDEF` table. Carets given as a point on the
+    /// glyph's outline are resolved to that point's unhinted x coordinate; the rare ones that
+    /// can't be, because the point isn't in the `glyf` table, are left out. Glyphs that aren't
+    /// ligatures yield an empty vector.
+    fn ligature_carets(&self, glyph_id: u32) -> Vec<f32> {
+        if glyph_id > u16::MAX as u32 {
+            return vec![];
+        }
+        let carets = match self
+            .load_font_table(gdef::TAG)
+            .and_then(|data| gdef::ligature_carets(&data, glyph_id as u16))
+        {
+            None => return vec![],
+            Some(carets) => carets,
+        };
+        carets
+            .into_iter()
+            .filter_map(|caret| match caret {
+                CaretValue::Coordinate(x) => Some(x as f32),
+                CaretValue::ContourPoint(point_index) => {
+                    let head = self.load_font_table(glyf::HEAD_TAG)?;
+                    let loca = self.load_font_table(glyf::LOCA_TAG)?;
+                    let glyf = self.load_font_table(glyf::TAG)?;
+                    let glyph = glyf::glyph_data(&head, &loca, &glyf, glyph_id)?;
+                    glyf::point_x(glyph, point_index).map(|x| x as f32)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the OpenType layout features that the font's `GSUB` and `GPOS` tables declare,
+    /// grouped by script and language system.
+    ///
+    /// Each feature notes which of the two tables it was found in. This only describes the
+    /// features; it doesn't check whether font-kit or the shaper can apply their lookups.
+    fn layout_features(&self) -> LayoutFeatures {
+        let gsub = self.load_font_table(gsub::TAG);
+        let gpos = self.load_font_table(gpos::TAG);
+        layout::layout_features(gsub.as_deref(), gpos.as_deref())
+    }
+
+    /// Returns the OpenType script tags that the font's `GSUB` and `GPOS` tables declare, sorted.
+    ///
+    /// The `DFLT` script, which isn't a writing system, is left out. Fonts without layout tables
+    /// return an empty list.
+    fn supported_scripts(&self) -> Vec<ScriptTag> {
+        self.layout_features()
+            .scripts
+            .iter()
+            .map(|script| ScriptTag(script.script_tag))
+            .filter(|&script| script != ScriptTag::DEFAULT)
+            .collect()
+    }
+
+    /// Returns the OpenType language system tags that the font's `GSUB` and `GPOS` tables
+    /// declare for the given script, sorted.
+    ///
+    /// The script's default language system has no tag and so isn't listed. Fonts that don't
+    /// declare the script return an empty list.
+    fn supported_languages(&self, script: ScriptTag) -> Vec<LangTag> {
+        self.layout_features()
+            .scripts
+            .iter()
+            .filter(|script_features| script_features.script_tag == script.0)
+            .flat_map(|script_features| script_features.language_systems.iter())
+            .filter_map(|language_system| language_system.language_tag.map(LangTag))
+            .collect()
+    }
+
+    /// Returns the position of the baseline with the given tag, such as `romn`, `ideo`, or
+    /// `hang` packed big-endian, for the given script in horizontal text, in font units above
+    /// the alphabetic baseline that glyphs are drawn on.
+    ///
+    /// The positions come from the `BASE` table, using its `DFLT` script for scripts it doesn't
+    /// list. Fonts without the table, or without the baseline, return `None`; callers would
+    /// usually fall back to the alphabetic baseline, at 0.
+    fn baseline(&self, baseline_tag: u32, script: ScriptTag) -> Option<f32> {
+        self.load_font_table(base::TAG)
+            .and_then(|data| base::baseline(&data, baseline_tag, script.0))
+            .map(|coordinate| coordinate as f32)
+    }
+
+    /// Shapes a run of text with HarfBuzz, returning its glyphs in visual order with their
+    /// positions in font units.
+    ///
+    /// The text should be a single run of one script and direction; splitting text into runs
+    /// (bidi and script itemization) is left to the caller. `features` are applied on top of the
+    /// shaper's defaults for the script. If the font is a variable font, the instance selected by
+    /// `set_variations()` is shaped.
+    ///
+    /// This method is only available with the `harfbuzz` Cargo feature.
+    #[cfg(feature = "harfbuzz")]
+    fn shape(
+        &self,
+        text: &str,
+        direction: Direction,
+        script: Script,
+        features: &[Feature],
+    ) -> Vec<ShapedGlyph> {
+        shaping::shape(self, text, direction, script, features)
+    }
+
+    /// Returns the set of Unicode codepoints that this font maps to glyphs, read from the best
+    /// Unicode subtable of its `cmap` table.
+    ///
+    /// A format 12 subtable is preferred, since it can cover every plane of Unicode; otherwise a
+    /// format 4 subtable, covering only the Basic Multilingual Plane, is used. Fonts without a
+    /// Unicode `cmap` subtable of either format report no coverage.
+    fn codepoint_coverage(&self) -> CodepointCoverage {
+        self.load_font_table(cmap::TAG)
+            .and_then(|data| cmap::codepoint_ranges(&data))
+            .map(CodepointCoverage::from_ranges)
+            .unwrap_or_default()
+    }
+
+    /// Returns the glyph ID for each character of the given string, in order, or `None` for
+    /// characters that the font has no glyph for.
+    ///
+    /// This is equivalent to calling `glyph_for_char()` on each character, but backends can
+    /// implement it with a single native call.
+    fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        text.chars()
+            .map(|character| self.glyph_for_char(character))
+            .collect()
+    }
+
+    /// Returns the characters that the font's best Unicode `cmap` subtable maps to the given
+    /// glyph, in codepoint order.
+    ///
+    /// Several characters can map to the same glyph, so this can return more than one. Glyphs
+    /// that are only reachable through shaping, such as ligatures, map to no characters.
+    ///
+    /// The default implementation inverts the `cmap` table on every call; the built-in loaders
+    /// build the inverse once and cache it on the font.
+    fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        let data = self.load_font_table(cmap::TAG).unwrap_or_default();
+        ReverseCmap::parse(&data).chars_for_glyph(glyph_id)
+    }
+
+    /// Builds a new font containing only the given glyphs, along with glyph 0 (`.notdef`) and
+    /// the components of any composite glyphs among them.
+    ///
+    /// The glyphs are renumbered; the returned `FontSubset` records the mapping. The outlines,
+    /// metrics, and character mappings of the kept glyphs carry over, as do the naming and
+    /// hinting tables. Layout, color, bitmap, and variation tables are dropped.
+    ///
+    /// Only fonts with TrueType (`glyf`) or CFF outlines can be subset; others yield
+    /// `GlyphLoadingError::UnsupportedFormat`.
+    fn subset(&self, glyph_ids: &[u32]) -> Result<FontSubset, GlyphLoadingError> {
+        subset::subset(self, glyph_ids)
+    }
+
+    /// Returns the restrictions that the font's license places on embedding it in documents, as
+    /// recorded in the `fsType` field of its `OS/2` table.
+    ///
+    /// Fonts without an `OS/2` table are reported as installable, with no restrictions.
+    fn embedding_restrictions(&self) -> EmbeddingRestrictions {
+        self.load_font_table(os_2::TAG)
+            .and_then(|table| os_2::fs_type(&table))
+            .map(EmbeddingRestrictions::from_fs_type)
+            .unwrap_or_default()
+    }
+
+    /// Returns the flags and signature formats of the font's `DSIG` table, or `None` if it has no
+    /// such table.
+    ///
+    /// A placeholder table with no signatures is still returned; use `has_digital_signature()` to
+    /// check that the font is actually signed. The signatures aren't verified.
+    fn digital_signature(&self) -> Option<SignatureInfo> {
+        self.load_font_table(dsig::TAG)
+            .and_then(|data| dsig::signature_info(&data))
+    }
+
+    /// Returns true if the font's `DSIG` table holds at least one signature.
+    fn has_digital_signature(&self) -> bool {
+        self.digital_signature()
+            .is_some_and(|signature_info| signature_info.signature_count() > 0)
+    }
+
+    /// Returns every record of the font's `name` table, in every language, in table order.
+    ///
+    /// UTF-16BE strings (the Unicode and Windows platforms) and Mac Roman strings are decoded;
+    /// records in other encodings are left out. Fonts without a `name` table return an empty list.
+    fn name_records(&self) -> Vec<NameRecord> {
+        let name_table = match self
+            .load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data))
+        {
+            None => return vec![],
+            Some(name_table) => name_table,
+        };
+        name_table
+            .records
+            .into_iter()
+            .filter_map(|record| {
+                Some(NameRecord {
+                    name_id: record.name_id,
+                    platform_id: record.platform_id,
+                    encoding_id: record.encoding_id,
+                    language_id: record.language_id,
+                    string: record.string?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the family name of the font in the language of a BCP 47 locale, such as `ja` or
+    /// `zh-Hant`.
+    ///
+    /// The typographic family name (`name` ID 16) is preferred to the legacy family name (ID 1).
+    /// If the font has neither in the requested language, the English name is returned, and
+    /// failing that, the first one available. Returns `None` only if the font has no `name` table
+    /// or no family name records.
+    fn localized_family_name(&self, locale: &str) -> Option<String> {
+        let name_table = self
+            .load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data))?;
+        let name_ids = [name::TYPOGRAPHIC_FAMILY_NAME_ID, name::FAMILY_NAME_ID];
+        name_ids
+            .iter()
+            .find_map(|&name_id| name_table.localized_name(name_id, locale))
+            .or_else(|| {
+                name_ids
+                    .iter()
+                    .find_map(|&name_id| name_table.localized_name(name_id, "en"))
+            })
+            .or_else(|| {
+                name_ids
+                    .iter()
+                    .find_map(|&name_id| name_table.english_name(name_id))
+            })
+    }
+
+    /// Returns the PANOSE classification of the font from its `OS/2` table, or `None` if it has
+    /// no such table.
+    ///
+    /// Many fonts leave every digit at 0 ("any"); this is returned as is.
+    fn panose(&self) -> Option<Panose> {
+        self.load_font_table(os_2::TAG)
+            .and_then(|table| os_2::panose(&table))
+            .map(Panose::from_bytes)
+    }
+
+    /// Returns the four-character tag of the font's vendor, from the `achVendID` field of its
+    /// `OS/2` table, or `None` if it has no such table.
+    ///
+    /// Tags shorter than four characters are padded with spaces. Vendors are registered with
+    /// Microsoft, but fonts often leave the field blank (all spaces or zeros); this is returned as
+    /// is.
+    fn vendor_id(&self) -> Option<[u8; 4]> {
+        self.load_font_table(os_2::TAG)
+            .and_then(|table| os_2::vendor_id(&table))
+    }
+
+    /// Returns the version string of the font (`name` ID 5), such as `Version 1.000`, in
+    /// English if possible, or `None` if the font has no such record.
+    fn version_string(&self) -> Option<String> {
+        self.load_font_table(name::TAG)
+            .and_then(|data| NameTable::parse(&data))
+            .and_then(|name_table| name_table.english_name(names::VERSION))
+    }
+
+    /// Returns the distance that the pen moves down after drawing the given glyph in vertical
+    /// layout, in font units, from the `vmtx` table.
+    ///
+    /// Fonts without `vhea` and `vmtx` tables yield `GlyphLoadingError::NoVerticalMetrics`.
+    fn vertical_advance(&self, glyph_id: u32) -> Result<f32, GlyphLoadingError> {
+        vertical_metric(self, glyph_id).map(|(advance, _)| advance as f32)
+    }
+
+    /// Returns the vertical origin of the given glyph: the point in the glyph's own coordinate
+    /// space, in font units, that is placed on the pen position in vertical layout.
+    ///
+    /// The x coordinate is half the glyph's horizontal advance. The y coordinate comes from the
+    /// `VORG` table if there is one, and otherwise from the glyph's top side bearing in `vmtx`,
+    /// measured up from the top of its bounding box. Fonts with neither fall back to the ascent.
+    fn vertical_origin(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        let x = self.advance(glyph_id)?.x / 2.0;
+        let vert_origin_y = self
+            .load_font_table(vorg::TAG)
+            .and_then(|table| vorg::vert_origin_y(&table, glyph_id));
+        if let Some(y) = vert_origin_y {
+            return Ok(Vector2D::new(x, y as f32));
+        }
+        match vertical_metric(self, glyph_id) {
+            Ok((_, top_side_bearing)) => {
+                let bounds = self.typographic_bounds(glyph_id)?;
+                Ok(Vector2D::new(x, bounds.max_y() + top_side_bearing as f32))
+            }
+            Err(GlyphLoadingError::NoVerticalMetrics) => {
+                Ok(Vector2D::new(x, self.metrics().ascent))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the math layout constants of the font from its `MATH` table, in font units, or
+    /// `None` if the font has no such table.
+    fn math_constants(&self) -> Option<MathConstants> {
+        let data = self.load_font_table(math::TAG)?;
+        MathTable::parse(&data)?.constants()
+    }
+
+    /// Returns the prebuilt variants of a glyph for stretching in the given direction, from
+    /// smallest to largest, as listed in the font's `MATH` table.
+    ///
+    /// The glyph itself is usually the first variant. Glyph assemblies, which build arbitrarily
+    /// large glyphs out of parts, aren't included. If the font has no `MATH` table or no variants
+    /// of the glyph, this returns an empty vector.
+    fn math_glyph_variants(&self, glyph_id: u32, direction: StretchDirection) -> Vec<GlyphVariant> {
+        if glyph_id > u16::MAX as u32 {
+            return vec![];
+        }
+        self.load_font_table(math::TAG)
+            .and_then(|data| MathTable::parse(&data)?.glyph_variants(glyph_id as u16, direction))
+            .unwrap_or_default()
+    }
+}
+
+/// The raw data of a font, as returned by `Loader::font_data()`.
+///
+/// This dereferences to the bytes of the font file.
+#[derive(Clone, Debug)]
+pub enum FontData<'a> {
+    /// Data in memory, shared with the font.
+    Memory(Arc<Vec<u8>>),
+    /// Data borrowed from storage that the font owns, such as a memory-mapped file.
+    Borrowed(&'a [u8]),
+}
+
+impl<'a> FontData<'a> {
+    /// Returns the data as an owned buffer, copying it only if it's borrowed.
+    pub fn into_owned(self) -> Arc<Vec<u8>> {
+        match self {
+            FontData::Memory(data) => data,
+            FontData::Borrowed(data) => Arc::new(data.to_vec()),
+        }
+    }
+}
+
+impl<'a> Deref for FontData<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match *self {
+            FontData::Memory(ref data) => data,
+            FontData::Borrowed(data) => data,
+        }
+    }
 }
 
 /// The result of a fallback query.
@@ -300,3 +1988,373 @@ pub struct FallbackFont<Font> {
     pub scale: f32,
     // TODO: add font simulation data
 }
+
+/// An iterator over the fonts in a font collection, returned by `Loader::all_in_collection()`.
+///
+/// All the fonts share the same underlying font data.
+#[derive(Clone, Debug)]
+pub struct CollectionFonts<L> {
+    font_data: Arc<Vec<u8>>,
+    next_font_index: u32,
+    font_count: u32,
+    phantom: PhantomData<L>,
+}
+
+impl<L> Iterator for CollectionFonts<L>
+where
+    L: Loader,
+{
+    type Item = Result<L, FontLoadingError>;
+
+    fn next(&mut self) -> Option<Result<L, FontLoadingError>> {
+        if self.next_font_index >= self.font_count {
+            return None;
+        }
+        let font_index = self.next_font_index;
+        self.next_font_index += 1;
+        Some(L::from_bytes(self.font_data.clone(), font_index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.font_count - self.next_font_index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<L> ExactSizeIterator for CollectionFonts<L> where L: Loader {}
+
+/// An iterator over the glyphs of a font and their metrics, returned by `Loader::glyphs()`.
+#[derive(Clone, Debug)]
+pub struct Glyphs<'a, L> {
+    font: &'a L,
+    names: Vec<String>,
+    next_glyph_id: u32,
+    glyph_count: u32,
+}
+
+impl<'a, L> Iterator for Glyphs<'a, L>
+where
+    L: Loader,
+{
+    type Item = Result<GlyphInfo, GlyphLoadingError>;
+
+    fn next(&mut self) -> Option<Result<GlyphInfo, GlyphLoadingError>> {
+        if self.next_glyph_id >= self.glyph_count {
+            return None;
+        }
+        let glyph_id = self.next_glyph_id;
+        self.next_glyph_id += 1;
+        let glyph_info = self.font.advance(glyph_id).and_then(|advance| {
+            Ok(GlyphInfo {
+                glyph_id,
+                advance,
+                typographic_bounds: self.font.typographic_bounds(glyph_id)?,
+                name: self.names.get(glyph_id as usize).cloned(),
+            })
+        });
+        Some(glyph_info)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.glyph_count - self.next_glyph_id) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, L> ExactSizeIterator for Glyphs<'a, L> where L: Loader {}
+
+/// Synthesizes a name for a glyph from the character that it maps to, for fonts whose `post`
+/// table doesn't name their glyphs. See `Loader::glyph_name()`.
+pub(crate) fn synthesized_glyph_name<L>(loader: &L, glyph_id: u32) -> Option<String>
+where
+    L: Loader,
+{
+    if let Some(data) = loader.load_font_table(post::TAG) {
+        if post::has_glyph_names(&data) {
+            return None;
+        }
+    }
+    if glyph_id == 0 {
+        return Some(".notdef".to_owned());
+    }
+    let character = *loader.chars_for_glyph(glyph_id).first()?;
+    Some(post::glyph_name_for_char(character))
+}
+
+/// Looks up a glyph by a name synthesized from the character that it maps to, for fonts whose
+/// `post` table doesn't name their glyphs. See `Loader::glyph_by_name()`.
+pub(crate) fn glyph_by_synthesized_name<L>(loader: &L, name: &str) -> Option<u32>
+where
+    L: Loader,
+{
+    if let Some(data) = loader.load_font_table(post::TAG) {
+        if post::has_glyph_names(&data) {
+            return None;
+        }
+    }
+    if name == ".notdef" {
+        return Some(0);
+    }
+    loader.glyph_for_char(post::char_for_glyph_name(name)?)
+}
+
+/// Returns the raster bounds of a glyph computed from its typographic bounds. See
+/// `Loader::raster_bounds_with_adjustments()`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn typographic_raster_bounds<L>(
+    loader: &L,
+    glyph_id: u32,
+    point_size: f32,
+    transform: &FontTransform,
+    origin: &Point2D<f32>,
+    hinting_options: HintingOptions,
+    rasterization_options: RasterizationOptions,
+    rasterization_adjustments: RasterizationAdjustments,
+) -> Result<Rect<i32>, GlyphLoadingError>
+where
+    L: Loader,
+{
+    let typographic_bounds = loader.typographic_bounds(glyph_id)?;
+    let mut typographic_raster_bounds =
+        typographic_bounds * point_size / loader.metrics().units_per_em as f32;
+    typographic_raster_bounds.origin.y =
+        -typographic_raster_bounds.origin.y - typographic_raster_bounds.size.height;
+    let transform: Transform2D<f32> = Transform2D::column_major(
+        transform.scale_x,
+        transform.skew_x,
+        origin.x,
+        transform.skew_y,
+        transform.scale_y,
+        origin.y,
+    );
+    let raster_bounds = transform
+        .transform_rect(&typographic_raster_bounds)
+        .round_out()
+        .to_i32();
+    // Grid fitting and stem darkening can each move the edges of the glyph by up to a pixel,
+    // and subpixel filtering spreads coverage into the pixels on either side of the glyph.
+    let (mut dx, mut dy) = (0, 0);
+    if hinting_options != HintingOptions::None || rasterization_adjustments.stem_darkening {
+        dx += 1;
+        dy += 1;
+    }
+    if rasterization_options == RasterizationOptions::SubpixelAa {
+        dx += 1;
+    }
+    Ok(raster_bounds.inflate(dx, dy))
+}
+
+/// Rasterizes a glyph with bilevel rendering by thresholding grayscale coverage, for rasterization
+/// adjustments that set `bilevel_threshold`. See `Loader::rasterize_glyph_with_adjustments()`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn rasterize_glyph_with_bilevel_threshold<L>(
+    loader: &L,
+    canvas: &mut Canvas,
+    glyph_id: u32,
+    point_size: f32,
+    transform: &FontTransform,
+    origin: &Point2D<f32>,
+    hinting_options: HintingOptions,
+    rasterization_adjustments: RasterizationAdjustments,
+    threshold: u8,
+) -> Result<(), GlyphLoadingError>
+where
+    L: Loader,
+{
+    let grayscale_adjustments = RasterizationAdjustments {
+        gamma: 1.0,
+        bilevel_threshold: None,
+        ..rasterization_adjustments
+    };
+    let raster_bounds = loader.raster_bounds_with_adjustments(
+        glyph_id,
+        point_size,
+        transform,
+        origin,
+        hinting_options,
+        RasterizationOptions::GrayscaleAa,
+        grayscale_adjustments,
+    )?;
+    // Only the part of the glyph that lands on the canvas needs to be rasterized.
+    let canvas_rect = Rect::new(Point2D::zero(), canvas.size.to_i32());
+    let bounds = match raster_bounds.intersection(&canvas_rect) {
+        Some(bounds) if !bounds.is_empty() => bounds,
+        _ => return Ok(()),
+    };
+
+    let mut coverage = Canvas::new(&bounds.size.to_u32(), Format::A8);
+    loader.rasterize_glyph_with_adjustments(
+        &mut coverage,
+        glyph_id,
+        point_size,
+        transform,
+        &(*origin - bounds.origin.to_f32().to_vector()),
+        hinting_options,
+        RasterizationOptions::GrayscaleAa,
+        grayscale_adjustments,
+    )?;
+    for value in &mut coverage.pixels {
+        *value = if *value >= threshold { 255 } else { 0 };
+    }
+    canvas.blit_from(
+        bounds.origin,
+        &coverage.pixels,
+        &coverage.size,
+        coverage.stride,
+        Format::A8,
+    );
+    Ok(())
+}
+
+/// Returns true if the font has a table with the given tag, according to its table directory.
+///
+/// Fonts whose data is unavailable have no table directory to consult, so their tables have to be
+/// loaded instead.
+fn has_table<L>(loader: &L, table_tags: &[u32], tag: u32) -> bool
+where
+    L: Loader,
+{
+    if table_tags.is_empty() {
+        loader.load_font_table(tag).is_some()
+    } else {
+        table_tags.contains(&tag)
+    }
+}
+
+/// Decides whether a font is monospace, given whether the native font API says that it is. See
+/// `Loader::is_monospace()`.
+pub(crate) fn is_monospace<L>(loader: &L, natively_monospace: bool) -> bool
+where
+    L: Loader,
+{
+    let advances: Vec<f32> = ['i', 'm', 'W']
+        .iter()
+        .filter_map(|&character| loader.glyph_for_char(character))
+        .filter_map(|glyph_id| loader.advance(glyph_id).ok())
+        .map(|advance| advance.x)
+        .filter(|&advance| advance > 0.0)
+        .collect();
+    if advances.len() >= 2 {
+        return advances.iter().all(|&advance| advance == advances[0]);
+    }
+
+    natively_monospace
+        || loader
+            .load_font_table(post::TAG)
+            .and_then(|data| post::is_fixed_pitch(&data))
+            .unwrap_or(false)
+        || loader.panose().is_some_and(|panose| panose.is_monospaced())
+}
+
+/// Returns the advance height and top side bearing of a glyph from the `vmtx` table.
+fn vertical_metric<L>(loader: &L, glyph_id: u32) -> Result<(u16, i16), GlyphLoadingError>
+where
+    L: Loader,
+{
+    if glyph_id >= loader.glyph_count() {
+        return Err(GlyphLoadingError::NoSuchGlyph);
+    }
+    let vhea = loader
+        .load_font_table(vhea::TAG)
+        .and_then(|table| VheaTable::parse(&table))
+        .ok_or(GlyphLoadingError::NoVerticalMetrics)?;
+    let vmtx = loader
+        .load_font_table(vmtx::TAG)
+        .ok_or(GlyphLoadingError::NoVerticalMetrics)?;
+    vmtx::metric(&vmtx, vhea.long_metric_count, glyph_id)
+        .ok_or(GlyphLoadingError::NoVerticalMetrics)
+}
+
+/// A path builder that transforms points before sending them on to another path builder.
+struct TransformingPathBuilder<'a, B> {
+    path_builder: &'a mut B,
+    transform: Transform2D<f32>,
+    /// The current position, before transformation.
+    current_position: Point,
+}
+
+impl<'a, B> FlatPathBuilder for TransformingPathBuilder<'a, B>
+where
+    B: PathBuilder,
+{
+    fn move_to(&mut self, to: Point) {
+        self.path_builder
+            .move_to(self.transform.transform_point(to));
+        self.current_position = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.path_builder
+            .line_to(self.transform.transform_point(to));
+        self.current_position = to;
+    }
+
+    fn close(&mut self) {
+        self.path_builder.close();
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
+}
+
+impl<'a, B> PathBuilder for TransformingPathBuilder<'a, B>
+where
+    B: PathBuilder,
+{
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.path_builder.quadratic_bezier_to(
+            self.transform.transform_point(ctrl),
+            self.transform.transform_point(to),
+        );
+        self.current_position = to;
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.path_builder.cubic_bezier_to(
+            self.transform.transform_point(ctrl1),
+            self.transform.transform_point(ctrl2),
+            self.transform.transform_point(to),
+        );
+        self.current_position = to;
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        // An arc may not stay elliptical under an arbitrary transform, so send it on as curves.
+        let start_angle = (self.current_position - center).angle_from_x_axis() - x_rotation;
+        let arc = geom::Arc {
+            start_angle,
+            center,
+            radii,
+            sweep_angle,
+            x_rotation,
+        };
+        arc.for_each_quadratic_bezier(&mut |curve| self.quadratic_bezier_to(curve.ctrl, curve.to));
+    }
+}
+
+/// Loads the color (`sbix` or `CBDT`) bitmap of the given glyph from the strike that best
+/// matches `pixel_size`, as `Loader::best_strike_for_size()` picks it, falling back to the next
+/// best strikes if that one lacks the glyph.
+///
+/// Returns the pixels-per-em size of the strike along with the decoded glyph.
+fn load_color_bitmap<L>(
+    loader: &L,
+    glyph_id: u32,
+    pixel_size: f32,
+) -> Option<(f32, BitmapGlyph, png::Image)>
+where
+    L: Loader,
+{
+    let mut strikes = loader.bitmap_strikes();
+    strikes.retain(|strike| strike.source != BitmapSource::Ebdt);
+    bitmap::sort_by_preference(&mut strikes, pixel_size);
+    for strike in strikes {
+        if let Ok(Some(glyph)) = loader.load_bitmap_glyph(glyph_id, &strike) {
+            if let Some(image) = png::decode(&glyph.data) {
+                return Some((strike.pixels_per_em as f32, glyph, image));
+            }
+        }
+    }
+    None
+}
@@ -11,39 +11,74 @@
 //! A loader that uses Apple's Core Text API to load and rasterize fonts.
 
 use byteorder::{BigEndian, ReadBytesExt};
+use core_foundation::array::CFArray;
+use core_foundation::base::{CFIndex, TCFType};
+use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::number::CFNumber;
+use core_foundation::string::CFString;
 use core_graphics::base::{kCGImageAlphaPremultipliedLast, CGFloat};
 use core_graphics::color_space::CGColorSpace;
 use core_graphics::context::{CGContext, CGTextDrawingMode};
 use core_graphics::data_provider::CGDataProvider;
+#[cfg(feature = "mmap")]
+use core_graphics::data_provider::CustomData;
 use core_graphics::font::{CGFont, CGGlyph};
 use core_graphics::geometry::{CGAffineTransform, CGRect, CGSize};
 use core_graphics::geometry::{CGPoint, CG_AFFINE_TRANSFORM_IDENTITY, CG_ZERO_POINT, CG_ZERO_SIZE};
 use core_graphics::path::CGPathElementType;
 use core_text;
-use core_text::font::CTFont;
-use core_text::font_descriptor::kCTFontDefaultOrientation;
-use core_text::font_descriptor::{SymbolicTraitAccessors, TraitAccessors};
-use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use core_text::font::{CTFont, CTFontRef};
+use core_text::font_descriptor::CTFontOrientation;
+use core_text::font_descriptor::{self, kCTFontDefaultOrientation, kCTFontVariationAttribute};
+use core_text::font_descriptor::{CTFontDescriptorRef, SymbolicTraitAccessors, TraitAccessors};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
 use log::warn;
 use lyon_path::builder::PathBuilder;
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
 use std::f32;
 use std::fmt::{self, Debug, Formatter};
 use std::fs::File;
 use std::io::{Seek, SeekFrom};
 use std::ops::Deref;
 use std::path::Path;
-use std::sync::Arc;
-
-use crate::canvas::{Canvas, Format, RasterizationOptions};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use crate::bitmap::{BitmapGlyph, BitmapStrike};
+use crate::canvas::{Canvas, Format, RasterizationAdjustments, RasterizationOptions};
+use crate::color::{ColorFormat, ColorLayer, ColorPalette, Paint};
+use crate::coverage::CodepointCoverage;
+use crate::embedding::EmbeddingRestrictions;
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
+use crate::gzip;
 use crate::handle::Handle;
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackResult, FontTransform, Loader};
-use crate::metrics::Metrics;
+use crate::layout::{GlyphClass, LangTag, LayoutFeatures, ScriptTag};
+use crate::loader::{
+    self, CollectionFonts, FallbackFont, FallbackResult, FontTransform, Glyphs, Loader,
+};
+use crate::math::{GlyphVariant, MathConstants, StretchDirection};
+use crate::metrics::{HorizontalMetrics, MaxpLimits, Metrics};
+use crate::names::NameRecord;
+use crate::outline::Outline;
+use crate::outline_format::OutlineFormat;
+use crate::panose::Panose;
 use crate::properties::{Properties, Stretch, Style, Weight};
+#[cfg(feature = "harfbuzz")]
+use crate::shaping::{Direction, Feature, Script, ShapedGlyph};
+use crate::signature::SignatureInfo;
 use crate::sources;
+use crate::subset::FontSubset;
+use crate::synthesis::Synthesis;
+use crate::tables::cmap::{self, ReverseCmap};
+use crate::tables::hhea;
+use crate::tables::os_2;
+use crate::tables::vhea::{self, VheaTable};
 use crate::utils;
+use crate::variations::{self, NamedInstance, VariationAxis};
+use crate::woff;
 
 const TTC_TAG: [u8; 4] = [b't', b't', b'c', b'f'];
 
@@ -54,10 +89,10 @@ const kCGImageAlphaOnly: u32 = 7;
 pub type NativeFont = CTFont;
 
 /// A loader that uses Apple's Core Text API to load and rasterize fonts.
-#[derive(Clone)]
 pub struct Font {
     core_text_font: CTFont,
     font_data: FontData,
+    reverse_cmap: Mutex<Option<Arc<ReverseCmap>>>,
 }
 
 impl Font {
@@ -69,11 +104,15 @@ impl Font {
         mut font_data: Arc<Vec<u8>>,
         font_index: u32,
     ) -> Result<Font, FontLoadingError> {
+        font_data = woff::to_sfnt(gzip::decompress(font_data)?)?;
+
         // Sadly, there's no API to load OpenType collections on macOS, I don't believe…
         if font_is_collection(&**font_data) {
             let mut new_font_data = (*font_data).clone();
             unpack_otc_font(&mut new_font_data, font_index)?;
             font_data = Arc::new(new_font_data);
+        } else if font_index != 0 {
+            return Err(FontLoadingError::NoSuchFontInCollection);
         }
 
         let data_provider = CGDataProvider::from_buffer(font_data.clone());
@@ -83,6 +122,7 @@ impl Font {
         Ok(Font {
             core_text_font,
             font_data: FontData::Memory(font_data),
+            reverse_cmap: Mutex::new(None),
         })
     }
 
@@ -105,7 +145,53 @@ impl Font {
         <Font as Loader>::from_path(path, font_index)
     }
 
+    /// Loads a font from the path to a `.ttf`/`.otf`/etc. file by mapping the file into memory
+    /// instead of reading it.
+    ///
+    /// The mapping lives as long as the font and its clones do, so fonts loaded this way don't
+    /// add the size of the file to the resident memory of the process until their data is
+    /// actually used. Collections still have to be unpacked, and WOFF and WOFF2 files
+    /// decompressed, into memory.
+    ///
+    /// The file must not be modified or truncated while it's mapped.
+    ///
+    /// This method is only available with the `mmap` Cargo feature.
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap<P: AsRef<Path>>(
+        path: P,
+        font_index: u32,
+    ) -> Result<Font, FontLoadingError> {
+        // Safety: the mapping is only ever read, and the caller promises not to change the file
+        // underneath it.
+        let mapping = Arc::new(unsafe { Mmap::map(&File::open(path)?)? });
+        if font_is_collection(&mapping)
+            || woff::web_font_type(&mapping).is_some()
+            || gzip::is_compressed(&mapping)
+        {
+            return Font::from_bytes(Arc::new(mapping.to_vec()), font_index);
+        }
+        if font_index != 0 {
+            return Err(FontLoadingError::NoSuchFontInCollection);
+        }
+
+        let data_provider = unsafe {
+            CGDataProvider::from_custom_data(Box::new(Box::new(MappedData(mapping.clone()))))
+        };
+        let core_graphics_font =
+            CGFont::from_data_provider(data_provider).map_err(|_| FontLoadingError::Parse)?;
+        let core_text_font = core_text::font::new_from_CGFont(&core_graphics_font, 16.0);
+        Ok(Font {
+            core_text_font,
+            font_data: FontData::Mapped(mapping),
+            reverse_cmap: Mutex::new(None),
+        })
+    }
+
     /// Creates a font from a native API handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid, live font object of the underlying platform API.
     pub unsafe fn from_native_font(core_text_font: NativeFont) -> Font {
         Font::from_core_text_font(core_text_font)
     }
@@ -129,6 +215,7 @@ impl Font {
         Font {
             core_text_font,
             font_data,
+            reverse_cmap: Mutex::new(None),
         }
     }
 
@@ -149,6 +236,13 @@ impl Font {
 
     /// Determines whether a file represents a supported font, and if so, what type of font it is.
     pub fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        if gzip::is_compressed(&font_data) {
+            return Font::analyze_bytes(gzip::decompress(font_data)?);
+        }
+        if let Some(file_type) = woff::web_font_type(&font_data) {
+            let font_data = woff::to_sfnt(font_data)?;
+            return Font::analyze_bytes(font_data).map(|_| file_type);
+        }
         if let Ok(font_count) = read_number_of_fonts_from_otc_header(&font_data) {
             return Ok(FileType::Collection(font_count));
         }
@@ -164,6 +258,9 @@ impl Font {
         file.seek(SeekFrom::Start(0))?;
 
         let font_data = Arc::new(utils::slurp_file(file).map_err(FontLoadingError::Io)?);
+        if woff::web_font_type(&font_data).is_some() || gzip::is_compressed(&font_data) {
+            return Font::analyze_bytes(font_data);
+        }
         if let Ok(font_count) = read_number_of_fonts_from_otc_header(&font_data) {
             return Ok(FileType::Collection(font_count));
         }
@@ -181,7 +278,80 @@ impl Font {
         <Self as Loader>::analyze_path(path)
     }
 
+    /// Returns a fingerprint of the font at `font_index` in the given file, without loading the
+    /// font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_file(file: &mut File, font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_file(file, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in the file at the given path, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_path<P: AsRef<Path>>(
+        path: P,
+        font_index: u32,
+    ) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_path(path, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in a blob of raw font data, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_bytes(font_data: &[u8], font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_bytes(font_data, font_index)
+    }
+
+    /// Returns the number of fonts in a blob of raw font data: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    pub fn count_in_collection_bytes(font_data: Arc<Vec<u8>>) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection_bytes(font_data)
+    }
+
+    /// Returns the number of fonts in the file at a path: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    pub fn count_in_collection<P: AsRef<Path>>(path: P) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection(path)
+    }
+
+    /// Returns an iterator that loads each font in a blob of raw font data in turn.
+    #[inline]
+    pub fn all_in_collection_bytes(
+        font_data: Arc<Vec<u8>>,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection_bytes(font_data)
+    }
+
+    /// Loads the font with the given PostScript name from a blob of raw font data.
+    ///
+    /// See `Loader::from_bytes_by_postscript_name()`.
+    #[inline]
+    pub fn from_bytes_by_postscript_name(
+        font_data: Arc<Vec<u8>>,
+        postscript_name: &str,
+    ) -> Result<Font, FontLoadingError> {
+        <Self as Loader>::from_bytes_by_postscript_name(font_data, postscript_name)
+    }
+
+    /// Returns an iterator that loads each font in the file at a path in turn.
+    #[inline]
+    pub fn all_in_collection<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection(path)
+    }
+
     /// Returns the wrapped native font handle.
+    ///
+    /// The returned `CTFont` is retained, and is released when it is dropped.
     #[inline]
     pub fn native_font(&self) -> NativeFont {
         self.core_text_font.clone()
@@ -214,12 +384,14 @@ impl Font {
     }
 
     /// Returns true if and only if the font is monospace (fixed-width).
-    #[inline]
     pub fn is_monospace(&self) -> bool {
-        self.core_text_font.symbolic_traits().is_monospace()
+        loader::is_monospace(self, self.core_text_font.symbolic_traits().is_monospace())
     }
 
     /// Returns the values of various font properties, corresponding to those defined in CSS.
+    ///
+    /// For variable fonts, the weight, stretch, and style reflect the current variation
+    /// coordinates of the `wght`, `wdth`, `slnt`, and `ital` axes.
     pub fn properties(&self) -> Properties {
         let symbolic_traits = self.core_text_font.symbolic_traits();
         let all_traits = self.core_text_font.all_traits();
@@ -235,11 +407,20 @@ impl Font {
         let weight = core_text_to_css_font_weight(all_traits.normalized_weight() as f32);
         let stretch = core_text_width_to_css_stretchiness(all_traits.normalized_width() as f32);
 
-        Properties {
+        let properties = Properties {
             style,
             weight,
             stretch,
-        }
+            italic_angle: Some(self.italic_angle()),
+        };
+        variations::apply_to_properties(properties, &self.variations())
+    }
+
+    /// Returns the slant of the font in degrees counterclockwise from vertical, from the
+    /// `italicAngle` field of its `post` table.
+    #[inline]
+    pub fn italic_angle(&self) -> f32 {
+        <Self as Loader>::italic_angle(self)
     }
 
     /// Returns the number of glyphs in the font.
@@ -249,33 +430,85 @@ impl Font {
         self.core_text_font.glyph_count() as u32
     }
 
+    /// Returns an iterator over every glyph of the font, in order of glyph ID, with its metrics.
+    ///
+    /// See `Loader::glyphs()`.
+    #[inline]
+    pub fn glyphs(&self) -> Glyphs<'_, Font> {
+        <Self as Loader>::glyphs(self)
+    }
+
     /// Returns the usual glyph ID for a Unicode character.
     ///
     /// Be careful with this function; typographically correct character-to-glyph mapping must be
     /// done using a *shaper* such as HarfBuzz. This function is only useful for best-effort simple
     /// use cases like "what does character X look like on its own".
     pub fn glyph_for_char(&self, character: char) -> Option<u32> {
+        core_text_glyph_for_char(&self.core_text_font, character)
+    }
+
+    /// Returns the glyph ID for a Unicode character followed by a variation selector, such as
+    /// U+FE0E or U+FE0F for the text and emoji presentations or U+E0100 and up for ideographic
+    /// variants.
+    ///
+    /// The variation sequences come from the format 14 subtable of the `cmap`. If the font has no
+    /// specific glyph for the sequence, this returns the usual glyph for the character, like
+    /// `glyph_for_char()`.
+    #[inline]
+    pub fn glyph_for_char_with_variation(
+        &self,
+        character: char,
+        variation_selector: char,
+    ) -> Option<u32> {
+        <Self as Loader>::glyph_for_char_with_variation(self, character, variation_selector)
+    }
+
+    /// Returns the glyph ID for each character of the given string, in order, or `None` for
+    /// characters that the font has no glyph for.
+    ///
+    /// All of the characters are looked up with a single Core Text call.
+    pub fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        let characters: Vec<u16> = text.encode_utf16().collect();
+        let mut glyphs: Vec<CGGlyph> = vec![0; characters.len()];
         unsafe {
-            let (mut dest, mut src) = ([0, 0], [0, 0]);
-            let src = character.encode_utf16(&mut src);
-            self.core_text_font
-                .get_glyphs_for_characters(src.as_ptr(), dest.as_mut_ptr(), 2);
-
-            let id = dest[0] as u32;
-            if id != 0 {
-                Some(id)
-            } else {
-                None
-            }
+            self.core_text_font.get_glyphs_for_characters(
+                characters.as_ptr(),
+                glyphs.as_mut_ptr(),
+                characters.len() as CFIndex,
+            );
         }
+
+        // Core Text stores the glyph for a surrogate pair at the index of its first unit.
+        let mut index = 0;
+        text.chars()
+            .map(|character| {
+                let glyph = glyphs[index] as u32;
+                index += character.len_utf16();
+                if glyph != 0 {
+                    Some(glyph)
+                } else {
+                    None
+                }
+            })
+            .collect()
     }
 
     /// Returns the glyph ID for the specified glyph name.
     #[inline]
     pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
         let code = self.core_text_font.get_glyph_with_name(name);
+        if code != 0 || name == ".notdef" {
+            return Some(u32::from(code));
+        }
+        loader::glyph_by_synthesized_name(self, name)
+    }
 
-        Some(u32::from(code))
+    /// Returns the name of a glyph, the reverse of `glyph_by_name()`.
+    ///
+    /// See `Loader::glyph_name()`.
+    #[inline]
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        <Self as Loader>::glyph_name(self, glyph_id)
     }
 
     /// Sends the vector path for a glyph to a path builder.
@@ -301,7 +534,7 @@ impl Font {
             Err(_) => {
                 // This will happen if the path is empty (rdar://42832439). To distinguish this
                 // case from the case in which the glyph does not exist, call another API.
-                drop(self.typographic_bounds(glyph_id)?);
+                self.typographic_bounds(glyph_id)?;
                 return Ok(());
             }
         };
@@ -336,17 +569,43 @@ impl Font {
         let rect = self
             .core_text_font
             .get_bounding_rects_for_glyphs(kCTFontDefaultOrientation, &[glyph_id as u16]);
+        Ok(cg_rect_to_font_units(&rect, self.units_per_point()))
+    }
+
+    /// Returns the boundaries of each of the given glyphs in font units, in order, with a single
+    /// call to Core Text.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    pub fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        let glyph_count = self.glyph_count();
+        let glyphs = glyph_ids
+            .iter()
+            .map(|&glyph_id| {
+                if glyph_id < glyph_count {
+                    Ok(glyph_id as CGGlyph)
+                } else {
+                    Err(GlyphLoadingError::NoSuchGlyph)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rects = vec![CGRect::new(&CG_ZERO_POINT, &CG_ZERO_SIZE); glyphs.len()];
+        unsafe {
+            CTFontGetBoundingRectsForGlyphs(
+                self.core_text_font.as_concrete_TypeRef(),
+                kCTFontDefaultOrientation,
+                glyphs.as_ptr(),
+                rects.as_mut_ptr(),
+                glyphs.len() as CFIndex,
+            );
+        }
         let units_per_point = self.units_per_point();
-        Ok(Rect::new(
-            Point2D::new(
-                (rect.origin.x * units_per_point) as f32,
-                (rect.origin.y * units_per_point) as f32,
-            ),
-            Size2D::new(
-                (rect.size.width * units_per_point) as f32,
-                (rect.size.height * units_per_point) as f32,
-            ),
-        ))
+        Ok(rects
+            .iter()
+            .map(|rect| cg_rect_to_font_units(rect, units_per_point))
+            .collect())
     }
 
     /// Returns the distance from the origin of the glyph with the given ID to the next, in font
@@ -368,6 +627,64 @@ impl Font {
         }
     }
 
+    /// Returns the advances of each of the given glyphs in font units, in order, with a single
+    /// call to Core Text.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    pub fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        let glyph_count = self.glyph_count();
+        let glyphs = glyph_ids
+            .iter()
+            .map(|&glyph_id| {
+                if glyph_id < glyph_count {
+                    Ok(glyph_id as CGGlyph)
+                } else {
+                    Err(GlyphLoadingError::NoSuchGlyph)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut advances = vec![CG_ZERO_SIZE; glyphs.len()];
+        unsafe {
+            self.core_text_font.get_advances_for_glyphs(
+                kCTFontDefaultOrientation,
+                glyphs.as_ptr(),
+                advances.as_mut_ptr(),
+                glyphs.len() as CFIndex,
+            );
+        }
+        let units_per_point = self.units_per_point();
+        Ok(advances
+            .iter()
+            .map(|advance| {
+                Vector2D::new(
+                    (advance.width * units_per_point) as f32,
+                    (advance.height * units_per_point) as f32,
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the advance of the glyph with the given ID in font units, as recorded in the
+    /// font's `hmtx` table, independent of any point size.
+    ///
+    /// Unlike `advance()`, this doesn't reflect variations applied with `set_variations()`.
+    #[inline]
+    pub fn advance_font_units(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::advance_font_units(self, glyph_id)
+    }
+
+    /// Returns the advance width and left side bearing of the given glyph, in font units, from
+    /// the `hmtx` table.
+    ///
+    /// See `Loader::horizontal_metrics()`.
+    #[inline]
+    pub fn horizontal_metrics(
+        &self,
+        glyph_id: u32,
+    ) -> Result<HorizontalMetrics, GlyphLoadingError> {
+        <Self as Loader>::horizontal_metrics(self, glyph_id)
+    }
+
     /// Returns the amount that the given glyph should be displaced from the origin.
     pub fn origin(&self, glyph_id: u32) -> Result<Point2D<f32>, GlyphLoadingError> {
         unsafe {
@@ -386,10 +703,40 @@ impl Font {
         }
     }
 
+    /// Returns the number of font units per em, from the `head` table.
+    ///
+    /// This is always the same as the `units_per_em` field of `metrics()`.
+    #[inline]
+    pub fn units_per_em(&self) -> u32 {
+        self.core_text_font.units_per_em()
+    }
+
     /// Retrieves various metrics that apply to the entire font.
     pub fn metrics(&self) -> Metrics {
-        let units_per_em = self.core_text_font.units_per_em();
+        let units_per_em = self.units_per_em();
         let units_per_point = (units_per_em as f64) / self.core_text_font.pt_size();
+        let vhea = self
+            .load_font_table(vhea::TAG)
+            .and_then(|table| VheaTable::parse(&table));
+        let os_2_data = self.load_font_table(os_2::TAG);
+        let hhea_data = self.load_font_table(hhea::TAG);
+        let (strikeout_size, strikeout_position) = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::strikeout(table))
+            .unwrap_or((0, 0));
+        let (caret_slope_rise, caret_slope_run) = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::caret_slope(table))
+            .unwrap_or((1, 0));
+        let hhea_metrics = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::line_metrics(table));
+        let typo_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::typo_metrics(table));
+        let win_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::win_metrics(table));
         Metrics {
             units_per_em,
             ascent: (self.core_text_font.ascent() * units_per_point) as f32,
@@ -398,9 +745,37 @@ impl Font {
             underline_position: (self.core_text_font.underline_position() * units_per_point) as f32,
             underline_thickness: (self.core_text_font.underline_thickness() * units_per_point)
                 as f32,
+            strikeout_position: strikeout_position as f32,
+            strikeout_size: strikeout_size as f32,
             cap_height: (self.core_text_font.cap_height() * units_per_point) as f32,
             x_height: (self.core_text_font.x_height() * units_per_point) as f32,
+            vertical_ascent: vhea.map(|vhea| vhea.ascent as f32),
+            vertical_descent: vhea.map(|vhea| vhea.descent as f32),
+            vertical_line_gap: vhea.map(|vhea| vhea.line_gap as f32),
+            hhea_ascent: hhea_metrics.map(|(ascent, _, _)| ascent as f32),
+            hhea_descent: hhea_metrics.map(|(_, descent, _)| descent as f32),
+            hhea_line_gap: hhea_metrics.map(|(_, _, line_gap)| line_gap as f32),
+            typo_ascent: typo_metrics.map(|(ascent, _, _)| ascent as f32),
+            typo_descent: typo_metrics.map(|(_, descent, _)| descent as f32),
+            typo_line_gap: typo_metrics.map(|(_, _, line_gap)| line_gap as f32),
+            win_ascent: win_metrics.map(|(ascent, _)| ascent as f32),
+            win_descent: win_metrics.map(|(_, descent)| -(descent as f32)),
+            use_typo_metrics: os_2_data
+                .as_ref()
+                .is_some_and(|table| os_2::use_typo_metrics(table)),
+            caret_slope_rise: caret_slope_rise as f32,
+            caret_slope_run: caret_slope_run as f32,
         }
+        .with_typo_line_metrics()
+        .with_decoration_fallbacks()
+    }
+
+    /// Returns the limits that the font's TrueType outlines stay within, from its `maxp` table.
+    ///
+    /// See `Loader::maxp_limits()`.
+    #[inline]
+    pub fn maxp_limits(&self) -> Option<MaxpLimits> {
+        <Self as Loader>::maxp_limits(self)
     }
 
     /// Returns a handle to this font, if possible.
@@ -419,6 +794,21 @@ impl Font {
         match self.font_data {
             FontData::Unavailable => None,
             FontData::Memory(ref memory) => Some((*memory).clone()),
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => Some(Arc::new(mapping.to_vec())),
+        }
+    }
+
+    /// Returns the raw font data without copying it, if it's available.
+    ///
+    /// Data in memory is shared with this font, and memory-mapped data is borrowed from the
+    /// mapping.
+    pub fn font_data(&self) -> Option<loader::FontData<'_>> {
+        match self.font_data {
+            FontData::Unavailable => None,
+            FontData::Memory(ref data) => Some(loader::FontData::Memory(data.clone())),
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => Some(loader::FontData::Borrowed(mapping)),
         }
     }
 
@@ -445,6 +835,32 @@ impl Font {
         )
     }
 
+    /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
+    /// rasterizer at the given size and origin, with the given adjustments.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        <Self as Loader>::raster_bounds_with_adjustments(
+            self,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )
+    }
+
     /// Rasterizes a glyph to a canvas with the given size and origin.
     ///
     /// Format conversion will be performed if the canvas format does not match the rasterization
@@ -455,9 +871,40 @@ impl Font {
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
     ///
+    /// See `Loader::rasterize_glyph()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn rasterize_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, with the given adjustments to
+    /// its coverage.
+    ///
+    /// See `Loader::rasterize_glyph_with_adjustments()`.
+    ///
     /// TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
     /// implementation.
-    pub fn rasterize_glyph(
+    #[allow(clippy::too_many_arguments)]
+    pub fn rasterize_glyph_with_adjustments(
         &self,
         canvas: &mut Canvas,
         glyph_id: u32,
@@ -466,7 +913,23 @@ impl Font {
         origin: &Point2D<f32>,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
     ) -> Result<(), GlyphLoadingError> {
+        if let Some(threshold) = rasterization_adjustments.bilevel_threshold(rasterization_options)
+        {
+            return loader::rasterize_glyph_with_bilevel_threshold(
+                self,
+                canvas,
+                glyph_id,
+                point_size,
+                transform,
+                origin,
+                hinting_options,
+                rasterization_adjustments,
+                threshold,
+            );
+        }
+
         let (cg_color_space, cg_image_format) =
             match format_to_cg_color_space_and_image_format(canvas.format) {
                 None => {
@@ -476,7 +939,7 @@ impl Font {
                     // FIXME(pcwalton): Could improve this by only allocating a canvas with a tight
                     // bounding rect and blitting only that part.
                     let mut temp_canvas = Canvas::new(&canvas.size, Format::Rgba32);
-                    self.rasterize_glyph(
+                    self.rasterize_glyph_with_adjustments(
                         &mut temp_canvas,
                         glyph_id,
                         point_size,
@@ -484,6 +947,7 @@ impl Font {
                         origin,
                         hinting_options,
                         rasterization_options,
+                        rasterization_adjustments,
                     )?;
                     canvas.blit_from_canvas(&temp_canvas);
                     return Ok(());
@@ -520,11 +984,19 @@ impl Font {
             RasterizationOptions::GrayscaleAa | RasterizationOptions::SubpixelAa => {
                 // FIXME(pcwalton): These shouldn't be handled the same!
                 core_graphics_context.set_allows_font_smoothing(true);
-                core_graphics_context.set_should_smooth_fonts(true);
+                core_graphics_context
+                    .set_should_smooth_fonts(rasterization_adjustments.stem_darkening);
                 core_graphics_context.set_should_antialias(true);
             }
         }
 
+        // Draw the glyph at the exact origin, fractional part included, rather than snapping it
+        // to a whole pixel or to one of a few quantized subpixel positions.
+        core_graphics_context.set_allows_font_subpixel_positioning(true);
+        core_graphics_context.set_should_subpixel_position_fonts(true);
+        core_graphics_context.set_allows_font_subpixel_quantization(false);
+        core_graphics_context.set_should_subpixel_quantize_fonts(false);
+
         match canvas.format {
             Format::Rgba32 | Format::Rgb24 => {
                 core_graphics_context.set_rgb_fill_color(1.0, 1.0, 1.0, 1.0);
@@ -547,7 +1019,10 @@ impl Font {
         });
         let origin = CGPoint::new(0. as CGFloat, 0. as CGFloat);
         core_graphics_context.show_glyphs_at_positions(&[glyph_id as CGGlyph], &[origin]);
+        drop(core_graphics_context);
 
+        // The whole canvas was cleared above, so all of it is coverage of this glyph.
+        rasterization_adjustments.apply_gamma(rasterization_options, &mut canvas.pixels);
         Ok(())
     }
 
@@ -563,19 +1038,55 @@ impl Font {
             HintingOptions::None => true,
             HintingOptions::Vertical(..)
             | HintingOptions::VerticalSubpixel(..)
-            | HintingOptions::Full(..) => false,
+            | HintingOptions::Full(..)
+            | HintingOptions::Autohint(..) => false,
         }
     }
 
+    /// Returns true if the font carries hints for its outlines.
+    ///
+    /// See `Loader::has_hinting_instructions()`.
+    #[inline]
+    pub fn has_hinting_instructions(&self) -> bool {
+        <Self as Loader>::has_hinting_instructions(self)
+    }
+
     /// Get font fallback results for the given text and locale.
     ///
-    /// Note: this is currently just a stub implementation, a proper implementation
-    /// would use CTFontCopyDefaultCascadeListForLanguages.
-    fn get_fallbacks(&self, text: &str, _locale: &str) -> FallbackResult<Font> {
-        warn!("unsupported");
+    /// The fonts are taken from Core Text's default cascade list for this font, preferring fonts
+    /// for the language given by `locale` (such as `"ja"`) if it isn't empty, and are limited to
+    /// those that can display the first character of the text. Accordingly, the result is only
+    /// valid for that character.
+    fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Font> {
+        let character = match text.chars().next() {
+            Some(character) => character,
+            None => {
+                return FallbackResult {
+                    fonts: vec![],
+                    valid_len: 0,
+                }
+            }
+        };
+
+        let languages: Vec<CFString> = if locale.is_empty() {
+            vec![]
+        } else {
+            vec![CFString::new(locale)]
+        };
+        let languages = CFArray::from_CFTypes(&languages);
+        let pt_size = self.core_text_font.pt_size();
+        let fonts = core_text::font::cascade_list_for_languages(&self.core_text_font, &languages)
+            .iter()
+            .map(|descriptor| core_text::font::new_from_descriptor(&descriptor, pt_size))
+            .filter(|core_text_font| core_text_glyph_for_char(core_text_font, character).is_some())
+            .map(|core_text_font| FallbackFont {
+                font: unsafe { Font::from_core_text_font(core_text_font) },
+                scale: 1.0,
+            })
+            .collect();
         FallbackResult {
-            fonts: Vec::new(),
-            valid_len: text.len(),
+            fonts,
+            valid_len: character.len_utf8(),
         }
     }
 
@@ -595,64 +1106,679 @@ impl Font {
             .get_font_table(table_tag)
             .map(|data| data.bytes().into())
     }
-}
 
-impl Loader for Font {
-    type NativeFont = NativeFont;
+    /// Returns the tags of the tables in the font's sfnt table directory, in directory order.
+    ///
+    /// This doesn't load any of the tables.
+    pub fn table_tags(&self) -> Vec<u32> {
+        match self.core_text_font.get_available_font_tables() {
+            None => vec![],
+            Some(tags) => tags.iter().map(|tag| *tag).collect(),
+        }
+    }
 
+    /// Returns true if the font has a table with the given tag, without loading it.
     #[inline]
-    fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Self, FontLoadingError> {
-        Font::from_bytes(font_data, font_index)
+    pub fn contains_table(&self, table_tag: u32) -> bool {
+        <Self as Loader>::contains_table(self, table_tag)
     }
 
+    /// Returns the format of the font's glyph outlines, from the tables present.
+    ///
+    /// See `Loader::outline_format()`.
     #[inline]
-    fn from_file(file: &mut File, font_index: u32) -> Result<Font, FontLoadingError> {
-        Font::from_file(file, font_index)
+    pub fn outline_format(&self) -> OutlineFormat {
+        <Self as Loader>::outline_format(self)
     }
 
+    /// Returns the color glyph technologies that the font carries, from the tables present.
+    ///
+    /// See `Loader::color_format()`.
     #[inline]
-    unsafe fn from_native_font(native_font: Self::NativeFont) -> Self {
-        Font::from_native_font(native_font)
+    pub fn color_format(&self) -> ColorFormat {
+        <Self as Loader>::color_format(self)
     }
 
+    /// Returns true if the font is a variable font, that is, if it has an `fvar` table.
     #[inline]
-    fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
-        Font::analyze_bytes(font_data)
+    pub fn is_variable(&self) -> bool {
+        <Self as Loader>::is_variable(self)
     }
 
+    /// Returns the design axes of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
     #[inline]
-    fn analyze_file(file: &mut File) -> Result<FileType, FontLoadingError> {
-        Font::analyze_file(file)
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        <Self as Loader>::variation_axes(self)
     }
 
+    /// Returns the named instances of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
     #[inline]
-    fn native_font(&self) -> Self::NativeFont {
-        self.native_font()
+    pub fn named_instances(&self) -> Vec<NamedInstance> {
+        <Self as Loader>::named_instances(self)
     }
 
-    #[inline]
-    fn postscript_name(&self) -> Option<String> {
-        self.postscript_name()
+    /// Instantiates a variable font at the given point in its design space.
+    ///
+    /// `coords` maps axis tags to values. Values outside the range of an axis are clamped to it,
+    /// and axes that aren't mentioned are left at their default values. Subsequent outlines,
+    /// metrics, and rasterization reflect the chosen instance.
+    ///
+    /// This has no effect on fonts that aren't variable fonts.
+    pub fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return;
+        }
+        let design_coords = variations::design_coordinates(&axes, coords);
+        let variation_pairs: Vec<(CFNumber, CFNumber)> = axes
+            .iter()
+            .zip(design_coords)
+            .map(|(axis, value)| {
+                (
+                    CFNumber::from(axis.tag as i64),
+                    CFNumber::from(value as f64),
+                )
+            })
+            .collect();
+        let variation_dictionary = CFDictionary::from_CFType_pairs(&variation_pairs);
+        unsafe {
+            let attributes = CFDictionary::from_CFType_pairs(&[(
+                CFString::wrap_under_get_rule(kCTFontVariationAttribute),
+                variation_dictionary.as_CFType(),
+            )]);
+            let font_descriptor = font_descriptor::new_from_attributes(&attributes);
+            let core_text_font = CTFontCreateCopyWithAttributes(
+                self.core_text_font.as_concrete_TypeRef(),
+                self.core_text_font.pt_size(),
+                ptr::null(),
+                font_descriptor.as_concrete_TypeRef(),
+            );
+            if core_text_font.is_null() {
+                warn!("set_variations(): failed to create a font with the given variations");
+                return;
+            }
+            self.core_text_font = CTFont::wrap_under_create_rule(core_text_font);
+        }
     }
 
+    /// Returns the current position of a variable font in its design space, as one `(tag, value)`
+    /// pair per axis, in axis order.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    pub fn variations(&self) -> Vec<(u32, f32)> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return vec![];
+        }
+        // Core Text leaves axes at their default values out of the dictionary.
+        let variation_dictionary: Option<CFDictionary<CFNumber, CFNumber>> = unsafe {
+            let dictionary = CTFontCopyVariation(self.core_text_font.as_concrete_TypeRef());
+            if dictionary.is_null() {
+                None
+            } else {
+                Some(CFDictionary::wrap_under_create_rule(dictionary))
+            }
+        };
+        axes.iter()
+            .map(|axis| {
+                let value = variation_dictionary
+                    .as_ref()
+                    .and_then(|dictionary| dictionary.find(CFNumber::from(axis.tag as i64)))
+                    .and_then(|value| value.to_f64())
+                    .map_or(axis.default_value, |value| value as f32);
+                (axis.tag, value)
+            })
+            .collect()
+    }
+
+    /// Returns the layers that make up the given color glyph, as declared in the font's `COLR`
+    /// table, with colors taken from the `CPAL` palette at `palette_index`.
+    ///
+    /// Layers are returned bottommost first. If the glyph isn't a color glyph, this returns an
+    /// empty vector. If the palette doesn't exist, palette 0 is used instead.
     #[inline]
-    fn full_name(&self) -> String {
-        self.full_name()
+    pub fn glyph_color_layers(
+        &self,
+        glyph_id: u32,
+        palette_index: u16,
+    ) -> Result<Vec<ColorLayer>, GlyphLoadingError> {
+        <Self as Loader>::glyph_color_layers(self, glyph_id, palette_index)
     }
 
+    /// Returns the paint graph of the given `COLR` version 1 color glyph, with colors taken from
+    /// the `CPAL` palette at `palette_index`.
+    ///
+    /// See `Loader::colrv1_paint()`.
     #[inline]
-    fn family_name(&self) -> String {
-        self.family_name()
+    pub fn colrv1_paint(&self, glyph_id: u32, palette_index: u16) -> Option<Paint> {
+        <Self as Loader>::colrv1_paint(self, glyph_id, palette_index)
     }
 
+    /// Returns the color palettes in the font's `CPAL` table, in order of palette index.
+    ///
+    /// See `Loader::color_palettes()`.
     #[inline]
-    fn is_monospace(&self) -> bool {
-        self.is_monospace()
+    pub fn color_palettes(&self) -> Vec<ColorPalette> {
+        <Self as Loader>::color_palettes(self)
     }
 
+    /// Rasterizes a color glyph to an `Rgba32` canvas with the given size and origin, from the
+    /// font's embedded color bitmaps or `COLR` layers.
+    ///
+    /// The result is in premultiplied R8G8B8A8 format; see `Format::Rgba32`. Returns
+    /// `GlyphLoadingError::NoColorData` if the font has no color data.
     #[inline]
-    fn properties(&self) -> Properties {
-        self.properties()
+    pub fn rasterize_color_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_color_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Returns the strikes of pre-rendered bitmap glyphs that the font contains, from its `sbix`,
+    /// `CBLC`/`CBDT`, and `EBLC`/`EBDT` tables.
+    ///
+    /// If the font has no embedded bitmaps, this returns an empty vector.
+    #[inline]
+    pub fn bitmap_strikes(&self) -> Vec<BitmapStrike> {
+        <Self as Loader>::bitmap_strikes(self)
+    }
+
+    /// Returns the bitmap strike that best matches the given size, in pixels per em.
+    ///
+    /// See `Loader::best_strike_for_size()`.
+    #[inline]
+    pub fn best_strike_for_size(&self, pixels_per_em: f32) -> Option<BitmapStrike> {
+        <Self as Loader>::best_strike_for_size(self, pixels_per_em)
+    }
+
+    /// Loads the pre-rendered bitmap of a glyph from one of the strikes returned by
+    /// `bitmap_strikes()`.
+    ///
+    /// Returns `None` if the strike has no bitmap for the glyph.
+    #[inline]
+    pub fn load_bitmap_glyph(
+        &self,
+        glyph_id: u32,
+        strike: &BitmapStrike,
+    ) -> Result<Option<BitmapGlyph>, GlyphLoadingError> {
+        <Self as Loader>::load_bitmap_glyph(self, glyph_id, strike)
+    }
+
+    /// Returns the SVG document from the font's `SVG ` table that describes the given glyph,
+    /// decompressed if necessary.
+    ///
+    /// Returns `None` if the glyph has no SVG representation.
+    #[inline]
+    pub fn glyph_svg_document(&self, glyph_id: u32) -> Result<Option<Vec<u8>>, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_document(self, glyph_id)
+    }
+
+    /// Returns the kerning adjustment between the given pair of glyphs, in pixels at the given
+    /// point size, from the `GPOS` or `kern` table.
+    ///
+    /// If the font doesn't kern the pair, this returns a zero vector.
+    #[inline]
+    pub fn kerning(&self, left_glyph: u32, right_glyph: u32, point_size: f32) -> Vector2D<f32> {
+        <Self as Loader>::kerning(self, left_glyph, right_glyph, point_size)
+    }
+
+    /// Applies the `GSUB` ligature substitutions of the given OpenType features to a run of
+    /// glyphs, returning the substituted run.
+    ///
+    /// If `features` is empty, the `liga` and `clig` features are used.
+    #[inline]
+    pub fn substitute_ligatures(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::substitute_ligatures(self, glyphs, features)
+    }
+
+    /// Applies the `GSUB` contextual and chaining contextual substitutions of the given OpenType
+    /// features to a run of glyphs, returning the substituted run.
+    ///
+    /// See `Loader::apply_contextual_substitutions()`.
+    #[inline]
+    pub fn apply_contextual_substitutions(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::apply_contextual_substitutions(self, glyphs, features)
+    }
+
+    /// Returns the `(input, output)` glyph pairs that the single and alternate substitutions of
+    /// the given OpenType feature can produce, sorted by input glyph.
+    ///
+    /// See `Loader::feature_affects()`.
+    #[inline]
+    pub fn feature_affects(&self, feature: u32) -> Vec<(u32, u32)> {
+        <Self as Loader>::feature_affects(self, feature)
+    }
+
+    /// Returns the alternates of the given glyph, for glyph pickers, in the order the font lists
+    /// them.
+    ///
+    /// See `Loader::glyph_alternates()`.
+    #[inline]
+    pub fn glyph_alternates(&self, glyph_id: u32) -> Vec<u32> {
+        <Self as Loader>::glyph_alternates(self, glyph_id)
+    }
+
+    /// Returns the caret positions within the given ligature glyph, in font units from its
+    /// origin, from the `GDEF` table.
+    ///
+    /// Glyphs that aren't ligatures yield an empty vector.
+    #[inline]
+    pub fn ligature_carets(&self, glyph_id: u32) -> Vec<f32> {
+        <Self as Loader>::ligature_carets(self, glyph_id)
+    }
+
+    /// Returns whether the given glyph is a base glyph, a ligature, a mark, or a component of a
+    /// glyph, from the `GDEF` table.
+    #[inline]
+    pub fn glyph_class(&self, glyph_id: u32) -> GlyphClass {
+        <Self as Loader>::glyph_class(self, glyph_id)
+    }
+
+    /// Returns the mark attachment class of the given glyph from the `GDEF` table, or 0 if it
+    /// has none.
+    #[inline]
+    pub fn mark_attach_class(&self, glyph_id: u32) -> u16 {
+        <Self as Loader>::mark_attach_class(self, glyph_id)
+    }
+
+    /// Shapes a run of text with HarfBuzz, returning its glyphs in visual order with their
+    /// positions in font units.
+    ///
+    /// This method is only available with the `harfbuzz` Cargo feature.
+    #[cfg(feature = "harfbuzz")]
+    #[inline]
+    pub fn shape(
+        &self,
+        text: &str,
+        direction: Direction,
+        script: Script,
+        features: &[Feature],
+    ) -> Vec<ShapedGlyph> {
+        <Self as Loader>::shape(self, text, direction, script, features)
+    }
+
+    /// Returns the set of Unicode codepoints that this font maps to glyphs, read from the best
+    /// Unicode subtable of its `cmap` table.
+    #[inline]
+    pub fn codepoint_coverage(&self) -> CodepointCoverage {
+        <Self as Loader>::codepoint_coverage(self)
+    }
+
+    /// Returns the characters that the font's best Unicode `cmap` subtable maps to the given
+    /// glyph, in codepoint order.
+    ///
+    /// The inverse of the `cmap` is built the first time this is called and cached on the font.
+    pub fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        let reverse_cmap = self
+            .reverse_cmap
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                let data = self.load_font_table(cmap::TAG).unwrap_or_default();
+                Arc::new(ReverseCmap::parse(&data))
+            })
+            .clone();
+        reverse_cmap.chars_for_glyph(glyph_id)
+    }
+
+    /// Builds a new font containing only the given glyphs, along with glyph 0 (`.notdef`) and
+    /// the components of any composite glyphs among them.
+    ///
+    /// The glyphs are renumbered; the returned `FontSubset` records the mapping. Layout, color,
+    /// bitmap, and variation tables are dropped.
+    #[inline]
+    pub fn subset(&self, glyph_ids: &[u32]) -> Result<FontSubset, GlyphLoadingError> {
+        <Self as Loader>::subset(self, glyph_ids)
+    }
+
+    /// Returns the restrictions that the font's license places on embedding it in documents, as
+    /// recorded in the `fsType` field of its `OS/2` table.
+    #[inline]
+    pub fn embedding_restrictions(&self) -> EmbeddingRestrictions {
+        <Self as Loader>::embedding_restrictions(self)
+    }
+
+    /// Returns the PANOSE classification of the font from its `OS/2` table, or `None` if it has
+    /// no such table.
+    #[inline]
+    pub fn panose(&self) -> Option<Panose> {
+        <Self as Loader>::panose(self)
+    }
+
+    /// Returns the four-character tag of the font's vendor, from its `OS/2` table.
+    ///
+    /// See `Loader::vendor_id()`.
+    #[inline]
+    pub fn vendor_id(&self) -> Option<[u8; 4]> {
+        <Self as Loader>::vendor_id(self)
+    }
+
+    /// Returns the version string of the font (`name` ID 5).
+    ///
+    /// See `Loader::version_string()`.
+    #[inline]
+    pub fn version_string(&self) -> Option<String> {
+        <Self as Loader>::version_string(self)
+    }
+
+    /// Returns the distance that the pen moves down after drawing the given glyph in vertical
+    /// layout, in font units, from the `vmtx` table.
+    #[inline]
+    pub fn vertical_advance(&self, glyph_id: u32) -> Result<f32, GlyphLoadingError> {
+        <Self as Loader>::vertical_advance(self, glyph_id)
+    }
+
+    /// Returns the point in the given glyph's coordinate space, in font units, that is placed on
+    /// the pen position in vertical layout.
+    #[inline]
+    pub fn vertical_origin(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::vertical_origin(self, glyph_id)
+    }
+
+    /// Returns how to make this font look like a face with the given properties, by slanting or
+    /// emboldening it.
+    ///
+    /// See `Loader::synthesis()`.
+    #[inline]
+    pub fn synthesis(&self, properties: &Properties) -> Synthesis {
+        <Self as Loader>::synthesis(self, properties)
+    }
+
+    /// Rasterizes a glyph to a canvas, slanted and emboldened as `synthesis` says.
+    ///
+    /// See `Loader::rasterize_glyph_with_synthesis()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn rasterize_glyph_with_synthesis(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        synthesis: &Synthesis,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_with_synthesis(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            synthesis,
+        )
+    }
+
+    /// Rasterizes a line of text to a canvas, laying its glyphs out from left to right.
+    ///
+    /// See `Loader::rasterize_string()`.
+    #[inline]
+    pub fn rasterize_string(
+        &self,
+        canvas: &mut Canvas,
+        text: &str,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_string(
+            self,
+            canvas,
+            text,
+            point_size,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a single-channel signed distance field of a glyph to a canvas, for rendering
+    /// text on the GPU.
+    ///
+    /// See `Loader::rasterize_glyph_sdf()`.
+    #[inline]
+    pub fn rasterize_glyph_sdf(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        spread: f32,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_sdf(self, canvas, glyph_id, point_size, origin, spread)
+    }
+
+    /// Rasterizes a glyph into floating-point coverage values in [0, 1], one per pixel of a
+    /// buffer of the given size, row by row.
+    ///
+    /// See `Loader::rasterize_glyph_f32()`.
+    #[inline]
+    pub fn rasterize_glyph_f32(
+        &self,
+        size: &Size2D<u32>,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<Vec<f32>, GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_f32(
+            self,
+            size,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Sends the vector path for a glyph to a path builder, after transforming it from font units
+    /// by `transform`.
+    ///
+    /// See `Loader::outline_transformed()`.
+    #[inline]
+    pub fn outline_transformed<B>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        transform: &Transform2D<f32>,
+        path_builder: &mut B,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        B: PathBuilder,
+    {
+        <Self as Loader>::outline_transformed(self, glyph_id, hinting_mode, transform, path_builder)
+    }
+
+    /// Returns the vector path for a glyph, in font units, as a `lyon_path` path.
+    ///
+    /// See `Loader::outline_path()`.
+    #[inline]
+    pub fn outline_path(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<lyon_path::Path, GlyphLoadingError> {
+        <Self as Loader>::outline_path(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the vector outline of a glyph, in font units, as an owned `Outline`.
+    ///
+    /// See `Loader::glyph_outline()`.
+    #[inline]
+    pub fn glyph_outline(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<Outline, GlyphLoadingError> {
+        <Self as Loader>::glyph_outline(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the unhinted outline of a glyph, scaled to `point_size`, as the `d` attribute of
+    /// an SVG `path` element.
+    ///
+    /// See `Loader::glyph_svg_path()`.
+    #[inline]
+    pub fn glyph_svg_path(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+    ) -> Result<String, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_path(self, glyph_id, point_size)
+    }
+
+    /// Returns the OpenType layout features that the font's `GSUB` and `GPOS` tables declare,
+    /// grouped by script and language system.
+    #[inline]
+    pub fn layout_features(&self) -> LayoutFeatures {
+        <Self as Loader>::layout_features(self)
+    }
+
+    /// Returns the OpenType script tags that the font's `GSUB` and `GPOS` tables declare, sorted.
+    #[inline]
+    pub fn supported_scripts(&self) -> Vec<ScriptTag> {
+        <Self as Loader>::supported_scripts(self)
+    }
+
+    /// Returns the OpenType language system tags that the font's `GSUB` and `GPOS` tables
+    /// declare for the given script, sorted.
+    #[inline]
+    pub fn supported_languages(&self, script: ScriptTag) -> Vec<LangTag> {
+        <Self as Loader>::supported_languages(self, script)
+    }
+
+    /// Returns the position of the baseline with the given tag for the given script in
+    /// horizontal text, in font units, from the `BASE` table.
+    #[inline]
+    pub fn baseline(&self, baseline_tag: u32, script: ScriptTag) -> Option<f32> {
+        <Self as Loader>::baseline(self, baseline_tag, script)
+    }
+
+    /// Returns every record of the font's `name` table, in every language, in table order.
+    #[inline]
+    pub fn name_records(&self) -> Vec<NameRecord> {
+        <Self as Loader>::name_records(self)
+    }
+
+    /// Returns the family name of the font in the language of a BCP 47 locale, falling back to
+    /// English and then to the first name available.
+    #[inline]
+    pub fn localized_family_name(&self, locale: &str) -> Option<String> {
+        <Self as Loader>::localized_family_name(self, locale)
+    }
+
+    /// Returns the math layout constants of the font from its `MATH` table, in font units, or
+    /// `None` if the font has no such table.
+    #[inline]
+    pub fn math_constants(&self) -> Option<MathConstants> {
+        <Self as Loader>::math_constants(self)
+    }
+
+    /// Returns the prebuilt variants of a glyph for stretching in the given direction, from
+    /// smallest to largest, as listed in the font's `MATH` table.
+    #[inline]
+    pub fn math_glyph_variants(
+        &self,
+        glyph_id: u32,
+        direction: StretchDirection,
+    ) -> Vec<GlyphVariant> {
+        <Self as Loader>::math_glyph_variants(self, glyph_id, direction)
+    }
+
+    /// Returns the flags and signature formats of the font's `DSIG` table, or `None` if it has no
+    /// such table.
+    #[inline]
+    pub fn digital_signature(&self) -> Option<SignatureInfo> {
+        <Self as Loader>::digital_signature(self)
+    }
+
+    /// Returns true if the font's `DSIG` table holds at least one signature.
+    #[inline]
+    pub fn has_digital_signature(&self) -> bool {
+        <Self as Loader>::has_digital_signature(self)
+    }
+}
+
+impl Loader for Font {
+    type NativeFont = NativeFont;
+
+    #[inline]
+    fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Self, FontLoadingError> {
+        Font::from_bytes(font_data, font_index)
+    }
+
+    #[inline]
+    fn from_file(file: &mut File, font_index: u32) -> Result<Font, FontLoadingError> {
+        Font::from_file(file, font_index)
+    }
+
+    #[inline]
+    unsafe fn from_native_font(native_font: Self::NativeFont) -> Self {
+        Font::from_native_font(native_font)
+    }
+
+    #[inline]
+    fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        Font::analyze_bytes(font_data)
+    }
+
+    #[inline]
+    fn analyze_file(file: &mut File) -> Result<FileType, FontLoadingError> {
+        Font::analyze_file(file)
+    }
+
+    #[inline]
+    fn native_font(&self) -> Self::NativeFont {
+        self.native_font()
+    }
+
+    #[inline]
+    fn postscript_name(&self) -> Option<String> {
+        self.postscript_name()
+    }
+
+    #[inline]
+    fn full_name(&self) -> String {
+        self.full_name()
+    }
+
+    #[inline]
+    fn family_name(&self) -> String {
+        self.family_name()
+    }
+
+    #[inline]
+    fn is_monospace(&self) -> bool {
+        self.is_monospace()
+    }
+
+    #[inline]
+    fn properties(&self) -> Properties {
+        self.properties()
     }
 
     #[inline]
@@ -660,6 +1786,16 @@ impl Loader for Font {
         self.glyph_for_char(character)
     }
 
+    #[inline]
+    fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        self.glyphs_for_str(text)
+    }
+
+    #[inline]
+    fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        self.chars_for_glyph(glyph_id)
+    }
+
     #[inline]
     fn glyph_by_name(&self, name: &str) -> Option<u32> {
         self.glyph_by_name(name)
@@ -688,11 +1824,24 @@ impl Loader for Font {
         self.typographic_bounds(glyph_id)
     }
 
+    #[inline]
+    fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        self.typographic_bounds_batch(glyph_ids)
+    }
+
     #[inline]
     fn advance(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
         self.advance(glyph_id)
     }
 
+    #[inline]
+    fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        self.advances(glyph_ids)
+    }
+
     #[inline]
     fn origin(&self, glyph_id: u32) -> Result<Point2D<f32>, GlyphLoadingError> {
         self.origin(glyph_id)
@@ -703,11 +1852,21 @@ impl Loader for Font {
         self.metrics()
     }
 
+    #[inline]
+    fn units_per_em(&self) -> u32 {
+        self.units_per_em()
+    }
+
     #[inline]
     fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
         self.copy_font_data()
     }
 
+    #[inline]
+    fn font_data(&self) -> Option<loader::FontData<'_>> {
+        self.font_data()
+    }
+
     #[inline]
     fn supports_hinting_options(
         &self,
@@ -718,7 +1877,7 @@ impl Loader for Font {
     }
 
     #[inline]
-    fn rasterize_glyph(
+    fn rasterize_glyph_with_adjustments(
         &self,
         canvas: &mut Canvas,
         glyph_id: u32,
@@ -727,8 +1886,9 @@ impl Loader for Font {
         origin: &Point2D<f32>,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
     ) -> Result<(), GlyphLoadingError> {
-        self.rasterize_glyph(
+        self.rasterize_glyph_with_adjustments(
             canvas,
             glyph_id,
             point_size,
@@ -736,6 +1896,7 @@ impl Loader for Font {
             origin,
             hinting_options,
             rasterization_options,
+            rasterization_adjustments,
         )
     }
 
@@ -748,6 +1909,32 @@ impl Loader for Font {
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
         self.load_font_table(table_tag)
     }
+
+    #[inline]
+    fn table_tags(&self) -> Vec<u32> {
+        self.table_tags()
+    }
+
+    #[inline]
+    fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        self.set_variations(coords)
+    }
+
+    #[inline]
+    fn variations(&self) -> Vec<(u32, f32)> {
+        self.variations()
+    }
+}
+
+impl Clone for Font {
+    #[inline]
+    fn clone(&self) -> Font {
+        Font {
+            core_text_font: self.core_text_font.clone(),
+            font_data: self.font_data.clone(),
+            reverse_cmap: Mutex::new((*self.reverse_cmap.lock().unwrap()).clone()),
+        }
+    }
 }
 
 impl Debug for Font {
@@ -760,6 +1947,8 @@ impl Debug for Font {
 enum FontData {
     Unavailable,
     Memory(Arc<Vec<u8>>),
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<Mmap>),
 }
 
 impl Deref for FontData {
@@ -768,10 +1957,27 @@ impl Deref for FontData {
         match *self {
             FontData::Unavailable => panic!("Font data unavailable!"),
             FontData::Memory(ref data) => &***data,
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => mapping,
         }
     }
 }
 
+/// Lends a memory mapping to Core Graphics, keeping it alive until the data provider is released.
+#[cfg(feature = "mmap")]
+struct MappedData(Arc<Mmap>);
+
+#[cfg(feature = "mmap")]
+impl CustomData for MappedData {
+    unsafe fn ptr(&self) -> *const u8 {
+        self.0.as_ptr()
+    }
+
+    unsafe fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
 trait CGPointExt {
     fn to_euclid_point(&self) -> Point2D<f32>;
 }
@@ -783,6 +1989,34 @@ impl CGPointExt for CGPoint {
     }
 }
 
+fn core_text_glyph_for_char(core_text_font: &CTFont, character: char) -> Option<u32> {
+    unsafe {
+        let (mut dest, mut src) = ([0, 0], [0, 0]);
+        let src = character.encode_utf16(&mut src);
+        core_text_font.get_glyphs_for_characters(src.as_ptr(), dest.as_mut_ptr(), 2);
+
+        let id = dest[0] as u32;
+        if id != 0 {
+            Some(id)
+        } else {
+            None
+        }
+    }
+}
+
+fn cg_rect_to_font_units(rect: &CGRect, units_per_point: f64) -> Rect<f32> {
+    Rect::new(
+        Point2D::new(
+            (rect.origin.x * units_per_point) as f32,
+            (rect.origin.y * units_per_point) as f32,
+        ),
+        Size2D::new(
+            (rect.size.width * units_per_point) as f32,
+            (rect.size.height * units_per_point) as f32,
+        ),
+    )
+}
+
 fn core_text_to_css_font_weight(core_text_weight: f32) -> Weight {
     let index = sources::core_text::piecewise_linear_find_index(
         core_text_weight,
@@ -847,6 +2081,23 @@ fn format_to_cg_color_space_and_image_format(format: Format) -> Option<(CGColorS
     }
 }
 
+extern "C" {
+    fn CTFontCopyVariation(font: CTFontRef) -> CFDictionaryRef;
+    fn CTFontGetBoundingRectsForGlyphs(
+        font: CTFontRef,
+        orientation: CTFontOrientation,
+        glyphs: *const CGGlyph,
+        bounding_rects: *mut CGRect,
+        count: CFIndex,
+    ) -> CGRect;
+    fn CTFontCreateCopyWithAttributes(
+        font: CTFontRef,
+        size: CGFloat,
+        matrix: *const CGAffineTransform,
+        attributes: CTFontDescriptorRef,
+    ) -> CTFontRef;
+}
+
 #[cfg(test)]
 mod test {
     use super::Font;
@@ -23,8 +23,9 @@ use dwrote::InformationalStringId as DWriteInformationalStringId;
 use dwrote::{DWRITE_TEXTURE_ALIASED_1x1, DWRITE_RENDERING_MODE_NATURAL};
 use dwrote::{DWRITE_TEXTURE_CLEARTYPE_3x1, OutlineBuilder};
 use dwrote::{DWRITE_GLYPH_RUN, DWRITE_MEASURING_MODE_NATURAL, DWRITE_RENDERING_MODE_ALIASED};
-use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
 use euclid::point2;
+use log::warn;
 use lyon_path::builder::PathBuilder;
 use std::borrow::Cow;
 use std::ffi::OsString;
@@ -34,22 +35,52 @@ use std::io::{self, Read, Seek, SeekFrom};
 use std::os::windows::ffi::OsStringExt;
 use std::os::windows::io::AsRawHandle;
 use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::{Arc, Mutex};
+use winapi::ctypes::c_void;
 use winapi::shared::minwindef::{FALSE, MAX_PATH};
+use winapi::shared::winerror::S_OK;
 use winapi::um::dwrite::{
-    DWRITE_NUMBER_SUBSTITUTION_METHOD_NONE, DWRITE_READING_DIRECTION,
-    DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+    DWRITE_FONT_SIMULATIONS, DWRITE_GLYPH_METRICS, DWRITE_NUMBER_SUBSTITUTION_METHOD_NONE,
+    DWRITE_READING_DIRECTION, DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
 };
+use winapi::um::dwrite_3::{IDWriteFontFace5, DWRITE_FONT_AXIS_VALUE};
 use winapi::um::fileapi;
+use winapi::Interface;
 
-use crate::canvas::{Canvas, Format, RasterizationOptions};
+use crate::bitmap::{BitmapGlyph, BitmapStrike};
+use crate::canvas::{Canvas, Format, RasterizationAdjustments, RasterizationOptions};
+use crate::color::{ColorFormat, ColorLayer, ColorPalette, Paint};
+use crate::coverage::CodepointCoverage;
+use crate::embedding::EmbeddingRestrictions;
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
+use crate::gzip;
 use crate::handle::Handle;
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackFont, FallbackResult, FontTransform, Loader};
-use crate::metrics::Metrics;
+use crate::layout::{GlyphClass, LangTag, LayoutFeatures, ScriptTag};
+use crate::loader::{
+    self, CollectionFonts, FallbackFont, FallbackResult, FontData, FontTransform, Glyphs, Loader,
+};
+use crate::math::{GlyphVariant, MathConstants, StretchDirection};
+use crate::metrics::{HorizontalMetrics, MaxpLimits, Metrics};
+use crate::names::NameRecord;
+use crate::outline::Outline;
+use crate::outline_format::OutlineFormat;
+use crate::panose::Panose;
 use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::sfnt;
+#[cfg(feature = "harfbuzz")]
+use crate::shaping::{Direction, Feature, Script, ShapedGlyph};
+use crate::signature::SignatureInfo;
+use crate::subset::FontSubset;
+use crate::synthesis::Synthesis;
+use crate::tables::cmap::{self, ReverseCmap};
+use crate::tables::hhea;
+use crate::tables::os_2;
+use crate::tables::vhea::{self, VheaTable};
+use crate::variations::{self, NamedInstance, VariationAxis};
+use crate::woff;
 
 const ERROR_BOUND: f32 = 0.0001;
 
@@ -66,6 +97,7 @@ pub struct Font {
     dwrite_font: DWriteFont,
     dwrite_font_face: DWriteFontFace,
     cached_data: Mutex<Option<Arc<Vec<u8>>>>,
+    reverse_cmap: Mutex<Option<Arc<ReverseCmap>>>,
 }
 
 struct MyTextAnalysisSource {
@@ -104,6 +136,7 @@ impl Font {
                     dwrite_font,
                     dwrite_font_face,
                     cached_data: Mutex::new(font_data),
+                    reverse_cmap: Mutex::new(None),
                 });
             }
         }
@@ -115,6 +148,7 @@ impl Font {
     /// If the data represents a collection (`.ttc`/`.otc`/etc.), `font_index` specifies the index
     /// of the font to load from it. If the data represents a single font, pass 0 for `font_index`.
     pub fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Font, FontLoadingError> {
+        let font_data = woff::to_sfnt(gzip::decompress(font_data)?)?;
         let font_file =
             DWriteFontFile::new_from_data(font_data.clone()).ok_or(FontLoadingError::Parse)?;
         Font::from_dwrite_font_file(font_file, font_index, Some(font_data))
@@ -128,7 +162,7 @@ impl Font {
         unsafe {
             let mut path = vec![0; MAX_PATH + 1];
             let path_len = fileapi::GetFinalPathNameByHandleW(
-                file.as_raw_handle(),
+                file.as_raw_handle() as _,
                 path.as_mut_ptr(),
                 path.len() as u32 - 1,
                 0,
@@ -147,17 +181,48 @@ impl Font {
     /// font to load from it. If the file represents a single font, pass 0 for `font_index`.
     #[inline]
     pub fn from_path<P: AsRef<Path>>(path: P, font_index: u32) -> Result<Font, FontLoadingError> {
+        // DirectWrite can't read WOFF or gzip-compressed files itself, so decompress them into
+        // memory.
+        let mut signature = [0; 4];
+        let mut file = File::open(path.as_ref())?;
+        if file.read_exact(&mut signature).is_ok()
+            && (woff::web_font_type(&signature).is_some() || gzip::is_compressed(&signature))
+        {
+            file.seek(SeekFrom::Start(0))?;
+            let mut font_data = vec![];
+            file.read_to_end(&mut font_data)?;
+            return Font::from_bytes(Arc::new(font_data), font_index);
+        }
         let font_file = DWriteFontFile::new_from_path(path).ok_or(FontLoadingError::Parse)?;
         Font::from_dwrite_font_file(font_file, font_index, None)
     }
 
+    /// Loads a font from the path to a `.ttf`/`.otf`/etc. file without reading the whole file
+    /// into memory.
+    ///
+    /// DirectWrite already reads font files on demand, so this is the same as `from_path()`. It
+    /// exists so that code using the `mmap` Cargo feature works with every loader.
+    #[cfg(feature = "mmap")]
+    #[inline]
+    pub fn from_path_mmap<P: AsRef<Path>>(
+        path: P,
+        font_index: u32,
+    ) -> Result<Font, FontLoadingError> {
+        Font::from_path(path, font_index)
+    }
+
     /// Creates a font from a native API handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid, live font object of the underlying platform API.
     #[inline]
     pub unsafe fn from_native_font(native_font: NativeFont) -> Font {
         Font {
             dwrite_font: native_font.dwrite_font,
             dwrite_font_face: native_font.dwrite_font_face,
             cached_data: Mutex::new(None),
+            reverse_cmap: Mutex::new(None),
         }
     }
 
@@ -170,6 +235,13 @@ impl Font {
     /// Determines whether a blob of raw font data represents a supported font, and, if so, what
     /// type of font it is.
     pub fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        if gzip::is_compressed(&font_data) {
+            return Font::analyze_bytes(gzip::decompress(font_data)?);
+        }
+        if let Some(file_type) = woff::web_font_type(&font_data) {
+            let font_data = woff::to_sfnt(font_data)?;
+            return Font::analyze_bytes(font_data).map(|_| file_type);
+        }
         match DWriteFontFile::analyze_data(font_data) {
             0 => Err(FontLoadingError::Parse),
             1 => Ok(FileType::Single),
@@ -189,6 +261,9 @@ impl Font {
     }
 
     /// Returns the wrapped native font handle.
+    ///
+    /// The returned `IDWriteFont` and `IDWriteFontFace` are new references to the same objects,
+    /// and are released when they are dropped.
     pub fn native_font(&self) -> NativeFont {
         NativeFont {
             dwrite_font: self.dwrite_font.clone(),
@@ -202,6 +277,77 @@ impl Font {
         <Self as Loader>::analyze_path(path)
     }
 
+    /// Returns a fingerprint of the font at `font_index` in the given file, without loading the
+    /// font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_file(file: &mut File, font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_file(file, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in the file at the given path, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_path<P: AsRef<Path>>(
+        path: P,
+        font_index: u32,
+    ) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_path(path, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in a blob of raw font data, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_bytes(font_data: &[u8], font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_bytes(font_data, font_index)
+    }
+
+    /// Returns the number of fonts in a blob of raw font data: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    pub fn count_in_collection_bytes(font_data: Arc<Vec<u8>>) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection_bytes(font_data)
+    }
+
+    /// Returns the number of fonts in the file at a path: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    pub fn count_in_collection<P: AsRef<Path>>(path: P) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection(path)
+    }
+
+    /// Returns an iterator that loads each font in a blob of raw font data in turn.
+    #[inline]
+    pub fn all_in_collection_bytes(
+        font_data: Arc<Vec<u8>>,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection_bytes(font_data)
+    }
+
+    /// Loads the font with the given PostScript name from a blob of raw font data.
+    ///
+    /// See `Loader::from_bytes_by_postscript_name()`.
+    #[inline]
+    pub fn from_bytes_by_postscript_name(
+        font_data: Arc<Vec<u8>>,
+        postscript_name: &str,
+    ) -> Result<Font, FontLoadingError> {
+        <Self as Loader>::from_bytes_by_postscript_name(font_data, postscript_name)
+    }
+
+    /// Returns an iterator that loads each font in the file at a path in turn.
+    #[inline]
+    pub fn all_in_collection<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection(path)
+    }
+
     /// Returns the PostScript name of the font. This should be globally unique.
     #[inline]
     pub fn postscript_name(&self) -> Option<String> {
@@ -225,19 +371,30 @@ impl Font {
     }
 
     /// Returns true if and only if the font is monospace (fixed-width).
-    #[inline]
     pub fn is_monospace(&self) -> bool {
-        self.dwrite_font.is_monospace().unwrap_or(false)
+        loader::is_monospace(self, self.dwrite_font.is_monospace().unwrap_or(false))
     }
 
     /// Returns the values of various font properties, corresponding to those defined in CSS.
+    ///
+    /// For variable fonts, the weight, stretch, and style reflect the current variation
+    /// coordinates of the `wght`, `wdth`, `slnt`, and `ital` axes.
     pub fn properties(&self) -> Properties {
         let dwrite_font = &self.dwrite_font;
-        Properties {
+        let properties = Properties {
             style: style_for_dwrite_style(dwrite_font.style()),
             stretch: Stretch(Stretch::MAPPING[(dwrite_font.stretch() as usize) - 1]),
             weight: Weight(dwrite_font.weight().to_u32() as f32),
-        }
+            italic_angle: Some(self.italic_angle()),
+        };
+        variations::apply_to_properties(properties, &self.variations())
+    }
+
+    /// Returns the slant of the font in degrees counterclockwise from vertical, from the
+    /// `italicAngle` field of its `post` table.
+    #[inline]
+    pub fn italic_angle(&self) -> f32 {
+        <Self as Loader>::italic_angle(self)
     }
 
     /// Returns the usual glyph ID for a Unicode character.
@@ -251,9 +408,53 @@ impl Font {
             .get_glyph_indices(&chars)
             .into_iter()
             .next()
+            .filter(|&g| g != 0)
             .map(|g| g as u32)
     }
 
+    /// Returns the glyph ID for a Unicode character followed by a variation selector, such as
+    /// U+FE0E or U+FE0F for the text and emoji presentations or U+E0100 and up for ideographic
+    /// variants.
+    ///
+    /// The variation sequences come from the format 14 subtable of the `cmap`. If the font has no
+    /// specific glyph for the sequence, this returns the usual glyph for the character, like
+    /// `glyph_for_char()`.
+    #[inline]
+    pub fn glyph_for_char_with_variation(
+        &self,
+        character: char,
+        variation_selector: char,
+    ) -> Option<u32> {
+        <Self as Loader>::glyph_for_char_with_variation(self, character, variation_selector)
+    }
+
+    /// Returns the glyph ID for each character of the given string, in order, or `None` for
+    /// characters that the font has no glyph for.
+    ///
+    /// All of the characters are looked up with a single DirectWrite call.
+    pub fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        let characters: Vec<u32> = text.chars().map(|character| character as u32).collect();
+        if characters.is_empty() {
+            return vec![];
+        }
+        self.dwrite_font_face
+            .get_glyph_indices(&characters)
+            .into_iter()
+            .map(|glyph_id| match glyph_id {
+                0 => None,
+                glyph_id => Some(glyph_id as u32),
+            })
+            .collect()
+    }
+
+    /// Returns the name of a glyph, the reverse of `glyph_by_name()`.
+    ///
+    /// See `Loader::glyph_name()`.
+    #[inline]
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        <Self as Loader>::glyph_name(self, glyph_id)
+    }
+
     /// Returns the number of glyphs in the font.
     ///
     /// Glyph IDs range from 0 inclusive to this value exclusive.
@@ -262,6 +463,14 @@ impl Font {
         self.dwrite_font_face.get_glyph_count() as u32
     }
 
+    /// Returns an iterator over every glyph of the font, in order of glyph ID, with its metrics.
+    ///
+    /// See `Loader::glyphs()`.
+    #[inline]
+    pub fn glyphs(&self) -> Glyphs<'_, Font> {
+        <Self as Loader>::glyphs(self)
+    }
+
     /// Sends the vector path for a glyph to a path builder.
     ///
     /// If `hinting_mode` is not None, this function performs grid-fitting as requested before
@@ -296,24 +505,35 @@ impl Font {
         let metrics = self
             .dwrite_font_face
             .get_design_glyph_metrics(&[glyph_id as u16], false);
+        Ok(design_glyph_metrics_to_bounds(&metrics[0]))
+    }
 
-        let metrics = &metrics[0];
-        let advance_width = metrics.advanceWidth as i32;
-        let advance_height = metrics.advanceHeight as i32;
-        let left_side_bearing = metrics.leftSideBearing as i32;
-        let right_side_bearing = metrics.rightSideBearing as i32;
-        let top_side_bearing = metrics.topSideBearing as i32;
-        let bottom_side_bearing = metrics.bottomSideBearing as i32;
-        let vertical_origin_y = metrics.verticalOriginY as i32;
-
-        let y_offset = vertical_origin_y + bottom_side_bearing - advance_height;
-        let width = advance_width - (left_side_bearing + right_side_bearing);
-        let height = advance_height - (top_side_bearing + bottom_side_bearing);
-
-        Ok(Rect::new(
-            Point2D::new(left_side_bearing as f32, y_offset as f32),
-            Size2D::new(width as f32, height as f32),
-        ))
+    /// Returns the boundaries of each of the given glyphs in font units, in order, with a single
+    /// call to DirectWrite.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    pub fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        let glyph_count = self.glyph_count();
+        let glyphs = glyph_ids
+            .iter()
+            .map(|&glyph_id| {
+                if glyph_id < glyph_count {
+                    Ok(glyph_id as u16)
+                } else {
+                    Err(GlyphLoadingError::NoSuchGlyph)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if glyphs.is_empty() {
+            return Ok(vec![]);
+        }
+        let metrics = self
+            .dwrite_font_face
+            .get_design_glyph_metrics(&glyphs, false);
+        Ok(metrics.iter().map(design_glyph_metrics_to_bounds).collect())
     }
 
     /// Returns the distance from the origin of the glyph with the given ID to the next, in font
@@ -326,6 +546,55 @@ impl Font {
         Ok(Vector2D::new(metrics.advanceWidth as f32, 0.0))
     }
 
+    /// Returns the advances of each of the given glyphs in font units, in order, with a single
+    /// call to DirectWrite.
+    ///
+    /// If any of the glyphs doesn't exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    pub fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        let glyph_count = self.glyph_count();
+        let glyphs = glyph_ids
+            .iter()
+            .map(|&glyph_id| {
+                if glyph_id < glyph_count {
+                    Ok(glyph_id as u16)
+                } else {
+                    Err(GlyphLoadingError::NoSuchGlyph)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if glyphs.is_empty() {
+            return Ok(vec![]);
+        }
+        let metrics = self
+            .dwrite_font_face
+            .get_design_glyph_metrics(&glyphs, false);
+        Ok(metrics
+            .iter()
+            .map(|metrics| Vector2D::new(metrics.advanceWidth as f32, 0.0))
+            .collect())
+    }
+
+    /// Returns the advance of the glyph with the given ID in font units, as recorded in the
+    /// font's `hmtx` table, independent of any point size.
+    ///
+    /// Unlike `advance()`, this doesn't reflect variations applied with `set_variations()`.
+    #[inline]
+    pub fn advance_font_units(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::advance_font_units(self, glyph_id)
+    }
+
+    /// Returns the advance width and left side bearing of the given glyph, in font units, from
+    /// the `hmtx` table.
+    ///
+    /// See `Loader::horizontal_metrics()`.
+    #[inline]
+    pub fn horizontal_metrics(
+        &self,
+        glyph_id: u32,
+    ) -> Result<HorizontalMetrics, GlyphLoadingError> {
+        <Self as Loader>::horizontal_metrics(self, glyph_id)
+    }
+
     /// Returns the amount that the given glyph should be displaced from the origin.
     pub fn origin(&self, glyph: u32) -> Result<Point2D<f32>, GlyphLoadingError> {
         let metrics = self
@@ -337,12 +606,42 @@ impl Font {
         ))
     }
 
+    /// Returns the number of font units per em, from the `head` table.
+    ///
+    /// This is always the same as the `units_per_em` field of `metrics()`.
+    #[inline]
+    pub fn units_per_em(&self) -> u32 {
+        self.dwrite_font.metrics().designUnitsPerEm as u32
+    }
+
     /// Retrieves various metrics that apply to the entire font.
     pub fn metrics(&self) -> Metrics {
         let dwrite_font = &self.dwrite_font;
         let dwrite_metrics = dwrite_font.metrics();
+        let vhea = self
+            .load_font_table(vhea::TAG)
+            .and_then(|table| VheaTable::parse(&table));
+        let os_2_data = self.load_font_table(os_2::TAG);
+        let hhea_data = self.load_font_table(hhea::TAG);
+        let (strikeout_size, strikeout_position) = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::strikeout(table))
+            .unwrap_or((0, 0));
+        let (caret_slope_rise, caret_slope_run) = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::caret_slope(table))
+            .unwrap_or((1, 0));
+        let hhea_metrics = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::line_metrics(table));
+        let typo_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::typo_metrics(table));
+        let win_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::win_metrics(table));
         Metrics {
-            units_per_em: dwrite_metrics.designUnitsPerEm as u32,
+            units_per_em: self.units_per_em(),
             ascent: dwrite_metrics.ascent as f32,
             descent: -(dwrite_metrics.descent as f32),
             line_gap: dwrite_metrics.lineGap as f32,
@@ -350,7 +649,35 @@ impl Font {
             x_height: dwrite_metrics.xHeight as f32,
             underline_position: dwrite_metrics.underlinePosition as f32,
             underline_thickness: dwrite_metrics.underlineThickness as f32,
+            strikeout_position: strikeout_position as f32,
+            strikeout_size: strikeout_size as f32,
+            vertical_ascent: vhea.map(|vhea| vhea.ascent as f32),
+            vertical_descent: vhea.map(|vhea| vhea.descent as f32),
+            vertical_line_gap: vhea.map(|vhea| vhea.line_gap as f32),
+            hhea_ascent: hhea_metrics.map(|(ascent, _, _)| ascent as f32),
+            hhea_descent: hhea_metrics.map(|(_, descent, _)| descent as f32),
+            hhea_line_gap: hhea_metrics.map(|(_, _, line_gap)| line_gap as f32),
+            typo_ascent: typo_metrics.map(|(ascent, _, _)| ascent as f32),
+            typo_descent: typo_metrics.map(|(_, descent, _)| descent as f32),
+            typo_line_gap: typo_metrics.map(|(_, _, line_gap)| line_gap as f32),
+            win_ascent: win_metrics.map(|(ascent, _)| ascent as f32),
+            win_descent: win_metrics.map(|(_, descent)| -(descent as f32)),
+            use_typo_metrics: os_2_data
+                .as_ref()
+                .is_some_and(|table| os_2::use_typo_metrics(table)),
+            caret_slope_rise: caret_slope_rise as f32,
+            caret_slope_run: caret_slope_run as f32,
         }
+        .with_typo_line_metrics()
+        .with_decoration_fallbacks()
+    }
+
+    /// Returns the limits that the font's TrueType outlines stay within, from its `maxp` table.
+    ///
+    /// See `Loader::maxp_limits()`.
+    #[inline]
+    pub fn maxp_limits(&self) -> Option<MaxpLimits> {
+        <Self as Loader>::maxp_limits(self)
     }
 
     /// Returns a handle to this font, if possible.
@@ -377,8 +704,18 @@ impl Font {
         (*font_data).clone()
     }
 
+    /// Returns the raw font data without copying it, if it's available.
+    ///
+    /// The data is read from the font file once and then shared with this font.
+    #[inline]
+    pub fn font_data(&self) -> Option<FontData<'_>> {
+        <Self as Loader>::font_data(self)
+    }
+
     /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
     /// rasterizer at the given size and origin.
+    ///
+    /// See `Loader::raster_bounds()`.
     #[inline]
     pub fn raster_bounds(
         &self,
@@ -389,30 +726,53 @@ impl Font {
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
     ) -> Result<Rect<i32>, GlyphLoadingError> {
-        let dwrite_analysis = self.build_glyph_analysis(
+        <Self as Loader>::raster_bounds(
+            self,
             glyph_id,
             point_size,
             transform,
             origin,
             hinting_options,
             rasterization_options,
+        )
+    }
+
+    /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
+    /// rasterizer at the given size and origin, with the given adjustments.
+    ///
+    /// The bounds are estimated from the glyph's design metrics rather than by having DirectWrite
+    /// analyze the glyph run, which rasterizes it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        _: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        let raster_bounds = loader::typographic_raster_bounds(
+            self,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            HintingOptions::None,
+            rasterization_options,
+            rasterization_adjustments,
         )?;
 
-        let texture_type = match rasterization_options {
-            RasterizationOptions::Bilevel => DWRITE_TEXTURE_ALIASED_1x1,
-            RasterizationOptions::GrayscaleAa | RasterizationOptions::SubpixelAa => {
-                DWRITE_TEXTURE_CLEARTYPE_3x1
+        // The default bounds leave room for subpixel filtering, but DirectWrite also filters the
+        // ClearType textures that it renders grayscale antialiasing with. It grid-fits glyphs
+        // whatever the hinting options are, too.
+        match rasterization_options {
+            RasterizationOptions::GrayscaleAa => Ok(raster_bounds.inflate(2, 1)),
+            RasterizationOptions::Bilevel | RasterizationOptions::SubpixelAa => {
+                Ok(raster_bounds.inflate(1, 1))
             }
-        };
-
-        let texture_bounds = dwrite_analysis.get_alpha_texture_bounds(texture_type)?;
-        let texture_width = texture_bounds.right - texture_bounds.left;
-        let texture_height = texture_bounds.bottom - texture_bounds.top;
-
-        Ok(Rect::new(
-            Point2D::new(texture_bounds.left, texture_bounds.top),
-            Size2D::new(texture_width, texture_height).to_i32(),
-        ))
+        }
     }
 
     /// Rasterizes a glyph to a canvas with the given size and origin.
@@ -424,6 +784,10 @@ impl Font {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// See `Loader::rasterize_glyph()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
     pub fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -434,6 +798,49 @@ impl Font {
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, with the given adjustments to
+    /// its coverage.
+    ///
+    /// See `Loader::rasterize_glyph_with_adjustments()`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rasterize_glyph_with_adjustments(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<(), GlyphLoadingError> {
+        if let Some(threshold) = rasterization_adjustments.bilevel_threshold(rasterization_options)
+        {
+            return loader::rasterize_glyph_with_bilevel_threshold(
+                self,
+                canvas,
+                glyph_id,
+                point_size,
+                transform,
+                origin,
+                hinting_options,
+                rasterization_adjustments,
+                threshold,
+            );
+        }
+
         // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
         // implementation.
 
@@ -476,6 +883,7 @@ impl Font {
 
         let mut texture_bytes =
             dwrite_analysis.create_alpha_texture(texture_type, texture_bounds)?;
+        rasterization_adjustments.apply_gamma(rasterization_options, &mut texture_bytes);
         canvas.blit_from(
             point2(texture_bounds.left, texture_bounds.top),
             &mut texture_bytes,
@@ -504,10 +912,19 @@ impl Font {
             | (HintingOptions::VerticalSubpixel(_), true) => true,
             (HintingOptions::Vertical(_), false)
             | (HintingOptions::VerticalSubpixel(_), false)
-            | (HintingOptions::Full(_), _) => false,
+            | (HintingOptions::Full(_), _)
+            | (HintingOptions::Autohint(_), _) => false,
         }
     }
 
+    /// Returns true if the font carries hints for its outlines.
+    ///
+    /// See `Loader::has_hinting_instructions()`.
+    #[inline]
+    pub fn has_hinting_instructions(&self) -> bool {
+        <Self as Loader>::has_hinting_instructions(self)
+    }
+
     fn build_glyph_analysis(
         &self,
         glyph_id: u32,
@@ -607,6 +1024,7 @@ impl Font {
                 dwrite_font,
                 dwrite_font_face,
                 cached_data: Mutex::new(None),
+                reverse_cmap: Mutex::new(None),
             };
             let fallback_font = FallbackFont {
                 font,
@@ -629,6 +1047,644 @@ impl Font {
             .get_font_table(table_tag)
             .map(|v| v.into())
     }
+
+    /// Returns the tags of the tables in the font's sfnt table directory, in directory order.
+    ///
+    /// This doesn't load any of the tables.
+    ///
+    /// DirectWrite doesn't expose the table directory, so this reads it from the font data.
+    pub fn table_tags(&self) -> Vec<u32> {
+        self.copy_font_data()
+            .and_then(|font_data| sfnt::table_tags(&font_data, self.dwrite_font_face.get_index()))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the font has a table with the given tag, without loading it.
+    #[inline]
+    pub fn contains_table(&self, table_tag: u32) -> bool {
+        <Self as Loader>::contains_table(self, table_tag)
+    }
+
+    /// Returns the format of the font's glyph outlines, from the tables present.
+    ///
+    /// See `Loader::outline_format()`.
+    #[inline]
+    pub fn outline_format(&self) -> OutlineFormat {
+        <Self as Loader>::outline_format(self)
+    }
+
+    /// Returns the color glyph technologies that the font carries, from the tables present.
+    ///
+    /// See `Loader::color_format()`.
+    #[inline]
+    pub fn color_format(&self) -> ColorFormat {
+        <Self as Loader>::color_format(self)
+    }
+
+    /// Returns true if the font is a variable font, that is, if it has an `fvar` table.
+    #[inline]
+    pub fn is_variable(&self) -> bool {
+        <Self as Loader>::is_variable(self)
+    }
+
+    /// Returns the design axes of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    #[inline]
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        <Self as Loader>::variation_axes(self)
+    }
+
+    /// Returns the named instances of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    #[inline]
+    pub fn named_instances(&self) -> Vec<NamedInstance> {
+        <Self as Loader>::named_instances(self)
+    }
+
+    /// Instantiates a variable font at the given point in its design space.
+    ///
+    /// `coords` maps axis tags to values. Values outside the range of an axis are clamped to it,
+    /// and axes that aren't mentioned are left at their default values. Subsequent outlines,
+    /// metrics, and rasterization reflect the chosen instance.
+    ///
+    /// This has no effect on fonts that aren't variable fonts.
+    pub fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return;
+        }
+        let design_coords = variations::design_coordinates(&axes, coords);
+        let axis_values: Vec<DWRITE_FONT_AXIS_VALUE> = axes
+            .iter()
+            .zip(design_coords)
+            .map(|(axis, value)| DWRITE_FONT_AXIS_VALUE {
+                // DirectWrite packs tags with the first character in the low byte.
+                axisTag: axis.tag.swap_bytes(),
+                value,
+            })
+            .collect();
+        match self.dwrite_font_face.create_font_face_with_variations(
+            self.dwrite_font.simulations() as DWRITE_FONT_SIMULATIONS,
+            &axis_values,
+        ) {
+            Some(dwrite_font_face) => self.dwrite_font_face = dwrite_font_face,
+            None => {
+                warn!("set_variations(): failed to create a font face with the given variations")
+            }
+        }
+    }
+
+    /// Returns the current position of a variable font in its design space, as one `(tag, value)`
+    /// pair per axis, in axis order.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    pub fn variations(&self) -> Vec<(u32, f32)> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return vec![];
+        }
+        let mut axis_values = vec![];
+        unsafe {
+            let mut font_face_5: *mut IDWriteFontFace5 = ptr::null_mut();
+            let result = (*self.dwrite_font_face.as_ptr()).QueryInterface(
+                &IDWriteFontFace5::uuidof(),
+                &mut font_face_5 as *mut *mut IDWriteFontFace5 as *mut *mut c_void,
+            );
+            if result == S_OK && !font_face_5.is_null() {
+                let axis_value_count = (*font_face_5).GetFontAxisValueCount();
+                axis_values = vec![
+                    DWRITE_FONT_AXIS_VALUE {
+                        axisTag: 0,
+                        value: 0.0,
+                    };
+                    axis_value_count as usize
+                ];
+                if (*font_face_5).GetFontAxisValues(axis_values.as_mut_ptr(), axis_value_count)
+                    != S_OK
+                {
+                    axis_values.clear();
+                }
+                (*font_face_5).Release();
+            }
+        }
+        axes.iter()
+            .map(|axis| {
+                let value = axis_values
+                    .iter()
+                    .find(|axis_value| axis_value.axisTag == axis.tag.swap_bytes())
+                    .map_or(axis.default_value, |axis_value| axis_value.value);
+                (axis.tag, value)
+            })
+            .collect()
+    }
+
+    /// Returns the layers that make up the given color glyph, as declared in the font's `COLR`
+    /// table, with colors taken from the `CPAL` palette at `palette_index`.
+    ///
+    /// Layers are returned bottommost first. If the glyph isn't a color glyph, this returns an
+    /// empty vector. If the palette doesn't exist, palette 0 is used instead.
+    #[inline]
+    pub fn glyph_color_layers(
+        &self,
+        glyph_id: u32,
+        palette_index: u16,
+    ) -> Result<Vec<ColorLayer>, GlyphLoadingError> {
+        <Self as Loader>::glyph_color_layers(self, glyph_id, palette_index)
+    }
+
+    /// Returns the paint graph of the given `COLR` version 1 color glyph, with colors taken from
+    /// the `CPAL` palette at `palette_index`.
+    ///
+    /// See `Loader::colrv1_paint()`.
+    #[inline]
+    pub fn colrv1_paint(&self, glyph_id: u32, palette_index: u16) -> Option<Paint> {
+        <Self as Loader>::colrv1_paint(self, glyph_id, palette_index)
+    }
+
+    /// Returns the color palettes in the font's `CPAL` table, in order of palette index.
+    ///
+    /// See `Loader::color_palettes()`.
+    #[inline]
+    pub fn color_palettes(&self) -> Vec<ColorPalette> {
+        <Self as Loader>::color_palettes(self)
+    }
+
+    /// Rasterizes a color glyph to an `Rgba32` canvas with the given size and origin, from the
+    /// font's embedded color bitmaps or `COLR` layers.
+    ///
+    /// The result is in premultiplied R8G8B8A8 format; see `Format::Rgba32`. Returns
+    /// `GlyphLoadingError::NoColorData` if the font has no color data.
+    #[inline]
+    pub fn rasterize_color_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_color_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Returns the strikes of pre-rendered bitmap glyphs that the font contains, from its `sbix`,
+    /// `CBLC`/`CBDT`, and `EBLC`/`EBDT` tables.
+    ///
+    /// If the font has no embedded bitmaps, this returns an empty vector.
+    #[inline]
+    pub fn bitmap_strikes(&self) -> Vec<BitmapStrike> {
+        <Self as Loader>::bitmap_strikes(self)
+    }
+
+    /// Returns the bitmap strike that best matches the given size, in pixels per em.
+    ///
+    /// See `Loader::best_strike_for_size()`.
+    #[inline]
+    pub fn best_strike_for_size(&self, pixels_per_em: f32) -> Option<BitmapStrike> {
+        <Self as Loader>::best_strike_for_size(self, pixels_per_em)
+    }
+
+    /// Loads the pre-rendered bitmap of a glyph from one of the strikes returned by
+    /// `bitmap_strikes()`.
+    ///
+    /// Returns `None` if the strike has no bitmap for the glyph.
+    #[inline]
+    pub fn load_bitmap_glyph(
+        &self,
+        glyph_id: u32,
+        strike: &BitmapStrike,
+    ) -> Result<Option<BitmapGlyph>, GlyphLoadingError> {
+        <Self as Loader>::load_bitmap_glyph(self, glyph_id, strike)
+    }
+
+    /// Returns the SVG document from the font's `SVG ` table that describes the given glyph,
+    /// decompressed if necessary.
+    ///
+    /// Returns `None` if the glyph has no SVG representation.
+    #[inline]
+    pub fn glyph_svg_document(&self, glyph_id: u32) -> Result<Option<Vec<u8>>, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_document(self, glyph_id)
+    }
+
+    /// Returns the kerning adjustment between the given pair of glyphs, in pixels at the given
+    /// point size, from the `GPOS` or `kern` table.
+    ///
+    /// If the font doesn't kern the pair, this returns a zero vector.
+    #[inline]
+    pub fn kerning(&self, left_glyph: u32, right_glyph: u32, point_size: f32) -> Vector2D<f32> {
+        <Self as Loader>::kerning(self, left_glyph, right_glyph, point_size)
+    }
+
+    /// Applies the `GSUB` ligature substitutions of the given OpenType features to a run of
+    /// glyphs, returning the substituted run.
+    ///
+    /// If `features` is empty, the `liga` and `clig` features are used.
+    #[inline]
+    pub fn substitute_ligatures(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::substitute_ligatures(self, glyphs, features)
+    }
+
+    /// Applies the `GSUB` contextual and chaining contextual substitutions of the given OpenType
+    /// features to a run of glyphs, returning the substituted run.
+    ///
+    /// See `Loader::apply_contextual_substitutions()`.
+    #[inline]
+    pub fn apply_contextual_substitutions(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::apply_contextual_substitutions(self, glyphs, features)
+    }
+
+    /// Returns the `(input, output)` glyph pairs that the single and alternate substitutions of
+    /// the given OpenType feature can produce, sorted by input glyph.
+    ///
+    /// See `Loader::feature_affects()`.
+    #[inline]
+    pub fn feature_affects(&self, feature: u32) -> Vec<(u32, u32)> {
+        <Self as Loader>::feature_affects(self, feature)
+    }
+
+    /// Returns the alternates of the given glyph, for glyph pickers, in the order the font lists
+    /// them.
+    ///
+    /// See `Loader::glyph_alternates()`.
+    #[inline]
+    pub fn glyph_alternates(&self, glyph_id: u32) -> Vec<u32> {
+        <Self as Loader>::glyph_alternates(self, glyph_id)
+    }
+
+    /// Returns the caret positions within the given ligature glyph, in font units from its
+    /// origin, from the `GDEF` table.
+    ///
+    /// Glyphs that aren't ligatures yield an empty vector.
+    #[inline]
+    pub fn ligature_carets(&self, glyph_id: u32) -> Vec<f32> {
+        <Self as Loader>::ligature_carets(self, glyph_id)
+    }
+
+    /// Returns whether the given glyph is a base glyph, a ligature, a mark, or a component of a
+    /// glyph, from the `GDEF` table.
+    #[inline]
+    pub fn glyph_class(&self, glyph_id: u32) -> GlyphClass {
+        <Self as Loader>::glyph_class(self, glyph_id)
+    }
+
+    /// Returns the mark attachment class of the given glyph from the `GDEF` table, or 0 if it
+    /// has none.
+    #[inline]
+    pub fn mark_attach_class(&self, glyph_id: u32) -> u16 {
+        <Self as Loader>::mark_attach_class(self, glyph_id)
+    }
+
+    /// Shapes a run of text with HarfBuzz, returning its glyphs in visual order with their
+    /// positions in font units.
+    ///
+    /// This method is only available with the `harfbuzz` Cargo feature.
+    #[cfg(feature = "harfbuzz")]
+    #[inline]
+    pub fn shape(
+        &self,
+        text: &str,
+        direction: Direction,
+        script: Script,
+        features: &[Feature],
+    ) -> Vec<ShapedGlyph> {
+        <Self as Loader>::shape(self, text, direction, script, features)
+    }
+
+    /// Returns the set of Unicode codepoints that this font maps to glyphs, read from the best
+    /// Unicode subtable of its `cmap` table.
+    #[inline]
+    pub fn codepoint_coverage(&self) -> CodepointCoverage {
+        <Self as Loader>::codepoint_coverage(self)
+    }
+
+    /// Returns the characters that the font's best Unicode `cmap` subtable maps to the given
+    /// glyph, in codepoint order.
+    ///
+    /// The inverse of the `cmap` is built the first time this is called and cached on the font.
+    pub fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        let reverse_cmap = self
+            .reverse_cmap
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                let data = self.load_font_table(cmap::TAG).unwrap_or_default();
+                Arc::new(ReverseCmap::parse(&data))
+            })
+            .clone();
+        reverse_cmap.chars_for_glyph(glyph_id)
+    }
+
+    /// Builds a new font containing only the given glyphs, along with glyph 0 (`.notdef`) and
+    /// the components of any composite glyphs among them.
+    ///
+    /// The glyphs are renumbered; the returned `FontSubset` records the mapping. Layout, color,
+    /// bitmap, and variation tables are dropped.
+    #[inline]
+    pub fn subset(&self, glyph_ids: &[u32]) -> Result<FontSubset, GlyphLoadingError> {
+        <Self as Loader>::subset(self, glyph_ids)
+    }
+
+    /// Returns the restrictions that the font's license places on embedding it in documents, as
+    /// recorded in the `fsType` field of its `OS/2` table.
+    #[inline]
+    pub fn embedding_restrictions(&self) -> EmbeddingRestrictions {
+        <Self as Loader>::embedding_restrictions(self)
+    }
+
+    /// Returns the PANOSE classification of the font from its `OS/2` table, or `None` if it has
+    /// no such table.
+    #[inline]
+    pub fn panose(&self) -> Option<Panose> {
+        <Self as Loader>::panose(self)
+    }
+
+    /// Returns the four-character tag of the font's vendor, from its `OS/2` table.
+    ///
+    /// See `Loader::vendor_id()`.
+    #[inline]
+    pub fn vendor_id(&self) -> Option<[u8; 4]> {
+        <Self as Loader>::vendor_id(self)
+    }
+
+    /// Returns the version string of the font (`name` ID 5).
+    ///
+    /// See `Loader::version_string()`.
+    #[inline]
+    pub fn version_string(&self) -> Option<String> {
+        <Self as Loader>::version_string(self)
+    }
+
+    /// Returns the distance that the pen moves down after drawing the given glyph in vertical
+    /// layout, in font units, from the `vmtx` table.
+    #[inline]
+    pub fn vertical_advance(&self, glyph_id: u32) -> Result<f32, GlyphLoadingError> {
+        <Self as Loader>::vertical_advance(self, glyph_id)
+    }
+
+    /// Returns the point in the given glyph's coordinate space, in font units, that is placed on
+    /// the pen position in vertical layout.
+    #[inline]
+    pub fn vertical_origin(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::vertical_origin(self, glyph_id)
+    }
+
+    /// Returns how to make this font look like a face with the given properties, by slanting or
+    /// emboldening it.
+    ///
+    /// See `Loader::synthesis()`.
+    #[inline]
+    pub fn synthesis(&self, properties: &Properties) -> Synthesis {
+        <Self as Loader>::synthesis(self, properties)
+    }
+
+    /// Rasterizes a glyph to a canvas, slanted and emboldened as `synthesis` says.
+    ///
+    /// See `Loader::rasterize_glyph_with_synthesis()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn rasterize_glyph_with_synthesis(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        synthesis: &Synthesis,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_with_synthesis(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            synthesis,
+        )
+    }
+
+    /// Rasterizes a line of text to a canvas, laying its glyphs out from left to right.
+    ///
+    /// See `Loader::rasterize_string()`.
+    #[inline]
+    pub fn rasterize_string(
+        &self,
+        canvas: &mut Canvas,
+        text: &str,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_string(
+            self,
+            canvas,
+            text,
+            point_size,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a single-channel signed distance field of a glyph to a canvas, for rendering
+    /// text on the GPU.
+    ///
+    /// See `Loader::rasterize_glyph_sdf()`.
+    #[inline]
+    pub fn rasterize_glyph_sdf(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        spread: f32,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_sdf(self, canvas, glyph_id, point_size, origin, spread)
+    }
+
+    /// Rasterizes a glyph into floating-point coverage values in [0, 1], one per pixel of a
+    /// buffer of the given size, row by row.
+    ///
+    /// See `Loader::rasterize_glyph_f32()`.
+    #[inline]
+    pub fn rasterize_glyph_f32(
+        &self,
+        size: &Size2D<u32>,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<Vec<f32>, GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_f32(
+            self,
+            size,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Sends the vector path for a glyph to a path builder, after transforming it from font units
+    /// by `transform`.
+    ///
+    /// See `Loader::outline_transformed()`.
+    #[inline]
+    pub fn outline_transformed<B>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        transform: &Transform2D<f32>,
+        path_builder: &mut B,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        B: PathBuilder,
+    {
+        <Self as Loader>::outline_transformed(self, glyph_id, hinting_mode, transform, path_builder)
+    }
+
+    /// Returns the vector path for a glyph, in font units, as a `lyon_path` path.
+    ///
+    /// See `Loader::outline_path()`.
+    #[inline]
+    pub fn outline_path(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<lyon_path::Path, GlyphLoadingError> {
+        <Self as Loader>::outline_path(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the vector outline of a glyph, in font units, as an owned `Outline`.
+    ///
+    /// See `Loader::glyph_outline()`.
+    #[inline]
+    pub fn glyph_outline(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<Outline, GlyphLoadingError> {
+        <Self as Loader>::glyph_outline(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the unhinted outline of a glyph, scaled to `point_size`, as the `d` attribute of
+    /// an SVG `path` element.
+    ///
+    /// See `Loader::glyph_svg_path()`.
+    #[inline]
+    pub fn glyph_svg_path(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+    ) -> Result<String, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_path(self, glyph_id, point_size)
+    }
+
+    /// Returns the OpenType layout features that the font's `GSUB` and `GPOS` tables declare,
+    /// grouped by script and language system.
+    #[inline]
+    pub fn layout_features(&self) -> LayoutFeatures {
+        <Self as Loader>::layout_features(self)
+    }
+
+    /// Returns the OpenType script tags that the font's `GSUB` and `GPOS` tables declare, sorted.
+    #[inline]
+    pub fn supported_scripts(&self) -> Vec<ScriptTag> {
+        <Self as Loader>::supported_scripts(self)
+    }
+
+    /// Returns the OpenType language system tags that the font's `GSUB` and `GPOS` tables
+    /// declare for the given script, sorted.
+    #[inline]
+    pub fn supported_languages(&self, script: ScriptTag) -> Vec<LangTag> {
+        <Self as Loader>::supported_languages(self, script)
+    }
+
+    /// Returns the position of the baseline with the given tag for the given script in
+    /// horizontal text, in font units, from the `BASE` table.
+    #[inline]
+    pub fn baseline(&self, baseline_tag: u32, script: ScriptTag) -> Option<f32> {
+        <Self as Loader>::baseline(self, baseline_tag, script)
+    }
+
+    /// Returns every record of the font's `name` table, in every language, in table order.
+    #[inline]
+    pub fn name_records(&self) -> Vec<NameRecord> {
+        <Self as Loader>::name_records(self)
+    }
+
+    /// Returns the family name of the font in the language of a BCP 47 locale, falling back to
+    /// English and then to the first name available.
+    #[inline]
+    pub fn localized_family_name(&self, locale: &str) -> Option<String> {
+        <Self as Loader>::localized_family_name(self, locale)
+    }
+
+    /// Returns the math layout constants of the font from its `MATH` table, in font units, or
+    /// `None` if the font has no such table.
+    #[inline]
+    pub fn math_constants(&self) -> Option<MathConstants> {
+        <Self as Loader>::math_constants(self)
+    }
+
+    /// Returns the prebuilt variants of a glyph for stretching in the given direction, from
+    /// smallest to largest, as listed in the font's `MATH` table.
+    #[inline]
+    pub fn math_glyph_variants(
+        &self,
+        glyph_id: u32,
+        direction: StretchDirection,
+    ) -> Vec<GlyphVariant> {
+        <Self as Loader>::math_glyph_variants(self, glyph_id, direction)
+    }
+
+    /// Returns the flags and signature formats of the font's `DSIG` table, or `None` if it has no
+    /// such table.
+    #[inline]
+    pub fn digital_signature(&self) -> Option<SignatureInfo> {
+        <Self as Loader>::digital_signature(self)
+    }
+
+    /// Returns true if the font's `DSIG` table holds at least one signature.
+    #[inline]
+    pub fn has_digital_signature(&self) -> bool {
+        <Self as Loader>::has_digital_signature(self)
+    }
+}
+
+fn design_glyph_metrics_to_bounds(metrics: &DWRITE_GLYPH_METRICS) -> Rect<f32> {
+    let advance_width = metrics.advanceWidth as i32;
+    let advance_height = metrics.advanceHeight as i32;
+    let left_side_bearing = metrics.leftSideBearing as i32;
+    let right_side_bearing = metrics.rightSideBearing as i32;
+    let top_side_bearing = metrics.topSideBearing as i32;
+    let bottom_side_bearing = metrics.bottomSideBearing as i32;
+    let vertical_origin_y = metrics.verticalOriginY as i32;
+
+    let y_offset = vertical_origin_y + bottom_side_bearing - advance_height;
+    let width = advance_width - (left_side_bearing + right_side_bearing);
+    let height = advance_height - (top_side_bearing + bottom_side_bearing);
+
+    Rect::new(
+        Point2D::new(left_side_bearing as f32, y_offset as f32),
+        Size2D::new(width as f32, height as f32),
+    )
 }
 
 // There might well be a more efficient impl that doesn't fully decode the text,
@@ -655,6 +1711,7 @@ impl Clone for Font {
             dwrite_font: self.dwrite_font.clone(),
             dwrite_font_face: self.dwrite_font_face.clone(),
             cached_data: Mutex::new((*self.cached_data.lock().unwrap()).clone()),
+            reverse_cmap: Mutex::new((*self.reverse_cmap.lock().unwrap()).clone()),
         }
     }
 }
@@ -728,6 +1785,16 @@ impl Loader for Font {
         self.glyph_for_char(character)
     }
 
+    #[inline]
+    fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        self.glyphs_for_str(text)
+    }
+
+    #[inline]
+    fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        self.chars_for_glyph(glyph_id)
+    }
+
     #[inline]
     fn glyph_count(&self) -> u32 {
         self.glyph_count()
@@ -751,11 +1818,24 @@ impl Loader for Font {
         self.typographic_bounds(glyph_id)
     }
 
+    #[inline]
+    fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        self.typographic_bounds_batch(glyph_ids)
+    }
+
     #[inline]
     fn advance(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
         self.advance(glyph_id)
     }
 
+    #[inline]
+    fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        self.advances(glyph_ids)
+    }
+
     #[inline]
     fn origin(&self, origin: u32) -> Result<Point2D<f32>, GlyphLoadingError> {
         self.origin(origin)
@@ -766,6 +1846,11 @@ impl Loader for Font {
         self.metrics()
     }
 
+    #[inline]
+    fn units_per_em(&self) -> u32 {
+        self.units_per_em()
+    }
+
     #[inline]
     fn supports_hinting_options(
         &self,
@@ -781,7 +1866,29 @@ impl Loader for Font {
     }
 
     #[inline]
-    fn rasterize_glyph(
+    fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        self.raster_bounds_with_adjustments(
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )
+    }
+
+    #[inline]
+    fn rasterize_glyph_with_adjustments(
         &self,
         canvas: &mut Canvas,
         glyph_id: u32,
@@ -790,8 +1897,9 @@ impl Loader for Font {
         origin: &Point2D<f32>,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
     ) -> Result<(), GlyphLoadingError> {
-        self.rasterize_glyph(
+        self.rasterize_glyph_with_adjustments(
             canvas,
             glyph_id,
             point_size,
@@ -799,6 +1907,7 @@ impl Loader for Font {
             origin,
             hinting_options,
             rasterization_options,
+            rasterization_adjustments,
         )
     }
 
@@ -811,6 +1920,21 @@ impl Loader for Font {
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
         self.load_font_table(table_tag)
     }
+
+    #[inline]
+    fn table_tags(&self) -> Vec<u32> {
+        self.table_tags()
+    }
+
+    #[inline]
+    fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        self.set_variations(coords)
+    }
+
+    #[inline]
+    fn variations(&self) -> Vec<(u32, f32)> {
+        self.variations()
+    }
 }
 
 enum Event {
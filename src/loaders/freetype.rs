@@ -14,7 +14,12 @@
 //! loader by default.
 
 use byteorder::{BigEndian, ReadBytesExt};
-use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use freetype::freetype::FT_Get_Glyph_Name;
+use freetype::freetype::FT_Set_Charmap;
+use freetype::freetype::FT_Sfnt_Table_Info;
+use freetype::freetype::{FT_BBox, FT_Glyph_Format, FT_Outline_Get_CBox};
+use freetype::freetype::{FT_Bool, FT_Parameter, FT_Pointer};
 use freetype::freetype::{FT_Byte, FT_Done_Face, FT_Error, FT_Face, FT_FACE_FLAG_FIXED_WIDTH};
 use freetype::freetype::{FT_Fixed, FT_Matrix, FT_UShort, FT_Vector};
 use freetype::freetype::{FT_Get_Char_Index, FT_Get_Name_Index, FT_Get_Postscript_Name};
@@ -25,29 +30,56 @@ use freetype::freetype::{FT_LcdFilter, FT_Library_SetLcdFilter};
 use freetype::freetype::{FT_Library, FT_Load_Glyph, FT_Long, FT_LOAD_NO_HINTING, FT_LOAD_RENDER};
 use freetype::freetype::{FT_New_Memory_Face, FT_Reference_Face, FT_STYLE_FLAG_ITALIC};
 use freetype::freetype::{FT_Set_Char_Size, FT_Set_Transform, FT_Sfnt_Tag, FT_UInt, FT_ULong};
+use freetype::freetype::{FT_FACE_FLAG_GLYPH_NAMES, FT_FACE_FLAG_SCALABLE, FT_FACE_FLAG_TRICKY};
+use freetype::freetype::{FT_LOAD_FORCE_AUTOHINT, FT_LOAD_NO_SCALE};
 use freetype::tt_os2::TT_OS2;
 use log::warn;
 use lyon_path::builder::PathBuilder;
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+use std::borrow::Cow;
 use std::f32;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
 use std::io::{Seek, SeekFrom};
 use std::iter;
 use std::mem;
+use std::ops::Deref;
 use std::os::raw::{c_char, c_void};
 use std::ptr;
 use std::slice;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use crate::canvas::{Canvas, Format, RasterizationOptions};
+use crate::bitmap::{BitmapGlyph, BitmapStrike};
+use crate::canvas::{Canvas, Format, LcdFilter, RasterizationAdjustments, RasterizationOptions};
+use crate::color::{ColorFormat, ColorLayer, ColorPalette, Paint};
+use crate::coverage::CodepointCoverage;
+use crate::embedding::EmbeddingRestrictions;
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
+use crate::gzip;
 use crate::handle::Handle;
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackResult, FontTransform, Loader};
-use crate::metrics::Metrics;
+use crate::layout::{GlyphClass, LangTag, LayoutFeatures, ScriptTag};
+use crate::loader::{self, CollectionFonts, FallbackResult, FontTransform, Glyphs, Loader};
+use crate::math::{GlyphVariant, MathConstants, StretchDirection};
+use crate::metrics::{HorizontalMetrics, MaxpLimits, Metrics};
+use crate::names::NameRecord;
+use crate::outline::Outline;
+use crate::outline_format::OutlineFormat;
+use crate::panose::Panose;
 use crate::properties::{Properties, Stretch, Style, Weight};
+#[cfg(feature = "harfbuzz")]
+use crate::shaping::{Direction, Feature, Script, ShapedGlyph};
+use crate::signature::SignatureInfo;
+use crate::subset::FontSubset;
+use crate::synthesis::Synthesis;
+use crate::tables::cmap::{self, ReverseCmap};
+use crate::tables::vhea::{self, VheaTable};
+use crate::tables::{head, hhea, hmtx, name, os_2, post};
 use crate::utils;
+use crate::variations::{self, NamedInstance, VariationAxis};
+use crate::woff;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
@@ -72,13 +104,32 @@ const FT_LOAD_TARGET_LCD: u32 = (FT_RENDER_MODE_LCD & 15) << 16;
 const FT_LOAD_TARGET_MONO: u32 = (FT_RENDER_MODE_MONO & 15) << 16;
 const FT_LOAD_TARGET_NORMAL: u32 = (FT_RENDER_MODE_NORMAL & 15) << 16;
 
+const FT_PARAM_TAG_STEM_DARKENING: FT_ULong = 0x6461_726b; // 'dark'
+
 const FT_PIXEL_MODE_MONO: u8 = 1;
 const FT_PIXEL_MODE_GRAY: u8 = 2;
 const FT_PIXEL_MODE_LCD: u8 = 5;
 const FT_PIXEL_MODE_LCD_V: u8 = 6;
+const FT_PIXEL_MODE_BGRA: u8 = 7;
 
 const OS2_FS_SELECTION_OBLIQUE: u16 = 1 << 9;
 
+// FreeType error codes, from `fterrdef.h`.
+const FT_ERR_UNKNOWN_FILE_FORMAT: FT_Error = 0x02;
+const FT_ERR_INVALID_FILE_FORMAT: FT_Error = 0x03;
+const FT_ERR_UNIMPLEMENTED_FEATURE: FT_Error = 0x07;
+const FT_ERR_INVALID_TABLE: FT_Error = 0x08;
+const FT_ERR_INVALID_OFFSET: FT_Error = 0x09;
+const FT_ERR_INVALID_GLYPH_INDEX: FT_Error = 0x10;
+const FT_ERR_INVALID_GLYPH_FORMAT: FT_Error = 0x12;
+const FT_ERR_CANNOT_RENDER_GLYPH: FT_Error = 0x13;
+const FT_ERR_INVALID_OUTLINE: FT_Error = 0x14;
+const FT_ERR_INVALID_COMPOSITE: FT_Error = 0x15;
+const FT_ERR_TOO_MANY_HINTS: FT_Error = 0x16;
+const FT_ERR_OUT_OF_MEMORY: FT_Error = 0x40;
+const FT_ERR_FIRST_DRIVER_ERROR: FT_Error = 0x80;
+const FT_ERR_LAST_DRIVER_ERROR: FT_Error = 0xBA;
+
 // Not in our FreeType bindings, so we define these ourselves.
 #[allow(dead_code)]
 const BDF_PROPERTY_TYPE_NONE: BDF_PropertyType = 0;
@@ -94,7 +145,8 @@ thread_local! {
         unsafe {
             let mut library = ptr::null_mut();
             assert_eq!(FT_Init_FreeType(&mut library), 0);
-            FT_Library_SetLcdFilter(library, FT_LcdFilter::FT_LCD_FILTER_DEFAULT);
+            // We filter subpixel coverage ourselves, according to the rasterization options.
+            FT_Library_SetLcdFilter(library, FT_LcdFilter::FT_LCD_FILTER_NONE);
             library
         }
     };
@@ -121,7 +173,29 @@ struct BDF_PropertyRec {
 /// loader by default.
 pub struct Font {
     freetype_face: FT_Face,
-    font_data: Arc<Vec<u8>>,
+    font_data: FontData,
+    reverse_cmap: Mutex<Option<Arc<ReverseCmap>>>,
+    load_flags: u32,
+}
+
+/// The data that a FreeType face reads from, which must outlive the face.
+#[derive(Clone)]
+enum FontData {
+    Memory(Arc<Vec<u8>>),
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<Mmap>),
+}
+
+impl Deref for FontData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match *self {
+            FontData::Memory(ref data) => data,
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => mapping,
+        }
+    }
 }
 
 impl Font {
@@ -130,17 +204,40 @@ impl Font {
     /// If the data represents a collection (`.ttc`/`.otc`/etc.), `font_index` specifies the index
     /// of the font to load from it. If the data represents a single font, pass 0 for `font_index`.
     pub fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Font, FontLoadingError> {
+        let font_data = woff::to_sfnt(gzip::decompress(font_data)?)?;
+        Font::from_font_data(FontData::Memory(font_data), font_index)
+    }
+
+    fn from_font_data(font_data: FontData, font_index: u32) -> Result<Font, FontLoadingError> {
         FREETYPE_LIBRARY.with(|freetype_library| unsafe {
             let mut freetype_face = ptr::null_mut();
             if FT_New_Memory_Face(
                 *freetype_library,
-                (*font_data).as_ptr(),
+                font_data.as_ptr(),
                 font_data.len() as FT_Long,
                 font_index as FT_Long,
                 &mut freetype_face,
             ) != 0
             {
-                return Err(FontLoadingError::Parse);
+                // FreeType doesn't distinguish bad indices from bad data, so check the index
+                // ourselves.
+                if FT_New_Memory_Face(
+                    *freetype_library,
+                    font_data.as_ptr(),
+                    font_data.len() as FT_Long,
+                    0,
+                    &mut freetype_face,
+                ) != 0
+                {
+                    return Err(FontLoadingError::Parse);
+                }
+                let font_count = (*freetype_face).num_faces;
+                FT_Done_Face(freetype_face);
+                return if font_index as FT_Long >= font_count {
+                    Err(FontLoadingError::NoSuchFontInCollection)
+                } else {
+                    Err(FontLoadingError::Parse)
+                };
             }
 
             setup_freetype_face(freetype_face);
@@ -148,6 +245,8 @@ impl Font {
             Ok(Font {
                 freetype_face,
                 font_data,
+                reverse_cmap: Mutex::new(None),
+                load_flags: 0,
             })
         })
     }
@@ -177,14 +276,43 @@ impl Font {
         <Font as Loader>::from_path(path, font_index)
     }
 
+    /// Loads a font from the path to a `.ttf`/`.otf`/etc. file by mapping the file into memory
+    /// instead of reading it.
+    ///
+    /// The mapping lives as long as the font and its clones do, so fonts loaded this way don't
+    /// add the size of the file to the resident memory of the process until their data is
+    /// actually used. WOFF, WOFF2, and gzip-compressed files still have to be decompressed into
+    /// memory.
+    ///
+    /// The file must not be modified or truncated while it's mapped.
+    ///
+    /// This method is only available with the `mmap` Cargo feature.
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap<P>(path: P, font_index: u32) -> Result<Font, FontLoadingError>
+    where
+        P: AsRef<Path>,
+    {
+        // Safety: the mapping is only ever read, and the caller promises not to change the file
+        // underneath it.
+        let mapping = unsafe { Mmap::map(&File::open(path)?)? };
+        if woff::web_font_type(&mapping).is_some() || gzip::is_compressed(&mapping) {
+            return Font::from_bytes(Arc::new(mapping.to_vec()), font_index);
+        }
+        Font::from_font_data(FontData::Mapped(Arc::new(mapping)), font_index)
+    }
+
     /// Creates a font from a native API handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid, live font object of the underlying platform API.
     pub unsafe fn from_native_font(freetype_face: NativeFont) -> Font {
         // We make an in-memory copy of the underlying font data. This is because the native font
         // does not necessarily hold a strong reference to the memory backing it.
         const CHUNK_SIZE: usize = 4096;
         let mut font_data = vec![];
         loop {
-            font_data.extend(iter::repeat(0).take(CHUNK_SIZE));
+            font_data.extend(iter::repeat_n(0, CHUNK_SIZE));
             let freetype_stream = (*freetype_face).stream;
             let n_read = ((*freetype_stream).read.unwrap())(
                 freetype_stream,
@@ -209,6 +337,13 @@ impl Font {
     /// Determines whether a blob of raw font data represents a supported font, and, if so, what
     /// type of font it is.
     pub fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        if gzip::is_compressed(&font_data) {
+            return Font::analyze_bytes(gzip::decompress(font_data)?);
+        }
+        if let Some(file_type) = woff::web_font_type(&font_data) {
+            let font_data = woff::to_sfnt(font_data)?;
+            return Font::analyze_bytes(font_data).map(|_| file_type);
+        }
         FREETYPE_LIBRARY.with(|freetype_library| unsafe {
             let mut freetype_face = ptr::null_mut();
             if FT_New_Memory_Face(
@@ -237,6 +372,9 @@ impl Font {
         FREETYPE_LIBRARY.with(|freetype_library| unsafe {
             file.seek(SeekFrom::Start(0))?;
             let font_data = Arc::new(utils::slurp_file(file).map_err(FontLoadingError::Io)?);
+            if woff::web_font_type(&font_data).is_some() || gzip::is_compressed(&font_data) {
+                return Font::analyze_bytes(font_data);
+            }
 
             let mut freetype_face = ptr::null_mut();
             if FT_New_Memory_Face(
@@ -269,6 +407,81 @@ impl Font {
         <Self as Loader>::analyze_path(path)
     }
 
+    /// Returns a fingerprint of the font at `font_index` in the given file, without loading the
+    /// font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fingerprint_file(file: &mut File, font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_file(file, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in the file at the given path, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fingerprint_path<P: AsRef<Path>>(
+        path: P,
+        font_index: u32,
+    ) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_path(path, font_index)
+    }
+
+    /// Returns a fingerprint of the font at `font_index` in a blob of raw font data, without
+    /// loading the font.
+    ///
+    /// See `Loader::fingerprint_file()`.
+    #[inline]
+    pub fn fingerprint_bytes(font_data: &[u8], font_index: u32) -> Result<u64, FontLoadingError> {
+        <Self as Loader>::fingerprint_bytes(font_data, font_index)
+    }
+
+    /// Returns the number of fonts in a blob of raw font data: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    pub fn count_in_collection_bytes(font_data: Arc<Vec<u8>>) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection_bytes(font_data)
+    }
+
+    /// Returns the number of fonts in the file at a path: the number of faces if it's a
+    /// collection (`.ttc`/`.otc`/etc.), or 1 if it's a single font.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn count_in_collection<P: AsRef<Path>>(path: P) -> Result<u32, FontLoadingError> {
+        <Self as Loader>::count_in_collection(path)
+    }
+
+    /// Returns an iterator that loads each font in a blob of raw font data in turn.
+    #[inline]
+    pub fn all_in_collection_bytes(
+        font_data: Arc<Vec<u8>>,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection_bytes(font_data)
+    }
+
+    /// Loads the font with the given PostScript name from a blob of raw font data.
+    ///
+    /// See `Loader::from_bytes_by_postscript_name()`.
+    #[inline]
+    pub fn from_bytes_by_postscript_name(
+        font_data: Arc<Vec<u8>>,
+        postscript_name: &str,
+    ) -> Result<Font, FontLoadingError> {
+        <Self as Loader>::from_bytes_by_postscript_name(font_data, postscript_name)
+    }
+
+    /// Returns an iterator that loads each font in the file at a path in turn.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn all_in_collection<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<CollectionFonts<Font>, FontLoadingError> {
+        <Self as Loader>::all_in_collection(path)
+    }
+
     /// Returns the wrapped native font handle.
     ///
     /// This function increments the reference count of the FreeType face before returning it.
@@ -280,6 +493,25 @@ impl Font {
         }
     }
 
+    /// Returns the extra FreeType load flags set with `set_load_flags()`.
+    #[inline]
+    pub fn load_flags(&self) -> u32 {
+        self.load_flags
+    }
+
+    /// Sets extra FreeType load flags (`FT_LOAD_*`) to OR into the ones that `outline()` and
+    /// `rasterize_glyph()` choose from the hinting and rasterization options, replacing any set
+    /// before.
+    ///
+    /// For example, `FT_LOAD_COLOR` renders color glyphs into RGBA canvases, and
+    /// `FT_LOAD_NO_BITMAP` ignores embedded bitmaps. With `FT_LOAD_NO_SCALE`, outlines are
+    /// unhinted and in font units regardless of the hinting options. Metrics such as
+    /// `typographic_bounds()` and `advance()` are unaffected.
+    #[inline]
+    pub fn set_load_flags(&mut self, load_flags: u32) {
+        self.load_flags = load_flags
+    }
+
     /// Returns the PostScript name of the font. This should be globally unique.
     pub fn postscript_name(&self) -> Option<String> {
         unsafe {
@@ -336,10 +568,16 @@ impl Font {
 
     /// Returns true if and only if the font is monospace (fixed-width).
     pub fn is_monospace(&self) -> bool {
-        unsafe { (*self.freetype_face).face_flags & (FT_FACE_FLAG_FIXED_WIDTH as FT_Long) != 0 }
+        let fixed_width = unsafe {
+            (*self.freetype_face).face_flags & (FT_FACE_FLAG_FIXED_WIDTH as FT_Long) != 0
+        };
+        loader::is_monospace(self, fixed_width)
     }
 
     /// Returns the values of various font properties, corresponding to those defined in CSS.
+    ///
+    /// For variable fonts, the weight, stretch, and style reflect the current variation
+    /// coordinates of the `wght`, `wdth`, `slnt`, and `ital` axes.
     pub fn properties(&self) -> Properties {
         unsafe {
             let os2_table = self.get_os2_table();
@@ -364,30 +602,104 @@ impl Font {
                 None => Weight::NORMAL,
                 Some(os2_table) => Weight((*os2_table).usWeightClass as u32 as f32),
             };
-            Properties {
+            let properties = Properties {
                 style,
                 stretch,
                 weight,
-            }
+                italic_angle: Some(self.italic_angle()),
+            };
+            variations::apply_to_properties(properties, &self.variations())
         }
     }
 
+    /// Returns the slant of the font in degrees counterclockwise from vertical, from the
+    /// `italicAngle` field of its `post` table.
+    #[inline]
+    pub fn italic_angle(&self) -> f32 {
+        <Self as Loader>::italic_angle(self)
+    }
+
     /// Returns the usual glyph ID for a Unicode character.
     ///
     /// Be careful with this function; typographically correct character-to-glyph mapping must be
     /// done using a *shaper* such as HarfBuzz. This function is only useful for best-effort simple
     /// use cases like "what does character X look like on its own".
+    ///
+    /// Of the font's `cmap` subtables, the full Unicode ones are preferred, then the BMP ones,
+    /// then the Windows symbol one, then the Mac Roman one. Symbol fonts usually map their codes
+    /// into U+F000 to U+F0FF, so characters up to U+00FF are looked up there too if they aren't
+    /// found directly.
     #[inline]
     pub fn glyph_for_char(&self, character: char) -> Option<u32> {
+        match self.char_index(character) {
+            0 => None,
+            glyph_id => Some(glyph_id),
+        }
+    }
+
+    /// Looks a character up in the face's charmap, returning glyph 0 if it isn't there.
+    ///
+    /// Symbol fonts, whose Windows symbol charmap maps the codes of their legacy 8-bit encoding
+    /// offset into the private use area at U+F000, have characters up to U+00FF looked up at that
+    /// offset if they aren't found directly. Characters are encoded as Mac Roman for Mac Roman
+    /// charmaps.
+    fn char_index(&self, character: char) -> u32 {
         unsafe {
-            let res = FT_Get_Char_Index(self.freetype_face, character as FT_ULong);
-            match res {
-                0 => None,
-                _ => Some(res),
+            let charmap = (*self.freetype_face).charmap;
+            if charmap.is_null() {
+                return 0;
+            }
+            match ((*charmap).platform_id, (*charmap).encoding_id) {
+                (cmap::PLATFORM_MACINTOSH, cmap::MACINTOSH_ENCODING_ROMAN) => {
+                    match name::encode_mac_roman(character) {
+                        Some(code) => FT_Get_Char_Index(self.freetype_face, code as FT_ULong),
+                        None => 0,
+                    }
+                }
+                (cmap::PLATFORM_WINDOWS, cmap::WINDOWS_ENCODING_SYMBOL) => {
+                    match FT_Get_Char_Index(self.freetype_face, character as FT_ULong) {
+                        0 if (character as u32) <= 0xff => FT_Get_Char_Index(
+                            self.freetype_face,
+                            (cmap::SYMBOL_CODE_OFFSET + character as u32) as FT_ULong,
+                        ),
+                        glyph_id => glyph_id,
+                    }
+                }
+                _ => FT_Get_Char_Index(self.freetype_face, character as FT_ULong),
             }
         }
     }
 
+    /// Returns the glyph ID for a Unicode character followed by a variation selector, such as
+    /// U+FE0E or U+FE0F for the text and emoji presentations or U+E0100 and up for ideographic
+    /// variants.
+    ///
+    /// The variation sequences come from the format 14 subtable of the `cmap`. If the font has no
+    /// specific glyph for the sequence, this returns the usual glyph for the character, like
+    /// `glyph_for_char()`.
+    #[inline]
+    pub fn glyph_for_char_with_variation(
+        &self,
+        character: char,
+        variation_selector: char,
+    ) -> Option<u32> {
+        <Self as Loader>::glyph_for_char_with_variation(self, character, variation_selector)
+    }
+
+    /// Returns the glyph ID for each character of the given string, in order, or `None` for
+    /// characters that the font has no glyph for.
+    ///
+    /// The characters are looked up in the face's selected charmap without any intermediate
+    /// allocation per character.
+    pub fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        text.chars()
+            .map(|character| match self.char_index(character) {
+                0 => None,
+                glyph_id => Some(glyph_id),
+            })
+            .collect()
+    }
+
     /// Returns the glyph ID for the specified glyph name.
     #[inline]
     pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
@@ -395,11 +707,42 @@ impl Font {
             let code =
                 unsafe { FT_Get_Name_Index(self.freetype_face, ffi_name.as_ptr() as *mut c_char) };
 
-            if code > 0 {
-                return Some(u32::from(code));
+            // Glyph 0 is `.notdef`, but FreeType also returns 0 for names it can't find.
+            if code > 0 || name == ".notdef" {
+                return Some(code);
+            }
+        }
+        loader::glyph_by_synthesized_name(self, name)
+    }
+
+    /// Returns the name of a glyph, the reverse of `glyph_by_name()`.
+    ///
+    /// FreeType reads the names from the `post` table, or from the charset of CFF fonts. Fonts
+    /// without names get names synthesized from the character that the glyph maps to; see
+    /// `Loader::glyph_name()`.
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        if glyph_id >= self.glyph_count() {
+            return None;
+        }
+        unsafe {
+            if (*self.freetype_face).face_flags & (FT_FACE_FLAG_GLYPH_NAMES as FT_Long) != 0 {
+                // Glyph names are limited to 63 characters, so this is plenty.
+                let mut buffer: [c_char; 128] = [0; 128];
+                let error = FT_Get_Glyph_Name(
+                    self.freetype_face,
+                    glyph_id,
+                    buffer.as_mut_ptr() as FT_Pointer,
+                    buffer.len() as FT_UInt,
+                );
+                let name = CStr::from_ptr(buffer.as_ptr());
+                return if error == 0 && !name.to_bytes().is_empty() {
+                    Some(name.to_string_lossy().into_owned())
+                } else {
+                    None
+                };
             }
         }
-        None
+        loader::synthesized_glyph_name(self, glyph_id)
     }
 
     /// Returns the number of glyphs in the font.
@@ -410,12 +753,27 @@ impl Font {
         unsafe { (*self.freetype_face).num_glyphs as u32 }
     }
 
+    /// Returns an iterator over every glyph of the font, in order of glyph ID, with its metrics.
+    ///
+    /// See `Loader::glyphs()`.
+    #[inline]
+    pub fn glyphs(&self) -> Glyphs<'_, Font> {
+        <Self as Loader>::glyphs(self)
+    }
+
     /// Sends the vector path for a glyph to a path builder.
     ///
     /// If `hinting_mode` is not None, this function performs grid-fitting as requested before
     /// sending the hinding outlines to the builder.
     ///
-    /// TODO(pcwalton): What should we do for bitmap glyphs?
+    /// The outlines of variable fonts, whether TrueType or CFF2, reflect the variations set with
+    /// `set_variations()`; FreeType's CFF driver evaluates the `blend` operators of CFF2
+    /// charstrings.
+    ///
+    /// Contours are sent in the direction that the font stores them; see `Loader::outline()`.
+    ///
+    /// Fonts that have only bitmaps, with neither `glyf` nor `CFF `/`CFF2` outlines, return
+    /// `GlyphLoadingError::UnsupportedFormat`; `load_bitmap_glyph()` gets at their glyphs instead.
     pub fn outline<B>(
         &self,
         glyph_id: u32,
@@ -425,13 +783,28 @@ impl Font {
     where
         B: PathBuilder,
     {
+        if glyph_id >= self.glyph_count() {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        if !self.has_outlines() {
+            return Err(GlyphLoadingError::UnsupportedFormat);
+        }
         unsafe {
             let rasterization_options = RasterizationOptions::GrayscaleAa;
             let load_flags = self
                 .hinting_and_rasterization_options_to_load_flags(hinting, rasterization_options);
 
-            let units_per_em = (*self.freetype_face).units_per_EM;
             let grid_fitting_size = hinting.grid_fitting_size();
+            // Points are in 26.6 pixels at the size the face is set to, which is normally the
+            // units per em, unless FreeType was told not to scale them at all.
+            let point_scale = if load_flags & FT_LOAD_NO_SCALE != 0 {
+                64.0
+            } else {
+                match grid_fitting_size {
+                    Some(size) => (*self.freetype_face).units_per_EM as f32 / size,
+                    None => 1.0,
+                }
+            };
             if let Some(size) = grid_fitting_size {
                 assert_eq!(
                     FT_Set_Char_Size(self.freetype_face, f32_to_ft_fixed_26_6(size), 0, 0, 0),
@@ -439,16 +812,22 @@ impl Font {
                 );
             }
 
-            if FT_Load_Glyph(self.freetype_face, glyph_id, load_flags as i32) != 0 {
-                return Err(GlyphLoadingError::NoSuchGlyph);
+            let error = FT_Load_Glyph(self.freetype_face, glyph_id, load_flags as i32);
+            if error != 0 {
+                return Err(glyph_loading_error(self.freetype_face, glyph_id, error));
             }
 
             let outline = &(*(*self.freetype_face).glyph).outline;
-            let contours =
-                slice::from_raw_parts((*outline).contours, (*outline).n_contours as usize);
-            let point_positions =
-                slice::from_raw_parts((*outline).points, (*outline).n_points as usize);
-            let point_tags = slice::from_raw_parts((*outline).tags, (*outline).n_points as usize);
+            if outline.n_contours <= 0 || outline.n_points <= 0 {
+                // Empty glyphs (e.g. spaces) have no outline arrays at all.
+                if grid_fitting_size.is_some() {
+                    reset_freetype_face_char_size(self.freetype_face)
+                }
+                return Ok(());
+            }
+            let contours = slice::from_raw_parts(outline.contours, outline.n_contours as usize);
+            let point_positions = slice::from_raw_parts(outline.points, outline.n_points as usize);
+            let point_tags = slice::from_raw_parts(outline.tags, outline.n_points as usize);
 
             let mut current_point_index = 0;
             for &last_point_index_in_contour in contours {
@@ -458,8 +837,7 @@ impl Font {
                     point_positions,
                     point_tags,
                     last_point_index_in_contour,
-                    grid_fitting_size,
-                    units_per_em,
+                    point_scale,
                 );
                 if (first_tag & FT_POINT_TAG_ON_CURVE) == 0 {
                     // Rare, but can happen; e.g. with Inconsolata (see pathfinder#84).
@@ -471,8 +849,7 @@ impl Font {
                         point_positions,
                         point_tags,
                         last_point_index_in_contour,
-                        grid_fitting_size,
-                        units_per_em,
+                        point_scale,
                     );
                     if (last_tag & FT_POINT_TAG_ON_CURVE) != 0 {
                         first_point = last_point
@@ -490,8 +867,7 @@ impl Font {
                         point_positions,
                         point_tags,
                         last_point_index_in_contour,
-                        grid_fitting_size,
-                        units_per_em,
+                        point_scale,
                     );
                     if (tag0 & FT_POINT_TAG_ON_CURVE) != 0 {
                         path_builder.line_to(point0);
@@ -511,8 +887,7 @@ impl Font {
                             point_positions,
                             point_tags,
                             last_point_index_in_contour,
-                            grid_fitting_size,
-                            units_per_em,
+                            point_scale,
                         );
 
                         if (tag0 & FT_POINT_TAG_CUBIC_CONTROL) != 0 {
@@ -524,8 +899,7 @@ impl Font {
                                     point_positions,
                                     point_tags,
                                     last_point_index_in_contour,
-                                    grid_fitting_size,
-                                    units_per_em,
+                                    point_scale,
                                 );
                                 path_builder.cubic_bezier_to(point0, point1, point2);
                             } else {
@@ -551,7 +925,7 @@ impl Font {
             }
 
             if hinting.grid_fitting_size().is_some() {
-                reset_freetype_face_char_size((*self).freetype_face)
+                reset_freetype_face_char_size(self.freetype_face)
             }
         }
 
@@ -562,21 +936,17 @@ impl Font {
             point_positions: &[FT_Vector],
             point_tags: &[c_char],
             last_point_index_in_contour: usize,
-            grid_fitting_size: Option<f32>,
-            units_per_em: u16,
+            point_scale: f32,
         ) -> (Point2D<f32>, c_char) {
             assert!(*current_point_index <= last_point_index_in_contour);
             let point_position = point_positions[*current_point_index];
             let point_tag = point_tags[*current_point_index];
             *current_point_index += 1;
 
-            let mut point_position = Point2D::new(
+            let point_position = Point2D::new(
                 ft_fixed_26_6_to_f32(point_position.x),
                 ft_fixed_26_6_to_f32(point_position.y),
-            );
-            if let Some(grid_fitting_size) = grid_fitting_size {
-                point_position *= (units_per_em as f32) / grid_fitting_size
-            }
+            ) * point_scale;
 
             (point_position, point_tag)
         }
@@ -585,13 +955,13 @@ impl Font {
     /// Returns the boundaries of a glyph in font units.
     pub fn typographic_bounds(&self, glyph_id: u32) -> Result<Rect<f32>, GlyphLoadingError> {
         unsafe {
-            if FT_Load_Glyph(
+            let error = FT_Load_Glyph(
                 self.freetype_face,
                 glyph_id,
                 (FT_LOAD_DEFAULT | FT_LOAD_NO_HINTING) as i32,
-            ) != 0
-            {
-                return Err(GlyphLoadingError::NoSuchGlyph);
+            );
+            if error != 0 {
+                return Err(glyph_loading_error(self.freetype_face, glyph_id, error));
             }
 
             let metrics = &(*(*self.freetype_face).glyph).metrics;
@@ -608,17 +978,55 @@ impl Font {
         }
     }
 
+    /// Returns the boundaries of each of the given glyphs in font units, in order.
+    ///
+    /// FreeType has no batch API, so this loads each glyph in turn. If any of the glyphs doesn't
+    /// exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    #[inline]
+    pub fn typographic_bounds_batch(
+        &self,
+        glyph_ids: &[u32],
+    ) -> Result<Vec<Rect<f32>>, GlyphLoadingError> {
+        <Self as Loader>::typographic_bounds_batch(self, glyph_ids)
+    }
+
+    /// Returns the advances of each of the given glyphs in font units, in order.
+    ///
+    /// FreeType has no batch API, so this loads each glyph in turn. If any of the glyphs doesn't
+    /// exist, returns `GlyphLoadingError::NoSuchGlyph`.
+    #[inline]
+    pub fn advances(&self, glyph_ids: &[u32]) -> Result<Vec<Vector2D<f32>>, GlyphLoadingError> {
+        <Self as Loader>::advances(self, glyph_ids)
+    }
+
     /// Returns the distance from the origin of the glyph with the given ID to the next, in font
     /// units.
     pub fn advance(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        if !self.has_outlines() {
+            // FreeType only knows the advances of bitmap-only fonts at the sizes of their
+            // strikes, so read the font units from `hmtx` instead.
+            if glyph_id >= self.glyph_count() {
+                return Err(GlyphLoadingError::NoSuchGlyph);
+            }
+            let hhea = self
+                .load_font_table(hhea::TAG)
+                .ok_or(GlyphLoadingError::UnsupportedFormat)?;
+            let hmtx = self
+                .load_font_table(hmtx::TAG)
+                .ok_or(GlyphLoadingError::UnsupportedFormat)?;
+            return hhea::long_metric_count(&hhea)
+                .and_then(|long_metric_count| hmtx::metric(&hmtx, long_metric_count, glyph_id))
+                .map(|(advance, _)| Vector2D::new(advance as f32, 0.0))
+                .ok_or(GlyphLoadingError::MalformedFont);
+        }
         unsafe {
-            if FT_Load_Glyph(
+            let error = FT_Load_Glyph(
                 self.freetype_face,
                 glyph_id,
                 (FT_LOAD_DEFAULT | FT_LOAD_NO_HINTING) as i32,
-            ) != 0
-            {
-                return Err(GlyphLoadingError::NoSuchGlyph);
+            );
+            if error != 0 {
+                return Err(glyph_loading_error(self.freetype_face, glyph_id, error));
             }
 
             let advance = (*(*self.freetype_face).glyph).advance;
@@ -629,6 +1037,27 @@ impl Font {
         }
     }
 
+    /// Returns the advance of the glyph with the given ID in font units, as recorded in the
+    /// font's `hmtx` table, independent of any point size.
+    ///
+    /// Unlike `advance()`, this doesn't reflect variations applied with `set_variations()`.
+    #[inline]
+    pub fn advance_font_units(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::advance_font_units(self, glyph_id)
+    }
+
+    /// Returns the advance width and left side bearing of the given glyph, in font units, from
+    /// the `hmtx` table.
+    ///
+    /// See `Loader::horizontal_metrics()`.
+    #[inline]
+    pub fn horizontal_metrics(
+        &self,
+        glyph_id: u32,
+    ) -> Result<HorizontalMetrics, GlyphLoadingError> {
+        <Self as Loader>::horizontal_metrics(self, glyph_id)
+    }
+
     /// Returns the amount that the given glyph should be displaced from the origin.
     ///
     /// FIXME(pcwalton): This always returns zero on FreeType.
@@ -637,31 +1066,141 @@ impl Font {
         Ok(Point2D::zero())
     }
 
+    /// Returns the number of font units per em, from the `head` table.
+    ///
+    /// This is always the same as the `units_per_em` field of `metrics()`.
+    pub fn units_per_em(&self) -> u32 {
+        // FreeType leaves the font-wide metrics of bitmap-only fonts at zero, so the value of
+        // those comes straight from the table.
+        if self.has_outlines() {
+            return unsafe { (*self.freetype_face).units_per_EM as u32 };
+        }
+        self.load_font_table(head::TAG)
+            .and_then(|table| head::units_per_em(&table))
+            .unwrap_or(0) as u32
+    }
+
     /// Retrieves various metrics that apply to the entire font.
     pub fn metrics(&self) -> Metrics {
         let os2_table = self.get_os2_table();
+        let vhea = self
+            .load_font_table(vhea::TAG)
+            .and_then(|table| VheaTable::parse(&table));
+        let os_2_data = self.load_font_table(os_2::TAG);
+        let hhea_data = self.load_font_table(hhea::TAG);
+        let (strikeout_size, strikeout_position) = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::strikeout(table))
+            .unwrap_or((0, 0));
+        let (caret_slope_rise, caret_slope_run) = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::caret_slope(table))
+            .unwrap_or((1, 0));
+        let hhea_metrics = hhea_data
+            .as_ref()
+            .and_then(|table| hhea::line_metrics(table));
+        let typo_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::typo_metrics(table));
+        let win_metrics = os_2_data
+            .as_ref()
+            .and_then(|table| os_2::win_metrics(table));
         unsafe {
-            let ascender = (*self.freetype_face).ascender;
-            let descender = (*self.freetype_face).descender;
-            let underline_position = (*self.freetype_face).underline_position;
-            let underline_thickness = (*self.freetype_face).underline_thickness;
+            // FreeType leaves the font-wide metrics of bitmap-only fonts at zero, so those come
+            // straight from the tables.
+            let (units_per_em, ascent, descent, line_gap, underline_position, underline_thickness) =
+                if self.has_outlines() {
+                    let ascender = (*self.freetype_face).ascender;
+                    let descender = (*self.freetype_face).descender;
+                    let underline_position = (*self.freetype_face).underline_position;
+                    let underline_thickness = (*self.freetype_face).underline_thickness;
+                    (
+                        self.units_per_em(),
+                        ascender as f32,
+                        descender as f32,
+                        ((*self.freetype_face).height + descender - ascender) as f32,
+                        (underline_position + underline_thickness / 2) as f32,
+                        underline_thickness as f32,
+                    )
+                } else {
+                    let (ascent, descent, line_gap) = hhea_metrics.unwrap_or((0, 0, 0));
+                    let (underline_position, underline_thickness) = self
+                        .load_font_table(post::TAG)
+                        .and_then(|table| post::underline(&table))
+                        .unwrap_or((0, 0));
+                    (
+                        self.units_per_em(),
+                        ascent as f32,
+                        descent as f32,
+                        line_gap as f32,
+                        underline_position as f32,
+                        underline_thickness as f32,
+                    )
+                };
             Metrics {
-                units_per_em: (*self.freetype_face).units_per_EM as u32,
-                ascent: ascender as f32,
-                descent: descender as f32,
-                line_gap: ((*self.freetype_face).height + descender - ascender) as f32,
-                underline_position: (underline_position + underline_thickness / 2) as f32,
-                underline_thickness: underline_thickness as f32,
+                units_per_em,
+                ascent,
+                descent,
+                line_gap,
+                underline_position,
+                underline_thickness,
+                strikeout_position: strikeout_position as f32,
+                strikeout_size: strikeout_size as f32,
                 cap_height: os2_table
+                    .filter(|&table| (*table).version >= 2)
                     .map(|table| (*table).sCapHeight as f32)
+                    .or_else(|| self.top_of_glyph_for_char('H'))
                     .unwrap_or(0.0),
                 x_height: os2_table
+                    .filter(|&table| (*table).version >= 2)
                     .map(|table| (*table).sxHeight as f32)
+                    .or_else(|| self.top_of_glyph_for_char('x'))
                     .unwrap_or(0.0),
+                vertical_ascent: vhea.map(|vhea| vhea.ascent as f32),
+                vertical_descent: vhea.map(|vhea| vhea.descent as f32),
+                vertical_line_gap: vhea.map(|vhea| vhea.line_gap as f32),
+                hhea_ascent: hhea_metrics.map(|(ascent, _, _)| ascent as f32),
+                hhea_descent: hhea_metrics.map(|(_, descent, _)| descent as f32),
+                hhea_line_gap: hhea_metrics.map(|(_, _, line_gap)| line_gap as f32),
+                typo_ascent: typo_metrics.map(|(ascent, _, _)| ascent as f32),
+                typo_descent: typo_metrics.map(|(_, descent, _)| descent as f32),
+                typo_line_gap: typo_metrics.map(|(_, _, line_gap)| line_gap as f32),
+                win_ascent: win_metrics.map(|(ascent, _)| ascent as f32),
+                win_descent: win_metrics.map(|(_, descent)| -(descent as f32)),
+                use_typo_metrics: os_2_data
+                    .as_ref()
+                    .is_some_and(|table| os_2::use_typo_metrics(table)),
+                caret_slope_rise: caret_slope_rise as f32,
+                caret_slope_run: caret_slope_run as f32,
             }
+            .with_typo_line_metrics()
+            .with_decoration_fallbacks()
         }
     }
 
+    /// Returns the limits that the font's TrueType outlines stay within, from its `maxp` table.
+    ///
+    /// See `Loader::maxp_limits()`.
+    #[inline]
+    pub fn maxp_limits(&self) -> Option<MaxpLimits> {
+        <Self as Loader>::maxp_limits(self)
+    }
+
+    /// Returns true if the font has scalable outlines, rather than only bitmaps.
+    fn has_outlines(&self) -> bool {
+        unsafe { (*self.freetype_face).face_flags & (FT_FACE_FLAG_SCALABLE as FT_Long) != 0 }
+    }
+
+    /// Returns how far the glyph for the given character rises above the baseline, in font units,
+    /// for estimating metrics that older `OS/2` tables lack.
+    fn top_of_glyph_for_char(&self, character: char) -> Option<f32> {
+        let glyph_id = self.glyph_for_char(character)?;
+        self.typographic_bounds(glyph_id)
+            .ok()
+            .filter(|bounds| bounds.size.height > 0.0)
+            .map(|bounds| bounds.max_y())
+    }
+
     /// Returns true if and only if the font loader can perform hinting in the requested way.
     ///
     /// Some APIs support only rasterizing glyphs with hinting, not retriving hinted outlines. If
@@ -679,12 +1218,26 @@ impl Font {
             | (HintingOptions::Vertical(_), true)
             | (HintingOptions::VerticalSubpixel(_), true)
             | (HintingOptions::Full(_), true) => true,
+            // FreeType never autohints "tricky" fonts, which can't be rendered without their own
+            // instructions.
+            (HintingOptions::Autohint(_), true) => unsafe {
+                (*self.freetype_face).face_flags & (FT_FACE_FLAG_TRICKY as FT_Long) == 0
+            },
             (HintingOptions::Vertical(_), false)
             | (HintingOptions::VerticalSubpixel(_), false)
-            | (HintingOptions::Full(_), false) => false,
+            | (HintingOptions::Full(_), false)
+            | (HintingOptions::Autohint(_), false) => false,
         }
     }
 
+    /// Returns true if the font carries hints for its outlines.
+    ///
+    /// See `Loader::has_hinting_instructions()`.
+    #[inline]
+    pub fn has_hinting_instructions(&self) -> bool {
+        <Self as Loader>::has_hinting_instructions(self)
+    }
+
     fn get_type_1_or_sfnt_name(&self, type_1_id: u32, sfnt_id: u16) -> Option<String> {
         unsafe {
             let ps_value_size =
@@ -753,6 +1306,8 @@ impl Font {
 
     /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
     /// rasterizer at the given size and origin.
+    ///
+    /// See `Loader::raster_bounds()`.
     #[inline]
     pub fn raster_bounds(
         &self,
@@ -774,6 +1329,76 @@ impl Font {
         )
     }
 
+    /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
+    /// rasterizer at the given size and origin, with the given adjustments.
+    ///
+    /// The glyph's outline is loaded as `rasterize_glyph_with_adjustments()` would load it,
+    /// hinted and offset by the fractional part of the origin, and the bounds are the pixels that
+    /// its control box touches.
+    #[allow(clippy::too_many_arguments)]
+    pub fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        unsafe {
+            self.load_transformed_glyph(
+                glyph_id,
+                point_size,
+                transform,
+                origin,
+                hinting_options,
+                rasterization_options,
+                rasterization_adjustments,
+                FT_LOAD_DEFAULT,
+            )?;
+            let glyph = (*self.freetype_face).glyph;
+            let control_box = if (*glyph).format == FT_Glyph_Format::FT_GLYPH_FORMAT_OUTLINE {
+                let mut control_box: FT_BBox = mem::zeroed();
+                FT_Outline_Get_CBox(&(*glyph).outline, &mut control_box);
+                Some(control_box)
+            } else {
+                None
+            };
+            reset_freetype_face_transform(self.freetype_face);
+
+            let control_box = match control_box {
+                Some(control_box) => control_box,
+                // Bitmap glyphs aren't transformed, so estimate their bounds from their metrics.
+                None => {
+                    return loader::typographic_raster_bounds(
+                        self,
+                        glyph_id,
+                        point_size,
+                        transform,
+                        origin,
+                        hinting_options,
+                        rasterization_options,
+                        rasterization_adjustments,
+                    )
+                }
+            };
+
+            // The control box is in 26.6 pixels with y pointing up.
+            let (left, right) = (control_box.xMin >> 6, (control_box.xMax + 63) >> 6);
+            let (bottom, top) = (control_box.yMin >> 6, (control_box.yMax + 63) >> 6);
+            let raster_bounds = Rect::new(
+                Point2D::new(left as i32, -top as i32),
+                Size2D::new((right - left) as i32, (top - bottom) as i32),
+            );
+            // Subpixel filtering spreads coverage into the pixels on either side of the glyph.
+            match rasterization_options {
+                RasterizationOptions::SubpixelAa => Ok(raster_bounds.inflate(1, 0)),
+                _ => Ok(raster_bounds),
+            }
+        }
+    }
+
     /// Rasterizes a glyph to a canvas with the given size and origin.
     ///
     /// Format conversion will be performed if the canvas format does not match the rasterization
@@ -783,6 +1408,10 @@ impl Font {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// See `Loader::rasterize_glyph()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
     pub fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -793,84 +1422,234 @@ impl Font {
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError> {
-        // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
-        // implementation.
-        unsafe {
-            let mut delta = FT_Vector {
-                x: f32_to_ft_fixed_26_6(origin.x),
-                y: f32_to_ft_fixed_26_6(-origin.y),
-            };
-            let mut ft_shape = FT_Matrix {
-                xx: (transform.scale_x * 65536.0) as FT_Fixed,
-                xy: (transform.skew_x * -65536.0) as FT_Fixed,
-                yx: (transform.skew_y * -65536.0) as FT_Fixed,
-                yy: (transform.scale_y * 65536.0) as FT_Fixed,
-            };
-            FT_Set_Transform(self.freetype_face, &mut ft_shape, &mut delta);
+        <Self as Loader>::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
 
-            assert_eq!(
-                FT_Set_Char_Size(
-                    self.freetype_face,
-                    f32_to_ft_fixed_26_6(point_size),
-                    0,
-                    0,
-                    0
-                ),
-                0
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, with the given adjustments to
+    /// its coverage.
+    ///
+    /// See `Loader::rasterize_glyph_with_adjustments()`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rasterize_glyph_with_adjustments(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<(), GlyphLoadingError> {
+        if let Some(threshold) = rasterization_adjustments.bilevel_threshold(rasterization_options)
+        {
+            return loader::rasterize_glyph_with_bilevel_threshold(
+                self,
+                canvas,
+                glyph_id,
+                point_size,
+                transform,
+                origin,
+                hinting_options,
+                rasterization_adjustments,
+                threshold,
             );
+        }
 
-            let mut load_flags = FT_LOAD_DEFAULT | FT_LOAD_RENDER;
-            load_flags |= self.hinting_and_rasterization_options_to_load_flags(
+        // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
+        // implementation.
+        unsafe {
+            self.load_transformed_glyph(
+                glyph_id,
+                point_size,
+                transform,
+                origin,
                 hinting_options,
                 rasterization_options,
-            );
-            if FT_Load_Glyph(self.freetype_face, glyph_id, load_flags as i32) != 0 {
-                return Err(GlyphLoadingError::NoSuchGlyph);
-            }
+                rasterization_adjustments,
+                FT_LOAD_RENDER,
+            )?;
 
             // TODO(pcwalton): Use the FreeType "direct" API to save a copy here. Note that we will
             // need to keep this around for bilevel rendering, as the direct API doesn't work with
             // that mode.
             let bitmap = &(*(*self.freetype_face).glyph).bitmap;
-            let bitmap_stride = (*bitmap).pitch as usize;
-            let bitmap_width = (*bitmap).width as u32;
-            let bitmap_height = (*bitmap).rows as u32;
+            let bitmap_stride = bitmap.pitch as usize;
+            let bitmap_width = bitmap.width;
+            let bitmap_height = bitmap.rows;
             let bitmap_size = Size2D::new(bitmap_width, bitmap_height);
-            let bitmap_buffer = (*bitmap).buffer as *const i8 as *const u8;
+            let bitmap_buffer = bitmap.buffer as *const i8 as *const u8;
             let bitmap_length = bitmap_stride * bitmap_height as usize;
-            let buffer = slice::from_raw_parts(bitmap_buffer, bitmap_length);
-            let dst_point = Point2D::new(
+            let mut buffer = Cow::Borrowed(slice::from_raw_parts(bitmap_buffer, bitmap_length));
+            let mut dst_point = Point2D::new(
                 (*(*self.freetype_face).glyph).bitmap_left,
                 -(*(*self.freetype_face).glyph).bitmap_top,
             );
 
+            // FreeType's subpixel bitmaps have three times as many columns (or, for vertical
+            // LCDs, rows) as there are pixels. Pack them into RGB pixels.
+            let (bitmap_size, bitmap_stride) = match bitmap.pixel_mode {
+                FT_PIXEL_MODE_LCD => {
+                    let (packed, stride) = filter_lcd_bitmap(
+                        &buffer,
+                        &bitmap_size,
+                        bitmap_stride,
+                        rasterization_adjustments.lcd_filter,
+                    );
+                    buffer = Cow::Owned(packed);
+                    // The filter spreads coverage one pixel to the left and right.
+                    dst_point.x -= 1;
+                    (Size2D::new(bitmap_width / 3 + 2, bitmap_height), stride)
+                }
+                FT_PIXEL_MODE_LCD_V => {
+                    buffer = Cow::Owned(interleave_lcd_v_bitmap(
+                        &buffer,
+                        &bitmap_size,
+                        bitmap_stride,
+                    ));
+                    let size = Size2D::new(bitmap_width, bitmap_height / 3);
+                    (size, size.width as usize * 3)
+                }
+                _ => (bitmap_size, bitmap_stride),
+            };
+            // Color glyphs, with `FT_LOAD_COLOR`, are premultiplied BGRA, and already
+            // gamma-corrected.
+            if bitmap.pixel_mode == FT_PIXEL_MODE_BGRA {
+                let pixels = buffer.to_mut();
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+            } else if bitmap.pixel_mode != FT_PIXEL_MODE_MONO {
+                rasterization_adjustments.apply_gamma(rasterization_options, buffer.to_mut());
+            }
+
             // FIXME(pcwalton): This function should return a Result instead.
-            match (*bitmap).pixel_mode {
+            match bitmap.pixel_mode {
                 FT_PIXEL_MODE_GRAY => {
-                    canvas.blit_from(dst_point, buffer, &bitmap_size, bitmap_stride, Format::A8);
+                    canvas.blit_from(dst_point, &buffer, &bitmap_size, bitmap_stride, Format::A8);
                 }
                 FT_PIXEL_MODE_LCD | FT_PIXEL_MODE_LCD_V => {
                     canvas.blit_from(
                         dst_point,
-                        buffer,
+                        &buffer,
                         &bitmap_size,
                         bitmap_stride,
                         Format::Rgb24,
                     );
                 }
                 FT_PIXEL_MODE_MONO => {
-                    canvas.blit_from_bitmap_1bpp(dst_point, buffer, &bitmap_size, bitmap_stride);
+                    canvas.blit_from_bitmap_1bpp(dst_point, &buffer, &bitmap_size, bitmap_stride);
+                }
+                FT_PIXEL_MODE_BGRA if canvas.format == Format::A8 => {
+                    let alpha: Vec<u8> = buffer.chunks_exact(4).map(|pixel| pixel[3]).collect();
+                    let alpha_stride = bitmap_stride / 4;
+                    canvas.blit_from(dst_point, &alpha, &bitmap_size, alpha_stride, Format::A8);
+                }
+                FT_PIXEL_MODE_BGRA => {
+                    canvas.blit_from(
+                        dst_point,
+                        &buffer,
+                        &bitmap_size,
+                        bitmap_stride,
+                        Format::Rgba32,
+                    );
                 }
                 _ => panic!("Unexpected FreeType pixel mode!"),
             }
 
-            FT_Set_Transform(self.freetype_face, ptr::null_mut(), ptr::null_mut());
-            reset_freetype_face_char_size(self.freetype_face);
+            reset_freetype_face_transform(self.freetype_face);
             Ok(())
         }
     }
 
-    fn hinting_and_rasterization_options_to_load_flags(
+    /// Loads a glyph into the glyph slot of the face, scaled to the given point size and
+    /// transformed to pixels with a y axis that points up.
+    ///
+    /// The origin, fractional part included, is applied to the outline before it is rendered, so
+    /// glyphs can be positioned at subpixel offsets. Unless this fails, the face is left with the
+    /// transform and size set, for `reset_freetype_face_transform()` to undo.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn load_transformed_glyph(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+        load_flags: u32,
+    ) -> Result<(), GlyphLoadingError> {
+        let mut delta = FT_Vector {
+            x: f32_to_ft_fixed_26_6(origin.x),
+            y: f32_to_ft_fixed_26_6(-origin.y),
+        };
+        let mut ft_shape = FT_Matrix {
+            xx: (transform.scale_x * 65536.0) as FT_Fixed,
+            xy: (transform.skew_x * -65536.0) as FT_Fixed,
+            yx: (transform.skew_y * -65536.0) as FT_Fixed,
+            yy: (transform.scale_y * 65536.0) as FT_Fixed,
+        };
+        FT_Set_Transform(self.freetype_face, &mut ft_shape, &mut delta);
+
+        // Bitmap-only fonts can't be set to sizes that none of their strikes has.
+        let error = FT_Set_Char_Size(
+            self.freetype_face,
+            f32_to_ft_fixed_26_6(point_size),
+            0,
+            0,
+            0,
+        );
+        if error != 0 {
+            reset_freetype_face_transform(self.freetype_face);
+            if !self.has_outlines() {
+                return Err(GlyphLoadingError::UnsupportedFormat);
+            }
+            return Err(glyph_loading_error(self.freetype_face, glyph_id, error));
+        }
+
+        let load_flags = load_flags
+            | self.hinting_and_rasterization_options_to_load_flags(
+                hinting_options,
+                rasterization_options,
+            );
+        self.set_stem_darkening(Some(rasterization_adjustments.stem_darkening));
+        let error = FT_Load_Glyph(self.freetype_face, glyph_id, load_flags as i32);
+        self.set_stem_darkening(None);
+        if error != 0 {
+            reset_freetype_face_transform(self.freetype_face);
+            return Err(glyph_loading_error(self.freetype_face, glyph_id, error));
+        }
+        Ok(())
+    }
+
+    /// Turns the stem darkening of the CFF, Type 1, and CID drivers and of the autohinter on or
+    /// off for this face, or restores the library default if `enabled` is `None`.
+    fn set_stem_darkening(&self, enabled: Option<bool>) {
+        unsafe {
+            let mut value: FT_Bool = enabled.unwrap_or(false) as FT_Bool;
+            let mut property = FT_Parameter {
+                tag: FT_PARAM_TAG_STEM_DARKENING,
+                data: match enabled {
+                    Some(_) => &mut value as *mut FT_Bool as FT_Pointer,
+                    None => ptr::null_mut(),
+                },
+            };
+            // This fails only with FreeType versions before 2.8, which lack the property.
+            let _ = FT_Face_Properties(self.freetype_face, 1, &mut property);
+        }
+    }
+
+    fn hinting_and_rasterization_options_to_load_flags(
         &self,
         hinting: HintingOptions,
         rasterization: RasterizationOptions,
@@ -881,14 +1660,20 @@ impl Font {
             }
             (HintingOptions::None, _) => FT_LOAD_TARGET_NORMAL | FT_LOAD_NO_HINTING,
             (HintingOptions::Vertical(_), RasterizationOptions::Bilevel)
-            | (HintingOptions::Full(_), RasterizationOptions::Bilevel) => FT_LOAD_TARGET_MONO,
+            | (HintingOptions::Full(_), RasterizationOptions::Bilevel)
+            | (HintingOptions::Autohint(_), RasterizationOptions::Bilevel) => FT_LOAD_TARGET_MONO,
             (HintingOptions::Vertical(_), _) => FT_LOAD_TARGET_LIGHT,
-            (HintingOptions::Full(_), _) => FT_LOAD_TARGET_NORMAL,
+            (HintingOptions::Full(_), _) | (HintingOptions::Autohint(_), _) => {
+                FT_LOAD_TARGET_NORMAL
+            }
         };
+        if let HintingOptions::Autohint(_) = hinting {
+            options |= FT_LOAD_FORCE_AUTOHINT
+        }
         if rasterization == RasterizationOptions::Bilevel {
             options |= FT_LOAD_MONOCHROME
         }
-        options
+        options | self.load_flags
     }
 
     /// Returns a handle to this font, if possible.
@@ -904,7 +1689,23 @@ impl Font {
     /// If this font is a member of a collection, this function returns the data for the entire
     /// collection.
     pub fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
-        Some(self.font_data.clone())
+        match self.font_data {
+            FontData::Memory(ref data) => Some(data.clone()),
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => Some(Arc::new(mapping.to_vec())),
+        }
+    }
+
+    /// Returns the raw font data without copying it, if it's available.
+    ///
+    /// Data in memory is shared with this font, and memory-mapped data is borrowed from the
+    /// mapping.
+    pub fn font_data(&self) -> Option<loader::FontData<'_>> {
+        match self.font_data {
+            FontData::Memory(ref data) => Some(loader::FontData::Memory(data.clone())),
+            #[cfg(feature = "mmap")]
+            FontData::Mapped(ref mapping) => Some(loader::FontData::Borrowed(mapping)),
+        }
     }
 
     /// Get font fallback results for the given text and locale.
@@ -953,6 +1754,629 @@ impl Font {
             Some(buf)
         }
     }
+
+    /// Returns the tags of the tables in the font's sfnt table directory, in directory order.
+    ///
+    /// This doesn't load any of the tables.
+    pub fn table_tags(&self) -> Vec<u32> {
+        unsafe {
+            // With a null tag, FreeType returns the number of tables in the length.
+            let mut table_count = 0;
+            if FT_Sfnt_Table_Info(self.freetype_face, 0, ptr::null_mut(), &mut table_count) != 0 {
+                return vec![];
+            }
+            (0..(table_count as FT_UInt))
+                .filter_map(|table_index| {
+                    let (mut tag, mut length) = (0, 0);
+                    if FT_Sfnt_Table_Info(self.freetype_face, table_index, &mut tag, &mut length)
+                        != 0
+                    {
+                        return None;
+                    }
+                    Some(tag as u32)
+                })
+                .collect()
+        }
+    }
+
+    /// Returns true if the font has a table with the given tag, without loading it.
+    #[inline]
+    pub fn contains_table(&self, table_tag: u32) -> bool {
+        <Self as Loader>::contains_table(self, table_tag)
+    }
+
+    /// Returns the format of the font's glyph outlines, from the tables present.
+    ///
+    /// See `Loader::outline_format()`.
+    #[inline]
+    pub fn outline_format(&self) -> OutlineFormat {
+        <Self as Loader>::outline_format(self)
+    }
+
+    /// Returns the color glyph technologies that the font carries, from the tables present.
+    ///
+    /// See `Loader::color_format()`.
+    #[inline]
+    pub fn color_format(&self) -> ColorFormat {
+        <Self as Loader>::color_format(self)
+    }
+
+    /// Returns true if the font is a variable font, that is, if it has an `fvar` table.
+    #[inline]
+    pub fn is_variable(&self) -> bool {
+        <Self as Loader>::is_variable(self)
+    }
+
+    /// Returns the design axes of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    #[inline]
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        <Self as Loader>::variation_axes(self)
+    }
+
+    /// Returns the named instances of a variable font, as declared in its `fvar` table.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    #[inline]
+    pub fn named_instances(&self) -> Vec<NamedInstance> {
+        <Self as Loader>::named_instances(self)
+    }
+
+    /// Instantiates a variable font at the given point in its design space.
+    ///
+    /// `coords` maps axis tags to values. Values outside the range of an axis are clamped to it,
+    /// and axes that aren't mentioned are left at their default values. Subsequent outlines,
+    /// metrics, and rasterization reflect the chosen instance.
+    ///
+    /// This has no effect on fonts that aren't variable fonts.
+    pub fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return;
+        }
+        let mut design_coords: Vec<FT_Fixed> = variations::design_coordinates(&axes, coords)
+            .into_iter()
+            .map(f32_to_ft_fixed_16_16)
+            .collect();
+
+        // Clones of this font share its FreeType face, so make a fresh face for the new instance
+        // rather than changing theirs too.
+        let face_index = unsafe { (*self.freetype_face).face_index & 0xffff };
+        let font = match Font::from_font_data(self.font_data.clone(), face_index as u32) {
+            Ok(font) => font,
+            Err(_) => return,
+        };
+        unsafe {
+            if FT_Set_Var_Design_Coordinates(
+                font.freetype_face,
+                design_coords.len() as FT_UInt,
+                design_coords.as_mut_ptr(),
+            ) != 0
+            {
+                warn!("set_variations(): failed to set design coordinates");
+                return;
+            }
+        }
+        *self = font;
+    }
+
+    /// Returns the current position of a variable font in its design space, as one `(tag, value)`
+    /// pair per axis, in axis order.
+    ///
+    /// If the font isn't a variable font, this returns an empty vector.
+    pub fn variations(&self) -> Vec<(u32, f32)> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return vec![];
+        }
+        let mut design_coords: Vec<FT_Fixed> = vec![0; axes.len()];
+        unsafe {
+            if FT_Get_Var_Design_Coordinates(
+                self.freetype_face,
+                design_coords.len() as FT_UInt,
+                design_coords.as_mut_ptr(),
+            ) != 0
+            {
+                warn!("variations(): failed to get design coordinates");
+                return axes
+                    .iter()
+                    .map(|axis| (axis.tag, axis.default_value))
+                    .collect();
+            }
+        }
+        axes.iter()
+            .zip(design_coords)
+            .map(|(axis, value)| (axis.tag, ft_fixed_16_16_to_f32(value)))
+            .collect()
+    }
+
+    /// Returns the layers that make up the given color glyph, as declared in the font's `COLR`
+    /// table, with colors taken from the `CPAL` palette at `palette_index`.
+    ///
+    /// Layers are returned bottommost first. If the glyph isn't a color glyph, this returns an
+    /// empty vector. If the palette doesn't exist, palette 0 is used instead.
+    #[inline]
+    pub fn glyph_color_layers(
+        &self,
+        glyph_id: u32,
+        palette_index: u16,
+    ) -> Result<Vec<ColorLayer>, GlyphLoadingError> {
+        <Self as Loader>::glyph_color_layers(self, glyph_id, palette_index)
+    }
+
+    /// Returns the paint graph of the given `COLR` version 1 color glyph, with colors taken from
+    /// the `CPAL` palette at `palette_index`.
+    ///
+    /// See `Loader::colrv1_paint()`.
+    #[inline]
+    pub fn colrv1_paint(&self, glyph_id: u32, palette_index: u16) -> Option<Paint> {
+        <Self as Loader>::colrv1_paint(self, glyph_id, palette_index)
+    }
+
+    /// Returns the color palettes in the font's `CPAL` table, in order of palette index.
+    ///
+    /// See `Loader::color_palettes()`.
+    #[inline]
+    pub fn color_palettes(&self) -> Vec<ColorPalette> {
+        <Self as Loader>::color_palettes(self)
+    }
+
+    /// Rasterizes a color glyph to an `Rgba32` canvas with the given size and origin, from the
+    /// font's embedded color bitmaps or `COLR` layers.
+    ///
+    /// The result is in premultiplied R8G8B8A8 format; see `Format::Rgba32`. Returns
+    /// `GlyphLoadingError::NoColorData` if the font has no color data.
+    #[inline]
+    pub fn rasterize_color_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_color_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Returns the strikes of pre-rendered bitmap glyphs that the font contains, from its `sbix`,
+    /// `CBLC`/`CBDT`, and `EBLC`/`EBDT` tables.
+    ///
+    /// If the font has no embedded bitmaps, this returns an empty vector.
+    #[inline]
+    pub fn bitmap_strikes(&self) -> Vec<BitmapStrike> {
+        <Self as Loader>::bitmap_strikes(self)
+    }
+
+    /// Returns the bitmap strike that best matches the given size, in pixels per em.
+    ///
+    /// See `Loader::best_strike_for_size()`.
+    #[inline]
+    pub fn best_strike_for_size(&self, pixels_per_em: f32) -> Option<BitmapStrike> {
+        <Self as Loader>::best_strike_for_size(self, pixels_per_em)
+    }
+
+    /// Loads the pre-rendered bitmap of a glyph from one of the strikes returned by
+    /// `bitmap_strikes()`.
+    ///
+    /// Returns `None` if the strike has no bitmap for the glyph.
+    #[inline]
+    pub fn load_bitmap_glyph(
+        &self,
+        glyph_id: u32,
+        strike: &BitmapStrike,
+    ) -> Result<Option<BitmapGlyph>, GlyphLoadingError> {
+        <Self as Loader>::load_bitmap_glyph(self, glyph_id, strike)
+    }
+
+    /// Returns the SVG document from the font's `SVG ` table that describes the given glyph,
+    /// decompressed if necessary.
+    ///
+    /// Returns `None` if the glyph has no SVG representation.
+    #[inline]
+    pub fn glyph_svg_document(&self, glyph_id: u32) -> Result<Option<Vec<u8>>, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_document(self, glyph_id)
+    }
+
+    /// Returns the kerning adjustment between the given pair of glyphs, in pixels at the given
+    /// point size, from the `GPOS` or `kern` table.
+    ///
+    /// If the font doesn't kern the pair, this returns a zero vector.
+    #[inline]
+    pub fn kerning(&self, left_glyph: u32, right_glyph: u32, point_size: f32) -> Vector2D<f32> {
+        <Self as Loader>::kerning(self, left_glyph, right_glyph, point_size)
+    }
+
+    /// Applies the `GSUB` ligature substitutions of the given OpenType features to a run of
+    /// glyphs, returning the substituted run.
+    ///
+    /// If `features` is empty, the `liga` and `clig` features are used.
+    #[inline]
+    pub fn substitute_ligatures(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::substitute_ligatures(self, glyphs, features)
+    }
+
+    /// Applies the `GSUB` contextual and chaining contextual substitutions of the given OpenType
+    /// features to a run of glyphs, returning the substituted run.
+    ///
+    /// See `Loader::apply_contextual_substitutions()`.
+    #[inline]
+    pub fn apply_contextual_substitutions(&self, glyphs: &[u32], features: &[u32]) -> Vec<u32> {
+        <Self as Loader>::apply_contextual_substitutions(self, glyphs, features)
+    }
+
+    /// Returns the `(input, output)` glyph pairs that the single and alternate substitutions of
+    /// the given OpenType feature can produce, sorted by input glyph.
+    ///
+    /// See `Loader::feature_affects()`.
+    #[inline]
+    pub fn feature_affects(&self, feature: u32) -> Vec<(u32, u32)> {
+        <Self as Loader>::feature_affects(self, feature)
+    }
+
+    /// Returns the alternates of the given glyph, for glyph pickers, in the order the font lists
+    /// them.
+    ///
+    /// See `Loader::glyph_alternates()`.
+    #[inline]
+    pub fn glyph_alternates(&self, glyph_id: u32) -> Vec<u32> {
+        <Self as Loader>::glyph_alternates(self, glyph_id)
+    }
+
+    /// Returns the caret positions within the given ligature glyph, in font units from its
+    /// origin, from the `GDEF` table.
+    ///
+    /// Glyphs that aren't ligatures yield an empty vector.
+    #[inline]
+    pub fn ligature_carets(&self, glyph_id: u32) -> Vec<f32> {
+        <Self as Loader>::ligature_carets(self, glyph_id)
+    }
+
+    /// Returns whether the given glyph is a base glyph, a ligature, a mark, or a component of a
+    /// glyph, from the `GDEF` table.
+    #[inline]
+    pub fn glyph_class(&self, glyph_id: u32) -> GlyphClass {
+        <Self as Loader>::glyph_class(self, glyph_id)
+    }
+
+    /// Returns the mark attachment class of the given glyph from the `GDEF` table, or 0 if it
+    /// has none.
+    #[inline]
+    pub fn mark_attach_class(&self, glyph_id: u32) -> u16 {
+        <Self as Loader>::mark_attach_class(self, glyph_id)
+    }
+
+    /// Shapes a run of text with HarfBuzz, returning its glyphs in visual order with their
+    /// positions in font units.
+    ///
+    /// This method is only available with the `harfbuzz` Cargo feature.
+    #[cfg(feature = "harfbuzz")]
+    #[inline]
+    pub fn shape(
+        &self,
+        text: &str,
+        direction: Direction,
+        script: Script,
+        features: &[Feature],
+    ) -> Vec<ShapedGlyph> {
+        <Self as Loader>::shape(self, text, direction, script, features)
+    }
+
+    /// Returns the set of Unicode codepoints that this font maps to glyphs, read from the best
+    /// Unicode subtable of its `cmap` table.
+    #[inline]
+    pub fn codepoint_coverage(&self) -> CodepointCoverage {
+        <Self as Loader>::codepoint_coverage(self)
+    }
+
+    /// Returns the characters that the font's best Unicode `cmap` subtable maps to the given
+    /// glyph, in codepoint order.
+    ///
+    /// The inverse of the `cmap` is built the first time this is called and cached on the font.
+    pub fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        let reverse_cmap = self
+            .reverse_cmap
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                let data = self.load_font_table(cmap::TAG).unwrap_or_default();
+                Arc::new(ReverseCmap::parse(&data))
+            })
+            .clone();
+        reverse_cmap.chars_for_glyph(glyph_id)
+    }
+
+    /// Builds a new font containing only the given glyphs, along with glyph 0 (`.notdef`) and
+    /// the components of any composite glyphs among them.
+    ///
+    /// The glyphs are renumbered; the returned `FontSubset` records the mapping. Layout, color,
+    /// bitmap, and variation tables are dropped.
+    #[inline]
+    pub fn subset(&self, glyph_ids: &[u32]) -> Result<FontSubset, GlyphLoadingError> {
+        <Self as Loader>::subset(self, glyph_ids)
+    }
+
+    /// Returns the restrictions that the font's license places on embedding it in documents, as
+    /// recorded in the `fsType` field of its `OS/2` table.
+    #[inline]
+    pub fn embedding_restrictions(&self) -> EmbeddingRestrictions {
+        <Self as Loader>::embedding_restrictions(self)
+    }
+
+    /// Returns the PANOSE classification of the font from its `OS/2` table, or `None` if it has
+    /// no such table.
+    #[inline]
+    pub fn panose(&self) -> Option<Panose> {
+        <Self as Loader>::panose(self)
+    }
+
+    /// Returns the four-character tag of the font's vendor, from its `OS/2` table.
+    ///
+    /// See `Loader::vendor_id()`.
+    #[inline]
+    pub fn vendor_id(&self) -> Option<[u8; 4]> {
+        <Self as Loader>::vendor_id(self)
+    }
+
+    /// Returns the version string of the font (`name` ID 5).
+    ///
+    /// See `Loader::version_string()`.
+    #[inline]
+    pub fn version_string(&self) -> Option<String> {
+        <Self as Loader>::version_string(self)
+    }
+
+    /// Returns the distance that the pen moves down after drawing the given glyph in vertical
+    /// layout, in font units, from the `vmtx` table.
+    #[inline]
+    pub fn vertical_advance(&self, glyph_id: u32) -> Result<f32, GlyphLoadingError> {
+        <Self as Loader>::vertical_advance(self, glyph_id)
+    }
+
+    /// Returns the point in the given glyph's coordinate space, in font units, that is placed on
+    /// the pen position in vertical layout.
+    #[inline]
+    pub fn vertical_origin(&self, glyph_id: u32) -> Result<Vector2D<f32>, GlyphLoadingError> {
+        <Self as Loader>::vertical_origin(self, glyph_id)
+    }
+
+    /// Returns how to make this font look like a face with the given properties, by slanting or
+    /// emboldening it.
+    ///
+    /// See `Loader::synthesis()`.
+    #[inline]
+    pub fn synthesis(&self, properties: &Properties) -> Synthesis {
+        <Self as Loader>::synthesis(self, properties)
+    }
+
+    /// Rasterizes a glyph to a canvas, slanted and emboldened as `synthesis` says.
+    ///
+    /// See `Loader::rasterize_glyph_with_synthesis()`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn rasterize_glyph_with_synthesis(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        synthesis: &Synthesis,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_with_synthesis(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            synthesis,
+        )
+    }
+
+    /// Rasterizes a line of text to a canvas, laying its glyphs out from left to right.
+    ///
+    /// See `Loader::rasterize_string()`.
+    #[inline]
+    pub fn rasterize_string(
+        &self,
+        canvas: &mut Canvas,
+        text: &str,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_string(
+            self,
+            canvas,
+            text,
+            point_size,
+            origin,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a single-channel signed distance field of a glyph to a canvas, for rendering
+    /// text on the GPU.
+    ///
+    /// See `Loader::rasterize_glyph_sdf()`.
+    #[inline]
+    pub fn rasterize_glyph_sdf(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        origin: &Point2D<f32>,
+        spread: f32,
+    ) -> Result<(), GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_sdf(self, canvas, glyph_id, point_size, origin, spread)
+    }
+
+    /// Rasterizes a glyph into floating-point coverage values in [0, 1], one per pixel of a
+    /// buffer of the given size, row by row.
+    ///
+    /// See `Loader::rasterize_glyph_f32()`.
+    #[inline]
+    pub fn rasterize_glyph_f32(
+        &self,
+        size: &Size2D<u32>,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+    ) -> Result<Vec<f32>, GlyphLoadingError> {
+        <Self as Loader>::rasterize_glyph_f32(
+            self,
+            size,
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+        )
+    }
+
+    /// Sends the vector path for a glyph to a path builder, after transforming it from font units
+    /// by `transform`.
+    ///
+    /// See `Loader::outline_transformed()`.
+    #[inline]
+    pub fn outline_transformed<B>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        transform: &Transform2D<f32>,
+        path_builder: &mut B,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        B: PathBuilder,
+    {
+        <Self as Loader>::outline_transformed(self, glyph_id, hinting_mode, transform, path_builder)
+    }
+
+    /// Returns the vector path for a glyph, in font units, as a `lyon_path` path.
+    ///
+    /// See `Loader::outline_path()`.
+    #[inline]
+    pub fn outline_path(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<lyon_path::Path, GlyphLoadingError> {
+        <Self as Loader>::outline_path(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the vector outline of a glyph, in font units, as an owned `Outline`.
+    ///
+    /// See `Loader::glyph_outline()`.
+    #[inline]
+    pub fn glyph_outline(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+    ) -> Result<Outline, GlyphLoadingError> {
+        <Self as Loader>::glyph_outline(self, glyph_id, hinting_mode)
+    }
+
+    /// Returns the unhinted outline of a glyph, scaled to `point_size`, as the `d` attribute of
+    /// an SVG `path` element.
+    ///
+    /// See `Loader::glyph_svg_path()`.
+    #[inline]
+    pub fn glyph_svg_path(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+    ) -> Result<String, GlyphLoadingError> {
+        <Self as Loader>::glyph_svg_path(self, glyph_id, point_size)
+    }
+
+    /// Returns the OpenType layout features that the font's `GSUB` and `GPOS` tables declare,
+    /// grouped by script and language system.
+    #[inline]
+    pub fn layout_features(&self) -> LayoutFeatures {
+        <Self as Loader>::layout_features(self)
+    }
+
+    /// Returns the OpenType script tags that the font's `GSUB` and `GPOS` tables declare, sorted.
+    #[inline]
+    pub fn supported_scripts(&self) -> Vec<ScriptTag> {
+        <Self as Loader>::supported_scripts(self)
+    }
+
+    /// Returns the OpenType language system tags that the font's `GSUB` and `GPOS` tables
+    /// declare for the given script, sorted.
+    #[inline]
+    pub fn supported_languages(&self, script: ScriptTag) -> Vec<LangTag> {
+        <Self as Loader>::supported_languages(self, script)
+    }
+
+    /// Returns the position of the baseline with the given tag for the given script in
+    /// horizontal text, in font units, from the `BASE` table.
+    #[inline]
+    pub fn baseline(&self, baseline_tag: u32, script: ScriptTag) -> Option<f32> {
+        <Self as Loader>::baseline(self, baseline_tag, script)
+    }
+
+    /// Returns every record of the font's `name` table, in every language, in table order.
+    #[inline]
+    pub fn name_records(&self) -> Vec<NameRecord> {
+        <Self as Loader>::name_records(self)
+    }
+
+    /// Returns the family name of the font in the language of a BCP 47 locale, falling back to
+    /// English and then to the first name available.
+    #[inline]
+    pub fn localized_family_name(&self, locale: &str) -> Option<String> {
+        <Self as Loader>::localized_family_name(self, locale)
+    }
+
+    /// Returns the math layout constants of the font from its `MATH` table, in font units, or
+    /// `None` if the font has no such table.
+    #[inline]
+    pub fn math_constants(&self) -> Option<MathConstants> {
+        <Self as Loader>::math_constants(self)
+    }
+
+    /// Returns the prebuilt variants of a glyph for stretching in the given direction, from
+    /// smallest to largest, as listed in the font's `MATH` table.
+    #[inline]
+    pub fn math_glyph_variants(
+        &self,
+        glyph_id: u32,
+        direction: StretchDirection,
+    ) -> Vec<GlyphVariant> {
+        <Self as Loader>::math_glyph_variants(self, glyph_id, direction)
+    }
+
+    /// Returns the flags and signature formats of the font's `DSIG` table, or `None` if it has no
+    /// such table.
+    #[inline]
+    pub fn digital_signature(&self) -> Option<SignatureInfo> {
+        <Self as Loader>::digital_signature(self)
+    }
+
+    /// Returns true if the font's `DSIG` table holds at least one signature.
+    #[inline]
+    pub fn has_digital_signature(&self) -> bool {
+        <Self as Loader>::has_digital_signature(self)
+    }
 }
 
 impl Clone for Font {
@@ -962,6 +2386,8 @@ impl Clone for Font {
             Font {
                 freetype_face: self.freetype_face,
                 font_data: self.font_data.clone(),
+                reverse_cmap: Mutex::new((*self.reverse_cmap.lock().unwrap()).clone()),
+                load_flags: self.load_flags,
             }
         }
     }
@@ -1047,11 +2473,26 @@ impl Loader for Font {
         self.glyph_for_char(character)
     }
 
+    #[inline]
+    fn glyphs_for_str(&self, text: &str) -> Vec<Option<u32>> {
+        self.glyphs_for_str(text)
+    }
+
+    #[inline]
+    fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        self.chars_for_glyph(glyph_id)
+    }
+
     #[inline]
     fn glyph_by_name(&self, name: &str) -> Option<u32> {
         self.glyph_by_name(name)
     }
 
+    #[inline]
+    fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        self.glyph_name(glyph_id)
+    }
+
     #[inline]
     fn glyph_count(&self) -> u32 {
         self.glyph_count()
@@ -1090,11 +2531,21 @@ impl Loader for Font {
         self.metrics()
     }
 
+    #[inline]
+    fn units_per_em(&self) -> u32 {
+        self.units_per_em()
+    }
+
     #[inline]
     fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
         self.copy_font_data()
     }
 
+    #[inline]
+    fn font_data(&self) -> Option<loader::FontData<'_>> {
+        self.font_data()
+    }
+
     #[inline]
     fn supports_hinting_options(
         &self,
@@ -1105,7 +2556,29 @@ impl Loader for Font {
     }
 
     #[inline]
-    fn rasterize_glyph(
+    fn raster_bounds_with_adjustments(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: &FontTransform,
+        origin: &Point2D<f32>,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
+    ) -> Result<Rect<i32>, GlyphLoadingError> {
+        self.raster_bounds_with_adjustments(
+            glyph_id,
+            point_size,
+            transform,
+            origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )
+    }
+
+    #[inline]
+    fn rasterize_glyph_with_adjustments(
         &self,
         canvas: &mut Canvas,
         glyph_id: u32,
@@ -1114,8 +2587,9 @@ impl Loader for Font {
         origin: &Point2D<f32>,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+        rasterization_adjustments: RasterizationAdjustments,
     ) -> Result<(), GlyphLoadingError> {
-        self.rasterize_glyph(
+        self.rasterize_glyph_with_adjustments(
             canvas,
             glyph_id,
             point_size,
@@ -1123,6 +2597,7 @@ impl Loader for Font {
             origin,
             hinting_options,
             rasterization_options,
+            rasterization_adjustments,
         )
     }
 
@@ -1135,10 +2610,98 @@ impl Loader for Font {
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
         self.load_font_table(table_tag)
     }
+
+    #[inline]
+    fn table_tags(&self) -> Vec<u32> {
+        self.table_tags()
+    }
+
+    #[inline]
+    fn set_variations(&mut self, coords: &[(u32, f32)]) {
+        self.set_variations(coords)
+    }
+
+    #[inline]
+    fn variations(&self) -> Vec<(u32, f32)> {
+        self.variations()
+    }
+}
+
+/// Classifies an error that FreeType returned while loading the given glyph.
+///
+/// FreeType reports glyph IDs that are out of range as invalid arguments, so those are checked
+/// for directly.
+unsafe fn glyph_loading_error(face: FT_Face, glyph_id: u32, error: FT_Error) -> GlyphLoadingError {
+    if glyph_id as FT_Long >= (*face).num_glyphs {
+        return GlyphLoadingError::NoSuchGlyph;
+    }
+    match error {
+        FT_ERR_INVALID_GLYPH_INDEX => GlyphLoadingError::NoSuchGlyph,
+        FT_ERR_UNKNOWN_FILE_FORMAT
+        | FT_ERR_UNIMPLEMENTED_FEATURE
+        | FT_ERR_INVALID_GLYPH_FORMAT
+        | FT_ERR_CANNOT_RENDER_GLYPH => GlyphLoadingError::UnsupportedFormat,
+        FT_ERR_OUT_OF_MEMORY => GlyphLoadingError::PlatformError("out of memory".to_owned()),
+        // Errors in the file, and the errors of the TrueType bytecode interpreter and of the
+        // CFF, Type 1, and bitmap font drivers.
+        FT_ERR_INVALID_FILE_FORMAT
+        | FT_ERR_INVALID_TABLE
+        | FT_ERR_INVALID_OFFSET
+        | FT_ERR_INVALID_OUTLINE
+        | FT_ERR_INVALID_COMPOSITE
+        | FT_ERR_TOO_MANY_HINTS
+        | FT_ERR_FIRST_DRIVER_ERROR..=FT_ERR_LAST_DRIVER_ERROR => GlyphLoadingError::MalformedFont,
+        _ => GlyphLoadingError::PlatformError(format!("FreeType error {:#x}", error)),
+    }
 }
 
 unsafe fn setup_freetype_face(face: FT_Face) {
     reset_freetype_face_char_size(face);
+    select_best_charmap(face);
+}
+
+/// Selects the charmap that characters are best looked up in, for fonts with several.
+///
+/// The full Unicode subtables come first, then the Basic Multilingual Plane ones, then the
+/// Windows symbol subtable, whose codes `Font::char_index()` offsets into the private use area,
+/// and last the Mac Roman subtable, which `Font::char_index()` encodes characters for.
+unsafe fn select_best_charmap(face: FT_Face) {
+    // Faces without charmaps, such as those of fonts with no `cmap` table, have a null array.
+    if (*face).num_charmaps <= 0 || (*face).charmaps.is_null() {
+        return;
+    }
+    let charmaps = slice::from_raw_parts((*face).charmaps, (*face).num_charmaps as usize);
+    let best_charmap = charmaps
+        .iter()
+        .filter_map(|&charmap| {
+            let priority = match ((*charmap).platform_id, (*charmap).encoding_id) {
+                (cmap::PLATFORM_WINDOWS, cmap::WINDOWS_ENCODING_UNICODE_FULL) => 6,
+                (cmap::PLATFORM_UNICODE, cmap::UNICODE_ENCODING_FULL)
+                | (cmap::PLATFORM_UNICODE, cmap::UNICODE_ENCODING_FULL_REPERTOIRE) => 5,
+                (cmap::PLATFORM_WINDOWS, cmap::WINDOWS_ENCODING_UNICODE_BMP) => 4,
+                (cmap::PLATFORM_UNICODE, encoding_id)
+                    if encoding_id != cmap::UNICODE_ENCODING_VARIATION_SEQUENCES =>
+                {
+                    3
+                }
+                (cmap::PLATFORM_WINDOWS, cmap::WINDOWS_ENCODING_SYMBOL) => 2,
+                (cmap::PLATFORM_MACINTOSH, cmap::MACINTOSH_ENCODING_ROMAN) => 1,
+                _ => return None,
+            };
+            Some((priority, charmap))
+        })
+        // Of equally good charmaps, keep the first, as FreeType does.
+        .min_by_key(|&(priority, _)| -priority)
+        .map(|(_, charmap)| charmap);
+    if let Some(charmap) = best_charmap {
+        FT_Set_Charmap(face, charmap);
+    }
+}
+
+/// Removes the transform that `Font::load_transformed_glyph()` set, and resets the size.
+unsafe fn reset_freetype_face_transform(face: FT_Face) {
+    FT_Set_Transform(face, ptr::null_mut(), ptr::null_mut());
+    reset_freetype_face_char_size(face);
 }
 
 unsafe fn reset_freetype_face_char_size(face: FT_Face) {
@@ -1152,6 +2715,45 @@ unsafe fn reset_freetype_face_char_size(face: FT_Face) {
     }
 }
 
+/// Filters a horizontal FreeType LCD bitmap, `size.width` subpixels wide, into packed RGB
+/// pixels, returning the result and its stride.
+///
+/// The result is two pixels wider than the bitmap, as the filter spreads coverage into the
+/// pixels on either side of it.
+fn filter_lcd_bitmap(
+    src: &[u8],
+    size: &Size2D<u32>,
+    stride: usize,
+    filter: LcdFilter,
+) -> (Vec<u8>, usize) {
+    let (src_width, height) = (size.width as usize, size.height as usize);
+    let dest_stride = src_width + 6;
+    let mut dest = vec![0; dest_stride * height];
+    for y in 0..height {
+        let src_row = &src[(y * stride)..(y * stride + src_width)];
+        let dest_row = &mut dest[(y * dest_stride)..((y + 1) * dest_stride)];
+        filter.filter(src_row, dest_row);
+    }
+    (dest, dest_stride)
+}
+
+/// Packs a vertical FreeType LCD bitmap, whose pixels are split across three consecutive rows,
+/// into RGB pixels.
+///
+/// We never ask FreeType for vertical subpixel rendering, so this doesn't bother filtering.
+fn interleave_lcd_v_bitmap(src: &[u8], size: &Size2D<u32>, stride: usize) -> Vec<u8> {
+    let (width, height) = (size.width as usize, size.height as usize / 3);
+    let mut dest = vec![0; width * height * 3];
+    for y in 0..height {
+        for x in 0..width {
+            for channel in 0..3 {
+                dest[(y * width + x) * 3 + channel] = src[(y * 3 + channel) * stride + x];
+            }
+        }
+    }
+    dest
+}
+
 #[repr(C)]
 struct FT_SfntName {
     platform_id: FT_UShort,
@@ -1170,7 +2772,20 @@ fn f32_to_ft_fixed_26_6(float: f32) -> FT_Long {
     f32::round(float * 64.0) as FT_Long
 }
 
+fn ft_fixed_16_16_to_f32(fixed: FT_Fixed) -> f32 {
+    (fixed as f32) / 65536.0
+}
+
+fn f32_to_ft_fixed_16_16(float: f32) -> FT_Fixed {
+    f32::round(float * 65536.0) as FT_Fixed
+}
+
 extern "C" {
+    fn FT_Face_Properties(
+        face: FT_Face,
+        num_properties: FT_UInt,
+        properties: *mut FT_Parameter,
+    ) -> FT_Error;
     fn FT_Get_Font_Format(face: FT_Face) -> *const c_char;
     fn FT_Get_BDF_Property(
         face: FT_Face,
@@ -1186,14 +2801,24 @@ extern "C" {
     ) -> FT_Long;
     fn FT_Get_Sfnt_Name(face: FT_Face, idx: FT_UInt, aname: *mut FT_SfntName) -> FT_Error;
     fn FT_Get_Sfnt_Name_Count(face: FT_Face) -> FT_UInt;
+    fn FT_Get_Var_Design_Coordinates(
+        face: FT_Face,
+        num_coords: FT_UInt,
+        coords: *mut FT_Fixed,
+    ) -> FT_Error;
+    fn FT_Set_Var_Design_Coordinates(
+        face: FT_Face,
+        num_coords: FT_UInt,
+        coords: *mut FT_Fixed,
+    ) -> FT_Error;
 }
 
 #[cfg(test)]
 mod test {
     use crate::loaders::freetype::Font;
 
-    static PCF_FONT_PATH: &'static str = "resources/tests/times-roman-pcf/timR12.pcf";
-    static PCF_FONT_POSTSCRIPT_NAME: &'static str = "Times-Roman";
+    static PCF_FONT_PATH: &str = "resources/tests/times-roman-pcf/timR12.pcf";
+    static PCF_FONT_POSTSCRIPT_NAME: &str = "Times-Roman";
 
     #[test]
     fn get_pcf_postscript_name() {
@@ -14,6 +14,7 @@ use float_ord::FloatOrd;
 
 use crate::error::SelectionError;
 use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::source::MatchScore;
 
 /// This follows CSS Fonts Level 3 § 5.2 [1].
 ///
@@ -78,12 +79,11 @@ pub fn find_best_match(
     };
     let matching_style = *style_preference
         .iter()
-        .filter(|&query_style| {
+        .find(|&query_style| {
             matching_set
                 .iter()
                 .any(|&index| candidates[index].style == *query_style)
         })
-        .next()
         .unwrap();
     matching_set.retain(|&index| candidates[index].style == matching_style);
 
@@ -144,9 +144,108 @@ pub fn find_best_match(
 
     // Step 4d concerns `font-size`, but fonts in `font-kit` are unsized, so we ignore that.
 
+    // CSS leaves faces that are still tied in an unspecified order. Prefer the one whose italic
+    // angle is closest to the requested one, if any; this distinguishes, say, a 12° oblique from
+    // a 20° one.
+    if let Some(query_angle) = query.italic_angle {
+        let min_angle_distance = matching_set
+            .iter()
+            .map(|&index| italic_angle_distance(&candidates[index], query_angle))
+            .min();
+        matching_set.retain(|&index| {
+            Some(italic_angle_distance(&candidates[index], query_angle)) == min_angle_distance
+        });
+    }
+
     // Return the result.
     matching_set
         .into_iter()
         .next()
         .ok_or(SelectionError::NotFound)
 }
+
+/// Orders all of the candidates from best to worst match, following the same preferences as
+/// `find_best_match()`, so that the first index returned is the one that it returns.
+///
+/// Font stretch is compared first, then style, then weight, and then italic angle if the query
+/// specifies one. Candidates that match equally well keep their original order.
+pub fn rank_matches(candidates: &[Properties], query: &Properties) -> Vec<(usize, MatchScore)> {
+    let style_preference = match query.style {
+        Style::Italic => [Style::Italic, Style::Oblique, Style::Normal],
+        Style::Oblique => [Style::Oblique, Style::Italic, Style::Normal],
+        Style::Normal => [Style::Normal, Style::Oblique, Style::Italic],
+    };
+
+    let mut ranked: Vec<_> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| {
+            let stretch_difference = candidate.stretch.0 - query.stretch.0;
+            let weight_difference = candidate.weight.0 - query.weight.0;
+            let style_distance = style_preference
+                .iter()
+                .position(|&style| style == candidate.style)
+                .unwrap() as u8;
+
+            // Step 4a: prefer narrower widths for normal or narrower queries, and wider widths
+            // otherwise.
+            let stretch_key = if (query.stretch <= Stretch::NORMAL) == (stretch_difference <= 0.0)
+                || stretch_difference == 0.0
+            {
+                (0, FloatOrd(stretch_difference.abs()))
+            } else {
+                (1, FloatOrd(stretch_difference.abs()))
+            };
+
+            // Step 4c, with the same special cases for weights between 400 and 500.
+            let weight_key = if (query.weight >= Weight(400.0)
+                && query.weight < Weight(450.0)
+                && candidate.weight == Weight(500.0))
+                || (query.weight >= Weight(450.0)
+                    && query.weight <= Weight(500.0)
+                    && candidate.weight == Weight(400.0))
+            {
+                (0, FloatOrd(0.0))
+            } else if (query.weight <= Weight(500.0)) == (weight_difference <= 0.0)
+                || weight_difference == 0.0
+            {
+                (1, FloatOrd(weight_difference.abs()))
+            } else {
+                (2, FloatOrd(weight_difference.abs()))
+            };
+
+            let score = MatchScore {
+                family_index: 0,
+                stretch_difference,
+                style_distance,
+                weight_difference,
+            };
+            let italic_angle_key = match query.italic_angle {
+                None => FloatOrd(0.0),
+                Some(query_angle) => italic_angle_distance(candidate, query_angle),
+            };
+
+            (
+                index,
+                score,
+                (stretch_key, style_distance, weight_key, italic_angle_key),
+            )
+        })
+        .collect();
+
+    ranked.sort_by_key(|&(_, _, key)| key);
+    ranked
+        .into_iter()
+        .map(|(index, score, _)| (index, score))
+        .collect()
+}
+
+/// Returns how far the candidate's italic angle is from the requested one. Candidates with no
+/// known angle are the furthest away.
+fn italic_angle_distance(candidate: &Properties, query_angle: f32) -> FloatOrd<f32> {
+    FloatOrd(
+        candidate
+            .italic_angle
+            .map_or(f32::INFINITY, |angle| (angle - query_angle).abs()),
+    )
+}
@@ -0,0 +1,151 @@
+// font-kit/src/math.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Information for laying out mathematical formulas, from the OpenType `MATH` table.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/math
+
+/// The global layout constants of a math font, from the `MathConstants` table.
+///
+/// Distances are in font units. Device table adjustments aren't applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MathConstants {
+    /// Percentage by which to scale down the first level of subscripts and superscripts.
+    pub script_percent_scale_down: i16,
+    /// Percentage by which to scale down the second level of subscripts and superscripts.
+    pub script_script_percent_scale_down: i16,
+    /// Minimum height at which to treat a delimited expression as a subformula.
+    pub delimited_sub_formula_min_height: f32,
+    /// Minimum height of n-ary operators (such as integral and summation) in display style.
+    pub display_operator_min_height: f32,
+    /// White space to leave between lines of a multiline formula.
+    pub math_leading: f32,
+    /// Height of the math axis, the line on which fraction bars and operators are centered.
+    pub axis_height: f32,
+    /// Maximum height of a base that doesn't require raising an accent placed over it.
+    pub accent_base_height: f32,
+    /// Maximum height of a base that doesn't require a flattened accent.
+    pub flattened_accent_base_height: f32,
+    /// The standard shift down of subscripts.
+    pub subscript_shift_down: f32,
+    /// Maximum height of the top of a subscript above the baseline.
+    pub subscript_top_max: f32,
+    /// Minimum distance between the bottom of the base and the baseline of a subscript.
+    pub subscript_baseline_drop_min: f32,
+    /// The standard shift up of superscripts.
+    pub superscript_shift_up: f32,
+    /// The standard shift up of superscripts in cramped style.
+    pub superscript_shift_up_cramped: f32,
+    /// Minimum height of the bottom of a superscript above the baseline.
+    pub superscript_bottom_min: f32,
+    /// Maximum distance between the top of the base and the baseline of a superscript.
+    pub superscript_baseline_drop_max: f32,
+    /// Minimum gap between a superscript and a subscript attached to the same base.
+    pub sub_superscript_gap_min: f32,
+    /// Maximum height of the bottom of a superscript when the gap to its subscript is widened.
+    pub superscript_bottom_max_with_subscript: f32,
+    /// Extra white space to add after each subscript or superscript.
+    pub space_after_script: f32,
+    /// Minimum gap between an upper limit and the top of its operator.
+    pub upper_limit_gap_min: f32,
+    /// Minimum distance between the baseline of an upper limit and the top of its operator.
+    pub upper_limit_baseline_rise_min: f32,
+    /// Minimum gap between a lower limit and the bottom of its operator.
+    pub lower_limit_gap_min: f32,
+    /// Minimum distance between the baseline of a lower limit and the bottom of its operator.
+    pub lower_limit_baseline_drop_min: f32,
+    /// The standard shift up of the top element of a stack.
+    pub stack_top_shift_up: f32,
+    /// The standard shift up of the top element of a stack in display style.
+    pub stack_top_display_style_shift_up: f32,
+    /// The standard shift down of the bottom element of a stack.
+    pub stack_bottom_shift_down: f32,
+    /// The standard shift down of the bottom element of a stack in display style.
+    pub stack_bottom_display_style_shift_down: f32,
+    /// Minimum gap between the elements of a stack.
+    pub stack_gap_min: f32,
+    /// Minimum gap between the elements of a stack in display style.
+    pub stack_display_style_gap_min: f32,
+    /// The standard shift up of the top element of a stretch stack.
+    pub stretch_stack_top_shift_up: f32,
+    /// The standard shift down of the bottom element of a stretch stack.
+    pub stretch_stack_bottom_shift_down: f32,
+    /// Minimum gap between the stretched element and the element above it in a stretch stack.
+    pub stretch_stack_gap_above_min: f32,
+    /// Minimum gap between the stretched element and the element below it in a stretch stack.
+    pub stretch_stack_gap_below_min: f32,
+    /// The standard shift up of the numerator of a fraction.
+    pub fraction_numerator_shift_up: f32,
+    /// The standard shift up of the numerator of a fraction in display style.
+    pub fraction_numerator_display_style_shift_up: f32,
+    /// The standard shift down of the denominator of a fraction.
+    pub fraction_denominator_shift_down: f32,
+    /// The standard shift down of the denominator of a fraction in display style.
+    pub fraction_denominator_display_style_shift_down: f32,
+    /// Minimum gap between the bottom of the numerator and the fraction bar.
+    pub fraction_numerator_gap_min: f32,
+    /// Minimum gap between the bottom of the numerator and the fraction bar in display style.
+    pub fraction_num_display_style_gap_min: f32,
+    /// Thickness of the fraction bar.
+    pub fraction_rule_thickness: f32,
+    /// Minimum gap between the top of the denominator and the fraction bar.
+    pub fraction_denominator_gap_min: f32,
+    /// Minimum gap between the top of the denominator and the fraction bar in display style.
+    pub fraction_denom_display_style_gap_min: f32,
+    /// Horizontal distance between the top and bottom elements of a skewed fraction.
+    pub skewed_fraction_horizontal_gap: f32,
+    /// Vertical distance between the top and bottom elements of a skewed fraction.
+    pub skewed_fraction_vertical_gap: f32,
+    /// Distance between an overbar and the top of its base.
+    pub overbar_vertical_gap: f32,
+    /// Thickness of an overbar.
+    pub overbar_rule_thickness: f32,
+    /// Extra white space to reserve above an overbar.
+    pub overbar_extra_ascender: f32,
+    /// Distance between an underbar and the bottom of its base.
+    pub underbar_vertical_gap: f32,
+    /// Thickness of an underbar.
+    pub underbar_rule_thickness: f32,
+    /// Extra white space to reserve below an underbar.
+    pub underbar_extra_descender: f32,
+    /// Space between the top of the radicand and the radical bar.
+    pub radical_vertical_gap: f32,
+    /// Space between the top of the radicand and the radical bar in display style.
+    pub radical_display_style_vertical_gap: f32,
+    /// Thickness of the radical bar.
+    pub radical_rule_thickness: f32,
+    /// Extra white space to reserve above the radical bar.
+    pub radical_extra_ascender: f32,
+    /// Extra horizontal space to leave before the degree of a radical.
+    pub radical_kern_before_degree: f32,
+    /// Horizontal space to leave after the degree of a radical; usually negative.
+    pub radical_kern_after_degree: f32,
+    /// Height of the bottom of the degree of a radical, as a percentage of the height of the
+    /// radical sign.
+    pub radical_degree_bottom_raise_percent: i16,
+}
+
+/// The direction in which a glyph is stretched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StretchDirection {
+    /// Stretched horizontally, as with accents and arrows over a formula.
+    Horizontal,
+    /// Stretched vertically, as with parentheses and radicals around a formula.
+    Vertical,
+}
+
+/// A prebuilt, larger version of a glyph, from a `MathGlyphConstruction` table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphVariant {
+    /// The glyph ID of the variant.
+    pub glyph_id: u32,
+    /// The size of the variant in the stretch direction, in font units.
+    pub advance: f32,
+}
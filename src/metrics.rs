@@ -12,6 +12,8 @@
 //!
 //! For OpenType fonts, these mostly come from the `OS/2` table.
 
+use euclid::default::{Rect, Vector2D};
+
 /// Various metrics that apply to the entire font.
 ///
 /// For OpenType fonts, these mostly come from the `OS/2` table.
@@ -35,17 +37,191 @@ pub struct Metrics {
     /// Distance between baselines, in font units.
     pub line_gap: f32,
 
+    /// The ascent recorded in the `hhea` table, in font units, or `None` if the font has no
+    /// `hhea` table.
+    ///
+    /// Fonts record their ascent, descent, and line gap three times: in the `hhea` table, in the
+    /// typographic (`sTypo*`) fields of the `OS/2` table, and in its Windows (`usWin*`) fields.
+    /// The three don't always agree, and platforms differ in which they lay lines out with;
+    /// `ascent`, `descent`, and `line_gap` are the typographic metrics if the font sets the
+    /// `USE_TYPO_METRICS` flag, and otherwise those of the loader's platform. These fields give
+    /// each set as the font records it, so that the line height of any platform can be matched.
+    pub hhea_ascent: Option<f32>,
+
+    /// The descent recorded in the `hhea` table, in font units. Like `descent`, this is typically
+    /// negative.
+    pub hhea_descent: Option<f32>,
+
+    /// The line gap recorded in the `hhea` table, in font units.
+    pub hhea_line_gap: Option<f32>,
+
+    /// The `sTypoAscender` field of the `OS/2` table, in font units, or `None` if the font has no
+    /// `OS/2` table.
+    pub typo_ascent: Option<f32>,
+
+    /// The `sTypoDescender` field of the `OS/2` table, in font units. This is typically negative.
+    pub typo_descent: Option<f32>,
+
+    /// The `sTypoLineGap` field of the `OS/2` table, in font units.
+    pub typo_line_gap: Option<f32>,
+
+    /// The `usWinAscent` field of the `OS/2` table, in font units, or `None` if the font has no
+    /// `OS/2` table.
+    pub win_ascent: Option<f32>,
+
+    /// The `usWinDescent` field of the `OS/2` table, in font units, negated so that like
+    /// `descent` it's typically negative.
+    pub win_descent: Option<f32>,
+
+    /// True if the `USE_TYPO_METRICS` flag in the `fsSelection` field of the `OS/2` table is
+    /// set, asking for lines to be laid out with the typographic metrics.
+    pub use_typo_metrics: bool,
+
     /// The suggested distance of the top of the underline from the baseline (negative values
     /// indicate below baseline), in font units.
+    ///
+    /// If the font doesn't specify an underline, this and `underline_thickness` are derived from
+    /// the ascent and descent.
     pub underline_position: f32,
 
     /// A suggested value for the underline thickness, in font units.
     pub underline_thickness: f32,
 
+    /// The suggested distance of the top of the strikeout stroke from the baseline, in font
+    /// units.
+    ///
+    /// This and `strikeout_size` come from the `OS/2` table. If the font doesn't specify them,
+    /// the stroke is centered on half of the x-height, or a third of the ascent if the x-height
+    /// is unknown, and is as thick as the underline.
+    pub strikeout_position: f32,
+
+    /// A suggested value for the thickness of the strikeout stroke, in font units.
+    pub strikeout_size: f32,
+
     /// The approximate amount that uppercase letters rise above the baseline, in font units.
+    ///
+    /// If the font's `OS/2` table is too old to record this, it's estimated from the height of
+    /// the glyph for "H". It's zero if the font has neither.
     pub cap_height: f32,
 
     /// The approximate amount that non-ascending lowercase letters rise above the baseline, in
     /// font units.
+    ///
+    /// If the font's `OS/2` table is too old to record this, it's estimated from the height of
+    /// the glyph for "x". It's zero if the font has neither.
     pub x_height: f32,
+
+    /// The distance from the vertical centerline to the right edge of the design space, for
+    /// vertical layout, in font units.
+    ///
+    /// This, and the other vertical metrics, come from the `vhea` table; they're `None` if the
+    /// font has none.
+    pub vertical_ascent: Option<f32>,
+
+    /// The distance from the vertical centerline to the left edge of the design space, for
+    /// vertical layout, in font units.
+    pub vertical_descent: Option<f32>,
+
+    /// The gap between columns of vertical text, in font units.
+    pub vertical_line_gap: Option<f32>,
+
+    /// The rise of the slope that a text caret should be drawn at, from the `hhea` table.
+    ///
+    /// Together with `caret_slope_run`, this gives the slant of the caret: a rise of 1 and a run
+    /// of 0 is an upright caret, which is what fonts without an `hhea` table get.
+    pub caret_slope_rise: f32,
+
+    /// The run of the slope that a text caret should be drawn at, from the `hhea` table. This is
+    /// positive for fonts that lean to the right.
+    pub caret_slope_run: f32,
+}
+
+impl Metrics {
+    /// Replaces the ascent, descent, and line gap with the typographic metrics of the `OS/2`
+    /// table if the font sets `USE_TYPO_METRICS`, as browsers do.
+    pub(crate) fn with_typo_line_metrics(mut self) -> Metrics {
+        if let (true, Some(ascent), Some(descent), Some(line_gap)) = (
+            self.use_typo_metrics,
+            self.typo_ascent,
+            self.typo_descent,
+            self.typo_line_gap,
+        ) {
+            self.ascent = ascent;
+            self.descent = descent;
+            self.line_gap = line_gap;
+        }
+        self
+    }
+
+    /// Replaces the underline and strikeout metrics that the font left unspecified (zero) with
+    /// values derived from the rest of the metrics.
+    pub(crate) fn with_decoration_fallbacks(mut self) -> Metrics {
+        if self.underline_thickness <= 0.0 {
+            self.underline_thickness = (self.ascent - self.descent) / 20.0;
+            if self.underline_position == 0.0 {
+                self.underline_position = self.descent / 4.0;
+            }
+        }
+        if self.strikeout_size <= 0.0 {
+            self.strikeout_size = self.underline_thickness;
+        }
+        if self.strikeout_position == 0.0 {
+            let center = if self.x_height > 0.0 {
+                self.x_height / 2.0
+            } else {
+                self.ascent / 3.0
+            };
+            self.strikeout_position = center + self.strikeout_size / 2.0;
+        }
+        self
+    }
+}
+
+/// The horizontal metrics of a glyph, as the `hmtx` table records them.
+///
+/// See `Loader::horizontal_metrics()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HorizontalMetrics {
+    /// The distance that the pen moves right after drawing the glyph, in font units.
+    pub advance_width: f32,
+    /// The distance from the glyph origin to the left edge of the glyph, in font units.
+    ///
+    /// This is the value that the font records, which need not be the left edge of the glyph's
+    /// bounding box, notably in fonts whose hinting moves the outline.
+    pub left_side_bearing: f32,
+}
+
+/// A glyph and its metrics, as `Loader::glyphs()` yields them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphInfo {
+    /// The ID of the glyph.
+    pub glyph_id: u32,
+    /// The distance that the pen moves after drawing the glyph, in font units, as
+    /// `Loader::advance()` returns it.
+    pub advance: Vector2D<f32>,
+    /// The bounding box of the glyph, in font units, as `Loader::typographic_bounds()` returns it.
+    pub typographic_bounds: Rect<f32>,
+    /// The name of the glyph, if the font's `post` table names its glyphs.
+    pub name: Option<String>,
+}
+
+/// The limits that the TrueType outlines of a font stay within, from its `maxp` table, for
+/// sizing buffers before loading glyphs.
+///
+/// See `Loader::maxp_limits()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MaxpLimits {
+    /// The most points in a simple (non-composite) glyph.
+    pub max_points: u16,
+    /// The most contours in a simple glyph.
+    pub max_contours: u16,
+    /// The most points in a composite glyph, counting those of all its components.
+    pub max_composite_points: u16,
+    /// The most contours in a composite glyph, counting those of all its components.
+    pub max_composite_contours: u16,
+    /// The most components that a composite glyph refers to directly.
+    pub max_component_elements: u16,
+    /// The deepest that composite glyphs nest: 1 if components are all simple glyphs, and 0 if
+    /// the font has no composite glyphs.
+    pub max_component_depth: u16,
 }
@@ -0,0 +1,71 @@
+// font-kit/src/names.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The human-readable strings of a font's `name` table, in every language it supplies.
+
+/// The name ID of the copyright notice.
+pub const COPYRIGHT: u16 = 0;
+/// The name ID of the font family name, as grouped into at most four styles.
+pub const FAMILY: u16 = 1;
+/// The name ID of the font subfamily (style) name, as grouped into at most four styles.
+pub const SUBFAMILY: u16 = 2;
+/// The name ID of the unique font identifier.
+pub const UNIQUE_ID: u16 = 3;
+/// The name ID of the full font name.
+pub const FULL_NAME: u16 = 4;
+/// The name ID of the version string.
+pub const VERSION: u16 = 5;
+/// The name ID of the PostScript name.
+pub const POSTSCRIPT_NAME: u16 = 6;
+/// The name ID of the trademark notice.
+pub const TRADEMARK: u16 = 7;
+/// The name ID of the name of the manufacturer.
+pub const MANUFACTURER: u16 = 8;
+/// The name ID of the name of the designer.
+pub const DESIGNER: u16 = 9;
+/// The name ID of the description of the font.
+pub const DESCRIPTION: u16 = 10;
+/// The name ID of the URL of the font vendor.
+pub const VENDOR_URL: u16 = 11;
+/// The name ID of the URL of the designer.
+pub const DESIGNER_URL: u16 = 12;
+/// The name ID of the license description.
+pub const LICENSE: u16 = 13;
+/// The name ID of the URL of the license.
+pub const LICENSE_URL: u16 = 14;
+/// The name ID of the typographic family name, for families with more than four styles.
+pub const TYPOGRAPHIC_FAMILY: u16 = 16;
+/// The name ID of the typographic subfamily name, for families with more than four styles.
+pub const TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+/// The name ID of the sample text.
+pub const SAMPLE_TEXT: u16 = 19;
+
+/// The Unicode platform ID. Strings on this platform are UTF-16BE.
+pub const PLATFORM_UNICODE: u16 = 0;
+/// The Macintosh platform ID. Only Mac Roman strings on this platform (encoding ID 0) are decoded.
+pub const PLATFORM_MACINTOSH: u16 = 1;
+/// The Windows platform ID. Strings on this platform are UTF-16BE, and language IDs are Windows
+/// LCIDs, such as 0x0409 for US English.
+pub const PLATFORM_WINDOWS: u16 = 3;
+
+/// A record of the `name` table, with its string decoded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NameRecord {
+    /// What the string is, such as `FULL_NAME` or `LICENSE_URL`.
+    pub name_id: u16,
+    /// The platform that the string is for, such as `PLATFORM_WINDOWS`.
+    pub platform_id: u16,
+    /// The platform-specific encoding of the string as it is stored in the font.
+    pub encoding_id: u16,
+    /// The platform-specific language of the string.
+    pub language_id: u16,
+    /// The decoded string.
+    pub string: String,
+}
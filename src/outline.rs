@@ -0,0 +1,202 @@
+// font-kit/src/outline.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Glyph outlines as owned data, for caching or transforming them rather than streaming them to a
+//! path builder.
+
+use euclid::default::{Point2D, Transform2D};
+use lyon_path::builder::{Build, FlatPathBuilder, PathBuilder};
+use lyon_path::geom;
+use lyon_path::math::{Angle, Point, Vector};
+
+/// The vector outline of a glyph, as a list of contours.
+///
+/// See `Loader::glyph_outline()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    /// The contours of the outline, in the order that the font stores them.
+    pub contours: Vec<Contour>,
+}
+
+/// One contour of an outline: a starting point, followed by the segments that lead from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    /// The point that the first segment starts at.
+    pub start: Point2D<f32>,
+    /// The segments of the contour. Each starts where the one before it ends.
+    pub segments: Vec<Segment>,
+    /// True if the contour was closed, joining the end of its last segment to `start`.
+    pub closed: bool,
+}
+
+/// A segment of a contour, given by its control points and its endpoint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    /// A straight line to the given point.
+    Line(Point2D<f32>),
+    /// A quadratic Bézier curve, with a control point and an endpoint.
+    Quadratic(Point2D<f32>, Point2D<f32>),
+    /// A cubic Bézier curve, with two control points and an endpoint.
+    Cubic(Point2D<f32>, Point2D<f32>, Point2D<f32>),
+}
+
+impl Outline {
+    /// Creates an outline with no contours.
+    #[inline]
+    pub fn new() -> Outline {
+        Outline::default()
+    }
+
+    /// Returns true if the outline has no contours, as is the case for spaces.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// Sends the outline to a path builder, just as `Loader::outline()` would have.
+    pub fn copy_to<B>(&self, path_builder: &mut B)
+    where
+        B: PathBuilder,
+    {
+        for contour in &self.contours {
+            path_builder.move_to(contour.start);
+            for segment in &contour.segments {
+                match *segment {
+                    Segment::Line(to) => path_builder.line_to(to),
+                    Segment::Quadratic(ctrl, to) => path_builder.quadratic_bezier_to(ctrl, to),
+                    Segment::Cubic(ctrl1, ctrl2, to) => {
+                        path_builder.cubic_bezier_to(ctrl1, ctrl2, to)
+                    }
+                }
+            }
+            if contour.closed {
+                path_builder.close();
+            }
+        }
+    }
+
+    /// Transforms every point of the outline, in place.
+    pub fn transform(&mut self, transform: &Transform2D<f32>) {
+        for contour in &mut self.contours {
+            contour.start = transform.transform_point(contour.start);
+            for segment in &mut contour.segments {
+                *segment = match *segment {
+                    Segment::Line(to) => Segment::Line(transform.transform_point(to)),
+                    Segment::Quadratic(ctrl, to) => Segment::Quadratic(
+                        transform.transform_point(ctrl),
+                        transform.transform_point(to),
+                    ),
+                    Segment::Cubic(ctrl1, ctrl2, to) => Segment::Cubic(
+                        transform.transform_point(ctrl1),
+                        transform.transform_point(ctrl2),
+                        transform.transform_point(to),
+                    ),
+                };
+            }
+        }
+    }
+}
+
+/// A path builder that collects the path it receives into an `Outline`.
+#[derive(Clone, Debug, Default)]
+pub struct OutlineBuilder {
+    outline: Outline,
+    current_position: Point,
+}
+
+impl OutlineBuilder {
+    /// Creates a builder with an empty outline.
+    #[inline]
+    pub fn new() -> OutlineBuilder {
+        OutlineBuilder::default()
+    }
+
+    /// Returns the outline, consuming the builder.
+    #[inline]
+    pub fn into_outline(self) -> Outline {
+        self.outline
+    }
+
+    fn push_segment(&mut self, segment: Segment, to: Point) {
+        // Segments that arrive before any `move_to()` start at the origin, as they do in lyon.
+        if self.outline.contours.is_empty() {
+            self.outline.contours.push(Contour {
+                start: Point2D::zero(),
+                segments: vec![],
+                closed: false,
+            });
+        }
+        let contour = self.outline.contours.last_mut().unwrap();
+        contour.segments.push(segment);
+        self.current_position = to;
+    }
+}
+
+impl FlatPathBuilder for OutlineBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.outline.contours.push(Contour {
+            start: to,
+            segments: vec![],
+            closed: false,
+        });
+        self.current_position = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.push_segment(Segment::Line(to), to);
+    }
+
+    fn close(&mut self) {
+        if let Some(contour) = self.outline.contours.last_mut() {
+            contour.closed = true;
+            self.current_position = contour.start;
+        }
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
+}
+
+impl PathBuilder for OutlineBuilder {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.push_segment(Segment::Quadratic(ctrl, to), to);
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.push_segment(Segment::Cubic(ctrl1, ctrl2, to), to);
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        // Glyph outlines don't contain arcs, so they're stored as the curves approximating them.
+        let start_angle = (self.current_position - center).angle_from_x_axis() - x_rotation;
+        let arc = geom::Arc {
+            start_angle,
+            center,
+            radii,
+            sweep_angle,
+            x_rotation,
+        };
+        arc.for_each_quadratic_bezier(&mut |curve| self.quadratic_bezier_to(curve.ctrl, curve.to));
+    }
+}
+
+impl Build for OutlineBuilder {
+    type PathType = Outline;
+
+    fn build(self) -> Outline {
+        self.outline
+    }
+
+    fn build_and_reset(&mut self) -> Outline {
+        self.current_position = Point::zero();
+        std::mem::take(&mut self.outline)
+    }
+}
@@ -0,0 +1,29 @@
+// font-kit/src/outline_format.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The format of the glyph outlines of a font: TrueType, CFF, CFF2, or bitmaps only.
+
+/// The format of the glyph outlines of a font, as the tables present determine it.
+///
+/// See `Loader::outline_format()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OutlineFormat {
+    /// Quadratic TrueType outlines, in the `glyf` table.
+    TrueType,
+    /// Cubic PostScript outlines, in the `CFF ` table. These are embedded in PDFs as Type 1C (or
+    /// CIDFontType0C) font programs.
+    Cff,
+    /// Cubic PostScript outlines that can vary, in the `CFF2` table.
+    Cff2,
+    /// No outlines, only bitmaps, in the `CBDT`, `EBDT`, or `sbix` table.
+    Bitmap,
+    /// Neither outlines nor bitmaps.
+    None,
+}
@@ -0,0 +1,87 @@
+// font-kit/src/panose.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The PANOSE classification of a font's visual characteristics.
+//!
+//! https://monotype.github.io/panose/
+
+/// The ten-digit PANOSE classification of a font, from its `OS/2` table.
+///
+/// Each digit is a small integer. 0 means "any" and 1 means "no fit"; the meanings of the other
+/// values of every digit after the first depend on the family type. For the Latin Text family
+/// type (2), for example, a proportion of 9 means monospaced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Panose {
+    /// The kind of font: e.g. 2 for Latin Text, 3 for Latin Hand Written, 4 for Latin Decorative,
+    /// or 5 for Latin Symbol.
+    pub family_type: u8,
+    /// The style of the serifs, or the absence of them.
+    pub serif_style: u8,
+    /// The weight, from very light to extra black.
+    pub weight: u8,
+    /// The relative widths of the glyphs, including whether the font is monospaced.
+    pub proportion: u8,
+    /// The ratio between the thickest and thinnest parts of the strokes.
+    pub contrast: u8,
+    /// How the stroke thickness changes around curves.
+    pub stroke_variation: u8,
+    /// The style of the arms and the terminations of open curves.
+    pub arm_style: u8,
+    /// The roundness and slant of the letterforms.
+    pub letterform: u8,
+    /// The placement of the midline and the shape of the apexes.
+    pub midline: u8,
+    /// The relative size of the lowercase letters.
+    pub x_height: u8,
+}
+
+impl Panose {
+    /// Unpacks a PANOSE classification from its ten bytes, in the order in which they're stored.
+    pub fn from_bytes(bytes: [u8; 10]) -> Panose {
+        Panose {
+            family_type: bytes[0],
+            serif_style: bytes[1],
+            weight: bytes[2],
+            proportion: bytes[3],
+            contrast: bytes[4],
+            stroke_variation: bytes[5],
+            arm_style: bytes[6],
+            letterform: bytes[7],
+            midline: bytes[8],
+            x_height: bytes[9],
+        }
+    }
+
+    /// Returns true if the classification says that the font is monospaced, which only the Latin
+    /// Text and Latin Hand Written family types can.
+    pub(crate) fn is_monospaced(&self) -> bool {
+        match self.family_type {
+            2 => self.proportion == 9,
+            3 => self.proportion == 3,
+            _ => false,
+        }
+    }
+
+    /// Packs this classification into its ten bytes, in the order in which they're stored.
+    pub fn to_bytes(&self) -> [u8; 10] {
+        [
+            self.family_type,
+            self.serif_style,
+            self.weight,
+            self.proportion,
+            self.contrast,
+            self.stroke_variation,
+            self.arm_style,
+            self.letterform,
+            self.midline,
+            self.x_height,
+        ]
+    }
+}
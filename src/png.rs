@@ -0,0 +1,229 @@
+// font-kit/src/png.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A minimal PNG decoder for the bitmaps embedded in `sbix` and `CBDT` color fonts.
+//!
+//! Only non-interlaced images are supported, which in practice covers every color font.
+
+use byteorder::{BigEndian, ReadBytesExt};
+use euclid::default::Size2D;
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+use crate::utils;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+const COLOR_TYPE_GRAY: u8 = 0;
+const COLOR_TYPE_RGB: u8 = 2;
+const COLOR_TYPE_PALETTE: u8 = 3;
+const COLOR_TYPE_GRAY_ALPHA: u8 = 4;
+const COLOR_TYPE_RGBA: u8 = 6;
+
+/// A decoded image, in premultiplied R8G8B8A8 format with no padding between rows.
+pub(crate) struct Image {
+    pub(crate) size: Size2D<u32>,
+    pub(crate) pixels: Vec<u8>,
+}
+
+/// Returns the size of a PNG image from its header, without decoding it.
+pub(crate) fn size(data: &[u8]) -> Option<Size2D<u32>> {
+    let mut reader = data.get(PNG_SIGNATURE.len()..)?;
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return None;
+    }
+    let _length = reader.read_u32::<BigEndian>().ok()?;
+    if &reader.read_u32::<BigEndian>().ok()?.to_be_bytes() != b"IHDR" {
+        return None;
+    }
+    let width = reader.read_u32::<BigEndian>().ok()?;
+    let height = reader.read_u32::<BigEndian>().ok()?;
+    Some(Size2D::new(width, height))
+}
+
+/// Decodes a PNG image, returning `None` if it is malformed or uses an unsupported feature.
+pub(crate) fn decode(data: &[u8]) -> Option<Image> {
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return None;
+    }
+
+    let mut reader = &data[PNG_SIGNATURE.len()..];
+    let (mut width, mut height, mut bit_depth, mut color_type) = (0, 0, 0, 0);
+    let (mut palette, mut transparency, mut compressed) = (&[][..], &[][..], vec![]);
+    loop {
+        let length = reader.read_u32::<BigEndian>().ok()? as usize;
+        let chunk_type = reader.read_u32::<BigEndian>().ok()?;
+        let chunk_data = reader.get(..length)?;
+        reader = reader.get((length + 4)..)?;
+        match &chunk_type.to_be_bytes() {
+            b"IHDR" => {
+                let mut header = chunk_data;
+                width = header.read_u32::<BigEndian>().ok()?;
+                height = header.read_u32::<BigEndian>().ok()?;
+                bit_depth = header.read_u8().ok()?;
+                color_type = header.read_u8().ok()?;
+                let _compression_method = header.read_u8().ok()?;
+                let _filter_method = header.read_u8().ok()?;
+                if header.read_u8().ok()? != 0 {
+                    // Interlaced images aren't supported.
+                    return None;
+                }
+            }
+            b"PLTE" => palette = chunk_data,
+            b"tRNS" => transparency = chunk_data,
+            b"IDAT" => compressed.extend_from_slice(chunk_data),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let channels = match color_type {
+        COLOR_TYPE_GRAY | COLOR_TYPE_PALETTE => 1,
+        COLOR_TYPE_GRAY_ALPHA => 2,
+        COLOR_TYPE_RGB => 3,
+        COLOR_TYPE_RGBA => 4,
+        _ => return None,
+    };
+    match bit_depth {
+        1 | 2 | 4 | 8 if channels == 1 => {}
+        8 | 16 if color_type != COLOR_TYPE_PALETTE => {}
+        _ => return None,
+    }
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let bits_per_pixel = channels * bit_depth as usize;
+    let bytes_per_pixel = utils::div_round_up(bits_per_pixel, 8);
+    let row_size = utils::div_round_up(width as usize * bits_per_pixel, 8);
+    let mut scanlines = Vec::with_capacity((row_size + 1) * height as usize);
+    ZlibDecoder::new(&compressed[..])
+        .read_to_end(&mut scanlines)
+        .ok()?;
+    if scanlines.len() < (row_size + 1) * height as usize {
+        return None;
+    }
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    let mut previous_row = vec![0; row_size];
+    let mut row = vec![0; row_size];
+    for scanline in scanlines.chunks(row_size + 1).take(height as usize) {
+        row.copy_from_slice(&scanline[1..]);
+        unfilter(scanline[0], &mut row, &previous_row, bytes_per_pixel)?;
+
+        for x in 0..width as usize {
+            let sample = |channel: usize| -> u16 {
+                match bit_depth {
+                    8 => row[x * channels + channel] as u16,
+                    16 => row[(x * channels + channel) * 2] as u16,
+                    _ => {
+                        let bit = x * bit_depth as usize;
+                        let shift = 8 - bit_depth as usize - bit % 8;
+                        ((row[bit / 8] >> shift) & ((1 << bit_depth) - 1)) as u16
+                    }
+                }
+            };
+            let scale_to_8_bits = |value: u16| -> u8 {
+                match bit_depth {
+                    1 => (value * 0xff) as u8,
+                    2 => (value * 0x55) as u8,
+                    4 => (value * 0x11) as u8,
+                    _ => value as u8,
+                }
+            };
+            let (r, g, b, a) = match color_type {
+                COLOR_TYPE_GRAY => {
+                    let gray = scale_to_8_bits(sample(0));
+                    (gray, gray, gray, 0xff)
+                }
+                COLOR_TYPE_GRAY_ALPHA => {
+                    let gray = scale_to_8_bits(sample(0));
+                    (gray, gray, gray, sample(1) as u8)
+                }
+                COLOR_TYPE_RGB => (sample(0) as u8, sample(1) as u8, sample(2) as u8, 0xff),
+                COLOR_TYPE_RGBA => (
+                    sample(0) as u8,
+                    sample(1) as u8,
+                    sample(2) as u8,
+                    sample(3) as u8,
+                ),
+                _ => {
+                    let index = sample(0) as usize;
+                    let entry = palette.get((index * 3)..(index * 3 + 3))?;
+                    let alpha = transparency.get(index).cloned().unwrap_or(0xff);
+                    (entry[0], entry[1], entry[2], alpha)
+                }
+            };
+            pixels.extend_from_slice(&[premultiply(r, a), premultiply(g, a), premultiply(b, a), a]);
+        }
+
+        std::mem::swap(&mut row, &mut previous_row);
+    }
+
+    Some(Image {
+        size: Size2D::new(width, height),
+        pixels,
+    })
+}
+
+fn unfilter(filter_type: u8, row: &mut [u8], previous_row: &[u8], bpp: usize) -> Option<()> {
+    match filter_type {
+        0 => {}
+        1 => {
+            for i in bpp..row.len() {
+                row[i] = row[i].wrapping_add(row[i - bpp]);
+            }
+        }
+        2 => {
+            for (byte, &up) in row.iter_mut().zip(previous_row.iter()) {
+                *byte = byte.wrapping_add(up);
+            }
+        }
+        3 => {
+            for i in 0..row.len() {
+                let left = if i >= bpp { row[i - bpp] as u16 } else { 0 };
+                row[i] = row[i].wrapping_add(((left + previous_row[i] as u16) / 2) as u8);
+            }
+        }
+        4 => {
+            for i in 0..row.len() {
+                let (left, upper_left) = if i >= bpp {
+                    (row[i - bpp], previous_row[i - bpp])
+                } else {
+                    (0, 0)
+                };
+                row[i] = row[i].wrapping_add(paeth(left, previous_row[i], upper_left));
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+fn paeth(left: u8, up: u8, upper_left: u8) -> u8 {
+    let p = left as i16 + up as i16 - upper_left as i16;
+    let (pa, pb, pc) = (
+        (p - left as i16).abs(),
+        (p - up as i16).abs(),
+        (p - upper_left as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        left
+    } else if pb <= pc {
+        up
+    } else {
+        upper_left
+    }
+}
+
+#[inline]
+fn premultiply(value: u8, alpha: u8) -> u8 {
+    ((value as u16 * alpha as u16 + 127) / 255) as u8
+}
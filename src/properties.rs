@@ -29,6 +29,13 @@ pub struct Properties {
     pub weight: Weight,
     /// The font stretchiness, as defined in CSS.
     pub stretch: Stretch,
+    /// The slant of the font in degrees counterclockwise from vertical, as in the `italicAngle`
+    /// field of the `post` table. Faces that lean to the right, as most do, have negative angles.
+    ///
+    /// When matching, this breaks ties between faces that match the style, weight, and
+    /// stretchiness equally well: the face with the closest angle wins. `None` in a query means
+    /// that any angle will do.
+    pub italic_angle: Option<f32>,
 }
 
 impl Properties {
@@ -59,12 +66,21 @@ impl Properties {
         self.stretch = stretch;
         self
     }
+
+    /// Sets the value of the italic angle property and returns this property set for method
+    /// chaining.
+    #[inline]
+    pub fn italic_angle(&mut self, italic_angle: f32) -> &mut Properties {
+        self.italic_angle = Some(italic_angle);
+        self
+    }
 }
 
 /// Allows italic or oblique faces to be selected.
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub enum Style {
     /// A face that is neither italic not obliqued.
+    #[default]
     Normal,
     /// A form that is generally cursive in nature.
     Italic,
@@ -72,12 +88,6 @@ pub enum Style {
     Oblique,
 }
 
-impl Default for Style {
-    fn default() -> Style {
-        Style::Normal
-    }
-}
-
 impl Display for Style {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         Debug::fmt(self, f)
@@ -0,0 +1,130 @@
+// font-kit/src/script.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writing systems, as used for shaping and font fallback.
+
+/// The writing system of a run of text, identified by its ISO 15924 tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Script(pub u32);
+
+impl Script {
+    /// Characters shared by several scripts, such as digits and punctuation (`Zyyy`).
+    pub const COMMON: Script = Script::new(b"Zyyy");
+    /// The Arabic script (`Arab`).
+    pub const ARABIC: Script = Script::new(b"Arab");
+    /// The Cyrillic script (`Cyrl`).
+    pub const CYRILLIC: Script = Script::new(b"Cyrl");
+    /// The Devanagari script (`Deva`).
+    pub const DEVANAGARI: Script = Script::new(b"Deva");
+    /// The Greek script (`Grek`).
+    pub const GREEK: Script = Script::new(b"Grek");
+    /// Han ideographs (`Hani`).
+    pub const HAN: Script = Script::new(b"Hani");
+    /// The Hebrew script (`Hebr`).
+    pub const HEBREW: Script = Script::new(b"Hebr");
+    /// The Latin script (`Latn`).
+    pub const LATIN: Script = Script::new(b"Latn");
+
+    /// Creates a script from its four-character ISO 15924 tag, such as `b"Thai"`.
+    #[inline]
+    pub const fn new(tag: &[u8; 4]) -> Script {
+        Script(u32::from_be_bytes(*tag))
+    }
+
+    /// Returns a BCP 47 language tag for the language most commonly written in this script, for
+    /// use as a hint to the platform's font fallback machinery.
+    ///
+    /// Returns `None` for `Zyyy` and for scripts that no single language is representative of.
+    pub(crate) fn language(self) -> Option<&'static str> {
+        let language = match &self.0.to_be_bytes() {
+            b"Arab" => "ar",
+            b"Armn" => "hy",
+            b"Beng" => "bn",
+            b"Cyrl" => "ru",
+            b"Deva" => "hi",
+            b"Ethi" => "am",
+            b"Geor" => "ka",
+            b"Grek" => "el",
+            b"Gujr" => "gu",
+            b"Guru" => "pa",
+            b"Hang" | b"Kore" => "ko",
+            b"Hani" | b"Hans" => "zh-Hans",
+            b"Hant" => "zh-Hant",
+            b"Hebr" => "he",
+            b"Hira" | b"Jpan" | b"Kana" => "ja",
+            b"Khmr" => "km",
+            b"Knda" => "kn",
+            b"Laoo" => "lo",
+            b"Latn" => "en",
+            b"Mlym" => "ml",
+            b"Mymr" => "my",
+            b"Sinh" => "si",
+            b"Taml" => "ta",
+            b"Telu" => "te",
+            b"Thai" => "th",
+            b"Tibt" => "bo",
+            _ => return None,
+        };
+        Some(language)
+    }
+}
+
+/// Returns a few characters that any font able to display text in the language with the given
+/// BCP 47 tag has glyphs for, or `None` if the language isn't one that this knows about.
+///
+/// Only the primary language subtag matters, except for Chinese, where a script or region subtag
+/// picks between Simplified and Traditional characters.
+pub(crate) fn sample_chars_for_language(language: &str) -> Option<&'static str> {
+    let language = language.to_lowercase();
+    let mut subtags = language.split(['-', '_']);
+    let sample = match subtags.next()? {
+        "am" => "አበገ",
+        "ar" => "ابت",
+        "be" | "bg" | "kk" | "mk" | "ru" | "sr" => "абв",
+        "bn" => "অকখ",
+        "bo" => "ཀཁག",
+        "cs" => "čřšů",
+        "de" => "äöüß",
+        "el" => "αβγ",
+        "en" => "abc",
+        "es" => "ñáé",
+        "fa" => "ابپ",
+        "fr" => "éèçœ",
+        "gu" => "અકખ",
+        "he" => "אבג",
+        "hi" | "mr" | "ne" | "sa" => "अकि",
+        "hu" => "őű",
+        "hy" => "աբգ",
+        "it" => "àèé",
+        "ja" => "あア日本",
+        "ka" => "აბგ",
+        "km" => "កខគ",
+        "kn" => "ಅಕಖ",
+        "ko" => "한국어",
+        "lo" => "ກຂຄ",
+        "ml" => "അകഖ",
+        "my" => "ကခဂ",
+        "pa" => "ਅਕਖ",
+        "pl" => "ąęłż",
+        "pt" => "ãçé",
+        "ro" => "ăâîșț",
+        "si" => "අකඛ",
+        "ta" => "அகட",
+        "te" => "అకఖ",
+        "th" => "กขค",
+        "tr" => "ğışİ",
+        "uk" => "абвєї",
+        "vi" => "ăơưạ",
+        "zh" if subtags.any(|subtag| ["hant", "tw", "hk", "mo"].contains(&subtag)) => "中文們",
+        "zh" => "中文们",
+        _ => return None,
+    };
+    Some(sample)
+}
@@ -0,0 +1,169 @@
+// font-kit/src/sdf.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Signed distance field rasterization of glyph outlines.
+
+use euclid::default::{Point2D, Size2D};
+use lyon_path::builder::{Build, FlatPathBuilder};
+use lyon_path::math::Point;
+
+use crate::canvas::{Canvas, Format};
+use crate::error::GlyphLoadingError;
+use crate::hinting::HintingOptions;
+use crate::loader::Loader;
+
+/// The maximum distance, in pixels, between a curve and the line segments approximating it.
+const FLATTENING_TOLERANCE: f32 = 0.05;
+
+/// Rasterizes a signed distance field of a glyph into `canvas`. See
+/// `Loader::rasterize_glyph_sdf()`.
+pub(crate) fn rasterize_glyph_sdf<L>(
+    font: &L,
+    canvas: &mut Canvas,
+    glyph_id: u32,
+    point_size: f32,
+    origin: &Point2D<f32>,
+    spread: f32,
+) -> Result<(), GlyphLoadingError>
+where
+    L: Loader,
+{
+    // Outlines arrive in font units, so the curves are flattened in font units too, before the
+    // segments are transformed to pixels with y pointing down like the canvas.
+    let scale = point_size / font.metrics().units_per_em as f32;
+    let mut builder = SegmentBuilder::new(scale, *origin).flattened(FLATTENING_TOLERANCE / scale);
+    font.outline(glyph_id, HintingOptions::None, &mut builder)?;
+    let segments = builder.build();
+
+    let (width, height) = (canvas.size.width as usize, canvas.size.height as usize);
+    let mut distances = vec![0; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let center = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+            let distance = signed_distance(&segments, center);
+            let value = 0.5 + distance / (2.0 * spread);
+            distances[y * width + x] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    canvas.blit_from(
+        Point2D::zero(),
+        &distances,
+        &Size2D::new(width as u32, height as u32),
+        width,
+        Format::A8,
+    );
+    Ok(())
+}
+
+/// Returns the distance from `point` to the nearest edge of the outline, positive inside the
+/// outline and negative outside it.
+///
+/// Measuring the distance to whole segments, endpoints included, gives the correct rounded
+/// distances outside of convex corners and the correct mitered ones inside of them.
+fn signed_distance(segments: &[(Point, Point)], point: Point) -> f32 {
+    let mut min_squared_distance = f32::INFINITY;
+    let mut winding = 0;
+    for &(from, to) in segments {
+        let edge = to - from;
+        let length_squared = edge.square_length();
+        let t = if length_squared > 0.0 {
+            ((point - from).dot(edge) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        min_squared_distance = min_squared_distance.min((from + edge * t - point).square_length());
+
+        // Count crossings of a ray from the point toward +x for the nonzero winding rule.
+        if (from.y <= point.y) != (to.y <= point.y) {
+            let crossing_x = from.x + (point.y - from.y) / edge.y * edge.x;
+            if crossing_x > point.x {
+                winding += if edge.y > 0.0 { 1 } else { -1 };
+            }
+        }
+    }
+
+    let distance = min_squared_distance.sqrt();
+    if winding != 0 {
+        distance
+    } else {
+        -distance
+    }
+}
+
+/// Collects the line segments of a flattened outline, transformed from font units to pixels.
+struct SegmentBuilder {
+    segments: Vec<(Point, Point)>,
+    scale: f32,
+    origin: Point2D<f32>,
+    first_position: Point,
+    current_position: Point,
+}
+
+impl SegmentBuilder {
+    fn new(scale: f32, origin: Point2D<f32>) -> SegmentBuilder {
+        SegmentBuilder {
+            segments: vec![],
+            scale,
+            origin,
+            first_position: Point::zero(),
+            current_position: Point::zero(),
+        }
+    }
+
+    fn to_pixels(&self, point: Point) -> Point {
+        Point::new(
+            self.origin.x + point.x * self.scale,
+            self.origin.y - point.y * self.scale,
+        )
+    }
+}
+
+impl FlatPathBuilder for SegmentBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.close();
+        self.first_position = to;
+        self.current_position = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let segment = (self.to_pixels(self.current_position), self.to_pixels(to));
+        self.segments.push(segment);
+        self.current_position = to;
+    }
+
+    fn close(&mut self) {
+        // Contours are always filled, so close them even if the outline didn't.
+        if self.current_position != self.first_position {
+            let first_position = self.first_position;
+            self.line_to(first_position);
+        }
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
+}
+
+impl Build for SegmentBuilder {
+    type PathType = Vec<(Point, Point)>;
+
+    fn build(mut self) -> Vec<(Point, Point)> {
+        self.close();
+        self.segments
+    }
+
+    fn build_and_reset(&mut self) -> Vec<(Point, Point)> {
+        self.close();
+        self.first_position = Point::zero();
+        self.current_position = Point::zero();
+        self.segments.split_off(0)
+    }
+}
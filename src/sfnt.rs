@@ -0,0 +1,125 @@
+// font-kit/src/sfnt.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Assembly of OpenType (sfnt) font files from their tables, and reading of their table
+//! directories.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/otff
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const SFNT_HEADER_SIZE: usize = 12;
+pub(crate) const SFNT_TABLE_RECORD_SIZE: usize = 16;
+
+const COLLECTION_TAG: u32 = tables::tag(b"ttcf");
+const HEAD_TAG: u32 = tables::tag(b"head");
+const CHECKSUM_ADJUSTMENT_OFFSET: usize = 8;
+const CHECKSUM_MAGIC: u32 = 0xb1b0_afba;
+
+/// Lays out the given tables as an sfnt with the given flavor (e.g. `0x00010000` for TrueType
+/// outlines or `OTTO` for CFF outlines).
+///
+/// The tables are sorted by tag, and their checksums and the `checkSumAdjustment` field of the
+/// `head` table, if there is one, are filled in.
+pub(crate) fn write_sfnt(flavor: u32, mut tables: Vec<(u32, Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|&(tag, _)| tag);
+    if let Some((_, head)) = tables.iter_mut().find(|&&mut (tag, _)| tag == HEAD_TAG) {
+        if let Some(adjustment) =
+            head.get_mut(CHECKSUM_ADJUSTMENT_OFFSET..(CHECKSUM_ADJUSTMENT_OFFSET + 4))
+        {
+            adjustment.copy_from_slice(&[0; 4]);
+        }
+    }
+
+    let mut table_offset = SFNT_HEADER_SIZE + SFNT_TABLE_RECORD_SIZE * tables.len();
+    let length = tables.iter().fold(table_offset, |length, (_, table)| {
+        length + pad_to_4(table.len())
+    });
+    let mut sfnt = Vec::with_capacity(length);
+    write_sfnt_header(&mut sfnt, flavor, tables.len() as u16);
+    for &(tag, ref table) in &tables {
+        sfnt.extend_from_slice(&tag.to_be_bytes());
+        sfnt.extend_from_slice(&checksum(table).to_be_bytes());
+        sfnt.extend_from_slice(&(table_offset as u32).to_be_bytes());
+        sfnt.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        table_offset += pad_to_4(table.len());
+    }
+    let mut head_offset = None;
+    for &(tag, ref table) in &tables {
+        if tag == HEAD_TAG && table.len() >= CHECKSUM_ADJUSTMENT_OFFSET + 4 {
+            head_offset = Some(sfnt.len());
+        }
+        sfnt.extend_from_slice(table);
+        sfnt.resize(pad_to_4(sfnt.len()), 0);
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum(&sfnt));
+        let offset = head_offset + CHECKSUM_ADJUSTMENT_OFFSET;
+        sfnt[offset..(offset + 4)].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    sfnt
+}
+
+/// Returns the tags in the table directory of the sfnt, or of the font at `font_index` if the
+/// data is a collection, in directory order.
+pub(crate) fn table_tags(data: &[u8], font_index: u32) -> Option<Vec<u32>> {
+    let mut reader = data;
+    let mut offset = 0;
+    if reader.read_u32::<BigEndian>().ok()? == COLLECTION_TAG {
+        let mut reader = data.get((12 + font_index as usize * 4)..)?;
+        offset = reader.read_u32::<BigEndian>().ok()? as usize;
+    }
+    let mut reader = data.get((offset + 4)..)?;
+    let num_tables = reader.read_u16::<BigEndian>().ok()?;
+    let mut reader = data.get((offset + SFNT_HEADER_SIZE)..)?;
+    let mut tags = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        tags.push(reader.read_u32::<BigEndian>().ok()?);
+        reader = reader.get((SFNT_TABLE_RECORD_SIZE - 4)..)?;
+    }
+    Some(tags)
+}
+
+/// Writes the offset table that begins an sfnt with `num_tables` table records.
+pub(crate) fn write_sfnt_header(sfnt: &mut Vec<u8>, flavor: u32, num_tables: u16) {
+    // An empty directory has no power of two to search, so all three search fields are zero.
+    let (search_range, entry_selector, range_shift) = match num_tables {
+        0 => (0, 0, 0),
+        _ => {
+            let entry_selector = 15 - num_tables.leading_zeros();
+            let search_range = (1 << entry_selector) * SFNT_TABLE_RECORD_SIZE as u32;
+            let range_shift = num_tables as u32 * SFNT_TABLE_RECORD_SIZE as u32 - search_range;
+            (search_range, entry_selector, range_shift)
+        }
+    };
+    sfnt.extend_from_slice(&flavor.to_be_bytes());
+    sfnt.extend_from_slice(&num_tables.to_be_bytes());
+    sfnt.extend_from_slice(&(search_range as u16).to_be_bytes());
+    sfnt.extend_from_slice(&(entry_selector as u16).to_be_bytes());
+    sfnt.extend_from_slice(&(range_shift as u16).to_be_bytes());
+}
+
+/// Computes the checksum of a table, treating it as a sequence of big-endian 32-bit integers
+/// padded with zeroes.
+pub(crate) fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+#[inline]
+pub(crate) fn pad_to_4(length: usize) -> usize {
+    (length + 3) & !3
+}
@@ -0,0 +1,198 @@
+// font-kit/src/shaping.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Text shaping via HarfBuzz.
+//!
+//! This module is only available with the `harfbuzz` Cargo feature, which builds a bundled copy
+//! of HarfBuzz through the `harfbuzz-sys` crate.
+
+use euclid::default::Vector2D;
+use harfbuzz_sys::{
+    hb_blob_create, hb_blob_t, hb_buffer_add_utf8, hb_buffer_create, hb_buffer_destroy,
+    hb_buffer_get_glyph_infos, hb_buffer_get_glyph_positions, hb_buffer_guess_segment_properties,
+    hb_buffer_set_direction, hb_buffer_set_script, hb_face_create_for_tables, hb_face_destroy,
+    hb_face_t, hb_feature_t, hb_font_create, hb_font_destroy, hb_font_set_variations,
+    hb_script_from_iso15924_tag, hb_shape, hb_tag_t, hb_variation_t, HB_DIRECTION_BTT,
+    HB_DIRECTION_LTR, HB_DIRECTION_RTL, HB_DIRECTION_TTB, HB_MEMORY_MODE_READONLY,
+};
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr;
+use std::slice;
+
+use crate::loader::Loader;
+
+pub use crate::script::Script;
+
+/// The direction in which text is laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Left to right, as in Latin.
+    LeftToRight,
+    /// Right to left, as in Arabic or Hebrew.
+    RightToLeft,
+    /// Top to bottom, as in vertical Japanese.
+    TopToBottom,
+    /// Bottom to top.
+    BottomToTop,
+}
+
+/// An OpenType feature to enable or disable while shaping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Feature {
+    /// The four-character tag of the feature (e.g. `liga` or `smcp`), packed big-endian.
+    pub tag: u32,
+    /// The value of the feature: 0 disables it, 1 enables it, and larger values select
+    /// alternates.
+    pub value: u32,
+    /// The byte offset in the text at which the feature starts to apply.
+    pub start: usize,
+    /// The byte offset in the text at which the feature stops applying.
+    pub end: usize,
+}
+
+impl Feature {
+    /// Creates a feature that applies to the whole text.
+    #[inline]
+    pub fn new(tag: u32, value: u32) -> Feature {
+        Feature {
+            tag,
+            value,
+            start: 0,
+            end: usize::MAX,
+        }
+    }
+}
+
+/// A positioned glyph produced by shaping.
+///
+/// All distances are in font units, with y pointing up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShapedGlyph {
+    /// The glyph ID.
+    pub glyph_id: u32,
+    /// The byte offset in the text of the start of the cluster that this glyph belongs to.
+    pub cluster: u32,
+    /// How far to move the pen after drawing this glyph.
+    pub advance: Vector2D<i32>,
+    /// How far to offset this glyph from the pen position when drawing it.
+    pub offset: Vector2D<i32>,
+}
+
+/// Shapes `text` with HarfBuzz, reading the font's tables through `Loader::load_font_table()`.
+pub(crate) fn shape<L>(
+    font: &L,
+    text: &str,
+    direction: Direction,
+    script: Script,
+    features: &[Feature],
+) -> Vec<ShapedGlyph>
+where
+    L: Loader,
+{
+    let variations: Vec<hb_variation_t> = font
+        .variations()
+        .into_iter()
+        .map(|(tag, value)| hb_variation_t { tag, value })
+        .collect();
+    let features: Vec<hb_feature_t> = features
+        .iter()
+        .map(|feature| hb_feature_t {
+            tag: feature.tag,
+            value: feature.value,
+            start: feature.start.min(c_uint::MAX as usize) as c_uint,
+            end: feature.end.min(c_uint::MAX as usize) as c_uint,
+        })
+        .collect();
+    let direction = match direction {
+        Direction::LeftToRight => HB_DIRECTION_LTR,
+        Direction::RightToLeft => HB_DIRECTION_RTL,
+        Direction::TopToBottom => HB_DIRECTION_TTB,
+        Direction::BottomToTop => HB_DIRECTION_BTT,
+    };
+
+    unsafe {
+        let face = hb_face_create_for_tables(
+            Some(reference_table::<L>),
+            font as *const L as *mut c_void,
+            None,
+        );
+        let hb_font = hb_font_create(face);
+        hb_font_set_variations(hb_font, variations.as_ptr(), variations.len() as c_uint);
+
+        let buffer = hb_buffer_create();
+        hb_buffer_add_utf8(
+            buffer,
+            text.as_ptr() as *const c_char,
+            text.len() as c_int,
+            0,
+            text.len() as c_int,
+        );
+        hb_buffer_set_direction(buffer, direction);
+        hb_buffer_set_script(buffer, hb_script_from_iso15924_tag(script.0));
+        hb_buffer_guess_segment_properties(buffer);
+        hb_shape(hb_font, buffer, features.as_ptr(), features.len() as c_uint);
+
+        let mut glyph_count = 0;
+        let infos = hb_buffer_get_glyph_infos(buffer, &mut glyph_count);
+        let positions = hb_buffer_get_glyph_positions(buffer, &mut glyph_count);
+        let shaped_glyphs = if glyph_count == 0 {
+            vec![]
+        } else {
+            let infos = slice::from_raw_parts(infos, glyph_count as usize);
+            let positions = slice::from_raw_parts(positions, glyph_count as usize);
+            infos
+                .iter()
+                .zip(positions.iter())
+                .map(|(info, position)| ShapedGlyph {
+                    glyph_id: info.codepoint,
+                    cluster: info.cluster,
+                    advance: Vector2D::new(position.x_advance, position.y_advance),
+                    offset: Vector2D::new(position.x_offset, position.y_offset),
+                })
+                .collect()
+        };
+
+        hb_buffer_destroy(buffer);
+        hb_font_destroy(hb_font);
+        hb_face_destroy(face);
+        shaped_glyphs
+    }
+}
+
+unsafe extern "C" fn reference_table<L>(
+    _: *mut hb_face_t,
+    tag: hb_tag_t,
+    user_data: *mut c_void,
+) -> *mut hb_blob_t
+where
+    L: Loader,
+{
+    // HarfBuzz asks for tag 0 when it wants the whole font file, which we can't always supply.
+    if tag == 0 {
+        return ptr::null_mut();
+    }
+    let font = &*(user_data as *const L);
+    let table = match font.load_font_table(tag) {
+        Some(table) => Box::new(table),
+        None => return ptr::null_mut(),
+    };
+    let (data, length) = (table.as_ptr(), table.len());
+    hb_blob_create(
+        data as *const c_char,
+        length as c_uint,
+        HB_MEMORY_MODE_READONLY,
+        Box::into_raw(table) as *mut c_void,
+        Some(destroy_table),
+    )
+}
+
+unsafe extern "C" fn destroy_table(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut Box<[u8]>))
+}
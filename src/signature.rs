@@ -0,0 +1,43 @@
+// font-kit/src/signature.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The digital signatures of a font, as recorded in its `DSIG` table.
+
+/// The signature format for PKCS#7 packets, the only one that the OpenType specification
+/// defines.
+pub const FORMAT_PKCS7: u32 = 1;
+
+/// A summary of the digital signatures in a font's `DSIG` table.
+///
+/// The signatures themselves aren't verified.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SignatureInfo {
+    /// The flags of the table. Bit 0 set means that the font may not be resigned.
+    pub flags: u16,
+    /// The format of each signature, in table order, such as `FORMAT_PKCS7`.
+    pub formats: Vec<u32>,
+}
+
+impl SignatureInfo {
+    /// Returns the number of signatures in the table.
+    ///
+    /// Many fonts carry a placeholder `DSIG` table with no signatures at all, for the benefit of
+    /// old versions of Windows that required one.
+    #[inline]
+    pub fn signature_count(&self) -> usize {
+        self.formats.len()
+    }
+
+    /// Returns true if the font's creator has asked for it not to be resigned.
+    #[inline]
+    pub fn cannot_be_resigned(&self) -> bool {
+        self.flags & 1 != 0
+    }
+}
@@ -10,14 +10,23 @@
 
 //! A database of installed fonts that can be queried.
 
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
 use crate::error::SelectionError;
 use crate::family::Family;
 use crate::family_handle::FamilyHandle;
-use crate::family_name::FamilyName;
+use crate::family_name::{FamilyName, GenericFamilyConfig};
 use crate::font::Font;
 use crate::handle::Handle;
+use crate::loader::Loader;
 use crate::matching;
 use crate::properties::Properties;
+use crate::script::{self, Script};
+use crate::tables::name::{self, NameTable};
 
 #[cfg(all(
     any(target_os = "macos", target_os = "ios"),
@@ -42,28 +51,28 @@ pub use crate::sources::fs::FsSource as SystemSource;
 
 // FIXME(pcwalton): These could expand to multiple fonts, and they could be language-specific.
 #[cfg(any(target_family = "windows", target_os = "macos", target_os = "ios"))]
-const DEFAULT_FONT_FAMILY_SERIF: &'static str = "Times New Roman";
+const DEFAULT_FONT_FAMILY_SERIF: &str = "Times New Roman";
 #[cfg(any(target_family = "windows", target_os = "macos", target_os = "ios"))]
-const DEFAULT_FONT_FAMILY_SANS_SERIF: &'static str = "Arial";
+const DEFAULT_FONT_FAMILY_SANS_SERIF: &str = "Arial";
 #[cfg(any(target_family = "windows", target_os = "macos", target_os = "ios"))]
-const DEFAULT_FONT_FAMILY_MONOSPACE: &'static str = "Courier New";
+const DEFAULT_FONT_FAMILY_MONOSPACE: &str = "Courier New";
 #[cfg(any(target_family = "windows", target_os = "macos", target_os = "ios"))]
-const DEFAULT_FONT_FAMILY_CURSIVE: &'static str = "Comic Sans MS";
+const DEFAULT_FONT_FAMILY_CURSIVE: &str = "Comic Sans MS";
 #[cfg(target_family = "windows")]
-const DEFAULT_FONT_FAMILY_FANTASY: &'static str = "Impact";
+const DEFAULT_FONT_FAMILY_FANTASY: &str = "Impact";
 #[cfg(any(target_os = "macos", target_os = "ios"))]
-const DEFAULT_FONT_FAMILY_FANTASY: &'static str = "Papyrus";
+const DEFAULT_FONT_FAMILY_FANTASY: &str = "Papyrus";
 
 #[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
-const DEFAULT_FONT_FAMILY_SERIF: &'static str = "serif";
+const DEFAULT_FONT_FAMILY_SERIF: &str = "serif";
 #[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
-const DEFAULT_FONT_FAMILY_SANS_SERIF: &'static str = "sans-serif";
+const DEFAULT_FONT_FAMILY_SANS_SERIF: &str = "sans-serif";
 #[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
-const DEFAULT_FONT_FAMILY_MONOSPACE: &'static str = "monospace";
+const DEFAULT_FONT_FAMILY_MONOSPACE: &str = "monospace";
 #[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
-const DEFAULT_FONT_FAMILY_CURSIVE: &'static str = "cursive";
+const DEFAULT_FONT_FAMILY_CURSIVE: &str = "cursive";
 #[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
-const DEFAULT_FONT_FAMILY_FANTASY: &'static str = "fantasy";
+const DEFAULT_FONT_FAMILY_FANTASY: &str = "fantasy";
 
 /// A database of installed fonts that can be queried.
 ///
@@ -100,6 +109,22 @@ pub trait Source {
         Err(SelectionError::NotFound)
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    ///
+    /// The name is compared against the full name of each font in every language that its `name`
+    /// table provides. The default implementation does a brute-force search of installed fonts.
+    fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        let full_name = full_name.to_lowercase();
+        for handle in self.all_fonts()? {
+            if let Ok(font) = Font::from_handle(&handle) {
+                if font_has_full_name(&font, &full_name) {
+                    return Ok(handle);
+                }
+            }
+        }
+        Err(SelectionError::NotFound)
+    }
+
     // FIXME(pcwalton): This only returns one family instead of multiple families for the generic
     // family names.
     #[doc(hidden)]
@@ -117,6 +142,25 @@ pub trait Source {
         }
     }
 
+    /// Looks up the family that a family name resolves to, resolving the generic families with
+    /// the user's choices in `config` before the platform defaults.
+    ///
+    /// The families that `config` gives for a generic family are tried in order, and the first
+    /// one that is installed is returned. If none of them is, this falls back to the platform
+    /// default, as `select_best_match()` uses. Specific families are looked up by name.
+    fn select_family_by_generic_name_with_config(
+        &self,
+        family_name: &FamilyName,
+        config: &GenericFamilyConfig,
+    ) -> Result<FamilyHandle, SelectionError> {
+        for configured_family_name in config.families(family_name) {
+            if let Ok(family_handle) = self.select_family_by_name(configured_family_name) {
+                return Ok(family_handle);
+            }
+        }
+        self.select_family_by_generic_name(family_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -136,11 +180,124 @@ pub trait Source {
         Err(SelectionError::NotFound)
     }
 
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag, such as `ja` or `zh-Hant`.
+    ///
+    /// The matches in `family_names` are tried from best to worst, and the first one with glyphs
+    /// for a few characters representative of the language is returned. If none of them has
+    /// those glyphs, the best match in the first family of the source that does is returned
+    /// instead; this is a brute-force search of installed fonts. If no font covers the language,
+    /// or its characters aren't known, this returns the same font as `select_best_match()`.
+    fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        let sample_chars = match script::sample_chars_for_language(lang) {
+            Some(sample_chars) => sample_chars,
+            None => return self.select_best_match(family_names, properties),
+        };
+        let matches = match self.select_matches(family_names, properties, usize::MAX) {
+            Ok(matches) => matches,
+            Err(SelectionError::NotFound) => vec![],
+            Err(error) => return Err(error),
+        };
+        if let Some((handle, _)) = matches
+            .iter()
+            .find(|(handle, _)| font_covers(handle, sample_chars))
+        {
+            return Ok(handle.clone());
+        }
+
+        for family_name in self.all_families()? {
+            let family_handle = match self.select_family_by_name(&family_name) {
+                Ok(family_handle) => family_handle,
+                Err(_) => continue,
+            };
+            let candidates = self.select_descriptions_in_family(&family_handle)?;
+            for (index, _) in matching::rank_matches(&candidates, properties) {
+                let handle = &family_handle.fonts[index];
+                if font_covers(handle, sample_chars) {
+                    return Ok(handle.clone());
+                }
+            }
+        }
+
+        match matches.into_iter().next() {
+            Some((handle, _)) => Ok(handle),
+            None => Err(SelectionError::NotFound),
+        }
+    }
+
+    /// Returns up to `limit` fonts matching the given family names and properties, from best to
+    /// worst, along with how closely each one matches.
+    ///
+    /// This uses the same CSS-style matching as `select_best_match()`, so the first font returned
+    /// is the one that it would select. Fonts in earlier families in `family_names` are ranked
+    /// ahead of fonts in later ones.
+    fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        let mut matches = vec![];
+        for (family_index, family_name) in family_names.iter().enumerate() {
+            if matches.len() >= limit {
+                break;
+            }
+            if let Ok(family_handle) = self.select_family_by_generic_name(family_name) {
+                let candidates = self.select_descriptions_in_family(&family_handle)?;
+                let ranked = matching::rank_matches(&candidates, properties);
+                for (index, mut score) in ranked.into_iter().take(limit - matches.len()) {
+                    score.family_index = family_index;
+                    matches.push((family_handle.fonts[index].clone(), score));
+                }
+            }
+        }
+        if matches.is_empty() {
+            return Err(SelectionError::NotFound);
+        }
+        Ok(matches)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    ///
+    /// The platform's native fallback machinery is consulted first, via
+    /// `Loader::get_fallbacks()`. If that finds nothing, the default implementation falls back to
+    /// a brute-force search of installed fonts. The returned font is guaranteed to have a glyph
+    /// for `c`.
+    fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        if let Some(font) = select_native_fallback(primary, script, c) {
+            return Ok(font);
+        }
+
+        for handle in self.all_fonts()? {
+            if let Ok(font) = Font::from_handle(&handle) {
+                if font.glyph_for_char(c).is_some() {
+                    return Ok(font);
+                }
+            }
+        }
+        Err(SelectionError::NotFound)
+    }
+
     #[doc(hidden)]
     fn select_descriptions_in_family(
         &self,
         family: &FamilyHandle,
     ) -> Result<Vec<Properties>, SelectionError> {
+        if let Some(ref properties) = family.properties {
+            return Ok(properties.clone());
+        }
+
         let mut fields = vec![];
         for font_handle in family.fonts() {
             let font = Font::from_handle(font_handle).unwrap();
@@ -149,3 +306,146 @@ pub trait Source {
         Ok(fields)
     }
 }
+
+/// How closely a font returned by `Source::select_matches()` matches the query.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatchScore {
+    /// The index of the font's family in the list of family names that was queried.
+    pub family_index: usize,
+    /// How much wider (if positive) or narrower (if negative) the font is than requested, as a
+    /// fraction of the normal width.
+    pub stretch_difference: f32,
+    /// How far the font's style is from the requested one, in the order in which CSS falls back
+    /// to other styles: 0 is an exact match, 1 is the first fallback, and 2 is the last.
+    pub style_distance: u8,
+    /// How much heavier (if positive) or lighter (if negative) the font is than requested.
+    pub weight_difference: f32,
+}
+
+/// Returns true if the font's full name, in any language, is `full_name` when lowercased.
+fn font_has_full_name(font: &Font, full_name: &str) -> bool {
+    if font.full_name().to_lowercase() == full_name {
+        return true;
+    }
+    match font
+        .load_font_table(name::TAG)
+        .and_then(|data| NameTable::parse(&data))
+    {
+        Some(name_table) => name_table
+            .names(name::FULL_NAME_ID)
+            .any(|name| name.to_lowercase() == full_name),
+        None => false,
+    }
+}
+
+/// Returns true if the font that `handle` refers to has glyphs for all of `chars`.
+fn font_covers(handle: &Handle, chars: &str) -> bool {
+    match Font::from_handle(handle) {
+        Ok(font) => chars.chars().all(|c| font.glyph_for_char(c).is_some()),
+        Err(_) => false,
+    }
+}
+
+/// Returns the first font that the loader's native fallback machinery suggests for `c` that
+/// actually has a glyph for it.
+fn select_native_fallback(primary: &Font, script: Script, c: char) -> Option<Font> {
+    let mut text = [0; 4];
+    let text = c.encode_utf8(&mut text);
+    Loader::get_fallbacks(primary, text, script.language().unwrap_or(""))
+        .fonts
+        .into_iter()
+        .map(|fallback_font| fallback_font.font)
+        .find(|font| font.glyph_for_char(c).is_some())
+}
+
+/// A future that resolves to the handles of all the fonts installed on the system, returned by
+/// `SystemSource::all_fonts_async()`.
+///
+/// The fonts are enumerated on a background thread, so callers can wait for them without
+/// blocking: either by polling this future from an executor, or by calling `wait()` from a thread
+/// that doesn't mind blocking.
+#[derive(Debug)]
+pub struct AllFontsFuture {
+    shared: Arc<AllFontsShared>,
+}
+
+#[derive(Debug, Default)]
+struct AllFontsShared {
+    state: Mutex<AllFontsState>,
+    condvar: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct AllFontsState {
+    result: Option<Result<Vec<Handle>, SelectionError>>,
+    waker: Option<Waker>,
+}
+
+impl AllFontsFuture {
+    /// Runs `enumerate` on a new thread, resolving the future with its result.
+    pub(crate) fn spawn<F>(enumerate: F) -> AllFontsFuture
+    where
+        F: FnOnce() -> Result<Vec<Handle>, SelectionError> + Send + 'static,
+    {
+        let shared = Arc::new(AllFontsShared::default());
+        let thread_shared = shared.clone();
+        let spawned = thread::Builder::new()
+            .name("font-kit enumeration".to_owned())
+            .spawn(move || {
+                let result = enumerate();
+                thread_shared.resolve(result);
+            });
+        if spawned.is_err() {
+            shared.resolve(Err(SelectionError::CannotAccessSource));
+        }
+        AllFontsFuture { shared }
+    }
+
+    /// Returns a future that has already resolved to `result`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn ready(result: Result<Vec<Handle>, SelectionError>) -> AllFontsFuture {
+        let shared = Arc::new(AllFontsShared::default());
+        shared.resolve(result);
+        AllFontsFuture { shared }
+    }
+
+    /// Blocks the current thread until the fonts have been enumerated, and returns them.
+    pub fn wait(self) -> Result<Vec<Handle>, SelectionError> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self.shared.condvar.wait(state).unwrap();
+        }
+    }
+}
+
+impl Future for AllFontsFuture {
+    type Output = Result<Vec<Handle>, SelectionError>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl AllFontsShared {
+    fn resolve(&self, result: Result<Vec<Handle>, SelectionError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        self.condvar.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
@@ -28,7 +28,8 @@ use crate::file_type::FileType;
 use crate::font::Font;
 use crate::handle::Handle;
 use crate::properties::{Properties, Stretch, Weight};
-use crate::source::Source;
+use crate::script::Script;
+use crate::source::{AllFontsFuture, MatchScore, Source};
 use crate::utils;
 
 pub(crate) static FONT_WEIGHT_MAPPING: [f32; 9] = [-0.7, -0.5, -0.23, 0.0, 0.2, 0.3, 0.4, 0.6, 0.8];
@@ -54,6 +55,12 @@ impl CoreTextSource {
         create_handles_from_core_text_collection(collection)
     }
 
+    /// Returns paths of all fonts installed on the system, enumerating them on a background
+    /// thread so that the calling thread isn't blocked.
+    pub fn all_fonts_async(&self) -> AllFontsFuture {
+        AllFontsFuture::spawn(|| CoreTextSource::new().all_fonts())
+    }
+
     /// Returns the names of all families installed on the system.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         let core_text_family_names = font_manager::copy_available_font_family_names();
@@ -97,6 +104,12 @@ impl CoreTextSource {
         }
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -107,6 +120,44 @@ impl CoreTextSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    #[inline]
+    pub fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        <Self as Source>::select_fallback(self, primary, script, c)
+    }
 }
 
 impl Source for CoreTextSource {
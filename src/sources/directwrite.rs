@@ -16,9 +16,11 @@ use dwrote::FontCollection as DWriteFontCollection;
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
+use crate::font::Font;
 use crate::handle::Handle;
 use crate::properties::Properties;
-use crate::source::Source;
+use crate::script::Script;
+use crate::source::{AllFontsFuture, MatchScore, Source};
 
 /// A source that contains the installed fonts on Windows.
 #[allow(missing_debug_implementations)]
@@ -48,6 +50,12 @@ impl DirectWriteSource {
         Ok(handles)
     }
 
+    /// Returns paths of all fonts installed on the system, enumerating them on a background
+    /// thread so that the calling thread isn't blocked.
+    pub fn all_fonts_async(&self) -> AllFontsFuture {
+        AllFontsFuture::spawn(|| DirectWriteSource::new().all_fonts())
+    }
+
     /// Returns the names of all families installed on the system.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         Ok(self
@@ -87,6 +95,12 @@ impl DirectWriteSource {
         <Self as Source>::select_by_postscript_name(self, postscript_name)
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -98,6 +112,44 @@ impl DirectWriteSource {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
 
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    #[inline]
+    pub fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        <Self as Source>::select_fallback(self, primary, script, c)
+    }
+
     fn create_handle_from_dwrite_font(&self, dwrite_font: DWriteFont) -> Handle {
         let dwrite_font_face = dwrite_font.create_font_face();
         let dwrite_font_files = dwrite_font_face.get_files();
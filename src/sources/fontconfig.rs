@@ -15,12 +15,16 @@
 //! support. To prefer it over the native font source (only if you know what you're doing), use the
 //! `source-fontconfig-default` feature.
 
+use std::path::{Path, PathBuf};
+
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
+use crate::font::Font;
 use crate::handle::Handle;
 use crate::properties::Properties;
-use crate::source::Source;
+use crate::script::Script;
+use crate::source::{AllFontsFuture, MatchScore, Source};
 
 /// A source that contains the fonts installed on the system, as reported by the Fontconfig
 /// library.
@@ -31,16 +35,38 @@ use crate::source::Source;
 #[allow(missing_debug_implementations)]
 pub struct FontconfigSource {
     config: fc::Config,
+    config_path: Option<PathBuf>,
 }
 
 impl FontconfigSource {
-    /// Initializes Fontconfig and prepares it for queries.
+    /// Initializes Fontconfig with the default system configuration and prepares it for queries.
     pub fn new() -> FontconfigSource {
         FontconfigSource {
             config: fc::Config::new(),
+            config_path: None,
         }
     }
 
+    /// Initializes Fontconfig from the configuration at `path` instead of the system one, and
+    /// prepares it for queries.
+    ///
+    /// The path may name a single configuration file, such as a bundled `fonts.conf`, or a
+    /// directory, in which case every `.conf` file in it is loaded in order. Nothing from the
+    /// system configuration is loaded, so only the fonts that it lists are available. Returns
+    /// `CannotAccessSource` if the configuration cannot be read or parsed.
+    pub fn from_config_path<P>(path: P) -> Result<FontconfigSource, SelectionError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let config =
+            fc::Config::from_config_path(path).ok_or(SelectionError::CannotAccessSource)?;
+        Ok(FontconfigSource {
+            config,
+            config_path: Some(path.to_owned()),
+        })
+    }
+
     /// Returns paths of all fonts installed on the system.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         let pattern = fc::Pattern::new();
@@ -79,6 +105,19 @@ impl FontconfigSource {
         }
     }
 
+    /// Returns paths of all fonts installed on the system, enumerating them on a background
+    /// thread so that the calling thread isn't blocked.
+    ///
+    /// The background thread initializes its own copy of the configuration that this source was
+    /// created with.
+    pub fn all_fonts_async(&self) -> AllFontsFuture {
+        let config_path = self.config_path.clone();
+        AllFontsFuture::spawn(move || match config_path {
+            None => FontconfigSource::new().all_fonts(),
+            Some(config_path) => FontconfigSource::from_config_path(config_path)?.all_fonts(),
+        })
+    }
+
     /// Returns the names of all families installed on the system.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         let pattern = fc::Pattern::new();
@@ -195,6 +234,53 @@ impl FontconfigSource {
         }
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    ///
+    /// Fontconfig is asked for the installed fonts that best cover `c` in a language written in
+    /// the script, and the first of them that has a glyph for `c` is returned.
+    pub fn select_fallback(
+        &self,
+        _: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        let mut pattern = match script.language() {
+            Some(language) => fc::Pattern::from_name(&format!(":lang={}", language)),
+            None => fc::Pattern::new(),
+        };
+        let mut char_set = fc::CharSet::new();
+        char_set.push_char(c);
+        pattern.push_char_set(fc::Object::CharSet, &char_set);
+        pattern.config_substitute(fc::MatchKind::Pattern);
+        pattern.default_substitute();
+
+        let patterns = pattern
+            .sorted(&self.config)
+            .map_err(|_| SelectionError::NotFound)?;
+        for patt in patterns {
+            let (font_path, font_index) = match (
+                patt.get_string(fc::Object::File),
+                patt.get_integer(fc::Object::Index),
+            ) {
+                (Some(font_path), Some(font_index)) => (font_path, font_index as u32),
+                _ => continue,
+            };
+            if let Ok(font) = Font::from_path(font_path, font_index) {
+                if font.glyph_for_char(c).is_some() {
+                    return Ok(font);
+                }
+            }
+        }
+        Err(SelectionError::NotFound)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -205,6 +291,39 @@ impl FontconfigSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
+}
+
+impl Default for FontconfigSource {
+    #[inline]
+    fn default() -> FontconfigSource {
+        FontconfigSource::new()
+    }
 }
 
 impl Source for FontconfigSource {
@@ -227,6 +346,16 @@ impl Source for FontconfigSource {
     fn select_by_postscript_name(&self, postscript_name: &str) -> Result<Handle, SelectionError> {
         self.select_by_postscript_name(postscript_name)
     }
+
+    #[inline]
+    fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        self.select_fallback(primary, script, c)
+    }
 }
 
 // A minimal fontconfig wrapper.
@@ -237,6 +366,7 @@ mod fc {
 
     use std::ffi::{CStr, CString};
     use std::os::raw::{c_char, c_uchar};
+    use std::path::Path;
     use std::ptr;
 
     #[derive(Clone, Copy)]
@@ -255,7 +385,7 @@ mod fc {
     }
 
     impl MatchKind {
-        fn to_u32(&self) -> u32 {
+        fn to_u32(self) -> u32 {
             match self {
                 MatchKind::Pattern => ffi::FcMatchPattern,
                 MatchKind::Font => ffi::FcMatchFont,
@@ -267,6 +397,7 @@ mod fc {
     // https://www.freedesktop.org/software/fontconfig/fontconfig-devel/x19.html
     #[derive(Clone, Copy)]
     pub enum Object {
+        CharSet,
         Family,
         File,
         Index,
@@ -276,6 +407,7 @@ mod fc {
     impl Object {
         fn as_bytes(&self) -> &[u8] {
             match self {
+                Object::CharSet => b"charset\0",
                 Object::Family => b"family\0",
                 Object::File => b"file\0",
                 Object::Index => b"index\0",
@@ -301,6 +433,25 @@ mod fc {
                 }
             }
         }
+
+        // FcConfigCreate, FcConfigParseAndLoad, FcConfigBuildFonts
+        pub fn from_config_path(path: &Path) -> Option<Self> {
+            let path = CString::new(path.to_str()?).ok()?;
+            unsafe {
+                let config = Config {
+                    d: ffi::FcConfigCreate(),
+                };
+                if config.d.is_null() {
+                    return None;
+                }
+                if ffi::FcConfigParseAndLoad(config.d, path.as_ptr() as *const c_uchar, 1) == 0
+                    || ffi::FcConfigBuildFonts(config.d) == 0
+                {
+                    return None;
+                }
+                Some(config)
+            }
+        }
     }
 
     impl Drop for Config {
@@ -350,6 +501,14 @@ mod fc {
             }
         }
 
+        // FcPatternAddCharSet
+        pub fn push_char_set(&mut self, object: Object, char_set: &CharSet) {
+            unsafe {
+                // The pattern makes its own copy of the character set.
+                ffi::FcPatternAddCharSet(self.d, object.as_ptr(), char_set.d);
+            }
+        }
+
         // FcConfigSubstitute
         pub fn config_substitute(&mut self, match_kind: MatchKind) {
             unsafe {
@@ -462,7 +621,7 @@ mod fc {
             let idx = self.idx;
             self.idx += 1;
 
-            let d = unsafe { *(*self.d).fonts.offset(idx as isize) };
+            let d = unsafe { *(*self.d).fonts.add(idx) };
             Some(PatternRef { d })
         }
 
@@ -506,4 +665,32 @@ mod fc {
             unsafe { ffi::FcObjectSetDestroy(self.d) }
         }
     }
+
+    pub struct CharSet {
+        d: *mut ffi::FcCharSet,
+    }
+
+    impl CharSet {
+        // FcCharSetCreate
+        pub fn new() -> Self {
+            unsafe {
+                CharSet {
+                    d: ffi::FcCharSetCreate(),
+                }
+            }
+        }
+
+        // FcCharSetAddChar
+        pub fn push_char(&mut self, c: char) {
+            unsafe {
+                assert_eq!(ffi::FcCharSetAddChar(self.d, c as u32), 1);
+            }
+        }
+    }
+
+    impl Drop for CharSet {
+        fn drop(&mut self) {
+            unsafe { ffi::FcCharSetDestroy(self.d) }
+        }
+    }
 }
@@ -14,8 +14,10 @@
 //!
 //! This is the native source on Android.
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 #[cfg(not(any(target_os = "android", target_family = "windows")))]
@@ -29,15 +31,16 @@ use winapi::shared::minwindef::{MAX_PATH, UINT};
 #[cfg(target_family = "windows")]
 use winapi::um::sysinfoapi;
 
-use crate::error::SelectionError;
+use crate::error::{FontLoadingError, SelectionError};
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
 use crate::file_type::FileType;
 use crate::font::Font;
 use crate::handle::Handle;
 use crate::properties::Properties;
-use crate::source::Source;
-use crate::sources::mem::MemSource;
+use crate::script::Script;
+use crate::source::{AllFontsFuture, MatchScore, Source};
+use crate::sources::mem::{FontMetadata, MemSource};
 
 /// A source that loads fonts from a directory or directories on disk.
 ///
@@ -55,33 +58,26 @@ impl FsSource {
     /// Do not rely on this function for systems other than Android. It makes a best effort to
     /// locate fonts in the typical platform directories, but it is too simple to pick up fonts
     /// that are stored in unusual locations but nevertheless properly installed.
+    ///
+    /// With the `parallel` Cargo feature, the directories are still walked on the calling thread,
+    /// but the files found are loaded on the threads of the global `rayon` pool. The resulting
+    /// source is the same either way.
     pub fn new() -> FsSource {
-        let mut fonts = vec![];
+        let mut paths = vec![];
         for font_directory in default_font_directories() {
             for directory_entry in WalkDir::new(font_directory).into_iter() {
-                let directory_entry = match directory_entry {
-                    Ok(directory_entry) => directory_entry,
-                    Err(_) => continue,
-                };
-                let path = directory_entry.path();
-                let mut file = match File::open(path) {
-                    Err(_) => continue,
-                    Ok(file) => file,
-                };
-                match Font::analyze_file(&mut file) {
+                match directory_entry {
+                    Ok(directory_entry) => paths.push(directory_entry.into_path()),
                     Err(_) => continue,
-                    Ok(FileType::Single) => fonts.push(Handle::from_path(path.to_owned(), 0)),
-                    Ok(FileType::Collection(font_count)) => {
-                        for font_index in 0..font_count {
-                            fonts.push(Handle::from_path(path.to_owned(), font_index))
-                        }
-                    }
                 }
             }
         }
 
+        let fonts = index_files(&paths)
+            .into_iter()
+            .flat_map(|fonts| fonts.unwrap());
         FsSource {
-            mem_source: MemSource::from_fonts(fonts.into_iter()).unwrap(),
+            mem_source: MemSource::from_fonts_with_metadata(fonts),
         }
     }
 
@@ -90,6 +86,13 @@ impl FsSource {
         self.mem_source.all_fonts()
     }
 
+    /// Returns paths of all fonts installed on the system as a future.
+    ///
+    /// This source indexes its fonts when it's created, so the future has already resolved.
+    pub fn all_fonts_async(&self) -> AllFontsFuture {
+        AllFontsFuture::ready(self.all_fonts())
+    }
+
     /// Returns the names of all families installed on the system.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         self.mem_source.all_families()
@@ -111,6 +114,12 @@ impl FsSource {
         self.mem_source.select_by_postscript_name(postscript_name)
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -121,6 +130,51 @@ impl FsSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    #[inline]
+    pub fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        <Self as Source>::select_fallback(self, primary, script, c)
+    }
+}
+
+impl Default for FsSource {
+    #[inline]
+    fn default() -> FsSource {
+        FsSource::new()
+    }
 }
 
 impl Source for FsSource {
@@ -143,6 +197,41 @@ impl Source for FsSource {
     }
 }
 
+/// Loads every font in the file at `path`, skipping fonts without a PostScript name. Files that
+/// aren't fonts yield no fonts.
+fn index_file(path: &Path) -> Result<Vec<(Handle, FontMetadata)>, FontLoadingError> {
+    let mut file = match File::open(path) {
+        Err(_) => return Ok(vec![]),
+        Ok(file) => file,
+    };
+    let font_count = match Font::analyze_file(&mut file) {
+        Err(_) => return Ok(vec![]),
+        Ok(FileType::Single) | Ok(FileType::Woff) | Ok(FileType::Woff2) => 1,
+        Ok(FileType::Collection(font_count)) => font_count,
+    };
+
+    let mut fonts = vec![];
+    for font_index in 0..font_count {
+        let handle = Handle::from_path(path.to_owned(), font_index);
+        if let Some(metadata) = FontMetadata::from_font(&Font::from_handle(&handle)?) {
+            fonts.push((handle, metadata))
+        }
+    }
+    Ok(fonts)
+}
+
+/// Indexes each of the files at `paths`, returning the results in the same order.
+#[cfg(not(feature = "parallel"))]
+fn index_files(paths: &[PathBuf]) -> Vec<Result<Vec<(Handle, FontMetadata)>, FontLoadingError>> {
+    paths.iter().map(|path| index_file(path)).collect()
+}
+
+/// Indexes each of the files at `paths` in parallel, returning the results in the same order.
+#[cfg(feature = "parallel")]
+fn index_files(paths: &[PathBuf]) -> Vec<Result<Vec<(Handle, FontMetadata)>, FontLoadingError>> {
+    paths.par_iter().map(|path| index_file(path)).collect()
+}
+
 #[cfg(target_os = "android")]
 fn default_font_directories() -> Vec<PathBuf> {
     vec![PathBuf::from("/system/fonts")]
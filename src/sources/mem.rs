@@ -10,13 +10,16 @@
 
 //! A source that keeps fonts in memory.
 
+use std::collections::HashMap;
+
 use crate::error::{FontLoadingError, SelectionError};
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
 use crate::font::Font;
 use crate::handle::Handle;
 use crate::properties::Properties;
-use crate::source::Source;
+use crate::script::Script;
+use crate::source::{MatchScore, Source};
 
 /// A source that keeps fonts in memory.
 #[allow(missing_debug_implementations)]
@@ -27,24 +30,95 @@ pub struct MemSource {
 impl MemSource {
     /// Creates a new memory source that contains the given set of font handles.
     ///
-    /// The fonts referenced by the handles are eagerly loaded into memory.
+    /// The fonts referenced by the handles are eagerly loaded into memory. Fonts are identified
+    /// by PostScript name, so if several of the fonts share a PostScript name, only the last one
+    /// is kept; earlier versions of this crate kept all of them. Fonts without a PostScript name
+    /// are skipped.
     pub fn from_fonts<I>(fonts: I) -> Result<MemSource, FontLoadingError>
     where
         I: Iterator<Item = Handle>,
     {
-        let mut families = vec![];
+        let mut entries = vec![];
         for handle in fonts {
-            let font = Font::from_handle(&handle)?;
-            if let Some(postscript_name) = font.postscript_name() {
-                families.push(FamilyEntry {
-                    family_name: font.family_name(),
-                    postscript_name: postscript_name,
-                    font: handle,
-                })
+            if let Some(metadata) = FontMetadata::from_font(&Font::from_handle(&handle)?) {
+                entries.push(FamilyEntry::new(handle, metadata));
             }
         }
+        Ok(MemSource::from_entries(entries))
+    }
+
+    /// Creates a new memory source that contains the given fonts, whose names are already
+    /// known.
+    ///
+    /// Unlike `from_fonts()`, this doesn't load the fonts, which makes it much faster for large
+    /// numbers of fonts whose metadata was saved from a previous run. The handles aren't
+    /// checked: a handle whose metadata is wrong will be returned for the wrong queries, and one
+    /// that doesn't refer to a valid font will fail when it's loaded. As with `from_fonts()`, only
+    /// the last of several fonts with the same PostScript name is kept.
+    pub fn from_fonts_with_metadata<I>(fonts: I) -> MemSource
+    where
+        I: Iterator<Item = (Handle, FontMetadata)>,
+    {
+        MemSource::from_entries(
+            fonts
+                .map(|(handle, metadata)| FamilyEntry::new(handle, metadata))
+                .collect(),
+        )
+    }
+
+    /// Creates a source from unsorted entries, of which only the last with any given PostScript
+    /// name is kept.
+    fn from_entries(entries: Vec<FamilyEntry>) -> MemSource {
+        let mut last_indices = HashMap::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            last_indices.insert(entry.postscript_name.clone(), index);
+        }
+
+        let mut families: Vec<FamilyEntry> = entries
+            .into_iter()
+            .enumerate()
+            .filter(|(index, entry)| last_indices[&entry.postscript_name] == *index)
+            .map(|(_, entry)| entry)
+            .collect();
         families.sort_by(|a, b| a.family_name.cmp(&b.family_name));
-        Ok(MemSource { families })
+        MemSource { families }
+    }
+
+    /// Loads the font referenced by the given handle and adds it to this source, returning the
+    /// loaded font.
+    ///
+    /// Fonts are identified by PostScript name, so adding a font whose PostScript name is already
+    /// present replaces the existing entry instead of duplicating it. Fonts without a PostScript
+    /// name are loaded but can't be added.
+    pub fn add_font(&mut self, handle: Handle) -> Result<Font, FontLoadingError> {
+        let font = Font::from_handle(&handle)?;
+        if let Some(metadata) = FontMetadata::from_font(&font) {
+            self.add_font_with_metadata(handle, metadata);
+        }
+        Ok(font)
+    }
+
+    /// Adds a font whose names are already known to this source without loading it, replacing
+    /// any font with the same PostScript name, as `add_font()` does.
+    ///
+    /// See `from_fonts_with_metadata()`.
+    pub fn add_font_with_metadata(&mut self, handle: Handle, metadata: FontMetadata) {
+        self.remove_font(&metadata.postscript_name);
+        let index = self
+            .families
+            .partition_point(|family| family.family_name <= metadata.family_name);
+        self.families
+            .insert(index, FamilyEntry::new(handle, metadata));
+    }
+
+    /// Removes the font with the given PostScript name from this source, returning its handle, or
+    /// `None` if no such font was present.
+    pub fn remove_font(&mut self, postscript_name: &str) -> Option<Handle> {
+        let index = self
+            .families
+            .iter()
+            .position(|family| family.postscript_name == postscript_name)?;
+        Some(self.families.remove(index).font)
     }
 
     /// Returns paths of all fonts installed on the system.
@@ -76,7 +150,7 @@ impl MemSource {
     pub fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError> {
         let mut first_family_index = self
             .families
-            .binary_search_by(|family| (&*family.family_name).cmp(family_name))
+            .binary_search_by(|family| (*family.family_name).cmp(family_name))
             .map_err(|_| SelectionError::NotFound)?;
 
         while first_family_index > 0
@@ -91,9 +165,13 @@ impl MemSource {
             last_family_index += 1
         }
 
+        // The properties are passed along so that matching within the family doesn't have to load
+        // the fonts.
         let families = &self.families[first_family_index..(last_family_index + 1)];
-        Ok(FamilyHandle::from_font_handles(
-            families.iter().map(|family| family.font.clone()),
+        Ok(FamilyHandle::from_font_handles_and_properties(
+            families
+                .iter()
+                .map(|family| (family.font.clone(), family.properties)),
         ))
     }
 
@@ -113,6 +191,12 @@ impl MemSource {
             .ok_or(SelectionError::NotFound)
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -123,6 +207,44 @@ impl MemSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
+
+    /// Selects a font that can display the character `c`, written in the given script, for text
+    /// that the `primary` font can't display.
+    #[inline]
+    pub fn select_fallback(
+        &self,
+        primary: &Font,
+        script: Script,
+        c: char,
+    ) -> Result<Font, SelectionError> {
+        <Self as Source>::select_fallback(self, primary, script, c)
+    }
 }
 
 impl Source for MemSource {
@@ -145,8 +267,47 @@ impl Source for MemSource {
     }
 }
 
+/// The names and properties that a `MemSource` indexes a font by, which can be saved so that the
+/// font doesn't have to be loaded to be added to a source again.
+///
+/// See `MemSource::from_fonts_with_metadata()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontMetadata {
+    /// The family name of the font, as `Font::family_name()` returns it.
+    pub family_name: String,
+    /// The PostScript name of the font, which identifies it within a source.
+    pub postscript_name: String,
+    /// The properties of the font, as `Font::properties()` returns them, which are used to pick
+    /// fonts within a family.
+    pub properties: Properties,
+}
+
+impl FontMetadata {
+    /// Returns the metadata of a loaded font, or `None` if the font has no PostScript name and so
+    /// can't be added to a source.
+    pub fn from_font(font: &Font) -> Option<FontMetadata> {
+        Some(FontMetadata {
+            postscript_name: font.postscript_name()?,
+            family_name: font.family_name(),
+            properties: font.properties(),
+        })
+    }
+}
+
 struct FamilyEntry {
     family_name: String,
     postscript_name: String,
+    properties: Properties,
     font: Handle,
 }
+
+impl FamilyEntry {
+    fn new(font: Handle, metadata: FontMetadata) -> FamilyEntry {
+        FamilyEntry {
+            family_name: metadata.family_name,
+            postscript_name: metadata.postscript_name,
+            properties: metadata.properties,
+            font,
+        }
+    }
+}
@@ -13,47 +13,63 @@
 //! This is useful when an application wants a library of fonts consisting of the installed system
 //! fonts plus some other application-supplied fonts.
 
+use std::collections::HashSet;
+
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
 use crate::handle::Handle;
 use crate::properties::Properties;
-use crate::source::Source;
+use crate::source::{MatchScore, Source};
 
 /// A source that encapsulates multiple sources and allows them to be queried as a group.
 ///
 /// This is useful when an application wants a library of fonts consisting of the installed system
 /// fonts plus some other application-supplied fonts.
+///
+/// The subsources are consulted in order, and earlier ones take precedence: a family that more
+/// than one subsource has is looked up, and matched against, in the first of them only. To let
+/// bundled fonts override installed ones, list the source of the bundled fonts first.
 #[allow(missing_debug_implementations)]
 pub struct MultiSource {
     subsources: Vec<Box<dyn Source>>,
 }
 
 impl MultiSource {
-    /// Creates a new source that contains all the fonts in the supplied sources.
+    /// Creates a new source that contains all the fonts in the supplied sources, in order of
+    /// decreasing precedence.
     pub fn from_sources(subsources: Vec<Box<dyn Source>>) -> MultiSource {
         MultiSource { subsources }
     }
 
-    /// Returns paths of all fonts installed on the system.
+    /// Returns paths of all fonts in all the subsources, in the order of the subsources.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         let mut handles = vec![];
         for subsource in &self.subsources {
-            handles.extend(subsource.all_fonts()?.into_iter())
+            handles.extend(subsource.all_fonts()?)
         }
         Ok(handles)
     }
 
-    /// Returns the names of all families installed on the system.
+    /// Returns the names of all families in all the subsources, in the order of the subsources.
+    ///
+    /// Each name appears only once, at the position of the subsource that takes precedence.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         let mut families = vec![];
+        let mut seen = HashSet::new();
         for subsource in &self.subsources {
-            families.extend(subsource.all_families()?.into_iter())
+            for family in subsource.all_families()? {
+                if seen.insert(family.clone()) {
+                    families.push(family);
+                }
+            }
         }
         Ok(families)
     }
 
     /// Looks up a font family by name and returns the handles of all the fonts in that family.
+    ///
+    /// The family is taken from the first subsource that has it.
     pub fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError> {
         for subsource in &self.subsources {
             match subsource.select_family_by_name(family_name) {
@@ -66,6 +82,8 @@ impl MultiSource {
     }
 
     /// Selects a font by PostScript name, which should be a unique identifier.
+    ///
+    /// The font is taken from the first subsource that has it.
     pub fn select_by_postscript_name(
         &self,
         postscript_name: &str,
@@ -80,8 +98,16 @@ impl MultiSource {
         Err(SelectionError::NotFound)
     }
 
+    /// Selects a font by its full name (e.g. "Helvetica Neue Bold Italic"), ignoring case.
+    #[inline]
+    pub fn select_by_full_name(&self, full_name: &str) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_by_full_name(self, full_name)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
+    ///
+    /// Each family is matched against the fonts of the first subsource that has it.
     #[inline]
     pub fn select_best_match(
         &self,
@@ -90,6 +116,32 @@ impl MultiSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Performs font matching according to the CSS Fonts Level 3 specification and returns up to
+    /// `limit` handles, from best to worst match, along with how closely each one matches.
+    #[inline]
+    pub fn select_matches(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        limit: usize,
+    ) -> Result<Vec<(Handle, MatchScore)>, SelectionError> {
+        <Self as Source>::select_matches(self, family_names, properties, limit)
+    }
+
+    /// Performs font matching like `select_best_match()`, but prefers fonts that can display text
+    /// in the language with the given BCP 47 tag.
+    ///
+    /// See `Source::select_best_match_for_lang()`.
+    #[inline]
+    pub fn select_best_match_for_lang(
+        &self,
+        family_names: &[FamilyName],
+        properties: &Properties,
+        lang: &str,
+    ) -> Result<Handle, SelectionError> {
+        <Self as Source>::select_best_match_for_lang(self, family_names, properties, lang)
+    }
 }
 
 impl Source for MultiSource {
@@ -0,0 +1,367 @@
+// font-kit/src/subset.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Glyph subsetting: building a smaller font that contains only some of another font's glyphs.
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::error::GlyphLoadingError;
+use crate::loader::Loader;
+use crate::sfnt;
+use crate::tables::{self, cff, cmap, os_2, post, vhea, vmtx};
+
+const TRUETYPE_FLAVOR: u32 = 0x0001_0000;
+const CFF_FLAVOR: u32 = tables::tag(b"OTTO");
+
+const GLYF_TAG: u32 = tables::tag(b"glyf");
+const HEAD_TAG: u32 = tables::tag(b"head");
+const HHEA_TAG: u32 = tables::tag(b"hhea");
+const HMTX_TAG: u32 = tables::tag(b"hmtx");
+const LOCA_TAG: u32 = tables::tag(b"loca");
+const MAXP_TAG: u32 = tables::tag(b"maxp");
+
+/// Tables that don't refer to glyphs, and so can be copied into a subset as they are.
+const COPIED_TABLE_TAGS: [u32; 5] = [
+    tables::tag(b"name"),
+    tables::tag(b"cvt "),
+    tables::tag(b"fpgm"),
+    tables::tag(b"prep"),
+    tables::tag(b"gasp"),
+];
+
+const HEAD_INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
+const HHEA_NUMBER_OF_METRICS_OFFSET: usize = 34;
+const MAXP_NUM_GLYPHS_OFFSET: usize = 4;
+const OS_2_FIRST_CHAR_INDEX_OFFSET: usize = 64;
+const POST_HEADER_SIZE: usize = 32;
+const POST_FORMAT_3: u32 = 0x0003_0000;
+
+const GLYPH_HEADER_SIZE: usize = 10;
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// The largest `glyf` table that a short (16-bit, halved) `loca` table can index.
+const MAX_SHORT_LOCA_OFFSET: usize = 0x1_fffe;
+
+/// A font containing a subset of another font's glyphs, renumbered.
+#[derive(Clone, Debug)]
+pub struct FontSubset {
+    /// The OpenType data of the new font, loadable with `Font::from_bytes()`.
+    pub font_data: Vec<u8>,
+    /// The ID, in the original font, of each glyph of the new font, indexed by new glyph ID.
+    ///
+    /// This is sorted, and always starts with glyph 0, the `.notdef` glyph.
+    pub old_glyph_ids: Vec<u32>,
+}
+
+impl FontSubset {
+    /// Returns the ID in the new font of the glyph with the given ID in the original font, if the
+    /// subset contains it.
+    pub fn new_glyph_id(&self, old_glyph_id: u32) -> Option<u32> {
+        self.old_glyph_ids
+            .binary_search(&old_glyph_id)
+            .ok()
+            .map(|new_glyph_id| new_glyph_id as u32)
+    }
+}
+
+/// Builds a font containing the given glyphs of `font`, plus glyph 0 and the components of any
+/// composite glyphs.
+pub(crate) fn subset<L>(font: &L, glyph_ids: &[u32]) -> Result<FontSubset, GlyphLoadingError>
+where
+    L: Loader,
+{
+    let glyph_count = font.glyph_count();
+    if glyph_ids.iter().any(|&glyph_id| glyph_id >= glyph_count) {
+        return Err(GlyphLoadingError::NoSuchGlyph);
+    }
+    subset_opt(font, glyph_ids, glyph_count).ok_or(GlyphLoadingError::UnsupportedFormat)
+}
+
+fn subset_opt<L>(font: &L, glyph_ids: &[u32], glyph_count: u32) -> Option<FontSubset>
+where
+    L: Loader,
+{
+    let mut head = font.load_font_table(HEAD_TAG)?.into_vec();
+    let mut tables = vec![];
+
+    let mut included = vec![false; glyph_count as usize];
+    included[0] = true;
+    for &glyph_id in glyph_ids {
+        included[glyph_id as usize] = true;
+    }
+
+    let (flavor, old_glyph_ids) = if let Some(cff) = font.load_font_table(cff::TAG) {
+        let old_glyph_ids = included_glyph_ids(&included);
+        tables.push((cff::TAG, cff::subset(&cff, &old_glyph_ids)?));
+        (CFF_FLAVOR, old_glyph_ids)
+    } else if let (Some(glyf), Some(loca)) = (
+        font.load_font_table(GLYF_TAG),
+        font.load_font_table(LOCA_TAG),
+    ) {
+        let long_loca = read_u16(&head, HEAD_INDEX_TO_LOC_FORMAT_OFFSET)? != 0;
+        let glyph_offsets = parse_loca(&loca, long_loca, glyph_count)?;
+        let glyph_data = |glyph_id: u32| {
+            let (start, end) = (
+                glyph_offsets[glyph_id as usize],
+                glyph_offsets[glyph_id as usize + 1],
+            );
+            glyf.get(start..end)
+        };
+
+        // Pull in the components of composite glyphs, and then theirs, and so on.
+        let mut pending: Vec<u32> = included_glyph_ids(&included);
+        while let Some(glyph_id) = pending.pop() {
+            for component_offset in component_offsets(glyph_data(glyph_id)?)? {
+                let component = read_u16(glyph_data(glyph_id)?, component_offset)? as usize;
+                match included.get_mut(component) {
+                    None => return None,
+                    Some(true) => {}
+                    Some(included) => {
+                        *included = true;
+                        pending.push(component as u32);
+                    }
+                }
+            }
+        }
+        let old_glyph_ids = included_glyph_ids(&included);
+
+        let mut new_glyf = vec![];
+        let mut new_glyph_offsets = Vec::with_capacity(old_glyph_ids.len() + 1);
+        for &old_glyph_id in &old_glyph_ids {
+            new_glyph_offsets.push(new_glyf.len());
+            let data = glyph_data(old_glyph_id)?;
+            let start = new_glyf.len();
+            new_glyf.extend_from_slice(data);
+            for component_offset in component_offsets(data)? {
+                let component = read_u16(data, component_offset)? as u32;
+                let new_component = old_glyph_ids.binary_search(&component).ok()? as u16;
+                let offset = start + component_offset;
+                new_glyf[offset..(offset + 2)].copy_from_slice(&new_component.to_be_bytes());
+            }
+            new_glyf.resize(sfnt::pad_to_4(new_glyf.len()), 0);
+        }
+        new_glyph_offsets.push(new_glyf.len());
+
+        let long_loca = new_glyf.len() > MAX_SHORT_LOCA_OFFSET;
+        let mut new_loca = vec![];
+        for &offset in &new_glyph_offsets {
+            if long_loca {
+                new_loca.extend_from_slice(&(offset as u32).to_be_bytes());
+            } else {
+                new_loca.extend_from_slice(&((offset / 2) as u16).to_be_bytes());
+            }
+        }
+        write_u16(&mut head, HEAD_INDEX_TO_LOC_FORMAT_OFFSET, long_loca as u16)?;
+
+        tables.push((GLYF_TAG, new_glyf));
+        tables.push((LOCA_TAG, new_loca));
+        (TRUETYPE_FLAVOR, old_glyph_ids)
+    } else {
+        // This covers `CFF2` fonts, as well as bitmap-only fonts.
+        return None;
+    };
+    let new_glyph_count = old_glyph_ids.len() as u16;
+
+    // Horizontal, and if present vertical, metrics.
+    let metrics_tables = [(HHEA_TAG, HMTX_TAG, true), (vhea::TAG, vmtx::TAG, false)];
+    for &(header_tag, metrics_tag, required) in &metrics_tables {
+        let (mut header, metrics) = match (
+            font.load_font_table(header_tag),
+            font.load_font_table(metrics_tag),
+        ) {
+            (Some(header), Some(metrics)) => (header.into_vec(), metrics),
+            _ if required => return None,
+            _ => continue,
+        };
+        let metric_count = read_u16(&header, HHEA_NUMBER_OF_METRICS_OFFSET)?;
+        let (new_metric_count, new_metrics) =
+            subset_metrics(&metrics, metric_count, &old_glyph_ids)?;
+        write_u16(&mut header, HHEA_NUMBER_OF_METRICS_OFFSET, new_metric_count)?;
+        tables.push((header_tag, header));
+        tables.push((metrics_tag, new_metrics));
+    }
+
+    let mut maxp = font.load_font_table(MAXP_TAG)?.into_vec();
+    write_u16(&mut maxp, MAXP_NUM_GLYPHS_OFFSET, new_glyph_count)?;
+    tables.push((MAXP_TAG, maxp));
+
+    // Keep the character mappings of the glyphs that survive.
+    let mut mappings: Vec<(char, u16)> = font
+        .load_font_table(cmap::TAG)
+        .map(|cmap| cmap::glyph_mappings(&cmap))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(old_glyph_id, character)| {
+            let new_glyph_id = old_glyph_ids.binary_search(&old_glyph_id).ok()?;
+            Some((character, new_glyph_id as u16))
+        })
+        .collect();
+    mappings.sort_unstable();
+    mappings.dedup_by_key(|&mut (character, _)| character);
+    tables.push((cmap::TAG, cmap::write_unicode_cmap(&mappings)));
+
+    // Glyph names are dropped rather than rewritten.
+    if let Some(post) = font.load_font_table(post::TAG) {
+        let mut post = post.get(..POST_HEADER_SIZE)?.to_vec();
+        post[0..4].copy_from_slice(&POST_FORMAT_3.to_be_bytes());
+        tables.push((post::TAG, post));
+    }
+
+    if let Some(table) = font.load_font_table(os_2::TAG) {
+        let mut table = table.into_vec();
+        let first_char = mappings
+            .first()
+            .map_or(0, |&(character, _)| character as u32);
+        let last_char = mappings
+            .last()
+            .map_or(0, |&(character, _)| character as u32);
+        write_u16(
+            &mut table,
+            OS_2_FIRST_CHAR_INDEX_OFFSET,
+            first_char.min(0xffff) as u16,
+        )?;
+        write_u16(
+            &mut table,
+            OS_2_FIRST_CHAR_INDEX_OFFSET + 2,
+            last_char.min(0xffff) as u16,
+        )?;
+        tables.push((os_2::TAG, table));
+    }
+
+    for &tag in &COPIED_TABLE_TAGS {
+        if let Some(table) = font.load_font_table(tag) {
+            tables.push((tag, table.into_vec()));
+        }
+    }
+    tables.push((HEAD_TAG, head));
+
+    Some(FontSubset {
+        font_data: sfnt::write_sfnt(flavor, tables),
+        old_glyph_ids,
+    })
+}
+
+fn included_glyph_ids(included: &[bool]) -> Vec<u32> {
+    included
+        .iter()
+        .enumerate()
+        .filter(|&(_, &included)| included)
+        .map(|(glyph_id, _)| glyph_id as u32)
+        .collect()
+}
+
+/// Returns the offset of every glyph in the `glyf` table, plus the end of the last one.
+fn parse_loca(loca: &[u8], long: bool, glyph_count: u32) -> Option<Vec<usize>> {
+    let mut reader = loca;
+    (0..=glyph_count)
+        .map(|_| {
+            if long {
+                reader
+                    .read_u32::<BigEndian>()
+                    .ok()
+                    .map(|offset| offset as usize)
+            } else {
+                reader
+                    .read_u16::<BigEndian>()
+                    .ok()
+                    .map(|offset| offset as usize * 2)
+            }
+        })
+        .collect()
+}
+
+/// Returns the offsets within a glyph's data of the glyph IDs of its components, if it's a
+/// composite glyph, or nothing otherwise.
+fn component_offsets(glyph: &[u8]) -> Option<Vec<usize>> {
+    if glyph.is_empty() || (glyph.get(..2)?).read_i16::<BigEndian>().ok()? >= 0 {
+        return Some(vec![]);
+    }
+
+    let mut offsets = vec![];
+    let mut offset = GLYPH_HEADER_SIZE;
+    loop {
+        let flags = read_u16(glyph, offset)?;
+        offsets.push(offset + 2);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            offset += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            offset += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            offset += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(offsets);
+        }
+    }
+}
+
+/// Rebuilds an `hmtx` or `vmtx` table for the given glyphs, returning the new number of long
+/// metrics along with the table.
+fn subset_metrics(
+    metrics: &[u8],
+    metric_count: u16,
+    old_glyph_ids: &[u32],
+) -> Option<(u16, Vec<u8>)> {
+    if metric_count == 0 {
+        return None;
+    }
+    let metric_count = metric_count as usize;
+
+    // Glyphs past the last long metric share its advance.
+    let mut new_metrics = Vec::with_capacity(old_glyph_ids.len());
+    for &glyph_id in old_glyph_ids {
+        let glyph_id = glyph_id as usize;
+        let advance = read_u16(metrics, glyph_id.min(metric_count - 1) * 4)?;
+        let side_bearing = if glyph_id < metric_count {
+            read_u16(metrics, glyph_id * 4 + 2)?
+        } else {
+            read_u16(metrics, metric_count * 4 + (glyph_id - metric_count) * 2)?
+        };
+        new_metrics.push((advance, side_bearing));
+    }
+
+    // Compress a run of equal advances at the end back into side bearings only.
+    let mut new_metric_count = new_metrics.len();
+    while new_metric_count > 1
+        && new_metrics[new_metric_count - 2].0 == new_metrics[new_metric_count - 1].0
+    {
+        new_metric_count -= 1;
+    }
+
+    let mut table =
+        Vec::with_capacity(new_metric_count * 4 + (new_metrics.len() - new_metric_count) * 2);
+    for (index, &(advance, side_bearing)) in new_metrics.iter().enumerate() {
+        if index < new_metric_count {
+            table.extend_from_slice(&advance.to_be_bytes());
+        }
+        table.extend_from_slice(&side_bearing.to_be_bytes());
+    }
+    Some((new_metric_count as u16, table))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..)?.read_u16::<BigEndian>().ok()
+}
+
+fn write_u16(data: &mut [u8], offset: usize, value: u16) -> Option<()> {
+    data.get_mut(offset..(offset + 2))?
+        .copy_from_slice(&value.to_be_bytes());
+    Some(())
+}
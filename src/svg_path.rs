@@ -0,0 +1,118 @@
+// font-kit/src/svg_path.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversion of glyph outlines to the path data of SVG `path` elements.
+
+use lyon_path::builder::{Build, FlatPathBuilder, PathBuilder};
+use lyon_path::geom;
+use lyon_path::math::{Angle, Point, Vector};
+use std::fmt::Write;
+
+/// A path builder that writes the path it receives as the `d` attribute of an SVG `path`
+/// element, such as `M0 0L10 0L10 10Z`.
+///
+/// Points are written as they arrive, so flipping outlines from font units (with y pointing up)
+/// to SVG conventions (with y pointing down) is up to the caller; `Loader::glyph_svg_path()` does
+/// this.
+#[derive(Clone, Debug, Default)]
+pub struct SvgPathBuilder {
+    path: String,
+    current_position: Point,
+}
+
+impl SvgPathBuilder {
+    /// Creates a builder with an empty path.
+    #[inline]
+    pub fn new() -> SvgPathBuilder {
+        SvgPathBuilder::default()
+    }
+
+    /// Returns the path data written so far.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the path data, consuming the builder.
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.path
+    }
+
+    fn write_command(&mut self, command: char, points: &[Point]) {
+        self.path.push(command);
+        for (index, point) in points.iter().enumerate() {
+            if index > 0 {
+                self.path.push(' ');
+            }
+            // Adding zero turns negative zero, which would be written as `-0`, into zero.
+            write!(self.path, "{} {}", point.x + 0.0, point.y + 0.0).unwrap();
+        }
+    }
+}
+
+impl FlatPathBuilder for SvgPathBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.write_command('M', &[to]);
+        self.current_position = to;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.write_command('L', &[to]);
+        self.current_position = to;
+    }
+
+    fn close(&mut self) {
+        self.path.push('Z');
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
+}
+
+impl PathBuilder for SvgPathBuilder {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.write_command('Q', &[ctrl, to]);
+        self.current_position = to;
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.write_command('C', &[ctrl1, ctrl2, to]);
+        self.current_position = to;
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        // Glyph outlines don't contain arcs, so approximating them with curves is simpler than
+        // working out the endpoint parameterization of SVG's `A` command.
+        let start_angle = (self.current_position - center).angle_from_x_axis() - x_rotation;
+        let arc = geom::Arc {
+            start_angle,
+            center,
+            radii,
+            sweep_angle,
+            x_rotation,
+        };
+        arc.for_each_quadratic_bezier(&mut |curve| self.quadratic_bezier_to(curve.ctrl, curve.to));
+    }
+}
+
+impl Build for SvgPathBuilder {
+    type PathType = String;
+
+    fn build(self) -> String {
+        self.path
+    }
+
+    fn build_and_reset(&mut self) -> String {
+        self.current_position = Point::zero();
+        self.path.split_off(0)
+    }
+}
@@ -0,0 +1,97 @@
+// font-kit/src/synthesis.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Synthetic bold and oblique faces, for families that lack real ones.
+
+use euclid::default::Transform2D;
+
+use crate::loader::FontTransform;
+use crate::properties::{Properties, Style, Weight};
+
+/// The slant of synthetic obliques, in degrees, which is also the slant that CSS uses for
+/// `font-style: oblique` when it doesn't give an angle.
+const OBLIQUE_ANGLE: f32 = 14.0;
+
+/// How much synthetic emboldening widens strokes, as a fraction of the em. This is what
+/// FreeType's `FT_GlyphSlot_Embolden()` uses.
+const EMBOLDEN_STRENGTH: f32 = 1.0 / 24.0;
+
+/// How to imitate a bold or an oblique face with a font that isn't one, as browsers do when a
+/// family lacks the face that was asked for.
+///
+/// See `Loader::synthesis()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Synthesis {
+    /// How far to slant the glyphs to the right: the horizontal distance that a point moves per
+    /// unit of height above the baseline. Zero if no oblique is needed.
+    pub skew: f32,
+    /// How much to widen the strokes of the glyphs, as a fraction of the em. Zero if no bold is
+    /// needed.
+    pub embolden: f32,
+}
+
+impl Synthesis {
+    /// Returns the synthesis that makes a font with the properties `actual` look like one with
+    /// the properties `requested`.
+    ///
+    /// As in browsers, a bold is synthesized when the requested weight is bold (600 or more) and
+    /// the font's isn't, and an oblique when an italic or oblique style is requested of a font
+    /// with the normal style.
+    pub fn new(actual: &Properties, requested: &Properties) -> Synthesis {
+        let skew = if requested.style != Style::Normal && actual.style == Style::Normal {
+            OBLIQUE_ANGLE.to_radians().tan()
+        } else {
+            0.0
+        };
+        let embolden = if requested.weight >= Weight::SEMIBOLD && actual.weight < Weight::SEMIBOLD {
+            EMBOLDEN_STRENGTH
+        } else {
+            0.0
+        };
+        Synthesis { skew, embolden }
+    }
+
+    /// Returns true if nothing needs to be synthesized.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.skew == 0.0 && self.embolden == 0.0
+    }
+
+    /// Returns `transform` with the slant of the synthetic oblique applied first, for
+    /// rasterization.
+    pub fn apply_to_font_transform(&self, transform: &FontTransform) -> FontTransform {
+        // Font transforms map pixels with y pointing down, so slanting to the right moves points
+        // left as y grows.
+        FontTransform::new(
+            transform.scale_x,
+            transform.skew_x - self.skew * transform.scale_x,
+            transform.skew_y,
+            transform.scale_y - self.skew * transform.skew_y,
+        )
+    }
+
+    /// Returns the slant of the synthetic oblique as a transform of outlines in font units, for
+    /// `Loader::outline_transformed()`.
+    ///
+    /// Outlines can't be emboldened, so this doesn't account for `embolden`.
+    #[inline]
+    pub fn outline_transform(&self) -> Transform2D<f32> {
+        Transform2D::row_major(1.0, 0.0, self.skew, 1.0, 0.0, 0.0)
+    }
+
+    /// Returns how many pixels wider a glyph becomes when emboldened at the given size.
+    ///
+    /// Synthetic bold glyphs grow to the right, so this many pixels need to be added to the right
+    /// of their raster bounds.
+    #[inline]
+    pub fn embolden_offset(&self, point_size: f32) -> f32 {
+        self.embolden * point_size
+    }
+}
@@ -0,0 +1,93 @@
+// font-kit/src/tables/base.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The baseline table, `BASE`.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/base
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"BASE");
+
+const DEFAULT_SCRIPT_TAG: u32 = tables::tag(b"DFLT");
+
+/// Returns the coordinate, in font units, of the baseline with the given tag for the given script
+/// in horizontal text.
+///
+/// If the table doesn't list the script, the `DFLT` script is used instead. The device tables and
+/// variation data that adjust coordinates are ignored.
+pub(crate) fn baseline(data: &[u8], baseline_tag: u32, script_tag: u32) -> Option<i16> {
+    let mut reader = data;
+    let major_version = reader.read_u16::<BigEndian>().ok()?;
+    let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+    if major_version != 1 {
+        return None;
+    }
+    let horiz_axis_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    if horiz_axis_offset == 0 {
+        return None;
+    }
+
+    let axis = data.get(horiz_axis_offset..)?;
+    let mut reader = axis;
+    let base_tag_list_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let base_script_list_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    if base_tag_list_offset == 0 {
+        return None;
+    }
+
+    // The baseline coordinates of each script are in the order of the tag list.
+    let mut reader = axis.get(base_tag_list_offset..)?;
+    let base_tag_count = reader.read_u16::<BigEndian>().ok()?;
+    let baseline_index = (0..base_tag_count)
+        .position(|_| reader.read_u32::<BigEndian>().ok() == Some(baseline_tag))?;
+
+    let base_script_list = axis.get(base_script_list_offset..)?;
+    let base_script = base_script(base_script_list, script_tag)
+        .or_else(|| base_script(base_script_list, DEFAULT_SCRIPT_TAG))?;
+    let base_values_offset = (&base_script[..]).read_u16::<BigEndian>().ok()? as usize;
+    if base_values_offset == 0 {
+        return None;
+    }
+    let base_values = base_script.get(base_values_offset..)?;
+    let mut reader = base_values;
+    let _default_baseline_index = reader.read_u16::<BigEndian>().ok()?;
+    let base_coord_count = reader.read_u16::<BigEndian>().ok()? as usize;
+    if baseline_index >= base_coord_count {
+        return None;
+    }
+    let base_coord_offset = reader
+        .get((baseline_index * 2)..)?
+        .read_u16::<BigEndian>()
+        .ok()? as usize;
+
+    // All three formats of base coordinates start with the coordinate.
+    let mut reader = base_values.get(base_coord_offset..)?;
+    match reader.read_u16::<BigEndian>().ok()? {
+        1..=3 => reader.read_i16::<BigEndian>().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the `BaseScript` table for the given script, if the list has one.
+fn base_script(base_script_list: &[u8], script_tag: u32) -> Option<&[u8]> {
+    let mut reader = base_script_list;
+    let base_script_count = reader.read_u16::<BigEndian>().ok()?;
+    for _ in 0..base_script_count {
+        let tag = reader.read_u32::<BigEndian>().ok()?;
+        let offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        if tag == script_tag && offset != 0 {
+            return base_script_list.get(offset..);
+        }
+    }
+    None
+}
@@ -0,0 +1,111 @@
+// font-kit/src/tables/cbdt.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `CBDT` table, which holds the color bitmaps located by the `CBLC` table, and the `EBDT`
+//! table, which holds the monochrome and grayscale bitmaps located by the `EBLC` table.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/cbdt
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/ebdt
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+use crate::tables::cblc::{GlyphLocation, GlyphMetrics};
+use crate::utils;
+
+pub(crate) const TAG: u32 = tables::tag(b"CBDT");
+pub(crate) const EBDT_TAG: u32 = tables::tag(b"EBDT");
+
+/// The PNG image of a color bitmap glyph, along with its metrics.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PngGlyph<'a> {
+    pub(crate) metrics: GlyphMetrics,
+    pub(crate) data: &'a [u8],
+}
+
+/// Reads the PNG glyph at `location`, returning `None` for image formats other than the PNG
+/// formats 17, 18, and 19.
+pub(crate) fn png_glyph<'a>(data: &'a [u8], location: &GlyphLocation) -> Option<PngGlyph<'a>> {
+    let mut reader = data.get(location.offset..(location.offset + location.length))?;
+    let metrics = match location.image_format {
+        17 => GlyphMetrics::read(&mut reader)?,
+        18 => GlyphMetrics::read_big(&mut reader)?,
+        19 => location.metrics?,
+        _ => return None,
+    };
+    let data_len = reader.read_u32::<BigEndian>().ok()? as usize;
+    Some(PngGlyph {
+        metrics,
+        data: reader.get(..data_len)?,
+    })
+}
+
+/// The image of an `EBDT` glyph, unpacked to whole bytes.
+#[derive(Clone, Debug)]
+pub(crate) struct RawGlyph {
+    pub(crate) metrics: GlyphMetrics,
+    /// If `bit_depth` is 1, one bit per pixel with rows padded to a whole byte; otherwise, one
+    /// byte of coverage per pixel.
+    pub(crate) pixels: Vec<u8>,
+}
+
+/// Reads the `EBDT` glyph at `location`, returning `None` for unsupported image formats
+/// (including the composite formats 8 and 9).
+pub(crate) fn raw_glyph(data: &[u8], location: &GlyphLocation, bit_depth: u8) -> Option<RawGlyph> {
+    let mut reader = data.get(location.offset..(location.offset + location.length))?;
+    let (metrics, bit_aligned) = match location.image_format {
+        1 => (GlyphMetrics::read(&mut reader)?, false),
+        2 => (GlyphMetrics::read(&mut reader)?, true),
+        5 => (location.metrics?, true),
+        6 => (GlyphMetrics::read_big(&mut reader)?, false),
+        7 => (GlyphMetrics::read_big(&mut reader)?, true),
+        _ => return None,
+    };
+    let bit_depth = bit_depth as usize;
+    if bit_depth != 1 && bit_depth != 2 && bit_depth != 4 && bit_depth != 8 {
+        return None;
+    }
+
+    let (width, height) = (metrics.width as usize, metrics.height as usize);
+    let src_row_bits = if bit_aligned {
+        width * bit_depth
+    } else {
+        utils::div_round_up(width * bit_depth, 8) * 8
+    };
+    if reader.len() * 8 < src_row_bits * height {
+        return None;
+    }
+    let read_pixel = |x: usize, y: usize| {
+        let bit = y * src_row_bits + x * bit_depth;
+        let shift = 8 - bit_depth - bit % 8;
+        (reader[bit / 8] >> shift) & ((1 << bit_depth) - 1) as u8
+    };
+
+    let pixels = if bit_depth == 1 {
+        let dest_stride = utils::div_round_up(width, 8);
+        let mut pixels = vec![0; dest_stride * height];
+        for y in 0..height {
+            for x in 0..width {
+                pixels[y * dest_stride + x / 8] |= read_pixel(x, y) << (7 - x % 8);
+            }
+        }
+        pixels
+    } else {
+        let max_value = ((1 << bit_depth) - 1) as u32;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push((read_pixel(x, y) as u32 * 255 / max_value) as u8);
+            }
+        }
+        pixels
+    };
+    Some(RawGlyph { metrics, pixels })
+}
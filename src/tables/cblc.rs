@@ -0,0 +1,200 @@
+// font-kit/src/tables/cblc.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `CBLC` table, which locates the color bitmaps stored in the `CBDT` table.
+//!
+//! The `EBLC` table, which does the same for the monochrome and grayscale bitmaps in `EBDT`, has
+//! the same layout.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/cblc
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/eblc
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"CBLC");
+pub(crate) const EBLC_TAG: u32 = tables::tag(b"EBLC");
+
+const BITMAP_SIZE_RECORD_SIZE: usize = 48;
+const INDEX_SUB_TABLE_RECORD_SIZE: usize = 8;
+
+/// A single strike: a set of bitmaps for one pixels-per-em size.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BitmapSize {
+    index_sub_table_array_offset: usize,
+    number_of_index_sub_tables: usize,
+    pub(crate) ppem_y: u8,
+    pub(crate) bit_depth: u8,
+}
+
+/// The size and placement of a bitmap glyph, in pixels.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct GlyphMetrics {
+    pub(crate) height: u8,
+    pub(crate) width: u8,
+    /// The distance from the glyph origin to the left edge of the bitmap.
+    pub(crate) bearing_x: i8,
+    /// The distance from the glyph origin up to the top edge of the bitmap.
+    pub(crate) bearing_y: i8,
+}
+
+impl GlyphMetrics {
+    /// Reads `smallGlyphMetrics`.
+    pub(crate) fn read(reader: &mut &[u8]) -> Option<GlyphMetrics> {
+        let metrics = GlyphMetrics {
+            height: reader.read_u8().ok()?,
+            width: reader.read_u8().ok()?,
+            bearing_x: reader.read_i8().ok()?,
+            bearing_y: reader.read_i8().ok()?,
+        };
+        let _advance = reader.read_u8().ok()?;
+        Some(metrics)
+    }
+
+    /// Reads `bigGlyphMetrics`, discarding the vertical metrics.
+    pub(crate) fn read_big(reader: &mut &[u8]) -> Option<GlyphMetrics> {
+        let metrics = GlyphMetrics::read(reader)?;
+        *reader = reader.get(3..)?;
+        Some(metrics)
+    }
+}
+
+/// Where a glyph's bitmap lives in the data table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct GlyphLocation {
+    pub(crate) image_format: u16,
+    pub(crate) offset: usize,
+    pub(crate) length: usize,
+    /// The metrics shared by every glyph in the subtable, for formats that store them here
+    /// rather than alongside the image data.
+    pub(crate) metrics: Option<GlyphMetrics>,
+}
+
+/// A view of a `CBLC` or `EBLC` table.
+#[derive(Clone, Debug)]
+pub(crate) struct CblcTable<'a> {
+    data: &'a [u8],
+    pub(crate) sizes: Vec<BitmapSize>,
+}
+
+impl<'a> CblcTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<CblcTable<'a>> {
+        let mut reader = data;
+        let _major_version = reader.read_u16::<BigEndian>().ok()?;
+        let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+        let num_sizes = reader.read_u32::<BigEndian>().ok()? as usize;
+        let mut sizes = vec![];
+        for size_index in 0..num_sizes {
+            let mut reader = data.get((8 + size_index * BITMAP_SIZE_RECORD_SIZE)..)?;
+            let index_sub_table_array_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            let _index_tables_size = reader.read_u32::<BigEndian>().ok()?;
+            let number_of_index_sub_tables = reader.read_u32::<BigEndian>().ok()? as usize;
+            // Skip `colorRef`, the two `sbitLineMetrics`, the glyph range, and `ppemX`.
+            let mut reader = reader.get(33..)?;
+            sizes.push(BitmapSize {
+                index_sub_table_array_offset,
+                number_of_index_sub_tables,
+                ppem_y: reader.read_u8().ok()?,
+                bit_depth: reader.read_u8().ok()?,
+            });
+        }
+        Some(CblcTable { data, sizes })
+    }
+
+    /// Finds the bitmap of the given glyph in the given strike, if there is one.
+    pub(crate) fn locate(&self, size: &BitmapSize, glyph_id: u16) -> Option<GlyphLocation> {
+        for sub_table_index in 0..size.number_of_index_sub_tables {
+            let mut reader = self.data.get(
+                (size.index_sub_table_array_offset
+                    + sub_table_index * INDEX_SUB_TABLE_RECORD_SIZE)..,
+            )?;
+            let first_glyph_index = reader.read_u16::<BigEndian>().ok()?;
+            let last_glyph_index = reader.read_u16::<BigEndian>().ok()?;
+            let additional_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            if glyph_id < first_glyph_index || glyph_id > last_glyph_index {
+                continue;
+            }
+            let sub_table = self
+                .data
+                .get((size.index_sub_table_array_offset + additional_offset)..)?;
+            return locate_in_sub_table(sub_table, glyph_id, first_glyph_index);
+        }
+        None
+    }
+}
+
+fn locate_in_sub_table(
+    mut reader: &[u8],
+    glyph_id: u16,
+    first_glyph_index: u16,
+) -> Option<GlyphLocation> {
+    // Formats 1 through 3 are indexed by position in the range; formats 4 and 5 list glyph IDs.
+    let index = (glyph_id - first_glyph_index) as usize;
+    let index_format = reader.read_u16::<BigEndian>().ok()?;
+    let image_format = reader.read_u16::<BigEndian>().ok()?;
+    let image_data_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+    let (offset, length, metrics) = match index_format {
+        1 | 3 => {
+            let read_offset = |index: usize| -> Option<usize> {
+                if index_format == 1 {
+                    let mut reader = reader.get((index * 4)..)?;
+                    Some(reader.read_u32::<BigEndian>().ok()? as usize)
+                } else {
+                    let mut reader = reader.get((index * 2)..)?;
+                    Some(reader.read_u16::<BigEndian>().ok()? as usize)
+                }
+            };
+            let (start, end) = (read_offset(index)?, read_offset(index + 1)?);
+            (start, end.checked_sub(start)?, None)
+        }
+        2 => {
+            let image_size = reader.read_u32::<BigEndian>().ok()? as usize;
+            let metrics = GlyphMetrics::read_big(&mut reader)?;
+            (index * image_size, image_size, Some(metrics))
+        }
+        4 => {
+            let num_glyphs = reader.read_u32::<BigEndian>().ok()? as usize;
+            let mut location = None;
+            for pair_index in 0..num_glyphs {
+                let mut pair_reader = reader.get((pair_index * 4)..)?;
+                if pair_reader.read_u16::<BigEndian>().ok()? != glyph_id {
+                    continue;
+                }
+                let start = pair_reader.read_u16::<BigEndian>().ok()? as usize;
+                let _next_glyph_id = pair_reader.read_u16::<BigEndian>().ok()?;
+                let end = pair_reader.read_u16::<BigEndian>().ok()? as usize;
+                location = Some((start, end.checked_sub(start)?, None));
+                break;
+            }
+            location?
+        }
+        5 => {
+            let image_size = reader.read_u32::<BigEndian>().ok()? as usize;
+            let metrics = GlyphMetrics::read_big(&mut reader)?;
+            let num_glyphs = reader.read_u32::<BigEndian>().ok()? as usize;
+            let mut position = None;
+            for glyph_index in 0..num_glyphs {
+                if reader.read_u16::<BigEndian>().ok()? == glyph_id {
+                    position = Some(glyph_index);
+                    break;
+                }
+            }
+            (position? * image_size, image_size, Some(metrics))
+        }
+        _ => return None,
+    };
+    Some(GlyphLocation {
+        image_format,
+        offset: image_data_offset + offset,
+        length,
+        metrics,
+    })
+}
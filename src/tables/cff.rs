@@ -0,0 +1,540 @@
+// font-kit/src/tables/cff.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Compact Font Format table, `CFF `, which holds PostScript outlines.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/cff
+//! https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"CFF ");
+pub(crate) const CFF2_TAG: u32 = tables::tag(b"CFF2");
+
+const OP_CHARSET: u16 = 15;
+const OP_ENCODING: u16 = 16;
+const OP_CHAR_STRINGS: u16 = 17;
+const OP_PRIVATE: u16 = 18;
+const OP_SUBRS: u16 = 19;
+const OP_CHARSTRING_TYPE: u16 = 0x0c06;
+const OP_ROS: u16 = 0x0c1e;
+const OP_FD_ARRAY: u16 = 0x0c24;
+const OP_FD_SELECT: u16 = 0x0c25;
+
+const ISO_ADOBE_CHARSET_OFFSET: usize = 0;
+
+const CHARSTRING_OP_HSTEM: u8 = 1;
+const CHARSTRING_OP_VSTEM: u8 = 3;
+const CHARSTRING_OP_HSTEMHM: u8 = 18;
+const CHARSTRING_OP_HINTMASK: u8 = 19;
+const CHARSTRING_OP_CNTRMASK: u8 = 20;
+const CHARSTRING_OP_VSTEMHM: u8 = 23;
+
+/// Rebuilds a `CFF ` table so that it contains only the given glyphs, which must be sorted,
+/// unique, and start with glyph 0. Glyph `i` of the result is glyph `old_glyph_ids[i]` of the
+/// original.
+///
+/// Global and local subroutines are kept whole, since charstrings call them by index, and so is
+/// every font DICT of a CID-keyed font. The custom encoding of a name-keyed font, if any, is
+/// dropped. Returns `None` if the table is malformed or uses a predefined expert charset.
+pub(crate) fn subset(data: &[u8], old_glyph_ids: &[u32]) -> Option<Vec<u8>> {
+    if *data.first()? != 1 {
+        return None;
+    }
+    let header_size = *data.get(2)? as usize;
+    let (_, name_index_end) = parse_index(data, header_size)?;
+    let (top_dicts, top_dict_index_end) = parse_index(data, name_index_end)?;
+    let (_, string_index_end) = parse_index(data, top_dict_index_end)?;
+    let (_, global_subr_index_end) = parse_index(data, string_index_end)?;
+    let top_dict = parse_dict(top_dicts.first()?)?;
+
+    if let Some(&[charstring_type]) = operands(&top_dict, OP_CHARSTRING_TYPE) {
+        if charstring_type != 2 {
+            return None;
+        }
+    }
+    let (char_strings, _) = parse_index(data, offset_operand(&top_dict, OP_CHAR_STRINGS)?)?;
+    if old_glyph_ids
+        .iter()
+        .any(|&glyph_id| glyph_id as usize >= char_strings.len())
+    {
+        return None;
+    }
+    let charset_offset = offset_operand(&top_dict, OP_CHARSET).unwrap_or(ISO_ADOBE_CHARSET_OFFSET);
+    let charset = parse_charset(data, charset_offset, char_strings.len())?;
+
+    // Gather the private data: one block for a name-keyed font, or one per font DICT in the
+    // `FDArray` of a CID-keyed font.
+    let is_cid_keyed = operands(&top_dict, OP_ROS).is_some();
+    let (font_dicts, fd_select) = if is_cid_keyed {
+        let (font_dicts, _) = parse_index(data, offset_operand(&top_dict, OP_FD_ARRAY)?)?;
+        let font_dicts = font_dicts
+            .iter()
+            .map(|font_dict| parse_dict(font_dict))
+            .collect::<Option<Vec<_>>>()?;
+        let fd_select = parse_fd_select(
+            data,
+            offset_operand(&top_dict, OP_FD_SELECT)?,
+            char_strings.len(),
+        )?;
+        (font_dicts, Some(fd_select))
+    } else {
+        (vec![], None)
+    };
+    let private_blocks = if is_cid_keyed {
+        font_dicts
+            .iter()
+            .map(|font_dict| write_private(data, font_dict))
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        vec![write_private(data, &top_dict)?]
+    };
+
+    // Build the new glyph-indexed structures.
+    let mut new_charset = vec![0];
+    for &glyph_id in &old_glyph_ids[1..] {
+        new_charset.extend_from_slice(&charset[glyph_id as usize].to_be_bytes());
+    }
+    let new_fd_select = fd_select.map(|fd_select| {
+        let mut new_fd_select = vec![0];
+        new_fd_select.extend(
+            old_glyph_ids
+                .iter()
+                .map(|&glyph_id| fd_select[glyph_id as usize]),
+        );
+        new_fd_select
+    });
+    let new_char_strings = write_index(
+        &old_glyph_ids
+            .iter()
+            .map(|&glyph_id| char_strings[glyph_id as usize])
+            .collect::<Vec<_>>(),
+    );
+
+    // Every offset is written as a five-byte integer, so the DICTs have the same size no matter
+    // where things end up. That lets us measure them with placeholder offsets first.
+    let layout = |offsets: &Offsets| {
+        let top_dict = write_top_dict(&top_dict, offsets, is_cid_keyed);
+        let font_dicts = if is_cid_keyed {
+            let font_dicts: Vec<Vec<u8>> = font_dicts
+                .iter()
+                .zip(offsets.private_blocks.iter())
+                .map(|(font_dict, &(size, offset))| write_font_dict(font_dict, size, offset))
+                .collect();
+            Some(write_index(
+                &font_dicts.iter().map(|dict| &dict[..]).collect::<Vec<_>>(),
+            ))
+        } else {
+            None
+        };
+        (write_index(&[&top_dict]), font_dicts)
+    };
+
+    let placeholder_offsets = Offsets {
+        charset: 0,
+        fd_select: 0,
+        char_strings: 0,
+        fd_array: 0,
+        private_blocks: vec![(0, 0); private_blocks.len()],
+    };
+    let (top_dict_index, fd_array) = layout(&placeholder_offsets);
+
+    let mut offset = 4 + (name_index_end - header_size) + top_dict_index.len();
+    offset += global_subr_index_end - top_dict_index_end;
+    let mut offsets = Offsets {
+        charset: offset,
+        ..placeholder_offsets
+    };
+    offset += new_charset.len();
+    offsets.fd_select = offset;
+    offset += new_fd_select
+        .as_ref()
+        .map_or(0, |fd_select| fd_select.len());
+    offsets.char_strings = offset;
+    offset += new_char_strings.len();
+    offsets.fd_array = offset;
+    offset += fd_array.as_ref().map_or(0, |fd_array| fd_array.len());
+    for (private_offset, private_block) in
+        offsets.private_blocks.iter_mut().zip(private_blocks.iter())
+    {
+        *private_offset = (private_block.dict_size, offset);
+        offset += private_block.data.len();
+    }
+
+    let (top_dict_index, fd_array) = layout(&offsets);
+    let mut cff = Vec::with_capacity(offset);
+    cff.extend_from_slice(&[1, 0, 4, 4]);
+    cff.extend_from_slice(&data[header_size..name_index_end]);
+    cff.extend_from_slice(&top_dict_index);
+    cff.extend_from_slice(&data[top_dict_index_end..global_subr_index_end]);
+    cff.extend_from_slice(&new_charset);
+    cff.extend(new_fd_select.into_iter().flatten());
+    cff.extend_from_slice(&new_char_strings);
+    cff.extend(fd_array.into_iter().flatten());
+    for private_block in &private_blocks {
+        cff.extend_from_slice(&private_block.data);
+    }
+    debug_assert_eq!(cff.len(), offset);
+    Some(cff)
+}
+
+/// Returns true if any glyph of a `CFF ` table declares stem hints.
+///
+/// Hints have to come before the first drawing operator of a charstring, so only the start of
+/// each one is looked at. Hints that a charstring only reaches through a subroutine call aren't
+/// seen.
+pub(crate) fn has_stem_hints(data: &[u8]) -> bool {
+    char_strings(data).is_some_and(|char_strings| {
+        char_strings
+            .iter()
+            .any(|char_string| starts_with_stem_hints(char_string))
+    })
+}
+
+fn char_strings(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let header_size = *data.get(2)? as usize;
+    let (_, name_index_end) = parse_index(data, header_size)?;
+    let (top_dicts, _) = parse_index(data, name_index_end)?;
+    let top_dict = parse_dict(top_dicts.first()?)?;
+    let (char_strings, _) = parse_index(data, offset_operand(&top_dict, OP_CHAR_STRINGS)?)?;
+    Some(char_strings)
+}
+
+fn starts_with_stem_hints(char_string: &[u8]) -> bool {
+    let mut position = 0;
+    while let Some(&b0) = char_string.get(position) {
+        match b0 {
+            CHARSTRING_OP_HSTEM
+            | CHARSTRING_OP_VSTEM
+            | CHARSTRING_OP_HSTEMHM
+            | CHARSTRING_OP_VSTEMHM
+            | CHARSTRING_OP_HINTMASK
+            | CHARSTRING_OP_CNTRMASK => return true,
+            28 => position += 3,
+            32..=246 => position += 1,
+            247..=254 => position += 2,
+            255 => position += 5,
+            // Any other operator ends the hints.
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Where the rewritten structures of a subset table are placed.
+#[derive(Clone)]
+struct Offsets {
+    charset: usize,
+    fd_select: usize,
+    char_strings: usize,
+    fd_array: usize,
+    /// The size of each Private DICT, and the offset of the block that it starts.
+    private_blocks: Vec<(usize, usize)>,
+}
+
+/// A Private DICT, followed by its local subroutines if it has any.
+struct PrivateBlock {
+    dict_size: usize,
+    data: Vec<u8>,
+}
+
+fn write_top_dict(top_dict: &[DictEntry], offsets: &Offsets, is_cid_keyed: bool) -> Vec<u8> {
+    let mut dict = vec![];
+    for entry in top_dict {
+        match entry.operator {
+            OP_CHARSET | OP_ENCODING | OP_CHAR_STRINGS | OP_PRIVATE | OP_FD_ARRAY
+            | OP_FD_SELECT => {}
+            _ => dict.extend_from_slice(entry.raw),
+        }
+    }
+    write_dict_entry(&mut dict, OP_CHARSET, &[offsets.charset]);
+    write_dict_entry(&mut dict, OP_CHAR_STRINGS, &[offsets.char_strings]);
+    if is_cid_keyed {
+        write_dict_entry(&mut dict, OP_FD_ARRAY, &[offsets.fd_array]);
+        write_dict_entry(&mut dict, OP_FD_SELECT, &[offsets.fd_select]);
+    } else {
+        let (size, offset) = offsets.private_blocks[0];
+        write_dict_entry(&mut dict, OP_PRIVATE, &[size, offset]);
+    }
+    dict
+}
+
+fn write_font_dict(font_dict: &[DictEntry], private_size: usize, private_offset: usize) -> Vec<u8> {
+    let mut dict = vec![];
+    for entry in font_dict {
+        if entry.operator != OP_PRIVATE {
+            dict.extend_from_slice(entry.raw);
+        }
+    }
+    write_dict_entry(&mut dict, OP_PRIVATE, &[private_size, private_offset]);
+    dict
+}
+
+/// Copies the Private DICT that the given Top or Font DICT points to, along with its local
+/// subroutines, rewriting the offset of the subroutines to follow the DICT directly.
+fn write_private(data: &[u8], dict: &[DictEntry]) -> Option<PrivateBlock> {
+    let (size, offset) = match *operands(dict, OP_PRIVATE)? {
+        [size, offset] if size >= 0 && offset >= 0 => (size as usize, offset as usize),
+        _ => return None,
+    };
+    let private_dict = parse_dict(data.get(offset..(offset.checked_add(size)?))?)?;
+
+    let mut block = vec![];
+    for entry in &private_dict {
+        if entry.operator != OP_SUBRS {
+            block.extend_from_slice(entry.raw);
+        }
+    }
+    let subrs = match operands(&private_dict, OP_SUBRS) {
+        Some(&[subrs_offset]) if subrs_offset >= 0 => {
+            let subrs_offset = offset.checked_add(subrs_offset as usize)?;
+            let (_, subrs_end) = parse_index(data, subrs_offset)?;
+            Some(&data[subrs_offset..subrs_end])
+        }
+        _ => None,
+    };
+
+    let dict_size = match subrs {
+        None => block.len(),
+        Some(subrs) => {
+            let dict_size = block.len() + DICT_OFFSET_ENTRY_SIZE;
+            write_dict_entry(&mut block, OP_SUBRS, &[dict_size]);
+            block.extend_from_slice(subrs);
+            dict_size
+        }
+    };
+    Some(PrivateBlock {
+        dict_size,
+        data: block,
+    })
+}
+
+/// Returns the SID (or, for CID-keyed fonts, the CID) of each glyph.
+fn parse_charset(data: &[u8], offset: usize, glyph_count: usize) -> Option<Vec<u16>> {
+    if offset == ISO_ADOBE_CHARSET_OFFSET {
+        return Some((0..glyph_count).map(|glyph_id| glyph_id as u16).collect());
+    }
+    // The other predefined charsets, Expert and Expert Subset, aren't supported.
+    if offset <= 2 {
+        return None;
+    }
+
+    let mut charset = Vec::with_capacity(glyph_count);
+    charset.push(0);
+    let mut reader = data.get(offset..)?;
+    let format = reader.read_u8().ok()?;
+    while charset.len() < glyph_count {
+        match format {
+            0 => charset.push(reader.read_u16::<BigEndian>().ok()?),
+            1 | 2 => {
+                let first = reader.read_u16::<BigEndian>().ok()?;
+                let left = if format == 1 {
+                    reader.read_u8().ok()? as u16
+                } else {
+                    reader.read_u16::<BigEndian>().ok()?
+                };
+                for sid in first..=first.checked_add(left)? {
+                    charset.push(sid);
+                }
+            }
+            _ => return None,
+        }
+    }
+    charset.truncate(glyph_count);
+    Some(charset)
+}
+
+/// Returns the index of the font DICT that each glyph uses.
+fn parse_fd_select(data: &[u8], offset: usize, glyph_count: usize) -> Option<Vec<u8>> {
+    let mut reader = data.get(offset..)?;
+    match reader.read_u8().ok()? {
+        0 => Some(reader.get(..glyph_count)?.to_vec()),
+        3 => {
+            let range_count = reader.read_u16::<BigEndian>().ok()?;
+            let mut fd_select = Vec::with_capacity(glyph_count);
+            let mut first = reader.read_u16::<BigEndian>().ok()?;
+            if first != 0 {
+                return None;
+            }
+            for _ in 0..range_count {
+                let fd_index = reader.read_u8().ok()?;
+                let next = reader.read_u16::<BigEndian>().ok()?;
+                if next < first {
+                    return None;
+                }
+                fd_select.resize(next as usize, fd_index);
+                first = next;
+            }
+            if fd_select.len() < glyph_count {
+                return None;
+            }
+            fd_select.truncate(glyph_count);
+            Some(fd_select)
+        }
+        _ => None,
+    }
+}
+
+/// Parses the INDEX at the given offset, returning its objects and the offset just past it.
+fn parse_index(data: &[u8], offset: usize) -> Option<(Vec<&[u8]>, usize)> {
+    let mut reader = data.get(offset..)?;
+    let count = reader.read_u16::<BigEndian>().ok()? as usize;
+    if count == 0 {
+        return Some((vec![], offset + 2));
+    }
+    let offset_size = reader.read_u8().ok()? as usize;
+    if offset_size == 0 || offset_size > 4 {
+        return None;
+    }
+
+    let mut offsets = Vec::with_capacity(count + 1);
+    for _ in 0..=count {
+        offsets.push(reader.read_uint::<BigEndian>(offset_size).ok()? as usize);
+    }
+    // Object offsets count from 1, relative to the byte before the object data.
+    let data_start = offset + 3 + (count + 1) * offset_size - 1;
+    let mut objects = Vec::with_capacity(count);
+    for window in offsets.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        if start == 0 || end < start {
+            return None;
+        }
+        objects.push(data.get((data_start + start)..(data_start + end))?);
+    }
+    Some((objects, data_start + offsets[count]))
+}
+
+fn write_index(objects: &[&[u8]]) -> Vec<u8> {
+    let mut index = vec![];
+    index.extend_from_slice(&(objects.len() as u16).to_be_bytes());
+    if objects.is_empty() {
+        return index;
+    }
+
+    let last_offset = 1 + objects.iter().map(|object| object.len()).sum::<usize>();
+    let offset_size = match last_offset {
+        0..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xff_ffff => 3,
+        _ => 4,
+    };
+    index.push(offset_size as u8);
+    let mut offset = 1;
+    for object in objects.iter().map(|object| object.len()).chain(Some(0)) {
+        index.extend_from_slice(&(offset as u32).to_be_bytes()[(4 - offset_size)..]);
+        offset += object;
+    }
+    for object in objects {
+        index.extend_from_slice(object);
+    }
+    index
+}
+
+/// An operator of a DICT, along with its integer operands and its raw encoding.
+struct DictEntry<'a> {
+    operator: u16,
+    /// The integer operands. Real operands, which we never need the values of, read as 0.
+    operands: Vec<i32>,
+    raw: &'a [u8],
+}
+
+fn parse_dict(data: &[u8]) -> Option<Vec<DictEntry<'_>>> {
+    let mut entries = vec![];
+    let (mut entry_start, mut position) = (0, 0);
+    let mut operands = vec![];
+    while position < data.len() {
+        let b0 = data[position];
+        let byte = |index: usize| data.get(position + index).map(|&byte| byte as i32);
+        match b0 {
+            0..=21 => {
+                let operator = if b0 == 12 {
+                    let operator = 0x0c00 | byte(1)? as u16;
+                    position += 2;
+                    operator
+                } else {
+                    position += 1;
+                    b0 as u16
+                };
+                entries.push(DictEntry {
+                    operator,
+                    operands: operands.split_off(0),
+                    raw: &data[entry_start..position],
+                });
+                entry_start = position;
+            }
+            28 => {
+                operands.push((((byte(1)? << 8) | byte(2)?) as i16) as i32);
+                position += 3;
+            }
+            29 => {
+                operands.push((byte(1)? << 24) | (byte(2)? << 16) | (byte(3)? << 8) | byte(4)?);
+                position += 5;
+            }
+            30 => {
+                // Real numbers are packed into nibbles, ending with the nibble 0xf.
+                position += 1;
+                loop {
+                    let nibbles = *data.get(position)?;
+                    position += 1;
+                    if nibbles & 0x0f == 0x0f || nibbles >> 4 == 0x0f {
+                        break;
+                    }
+                }
+                operands.push(0);
+            }
+            32..=246 => {
+                operands.push(b0 as i32 - 139);
+                position += 1;
+            }
+            247..=250 => {
+                operands.push((b0 as i32 - 247) * 256 + byte(1)? + 108);
+                position += 2;
+            }
+            251..=254 => {
+                operands.push(-(b0 as i32 - 251) * 256 - byte(1)? - 108);
+                position += 2;
+            }
+            _ => return None,
+        }
+    }
+    Some(entries)
+}
+
+fn operands<'a>(dict: &'a [DictEntry], operator: u16) -> Option<&'a [i32]> {
+    dict.iter()
+        .find(|entry| entry.operator == operator)
+        .map(|entry| &entry.operands[..])
+}
+
+fn offset_operand(dict: &[DictEntry], operator: u16) -> Option<usize> {
+    match *operands(dict, operator)? {
+        [offset] if offset >= 0 => Some(offset as usize),
+        _ => None,
+    }
+}
+
+/// The size of a DICT entry with one five-byte integer operand and a one-byte operator.
+const DICT_OFFSET_ENTRY_SIZE: usize = 6;
+
+/// Writes a DICT entry whose operands are all five-byte integers, so that its size doesn't
+/// depend on their values.
+fn write_dict_entry(dict: &mut Vec<u8>, operator: u16, operands: &[usize]) {
+    for &operand in operands {
+        dict.push(29);
+        dict.extend_from_slice(&(operand as i32).to_be_bytes());
+    }
+    if operator > 0xff {
+        dict.extend_from_slice(&operator.to_be_bytes());
+    } else {
+        dict.push(operator as u8);
+    }
+}
@@ -0,0 +1,543 @@
+// font-kit/src/tables/cmap.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The character to glyph index mapping table, `cmap`.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/cmap
+
+use byteorder::{BigEndian, ReadBytesExt};
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"cmap");
+
+pub(crate) const PLATFORM_UNICODE: u16 = 0;
+pub(crate) const PLATFORM_WINDOWS: u16 = 3;
+
+const UNICODE_ENCODING_BMP: u16 = 3;
+pub(crate) const UNICODE_ENCODING_FULL: u16 = 4;
+pub(crate) const UNICODE_ENCODING_VARIATION_SEQUENCES: u16 = 5;
+
+pub(crate) const WINDOWS_ENCODING_UNICODE_BMP: u16 = 1;
+pub(crate) const WINDOWS_ENCODING_UNICODE_FULL: u16 = 10;
+
+// Only the FreeType loader picks charmaps itself, so the IDs that only it needs are left out of
+// builds without it.
+#[cfg(any(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
+    feature = "loader-freetype"
+))]
+pub(crate) const PLATFORM_MACINTOSH: u16 = 1;
+#[cfg(any(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
+    feature = "loader-freetype"
+))]
+pub(crate) const UNICODE_ENCODING_FULL_REPERTOIRE: u16 = 6;
+#[cfg(any(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
+    feature = "loader-freetype"
+))]
+pub(crate) const MACINTOSH_ENCODING_ROMAN: u16 = 0;
+#[cfg(any(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
+    feature = "loader-freetype"
+))]
+pub(crate) const WINDOWS_ENCODING_SYMBOL: u16 = 0;
+
+/// The start of the private use area that symbol fonts, which use the Windows symbol encoding,
+/// map the codes of their own 8-bit encoding into.
+#[cfg(any(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
+    feature = "loader-freetype"
+))]
+pub(crate) const SYMBOL_CODE_OFFSET: u32 = 0xf000;
+
+const MAX_CODEPOINT: u32 = 0x10ffff;
+
+/// Returns the ranges of codepoints that the best Unicode subtable maps to nonzero glyphs.
+///
+/// A format 12 subtable, which can cover all of Unicode, is preferred over a format 4 subtable,
+/// which only covers the Basic Multilingual Plane. The ranges are in subtable order and may be
+/// adjacent.
+pub(crate) fn codepoint_ranges(data: &[u8]) -> Option<Vec<RangeInclusive<u32>>> {
+    let mut ranges = vec![];
+    match parse_best_unicode_subtable(data)? {
+        Subtable::Format4(segments) => {
+            for segment in segments {
+                match segment.glyph_ids {
+                    None => {
+                        // Glyphs are `code + id_delta` modulo 65536, so at most one code in the
+                        // segment maps to glyph 0.
+                        let missing_code = 0u16.wrapping_sub(segment.id_delta);
+                        push_range_excluding(
+                            &mut ranges,
+                            segment.start_code,
+                            segment.end_code,
+                            missing_code,
+                        );
+                    }
+                    Some(_) => {
+                        let mut run_start = None;
+                        for code in segment.start_code..=segment.end_code {
+                            match (segment.glyph_id(code) != 0, run_start) {
+                                (true, None) => run_start = Some(code),
+                                (false, Some(start)) => {
+                                    ranges.push(start as u32..=(code - 1) as u32);
+                                    run_start = None;
+                                }
+                                _ => {}
+                            }
+                        }
+                        if let Some(start) = run_start {
+                            ranges.push(start as u32..=segment.end_code as u32);
+                        }
+                    }
+                }
+            }
+        }
+        Subtable::Format12(groups) => {
+            for group in groups {
+                // Glyph IDs increase through the group, so only its first code can map to glyph 0.
+                let start_char_code = if group.start_glyph_id == 0 {
+                    match group.start_char_code.checked_add(1) {
+                        Some(start_char_code) if start_char_code <= group.end_char_code => {
+                            start_char_code
+                        }
+                        _ => continue,
+                    }
+                } else {
+                    group.start_char_code
+                };
+                ranges.push(start_char_code..=group.end_char_code);
+            }
+        }
+    }
+    Some(ranges)
+}
+
+/// Returns every `(glyph ID, character)` pair of the best Unicode subtable that maps a character
+/// to a nonzero glyph, in subtable order.
+pub(crate) fn glyph_mappings(data: &[u8]) -> Vec<(u32, char)> {
+    let mut entries = vec![];
+    match parse_best_unicode_subtable(data) {
+        Some(Subtable::Format4(segments)) => {
+            for segment in segments {
+                for code in segment.start_code..=segment.end_code {
+                    let glyph_id = segment.glyph_id(code);
+                    if let (Some(character), true) = (char::from_u32(code as u32), glyph_id != 0) {
+                        entries.push((glyph_id as u32, character));
+                    }
+                }
+            }
+        }
+        Some(Subtable::Format12(groups)) => {
+            for group in groups {
+                let end_char_code = group.end_char_code.min(MAX_CODEPOINT);
+                for code in group.start_char_code..=end_char_code {
+                    let glyph_id = group
+                        .start_glyph_id
+                        .wrapping_add(code - group.start_char_code);
+                    if let (Some(character), true) = (char::from_u32(code), glyph_id != 0) {
+                        entries.push((glyph_id, character));
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+    entries
+}
+
+/// Builds a `cmap` table with Unicode subtables for the given `(character, glyph ID)` pairs,
+/// which must be sorted by character and contain no duplicate characters.
+///
+/// A format 4 subtable covers the Basic Multilingual Plane, and a format 12 subtable is added
+/// for all characters if any lie outside it, or if there are too many segments for format 4.
+pub(crate) fn write_unicode_cmap(mappings: &[(char, u16)]) -> Vec<u8> {
+    // Group the mappings into runs of consecutive characters and consecutive glyphs.
+    let mut runs: Vec<(u32, u32, u16)> = vec![];
+    for &(character, glyph_id) in mappings {
+        let code = character as u32;
+        match runs.last_mut() {
+            Some(&mut (start, ref mut end, start_glyph_id))
+                if code == *end + 1
+                    && glyph_id as u32 == start_glyph_id as u32 + (code - start) =>
+            {
+                *end = code
+            }
+            _ => runs.push((code, code, glyph_id)),
+        }
+    }
+
+    let format_4 = write_format_4(&runs);
+    let needs_format_12 = format_4.is_none() || runs.iter().any(|&(_, end, _)| end > 0xffff);
+    let format_12 = if needs_format_12 {
+        Some(write_format_12(&runs))
+    } else {
+        None
+    };
+
+    let mut encoding_records = vec![];
+    if format_4.is_some() {
+        encoding_records.push((PLATFORM_UNICODE, UNICODE_ENCODING_BMP, 4));
+    }
+    if format_12.is_some() {
+        encoding_records.push((PLATFORM_UNICODE, UNICODE_ENCODING_FULL, 12));
+    }
+    if format_4.is_some() {
+        encoding_records.push((PLATFORM_WINDOWS, WINDOWS_ENCODING_UNICODE_BMP, 4));
+    }
+    if format_12.is_some() {
+        encoding_records.push((PLATFORM_WINDOWS, WINDOWS_ENCODING_UNICODE_FULL, 12));
+    }
+
+    let mut cmap = vec![];
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&(encoding_records.len() as u16).to_be_bytes());
+    let format_4_offset = 4 + encoding_records.len() * 8;
+    let format_12_offset = format_4_offset + format_4.as_ref().map_or(0, |subtable| subtable.len());
+    for &(platform_id, encoding_id, format) in &encoding_records {
+        let offset = if format == 4 {
+            format_4_offset
+        } else {
+            format_12_offset
+        };
+        cmap.extend_from_slice(&platform_id.to_be_bytes());
+        cmap.extend_from_slice(&encoding_id.to_be_bytes());
+        cmap.extend_from_slice(&(offset as u32).to_be_bytes());
+    }
+    cmap.extend(format_4.into_iter().flatten());
+    cmap.extend(format_12.into_iter().flatten());
+    cmap
+}
+
+/// Writes a format 4 subtable for the runs in the Basic Multilingual Plane, or returns `None` if
+/// it would be too big.
+fn write_format_4(runs: &[(u32, u32, u16)]) -> Option<Vec<u8>> {
+    let mut segments: Vec<(u16, u16, u16)> = runs
+        .iter()
+        .filter(|&&(start, _, _)| start < 0xffff)
+        .map(|&(start, end, start_glyph_id)| {
+            let id_delta = start_glyph_id.wrapping_sub(start as u16);
+            (start as u16, end.min(0xfffe) as u16, id_delta)
+        })
+        .collect();
+    // The table must end with a segment mapping U+FFFF to glyph 0.
+    segments.push((0xffff, 0xffff, 1));
+
+    let seg_count = segments.len();
+    let length = 16 + seg_count * 8;
+    if length > u16::MAX as usize {
+        return None;
+    }
+    let entry_selector = 15 - (seg_count as u16).leading_zeros();
+    let search_range = 2 << entry_selector;
+
+    let mut subtable = Vec::with_capacity(length);
+    for value in &[
+        4,
+        length as u16,
+        0,
+        (seg_count * 2) as u16,
+        search_range as u16,
+        entry_selector as u16,
+        (seg_count * 2) as u16 - search_range as u16,
+    ] {
+        subtable.extend_from_slice(&value.to_be_bytes());
+    }
+    for &(_, end_code, _) in &segments {
+        subtable.extend_from_slice(&end_code.to_be_bytes());
+    }
+    subtable.extend_from_slice(&0u16.to_be_bytes());
+    for &(start_code, _, _) in &segments {
+        subtable.extend_from_slice(&start_code.to_be_bytes());
+    }
+    for &(_, _, id_delta) in &segments {
+        subtable.extend_from_slice(&id_delta.to_be_bytes());
+    }
+    subtable.resize(length, 0);
+    Some(subtable)
+}
+
+fn write_format_12(runs: &[(u32, u32, u16)]) -> Vec<u8> {
+    let length = 16 + runs.len() * 12;
+    let mut subtable = Vec::with_capacity(length);
+    subtable.extend_from_slice(&12u16.to_be_bytes());
+    subtable.extend_from_slice(&0u16.to_be_bytes());
+    subtable.extend_from_slice(&(length as u32).to_be_bytes());
+    subtable.extend_from_slice(&0u32.to_be_bytes());
+    subtable.extend_from_slice(&(runs.len() as u32).to_be_bytes());
+    for &(start, end, start_glyph_id) in runs {
+        subtable.extend_from_slice(&start.to_be_bytes());
+        subtable.extend_from_slice(&end.to_be_bytes());
+        subtable.extend_from_slice(&(start_glyph_id as u32).to_be_bytes());
+    }
+    subtable
+}
+
+/// Returns the glyph that the format 14 subtable maps the variation sequence of `character`
+/// followed by `variation_selector` to.
+///
+/// Returns `None` if the font has no such subtable, if the sequence isn't in it, or if the
+/// sequence uses the default glyph of `character`, as listed in the subtable's default UVS tables.
+pub(crate) fn variation_glyph(data: &[u8], character: u32, variation_selector: u32) -> Option<u32> {
+    let mut reader = data.get(2..)?;
+    let num_tables = reader.read_u16::<BigEndian>().ok()?;
+    let subtable = (0..num_tables).find_map(|_| {
+        let platform_id = reader.read_u16::<BigEndian>().ok()?;
+        let encoding_id = reader.read_u16::<BigEndian>().ok()?;
+        let offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        if platform_id != PLATFORM_UNICODE || encoding_id != UNICODE_ENCODING_VARIATION_SEQUENCES {
+            return None;
+        }
+        data.get(offset..)
+            .filter(|subtable| (&subtable[..]).read_u16::<BigEndian>().ok() == Some(14))
+    })?;
+
+    // Variation selector records are 11 bytes long, sorted by selector.
+    let mut reader = subtable.get(6..)?;
+    let num_records = reader.read_u32::<BigEndian>().ok()? as usize;
+    let records = reader.get(..num_records.checked_mul(11)?)?;
+    let index = binary_search_u24(records, 11, variation_selector)?;
+    let mut record = &records[(index * 11 + 3)..];
+    let _default_uvs_offset = record.read_u32::<BigEndian>().ok()?;
+    let non_default_uvs_offset = record.read_u32::<BigEndian>().ok()? as usize;
+    if non_default_uvs_offset == 0 {
+        return None;
+    }
+
+    // Non-default UVS mappings are 5 bytes long, sorted by character.
+    let mut reader = subtable.get(non_default_uvs_offset..)?;
+    let num_mappings = reader.read_u32::<BigEndian>().ok()? as usize;
+    let mappings = reader.get(..num_mappings.checked_mul(5)?)?;
+    let index = binary_search_u24(mappings, 5, character)?;
+    (&mappings[(index * 5 + 3)..])
+        .read_u16::<BigEndian>()
+        .ok()
+        .map(|glyph_id| glyph_id as u32)
+}
+
+/// Returns the index of the record that starts with the 24-bit value `key`, among records of
+/// `record_size` bytes sorted by that value.
+fn binary_search_u24(records: &[u8], record_size: usize, key: u32) -> Option<usize> {
+    let (mut low, mut high) = (0, records.len() / record_size);
+    while low < high {
+        let mid = (low + high) / 2;
+        let value = (&records[(mid * record_size)..])
+            .read_u24::<BigEndian>()
+            .ok()?;
+        match value.cmp(&key) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Some(mid),
+        }
+    }
+    None
+}
+
+/// The inverse of the best Unicode subtable, mapping glyph IDs to the characters that map to
+/// them.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReverseCmap {
+    /// `(glyph ID, character)` pairs, sorted.
+    entries: Vec<(u32, char)>,
+}
+
+impl ReverseCmap {
+    /// Inverts the given `cmap` table. Tables that can't be parsed yield an empty map.
+    pub(crate) fn parse(data: &[u8]) -> ReverseCmap {
+        let mut entries = glyph_mappings(data);
+        entries.sort_unstable();
+        entries.dedup();
+        ReverseCmap { entries }
+    }
+
+    /// Returns the characters that map to the given glyph, in codepoint order.
+    pub(crate) fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        let start = self
+            .entries
+            .partition_point(|&(entry_glyph_id, _)| entry_glyph_id < glyph_id);
+        self.entries[start..]
+            .iter()
+            .take_while(|&&(entry_glyph_id, _)| entry_glyph_id == glyph_id)
+            .map(|&(_, character)| character)
+            .collect()
+    }
+}
+
+enum Subtable<'a> {
+    Format4(Vec<Format4Segment<'a>>),
+    Format12(Vec<Format12Group>),
+}
+
+struct Format4Segment<'a> {
+    start_code: u16,
+    end_code: u16,
+    id_delta: u16,
+    /// The glyph ID array that the segment's `idRangeOffset` points into, if it's nonzero.
+    glyph_ids: Option<&'a [u8]>,
+}
+
+impl<'a> Format4Segment<'a> {
+    fn glyph_id(&self, code: u16) -> u16 {
+        match self.glyph_ids {
+            None => code.wrapping_add(self.id_delta),
+            Some(glyph_ids) => {
+                let index = (code - self.start_code) as usize * 2;
+                match glyph_ids
+                    .get(index..)
+                    .map(|mut reader| reader.read_u16::<BigEndian>())
+                {
+                    Some(Ok(0)) | Some(Err(_)) | None => 0,
+                    Some(Ok(glyph_id)) => glyph_id.wrapping_add(self.id_delta),
+                }
+            }
+        }
+    }
+}
+
+struct Format12Group {
+    start_char_code: u32,
+    end_char_code: u32,
+    start_glyph_id: u32,
+}
+
+fn parse_best_unicode_subtable(data: &[u8]) -> Option<Subtable<'_>> {
+    let subtable = best_unicode_subtable(data)?;
+    match (&subtable[..]).read_u16::<BigEndian>().ok()? {
+        4 => parse_format_4(subtable).map(Subtable::Format4),
+        12 => parse_format_12(subtable).map(Subtable::Format12),
+        _ => None,
+    }
+}
+
+fn best_unicode_subtable(data: &[u8]) -> Option<&[u8]> {
+    let mut reader = data;
+    let _version = reader.read_u16::<BigEndian>().ok()?;
+    let num_tables = reader.read_u16::<BigEndian>().ok()?;
+
+    let mut best: Option<(u8, &[u8])> = None;
+    for _ in 0..num_tables {
+        let platform_id = reader.read_u16::<BigEndian>().ok()?;
+        let encoding_id = reader.read_u16::<BigEndian>().ok()?;
+        let offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        let is_unicode = match platform_id {
+            PLATFORM_UNICODE => encoding_id <= 6,
+            PLATFORM_WINDOWS => {
+                encoding_id == WINDOWS_ENCODING_UNICODE_BMP
+                    || encoding_id == WINDOWS_ENCODING_UNICODE_FULL
+            }
+            _ => false,
+        };
+        if !is_unicode {
+            continue;
+        }
+
+        let subtable = match data.get(offset..) {
+            Some(subtable) => subtable,
+            None => continue,
+        };
+        let priority = match (&subtable[..]).read_u16::<BigEndian>() {
+            Ok(12) => 2,
+            Ok(4) => 1,
+            _ => continue,
+        };
+        if best.is_none_or(|(best_priority, _)| priority > best_priority) {
+            best = Some((priority, subtable));
+        }
+    }
+    best.map(|(_, subtable)| subtable)
+}
+
+fn parse_format_4(subtable: &[u8]) -> Option<Vec<Format4Segment<'_>>> {
+    let mut reader = subtable.get(6..)?;
+    let seg_count = (reader.read_u16::<BigEndian>().ok()? / 2) as usize;
+    let end_codes = subtable.get(14..)?;
+    let start_codes = end_codes.get((seg_count * 2 + 2)..)?;
+    let id_deltas = start_codes.get((seg_count * 2)..)?;
+    let id_range_offsets = id_deltas.get((seg_count * 2)..)?;
+
+    let mut segments = Vec::with_capacity(seg_count);
+    for segment in 0..seg_count {
+        let end_code = (&end_codes[(segment * 2)..]).read_u16::<BigEndian>().ok()?;
+        let start_code = start_codes
+            .get((segment * 2)..)?
+            .read_u16::<BigEndian>()
+            .ok()?;
+        let id_delta = id_deltas
+            .get((segment * 2)..)?
+            .read_u16::<BigEndian>()
+            .ok()?;
+        let id_range_offset = id_range_offsets
+            .get((segment * 2)..)?
+            .read_u16::<BigEndian>()
+            .ok()? as usize;
+
+        // The segment that terminates the table maps U+FFFF, which isn't a character.
+        let end_code = end_code.min(0xfffe);
+        if start_code > end_code {
+            continue;
+        }
+
+        // The glyph IDs are stored in an array that `id_range_offset` points into, relative to its
+        // own position in the table.
+        let glyph_ids = if id_range_offset == 0 {
+            None
+        } else {
+            Some(id_range_offsets.get((segment * 2 + id_range_offset)..)?)
+        };
+        segments.push(Format4Segment {
+            start_code,
+            end_code,
+            id_delta,
+            glyph_ids,
+        });
+    }
+    Some(segments)
+}
+
+fn parse_format_12(subtable: &[u8]) -> Option<Vec<Format12Group>> {
+    let mut reader = subtable.get(12..)?;
+    let num_groups = reader.read_u32::<BigEndian>().ok()?;
+
+    let mut groups = Vec::with_capacity((num_groups as usize).min(reader.len() / 12));
+    for _ in 0..num_groups {
+        let group = Format12Group {
+            start_char_code: reader.read_u32::<BigEndian>().ok()?,
+            end_char_code: reader.read_u32::<BigEndian>().ok()?,
+            start_glyph_id: reader.read_u32::<BigEndian>().ok()?,
+        };
+        if group.start_char_code <= group.end_char_code {
+            groups.push(group);
+        }
+    }
+    Some(groups)
+}
+
+fn push_range_excluding(
+    ranges: &mut Vec<RangeInclusive<u32>>,
+    start_code: u16,
+    end_code: u16,
+    excluded_code: u16,
+) {
+    let (start, end, excluded) = (start_code as u32, end_code as u32, excluded_code as u32);
+    if excluded < start || excluded > end {
+        ranges.push(start..=end);
+        return;
+    }
+    if excluded > start {
+        ranges.push(start..=(excluded - 1));
+    }
+    if excluded < end {
+        ranges.push((excluded + 1)..=end);
+    }
+}
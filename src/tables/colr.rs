@@ -0,0 +1,421 @@
+// font-kit/src/tables/colr.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `COLR` table, which describes color glyphs as stacks of colored layers (version 0) or as
+//! graphs of paints (version 1).
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/colr
+
+use byteorder::{BigEndian, ReadBytesExt};
+use euclid::default::{Point2D, Transform2D};
+
+use crate::color::{ColorLine, ColorStop, ColorU, CompositeMode, Extend, Paint};
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"COLR");
+
+/// The palette entry index that stands for the current text color.
+pub(crate) const FOREGROUND_PALETTE_INDEX: u16 = 0xffff;
+
+const BASE_GLYPH_RECORD_SIZE: usize = 6;
+const LAYER_RECORD_SIZE: usize = 4;
+const BASE_GLYPH_PAINT_RECORD_SIZE: usize = 6;
+const COLOR_STOP_SIZE: usize = 6;
+const VAR_COLOR_STOP_SIZE: usize = 10;
+
+/// How deeply paints may nest. Paints can refer to other glyphs' paints, so this also stops
+/// malformed fonts with cycles.
+const MAX_PAINT_DEPTH: u32 = 64;
+
+/// A single record of the (version 0) layer records array.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LayerRecord {
+    pub(crate) glyph_id: u16,
+    pub(crate) palette_index: u16,
+}
+
+/// A view of a `COLR` table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ColrTable<'a> {
+    data: &'a [u8],
+    num_base_glyph_records: usize,
+    base_glyph_records_offset: usize,
+    layer_records_offset: usize,
+    num_layer_records: usize,
+    base_glyph_list_offset: usize,
+    layer_list_offset: usize,
+}
+
+impl<'a> ColrTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<ColrTable<'a>> {
+        let mut reader = data;
+        let version = reader.read_u16::<BigEndian>().ok()?;
+        let num_base_glyph_records = reader.read_u16::<BigEndian>().ok()? as usize;
+        let base_glyph_records_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        let layer_records_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        let num_layer_records = reader.read_u16::<BigEndian>().ok()? as usize;
+        // Offsets of zero mean that version 1 tables lack the list, just as version 0 tables do.
+        let (base_glyph_list_offset, layer_list_offset) = if version >= 1 {
+            (
+                reader.read_u32::<BigEndian>().ok()? as usize,
+                reader.read_u32::<BigEndian>().ok()? as usize,
+            )
+        } else {
+            (0, 0)
+        };
+        Some(ColrTable {
+            data,
+            num_base_glyph_records,
+            base_glyph_records_offset,
+            layer_records_offset,
+            num_layer_records,
+            base_glyph_list_offset,
+            layer_list_offset,
+        })
+    }
+
+    /// Returns true if the table has any version 0 base glyphs, which are drawn with layers.
+    pub(crate) fn has_version_0_glyphs(&self) -> bool {
+        self.num_base_glyph_records > 0
+    }
+
+    /// Returns true if the table has any version 1 base glyphs, which are drawn with paints.
+    pub(crate) fn has_version_1_glyphs(&self) -> bool {
+        self.base_glyph_list_offset != 0
+            && self
+                .data
+                .get(self.base_glyph_list_offset..)
+                .and_then(|mut reader| reader.read_u32::<BigEndian>().ok())
+                .is_some_and(|num_base_glyph_paint_records| num_base_glyph_paint_records > 0)
+    }
+
+    /// Returns the version 0 layers of the given glyph, bottommost first.
+    ///
+    /// Glyphs that aren't version 0 color glyphs have no layers.
+    pub(crate) fn layers(&self, glyph_id: u16) -> Option<Vec<LayerRecord>> {
+        // Base glyph records are sorted by glyph ID.
+        let (mut low, mut high) = (0, self.num_base_glyph_records);
+        let (first_layer_index, num_layers) = loop {
+            if low >= high {
+                return Some(vec![]);
+            }
+            let mid = (low + high) / 2;
+            let mut reader = self
+                .data
+                .get((self.base_glyph_records_offset + mid * BASE_GLYPH_RECORD_SIZE)..)?;
+            let base_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+            if base_glyph_id < glyph_id {
+                low = mid + 1
+            } else if base_glyph_id > glyph_id {
+                high = mid
+            } else {
+                let first_layer_index = reader.read_u16::<BigEndian>().ok()? as usize;
+                let num_layers = reader.read_u16::<BigEndian>().ok()? as usize;
+                break (first_layer_index, num_layers);
+            }
+        };
+
+        if first_layer_index + num_layers > self.num_layer_records {
+            return None;
+        }
+        let mut layers = Vec::with_capacity(num_layers);
+        for layer_index in first_layer_index..(first_layer_index + num_layers) {
+            let mut reader = self
+                .data
+                .get((self.layer_records_offset + layer_index * LAYER_RECORD_SIZE)..)?;
+            layers.push(LayerRecord {
+                glyph_id: reader.read_u16::<BigEndian>().ok()?,
+                palette_index: reader.read_u16::<BigEndian>().ok()?,
+            });
+        }
+        Some(layers)
+    }
+
+    /// Returns the version 1 paint graph of the given glyph, with palette entries turned into
+    /// colors by `color`.
+    ///
+    /// Returns `None` if the glyph isn't a version 1 color glyph or its paints are malformed.
+    pub(crate) fn paint(
+        &self,
+        glyph_id: u16,
+        color: &dyn Fn(u16) -> Option<ColorU>,
+    ) -> Option<Paint> {
+        let parser = PaintParser { table: self, color };
+        parser.base_glyph_paint(glyph_id, 0)
+    }
+
+    /// Returns the offset of the given glyph's root paint, from the base glyph paint records.
+    fn base_glyph_paint_offset(&self, glyph_id: u16) -> Option<usize> {
+        if self.base_glyph_list_offset == 0 {
+            return None;
+        }
+        let mut reader = self.data.get(self.base_glyph_list_offset..)?;
+        let num_records = reader.read_u32::<BigEndian>().ok()? as usize;
+
+        // Base glyph paint records are sorted by glyph ID, like the version 0 records.
+        let (mut low, mut high) = (0, num_records);
+        while low < high {
+            let mid = (low + high) / 2;
+            let mut reader = reader.get((mid * BASE_GLYPH_PAINT_RECORD_SIZE)..)?;
+            let base_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+            if base_glyph_id < glyph_id {
+                low = mid + 1
+            } else if base_glyph_id > glyph_id {
+                high = mid
+            } else {
+                let paint_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+                return Some(self.base_glyph_list_offset + paint_offset);
+            }
+        }
+        None
+    }
+
+    /// Returns the offset of the paint at the given index of the layer list.
+    fn layer_paint_offset(&self, layer_index: usize) -> Option<usize> {
+        if self.layer_list_offset == 0 {
+            return None;
+        }
+        let mut reader = self.data.get(self.layer_list_offset..)?;
+        if layer_index >= reader.read_u32::<BigEndian>().ok()? as usize {
+            return None;
+        }
+        let mut reader = reader.get((layer_index * 4)..)?;
+        let paint_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        Some(self.layer_list_offset + paint_offset)
+    }
+}
+
+/// Reads the paint tables of a `COLR` table into `Paint`s.
+struct PaintParser<'a> {
+    table: &'a ColrTable<'a>,
+    color: &'a dyn Fn(u16) -> Option<ColorU>,
+}
+
+impl<'a> PaintParser<'a> {
+    fn base_glyph_paint(&self, glyph_id: u16, depth: u32) -> Option<Paint> {
+        let offset = self.table.base_glyph_paint_offset(glyph_id)?;
+        self.paint(offset, depth)
+    }
+
+    fn paint(&self, offset: usize, depth: u32) -> Option<Paint> {
+        if depth >= MAX_PAINT_DEPTH {
+            return None;
+        }
+        let depth = depth + 1;
+        let mut reader = self.table.data.get(offset..)?;
+        let format = reader.read_u8().ok()?;
+
+        // Every other format from 3 on is the variable version of the one before it, with the
+        // same fields followed by a variation index. Variations aren't applied, so both are read
+        // alike.
+        match format {
+            1 => {
+                let num_layers = reader.read_u8().ok()? as usize;
+                let first_layer_index = reader.read_u32::<BigEndian>().ok()? as usize;
+                let layers = (first_layer_index..(first_layer_index + num_layers))
+                    .map(|layer_index| {
+                        self.paint(self.table.layer_paint_offset(layer_index)?, depth)
+                    })
+                    .collect::<Option<_>>()?;
+                Some(Paint::Layers(layers))
+            }
+            2 | 3 => {
+                let palette_index = reader.read_u16::<BigEndian>().ok()?;
+                let alpha = read_f2dot14(&mut reader)?;
+                Some(Paint::Solid {
+                    color: (self.color)(palette_index),
+                    alpha,
+                })
+            }
+            4 | 5 => {
+                let color_line = self.color_line(offset, &mut reader, format == 5)?;
+                Some(Paint::LinearGradient {
+                    color_line,
+                    p0: read_point(&mut reader)?,
+                    p1: read_point(&mut reader)?,
+                    p2: read_point(&mut reader)?,
+                })
+            }
+            6 | 7 => {
+                let color_line = self.color_line(offset, &mut reader, format == 7)?;
+                Some(Paint::RadialGradient {
+                    color_line,
+                    c0: read_point(&mut reader)?,
+                    r0: reader.read_u16::<BigEndian>().ok()? as f32,
+                    c1: read_point(&mut reader)?,
+                    r1: reader.read_u16::<BigEndian>().ok()? as f32,
+                })
+            }
+            8 | 9 => {
+                let color_line = self.color_line(offset, &mut reader, format == 9)?;
+                Some(Paint::SweepGradient {
+                    color_line,
+                    center: read_point(&mut reader)?,
+                    start_angle: read_angle(&mut reader)?,
+                    end_angle: read_angle(&mut reader)?,
+                })
+            }
+            10 => {
+                let paint = self.child_paint(offset, &mut reader, depth)?;
+                let glyph_id = reader.read_u16::<BigEndian>().ok()? as u32;
+                Some(Paint::Glyph {
+                    glyph_id,
+                    paint: Box::new(paint),
+                })
+            }
+            11 => {
+                let glyph_id = reader.read_u16::<BigEndian>().ok()?;
+                let paint = self.base_glyph_paint(glyph_id, depth)?;
+                Some(Paint::ColrGlyph {
+                    glyph_id: glyph_id as u32,
+                    paint: Box::new(paint),
+                })
+            }
+            12..=31 => {
+                let paint = self.child_paint(offset, &mut reader, depth)?;
+                let transform = read_transform(format, offset, self.table.data, &mut reader)?;
+                Some(Paint::Transform {
+                    transform,
+                    paint: Box::new(paint),
+                })
+            }
+            32 => {
+                let source = self.child_paint(offset, &mut reader, depth)?;
+                let mode = CompositeMode::from_value(reader.read_u8().ok()?)?;
+                let backdrop = self.child_paint(offset, &mut reader, depth)?;
+                Some(Paint::Composite {
+                    source: Box::new(source),
+                    mode,
+                    backdrop: Box::new(backdrop),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads a 24-bit offset, relative to the paint at `paint_offset`, and the paint it points
+    /// to.
+    fn child_paint(&self, paint_offset: usize, reader: &mut &[u8], depth: u32) -> Option<Paint> {
+        let child_offset = reader.read_u24::<BigEndian>().ok()? as usize;
+        self.paint(paint_offset + child_offset, depth)
+    }
+
+    /// Reads a 24-bit offset, relative to the paint at `paint_offset`, and the color line it
+    /// points to.
+    fn color_line(
+        &self,
+        paint_offset: usize,
+        reader: &mut &[u8],
+        is_variable: bool,
+    ) -> Option<ColorLine> {
+        let color_line_offset = reader.read_u24::<BigEndian>().ok()? as usize;
+        let mut reader = self.table.data.get((paint_offset + color_line_offset)..)?;
+        let extend = match reader.read_u8().ok()? {
+            1 => Extend::Repeat,
+            2 => Extend::Reflect,
+            // Unknown modes are to be treated as padding.
+            _ => Extend::Pad,
+        };
+        let num_stops = reader.read_u16::<BigEndian>().ok()? as usize;
+        let stop_size = if is_variable {
+            VAR_COLOR_STOP_SIZE
+        } else {
+            COLOR_STOP_SIZE
+        };
+        let stops = (0..num_stops)
+            .map(|stop_index| {
+                let mut reader = reader.get((stop_index * stop_size)..)?;
+                let offset = read_f2dot14(&mut reader)?;
+                let palette_index = reader.read_u16::<BigEndian>().ok()?;
+                let alpha = read_f2dot14(&mut reader)?;
+                Some(ColorStop {
+                    offset,
+                    color: (self.color)(palette_index),
+                    alpha,
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(ColorLine { extend, stops })
+    }
+}
+
+/// Reads the fields of a transform paint (formats 12 to 31) that follow its child paint offset,
+/// as a transform.
+fn read_transform(
+    format: u8,
+    paint_offset: usize,
+    data: &[u8],
+    reader: &mut &[u8],
+) -> Option<Transform2D<f32>> {
+    // The formats come in pairs, so the variable ones read like the ones before them.
+    let transform = match format & !1 {
+        12 => {
+            let transform_offset = reader.read_u24::<BigEndian>().ok()? as usize;
+            let mut reader = data.get((paint_offset + transform_offset)..)?;
+            let mut fields = [0.0; 6];
+            for field in &mut fields {
+                *field = tables::fixed_16_16_to_f32(reader.read_i32::<BigEndian>().ok()?);
+            }
+            let [xx, yx, xy, yy, dx, dy] = fields;
+            Transform2D::row_major(xx, yx, xy, yy, dx, dy)
+        }
+        14 => {
+            let dx = reader.read_i16::<BigEndian>().ok()? as f32;
+            let dy = reader.read_i16::<BigEndian>().ok()? as f32;
+            Transform2D::create_translation(dx, dy)
+        }
+        16 | 18 => {
+            let scale_x = read_f2dot14(reader)?;
+            let scale_y = read_f2dot14(reader)?;
+            Transform2D::create_scale(scale_x, scale_y)
+        }
+        20 | 22 => {
+            let scale = read_f2dot14(reader)?;
+            Transform2D::create_scale(scale, scale)
+        }
+        24 | 26 => {
+            let (sin, cos) = read_angle(reader)?.to_radians().sin_cos();
+            Transform2D::row_major(cos, sin, -sin, cos, 0.0, 0.0)
+        }
+        _ => {
+            // Positive angles skew counterclockwise, so a positive x skew moves points left as y
+            // grows.
+            let x_skew = read_angle(reader)?.to_radians().tan();
+            let y_skew = read_angle(reader)?.to_radians().tan();
+            Transform2D::row_major(1.0, y_skew, -x_skew, 1.0, 0.0, 0.0)
+        }
+    };
+
+    // Formats 18, 22, 26, and 30 (and their variable versions) transform about a center point.
+    if let 18 | 22 | 26 | 30 = format & !1 {
+        let center = read_point(reader)?;
+        return Some(
+            Transform2D::create_translation(-center.x, -center.y)
+                .post_transform(&transform)
+                .post_transform(&Transform2D::create_translation(center.x, center.y)),
+        );
+    }
+    Some(transform)
+}
+
+fn read_f2dot14(reader: &mut &[u8]) -> Option<f32> {
+    Some(reader.read_i16::<BigEndian>().ok()? as f32 / 16384.0)
+}
+
+/// Reads an angle, stored as a fraction of 180 degrees, in degrees.
+fn read_angle(reader: &mut &[u8]) -> Option<f32> {
+    Some(read_f2dot14(reader)? * 180.0)
+}
+
+fn read_point(reader: &mut &[u8]) -> Option<Point2D<f32>> {
+    let x = reader.read_i16::<BigEndian>().ok()? as f32;
+    let y = reader.read_i16::<BigEndian>().ok()? as f32;
+    Some(Point2D::new(x, y))
+}
@@ -0,0 +1,138 @@
+// font-kit/src/tables/cpal.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `CPAL` table, which holds the color palettes used by `COLR` glyphs.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/cpal
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::color::ColorU;
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"CPAL");
+
+const COLOR_RECORD_SIZE: usize = 4;
+
+const VERSION_1: u16 = 1;
+
+const PALETTE_TYPE_USABLE_WITH_LIGHT_BACKGROUND: u32 = 0x0001;
+const PALETTE_TYPE_USABLE_WITH_DARK_BACKGROUND: u32 = 0x0002;
+
+/// The label of palettes and palette entries that have none.
+const NO_NAME_ID: u16 = 0xffff;
+
+/// A view of a `CPAL` table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CpalTable<'a> {
+    data: &'a [u8],
+    pub(crate) num_palette_entries: u16,
+    pub(crate) num_palettes: u16,
+    color_records_array_offset: usize,
+    palette_types_array_offset: usize,
+    palette_labels_array_offset: usize,
+    palette_entry_labels_array_offset: usize,
+}
+
+impl<'a> CpalTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<CpalTable<'a>> {
+        let mut reader = data;
+        let version = reader.read_u16::<BigEndian>().ok()?;
+        let num_palette_entries = reader.read_u16::<BigEndian>().ok()?;
+        let num_palettes = reader.read_u16::<BigEndian>().ok()?;
+        let _num_color_records = reader.read_u16::<BigEndian>().ok()?;
+        let color_records_array_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+
+        // Version 1 adds the offsets of three optional arrays after the color record indices.
+        // Offsets of zero mean that the array is absent.
+        let (mut palette_types_array_offset, mut palette_labels_array_offset) = (0, 0);
+        let mut palette_entry_labels_array_offset = 0;
+        if version >= VERSION_1 {
+            let mut reader = data.get((12 + num_palettes as usize * 2)..)?;
+            palette_types_array_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            palette_labels_array_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            palette_entry_labels_array_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+        }
+
+        Some(CpalTable {
+            data,
+            num_palette_entries,
+            num_palettes,
+            color_records_array_offset,
+            palette_types_array_offset,
+            palette_labels_array_offset,
+            palette_entry_labels_array_offset,
+        })
+    }
+
+    /// Returns the color of entry `entry_index` in palette `palette_index`.
+    pub(crate) fn color(&self, palette_index: u16, entry_index: u16) -> Option<ColorU> {
+        if palette_index >= self.num_palettes || entry_index >= self.num_palette_entries {
+            return None;
+        }
+        let mut reader = self.data.get((12 + palette_index as usize * 2)..)?;
+        let first_color_record_index = reader.read_u16::<BigEndian>().ok()? as usize;
+        let color_record_offset = self.color_records_array_offset
+            + (first_color_record_index + entry_index as usize) * COLOR_RECORD_SIZE;
+        let record = self
+            .data
+            .get(color_record_offset..(color_record_offset + COLOR_RECORD_SIZE))?;
+        Some(ColorU {
+            r: record[2],
+            g: record[1],
+            b: record[0],
+            a: record[3],
+        })
+    }
+    /// Returns whether palette `palette_index` is usable with light backgrounds and with dark
+    /// backgrounds, in that order. Palettes without a type are usable with neither in particular.
+    pub(crate) fn palette_type(&self, palette_index: u16) -> (bool, bool) {
+        let palette_type = self
+            .array_element(self.palette_types_array_offset, palette_index, 4)
+            .and_then(|mut reader| reader.read_u32::<BigEndian>().ok())
+            .unwrap_or(0);
+        (
+            palette_type & PALETTE_TYPE_USABLE_WITH_LIGHT_BACKGROUND != 0,
+            palette_type & PALETTE_TYPE_USABLE_WITH_DARK_BACKGROUND != 0,
+        )
+    }
+
+    /// Returns the ID of the `name` table entry that labels palette `palette_index`, if any.
+    pub(crate) fn palette_label(&self, palette_index: u16) -> Option<u16> {
+        self.label(self.palette_labels_array_offset, palette_index)
+    }
+
+    /// Returns the ID of the `name` table entry that labels entry `entry_index` of every palette,
+    /// if any.
+    pub(crate) fn palette_entry_label(&self, entry_index: u16) -> Option<u16> {
+        self.label(self.palette_entry_labels_array_offset, entry_index)
+    }
+
+    fn label(&self, array_offset: usize, index: u16) -> Option<u16> {
+        let mut reader = self.array_element(array_offset, index, 2)?;
+        match reader.read_u16::<BigEndian>().ok()? {
+            NO_NAME_ID => None,
+            name_id => Some(name_id),
+        }
+    }
+
+    fn array_element(
+        &self,
+        array_offset: usize,
+        index: u16,
+        element_size: usize,
+    ) -> Option<&'a [u8]> {
+        if array_offset == 0 {
+            return None;
+        }
+        self.data
+            .get((array_offset + index as usize * element_size)..)
+    }
+}
@@ -0,0 +1,39 @@
+// font-kit/src/tables/dsig.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `DSIG` table, which holds the digital signatures of a font.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/dsig
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::signature::SignatureInfo;
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"DSIG");
+
+const SIGNATURE_RECORD_SIZE: usize = 12;
+
+/// Reads the flags and signature formats of a `DSIG` table.
+pub(crate) fn signature_info(data: &[u8]) -> Option<SignatureInfo> {
+    let mut reader = data;
+    if reader.read_u32::<BigEndian>().ok()? != 1 {
+        return None;
+    }
+    let signature_count = reader.read_u16::<BigEndian>().ok()? as usize;
+    let flags = reader.read_u16::<BigEndian>().ok()?;
+
+    let mut formats = Vec::with_capacity(signature_count);
+    for signature_index in 0..signature_count {
+        let mut reader = reader.get((signature_index * SIGNATURE_RECORD_SIZE)..)?;
+        formats.push(reader.read_u32::<BigEndian>().ok()?);
+    }
+    Some(SignatureInfo { flags, formats })
+}
@@ -0,0 +1,118 @@
+// font-kit/src/tables/fvar.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `fvar` table, which declares the design axes of a variable font.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/fvar
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"fvar");
+
+const AXIS_FLAG_HIDDEN: u16 = 0x0001;
+const NO_NAME_ID: u16 = 0xffff;
+
+/// A single record of the axes array.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AxisRecord {
+    pub(crate) tag: u32,
+    pub(crate) min_value: f32,
+    pub(crate) default_value: f32,
+    pub(crate) max_value: f32,
+    pub(crate) hidden: bool,
+    pub(crate) name_id: u16,
+}
+
+/// A single record of the instances array.
+#[derive(Clone, Debug)]
+pub(crate) struct InstanceRecord {
+    pub(crate) subfamily_name_id: u16,
+    /// One coordinate per axis, in axis order.
+    pub(crate) coordinates: Vec<f32>,
+    pub(crate) postscript_name_id: Option<u16>,
+}
+
+/// The parsed contents of an `fvar` table.
+#[derive(Clone, Debug)]
+pub(crate) struct FvarTable {
+    pub(crate) axes: Vec<AxisRecord>,
+    pub(crate) instances: Vec<InstanceRecord>,
+}
+
+impl FvarTable {
+    pub(crate) fn parse(data: &[u8]) -> Option<FvarTable> {
+        let mut reader = data;
+        let major_version = reader.read_u16::<BigEndian>().ok()?;
+        let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+        if major_version != 1 {
+            return None;
+        }
+        let axes_array_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let _reserved = reader.read_u16::<BigEndian>().ok()?;
+        let axis_count = reader.read_u16::<BigEndian>().ok()? as usize;
+        let axis_size = reader.read_u16::<BigEndian>().ok()? as usize;
+        let instance_count = reader.read_u16::<BigEndian>().ok()? as usize;
+        let instance_size = reader.read_u16::<BigEndian>().ok()? as usize;
+
+        let mut axes = Vec::with_capacity(axis_count);
+        for axis_index in 0..axis_count {
+            let mut reader = data.get((axes_array_offset + axis_index * axis_size)..)?;
+            let tag = reader.read_u32::<BigEndian>().ok()?;
+            let min_value = tables::fixed_16_16_to_f32(reader.read_i32::<BigEndian>().ok()?);
+            let default_value = tables::fixed_16_16_to_f32(reader.read_i32::<BigEndian>().ok()?);
+            let max_value = tables::fixed_16_16_to_f32(reader.read_i32::<BigEndian>().ok()?);
+            let flags = reader.read_u16::<BigEndian>().ok()?;
+            let name_id = reader.read_u16::<BigEndian>().ok()?;
+            axes.push(AxisRecord {
+                tag,
+                min_value,
+                default_value,
+                max_value,
+                hidden: (flags & AXIS_FLAG_HIDDEN) != 0,
+                name_id,
+            });
+        }
+
+        // The PostScript name ID is optional, and is present only if the records are big enough
+        // to hold it.
+        let instances_array_offset = axes_array_offset + axis_count * axis_size;
+        let has_postscript_name_ids = instance_size >= axis_count * 4 + 6;
+        let mut instances = Vec::with_capacity(instance_count);
+        for instance_index in 0..instance_count {
+            let mut reader =
+                data.get((instances_array_offset + instance_index * instance_size)..)?;
+            let subfamily_name_id = reader.read_u16::<BigEndian>().ok()?;
+            let _flags = reader.read_u16::<BigEndian>().ok()?;
+            let mut coordinates = Vec::with_capacity(axis_count);
+            for _ in 0..axis_count {
+                coordinates.push(tables::fixed_16_16_to_f32(
+                    reader.read_i32::<BigEndian>().ok()?,
+                ));
+            }
+            let postscript_name_id = if has_postscript_name_ids {
+                match reader.read_u16::<BigEndian>().ok()? {
+                    NO_NAME_ID => None,
+                    name_id => Some(name_id),
+                }
+            } else {
+                None
+            };
+            instances.push(InstanceRecord {
+                subfamily_name_id,
+                coordinates,
+                postscript_name_id,
+            });
+        }
+
+        Some(FvarTable { axes, instances })
+    }
+}
@@ -0,0 +1,103 @@
+// font-kit/src/tables/gdef.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The glyph definition table, `GDEF`.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/gdef
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+use crate::tables::layout;
+
+pub(crate) const TAG: u32 = tables::tag(b"GDEF");
+
+const GLYPH_CLASS_DEF_OFFSET: usize = 4;
+const LIG_CARET_LIST_OFFSET: usize = 8;
+const MARK_ATTACH_CLASS_DEF_OFFSET: usize = 10;
+
+/// A caret position within a ligature, from the `LigCaretList`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CaretValue {
+    /// An x coordinate in font units (formats 1 and 3; the device table of format 3 is ignored).
+    Coordinate(i16),
+    /// The index of a point on the glyph's outline whose x coordinate is the caret position
+    /// (format 2).
+    ContourPoint(u16),
+}
+
+/// Returns the class of the glyph in the `GlyphClassDef`, or 0 if the table has none or doesn't
+/// assign the glyph one.
+pub(crate) fn glyph_class(data: &[u8], glyph_id: u16) -> u16 {
+    class_def(data, GLYPH_CLASS_DEF_OFFSET)
+        .map_or(0, |class_def| layout::glyph_class(class_def, glyph_id))
+}
+
+/// Returns the class of the glyph in the `MarkAttachClassDef`, or 0 if the table has none or
+/// doesn't assign the glyph one.
+pub(crate) fn mark_attach_class(data: &[u8], glyph_id: u16) -> u16 {
+    class_def(data, MARK_ATTACH_CLASS_DEF_OFFSET)
+        .map_or(0, |class_def| layout::glyph_class(class_def, glyph_id))
+}
+
+fn class_def(data: &[u8], offset_offset: usize) -> Option<&[u8]> {
+    let offset = data.get(offset_offset..)?.read_u16::<BigEndian>().ok()? as usize;
+    if offset == 0 {
+        return None;
+    }
+    data.get(offset..)
+}
+
+/// Returns the caret positions of the given ligature glyph, in the order the table lists them.
+///
+/// Glyphs that aren't ligatures, or fonts without a `LigCaretList`, yield `None`.
+pub(crate) fn ligature_carets(data: &[u8], glyph_id: u16) -> Option<Vec<CaretValue>> {
+    let lig_caret_list_offset = data
+        .get(LIG_CARET_LIST_OFFSET..)?
+        .read_u16::<BigEndian>()
+        .ok()? as usize;
+    if lig_caret_list_offset == 0 {
+        return None;
+    }
+    let lig_caret_list = data.get(lig_caret_list_offset..)?;
+    let mut reader = lig_caret_list;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let coverage = lig_caret_list.get(coverage_offset..)?;
+    let coverage_index = layout::coverage_index(coverage, glyph_id)? as usize;
+    let lig_glyph_count = reader.read_u16::<BigEndian>().ok()? as usize;
+    if coverage_index >= lig_glyph_count {
+        return None;
+    }
+
+    let lig_glyph_offset = reader
+        .get((coverage_index * 2)..)?
+        .read_u16::<BigEndian>()
+        .ok()? as usize;
+    let lig_glyph = lig_caret_list.get(lig_glyph_offset..)?;
+    let mut reader = lig_glyph;
+    let caret_count = reader.read_u16::<BigEndian>().ok()?;
+    (0..caret_count)
+        .map(|_| {
+            let caret_value_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            let mut caret_value = lig_glyph.get(caret_value_offset..)?;
+            match caret_value.read_u16::<BigEndian>().ok()? {
+                1 | 3 => caret_value
+                    .read_i16::<BigEndian>()
+                    .ok()
+                    .map(CaretValue::Coordinate),
+                2 => caret_value
+                    .read_u16::<BigEndian>()
+                    .ok()
+                    .map(CaretValue::ContourPoint),
+                _ => None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,157 @@
+// font-kit/src/tables/glyf.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The glyph data table, `glyf`, and the index to location table, `loca`, that points into it.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/glyf
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"glyf");
+pub(crate) const LOCA_TAG: u32 = tables::tag(b"loca");
+pub(crate) const HEAD_TAG: u32 = tables::tag(b"head");
+pub(crate) const FPGM_TAG: u32 = tables::tag(b"fpgm");
+pub(crate) const PREP_TAG: u32 = tables::tag(b"prep");
+
+const HEAD_INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
+const GLYPH_HEADER_SIZE: usize = 10;
+
+const X_SHORT_VECTOR: u8 = 0x02;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+/// Returns the data of the given glyph, which is empty for glyphs without an outline.
+pub(crate) fn glyph_data<'a>(
+    head: &[u8],
+    loca: &[u8],
+    glyf: &'a [u8],
+    glyph_id: u32,
+) -> Option<&'a [u8]> {
+    let long = head
+        .get(HEAD_INDEX_TO_LOC_FORMAT_OFFSET..)?
+        .read_i16::<BigEndian>()
+        .ok()?
+        != 0;
+    let glyph_id = glyph_id as usize;
+    let (start, end) = if long {
+        let mut reader = loca.get((glyph_id * 4)..)?;
+        (
+            reader.read_u32::<BigEndian>().ok()? as usize,
+            reader.read_u32::<BigEndian>().ok()? as usize,
+        )
+    } else {
+        let mut reader = loca.get((glyph_id * 2)..)?;
+        (
+            reader.read_u16::<BigEndian>().ok()? as usize * 2,
+            reader.read_u16::<BigEndian>().ok()? as usize * 2,
+        )
+    };
+    glyf.get(start..end)
+}
+
+/// Returns the x coordinate, in font units, of the point with the given index in a simple glyph.
+///
+/// Composite glyphs have no points of their own, so they yield `None`.
+pub(crate) fn point_x(glyph: &[u8], point_index: u16) -> Option<i16> {
+    let mut reader = glyph;
+    let contour_count = reader.read_i16::<BigEndian>().ok()?;
+    if contour_count <= 0 {
+        return None;
+    }
+    let mut reader = glyph.get(GLYPH_HEADER_SIZE..)?;
+    let mut point_count = 0;
+    for _ in 0..contour_count {
+        point_count = reader.read_u16::<BigEndian>().ok()? as usize + 1;
+    }
+    let point_index = point_index as usize;
+    if point_index >= point_count {
+        return None;
+    }
+    let instruction_length = reader.read_u16::<BigEndian>().ok()? as usize;
+    let mut reader = reader.get(instruction_length..)?;
+
+    // Flags may repeat, so they have to be decoded to find where the x coordinates start.
+    let mut flags = Vec::with_capacity(point_count);
+    while flags.len() < point_count {
+        let flag = reader.read_u8().ok()?;
+        let repeat_count = if flag & REPEAT_FLAG != 0 {
+            reader.read_u8().ok()? as usize + 1
+        } else {
+            1
+        };
+        flags.extend(std::iter::repeat_n(flag, repeat_count));
+    }
+
+    // The x coordinates are deltas from the previous point.
+    let mut x = 0i16;
+    for &flag in &flags[..=point_index] {
+        let delta = if flag & X_SHORT_VECTOR != 0 {
+            let magnitude = reader.read_u8().ok()? as i16;
+            if flag & X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR != 0 {
+                magnitude
+            } else {
+                -magnitude
+            }
+        } else if flag & X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR != 0 {
+            0
+        } else {
+            reader.read_i16::<BigEndian>().ok()?
+        };
+        x = x.wrapping_add(delta);
+    }
+    Some(x)
+}
+
+/// Returns the TrueType instructions of a glyph, which are empty if it has none.
+pub(crate) fn instructions(glyph: &[u8]) -> Option<&[u8]> {
+    if glyph.is_empty() {
+        return Some(&[]);
+    }
+    let contour_count = (&glyph[..]).read_i16::<BigEndian>().ok()?;
+    let mut reader = if contour_count >= 0 {
+        glyph.get((GLYPH_HEADER_SIZE + contour_count as usize * 2)..)?
+    } else {
+        // The instructions of a composite glyph follow its components, if the last one says so.
+        let mut reader = glyph.get(GLYPH_HEADER_SIZE..)?;
+        loop {
+            let flags = reader.read_u16::<BigEndian>().ok()?;
+            let mut length = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                6
+            } else {
+                4
+            };
+            if flags & WE_HAVE_A_SCALE != 0 {
+                length += 2;
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                length += 4;
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                length += 8;
+            }
+            reader = reader.get(length..)?;
+            if flags & MORE_COMPONENTS == 0 {
+                if flags & WE_HAVE_INSTRUCTIONS == 0 {
+                    return Some(&[]);
+                }
+                break reader;
+            }
+        }
+    };
+    let instruction_length = reader.read_u16::<BigEndian>().ok()? as usize;
+    reader.get(..instruction_length)
+}
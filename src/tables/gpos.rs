@@ -0,0 +1,159 @@
+// font-kit/src/tables/gpos.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `GPOS` table, which positions glyphs relative to one another.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/gpos
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+use crate::tables::layout::{self, LayoutTable};
+
+pub(crate) const TAG: u32 = tables::tag(b"GPOS");
+
+pub(crate) const KERN_FEATURE_TAG: u32 = tables::tag(b"kern");
+
+const LOOKUP_TYPE_PAIR_ADJUSTMENT: u16 = 2;
+const LOOKUP_TYPE_EXTENSION: u16 = 9;
+
+const VALUE_FORMAT_X_PLACEMENT: u16 = 0x0001;
+const VALUE_FORMAT_Y_PLACEMENT: u16 = 0x0002;
+const VALUE_FORMAT_X_ADVANCE: u16 = 0x0004;
+const VALUE_FORMAT_Y_ADVANCE: u16 = 0x0008;
+
+/// The part of a `ValueRecord` that doesn't depend on device tables, in font units.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct ValueRecord {
+    pub(crate) x_placement: i16,
+    pub(crate) y_placement: i16,
+    pub(crate) x_advance: i16,
+    pub(crate) y_advance: i16,
+}
+
+impl ValueRecord {
+    fn read(reader: &mut &[u8], value_format: u16) -> Option<ValueRecord> {
+        let mut value_record = ValueRecord::default();
+        let fields = [
+            (VALUE_FORMAT_X_PLACEMENT, &mut value_record.x_placement),
+            (VALUE_FORMAT_Y_PLACEMENT, &mut value_record.y_placement),
+            (VALUE_FORMAT_X_ADVANCE, &mut value_record.x_advance),
+            (VALUE_FORMAT_Y_ADVANCE, &mut value_record.y_advance),
+        ];
+        for (flag, field) in fields {
+            if (value_format & flag) != 0 {
+                *field = reader.read_i16::<BigEndian>().ok()?;
+            }
+        }
+        // Skip the device table offsets.
+        *reader = reader.get((((value_format >> 4) & 0xf).count_ones() as usize * 2)..)?;
+        Some(value_record)
+    }
+}
+
+#[inline]
+fn value_record_size(value_format: u16) -> usize {
+    (value_format & 0xff).count_ones() as usize * 2
+}
+
+/// Returns the adjustment that the `kern` feature's pair positioning lookups make to the first
+/// glyph of the given pair, or `None` if the table has no `kern` feature.
+///
+/// If several lookups apply to the pair, their adjustments are summed, saturating rather than
+/// overflowing.
+pub(crate) fn kerning(
+    data: &[u8],
+    first_glyph_id: u16,
+    second_glyph_id: u16,
+) -> Option<ValueRecord> {
+    let table = LayoutTable::parse(data)?;
+    let lookup_indices = table.feature_lookup_indices(&[KERN_FEATURE_TAG]);
+    if lookup_indices.is_empty() {
+        return None;
+    }
+
+    let mut total = ValueRecord::default();
+    for lookup_index in lookup_indices {
+        let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+            Some(ref lookup) if lookup.lookup_type == LOOKUP_TYPE_PAIR_ADJUSTMENT => lookup.clone(),
+            _ => continue,
+        };
+        // The first subtable that covers the pair wins.
+        for subtable in &lookup.subtables {
+            if let Some(value_record) = pair_adjustment(subtable, first_glyph_id, second_glyph_id) {
+                total.x_placement = total.x_placement.saturating_add(value_record.x_placement);
+                total.y_placement = total.y_placement.saturating_add(value_record.y_placement);
+                total.x_advance = total.x_advance.saturating_add(value_record.x_advance);
+                total.y_advance = total.y_advance.saturating_add(value_record.y_advance);
+                break;
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Looks up a pair in a `PairPos` subtable, returning the value record for the first glyph.
+fn pair_adjustment(
+    subtable: &[u8],
+    first_glyph_id: u16,
+    second_glyph_id: u16,
+) -> Option<ValueRecord> {
+    let mut reader = subtable;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let value_format_1 = reader.read_u16::<BigEndian>().ok()?;
+    let value_format_2 = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, first_glyph_id)?;
+    let record_size = value_record_size(value_format_1) + value_record_size(value_format_2);
+
+    match format {
+        1 => {
+            let pair_set_count = reader.read_u16::<BigEndian>().ok()?;
+            if coverage_index >= pair_set_count {
+                return None;
+            }
+            let mut reader = reader.get((coverage_index as usize * 2)..)?;
+            let pair_set = subtable.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+            let mut reader = pair_set;
+            let pair_value_count = reader.read_u16::<BigEndian>().ok()? as usize;
+
+            // Pair value records are sorted by the second glyph.
+            let (mut low, mut high) = (0, pair_value_count);
+            while low < high {
+                let mid = (low + high) / 2;
+                let mut reader = reader.get((mid * (2 + record_size))..)?;
+                let second_glyph = reader.read_u16::<BigEndian>().ok()?;
+                if second_glyph_id < second_glyph {
+                    high = mid
+                } else if second_glyph_id > second_glyph {
+                    low = mid + 1
+                } else {
+                    return ValueRecord::read(&mut reader, value_format_1);
+                }
+            }
+            None
+        }
+        2 => {
+            let class_def_1_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            let class_def_2_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            let class_1_count = reader.read_u16::<BigEndian>().ok()?;
+            let class_2_count = reader.read_u16::<BigEndian>().ok()?;
+            let class_1 = layout::glyph_class(subtable.get(class_def_1_offset..)?, first_glyph_id);
+            let class_2 = layout::glyph_class(subtable.get(class_def_2_offset..)?, second_glyph_id);
+            if class_1 >= class_1_count || class_2 >= class_2_count {
+                return None;
+            }
+            let record_index = class_1 as usize * class_2_count as usize + class_2 as usize;
+            let mut reader = reader.get((record_index * record_size)..)?;
+            ValueRecord::read(&mut reader, value_format_1)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,673 @@
+// font-kit/src/tables/gsub.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `GSUB` table, which substitutes glyphs for one another.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/gsub
+
+use byteorder::{BigEndian, ReadBytesExt};
+use std::convert::TryFrom;
+
+use crate::tables;
+use crate::tables::layout::{self, LayoutTable, Lookup};
+
+pub(crate) const TAG: u32 = tables::tag(b"GSUB");
+
+/// The features that ligature substitution applies when the caller doesn't ask for any:
+/// standard ligatures (`liga`) and contextual ligatures (`clig`).
+pub(crate) const DEFAULT_LIGATURE_FEATURES: [u32; 2] = [tables::tag(b"liga"), tables::tag(b"clig")];
+
+/// The features that contextual substitution applies when the caller doesn't ask for any:
+/// required ligatures (`rlig`), contextual alternates (`calt`), and contextual ligatures (`clig`).
+pub(crate) const DEFAULT_CONTEXTUAL_FEATURES: [u32; 3] = [
+    tables::tag(b"rlig"),
+    tables::tag(b"calt"),
+    tables::tag(b"clig"),
+];
+
+/// The feature that gathers every alternate of each glyph, for glyph pickers.
+const ACCESS_ALL_ALTERNATES_FEATURE: u32 = tables::tag(b"aalt");
+
+const LOOKUP_TYPE_SINGLE: u16 = 1;
+const LOOKUP_TYPE_MULTIPLE: u16 = 2;
+const LOOKUP_TYPE_ALTERNATE: u16 = 3;
+const LOOKUP_TYPE_LIGATURE: u16 = 4;
+const LOOKUP_TYPE_CONTEXT: u16 = 5;
+const LOOKUP_TYPE_CHAINED_CONTEXT: u16 = 6;
+const LOOKUP_TYPE_EXTENSION: u16 = 7;
+
+/// How deeply contextual lookups may invoke other contextual lookups, so that malicious fonts
+/// can't recurse forever.
+const MAX_NESTING_DEPTH: u32 = 8;
+
+/// Applies the ligature substitution lookups of the given features to `glyphs`, in lookup list
+/// order.
+///
+/// Lookups of other types are skipped, as are lookup flags, so marks are never ignored while
+/// matching components.
+pub(crate) fn substitute_ligatures(data: &[u8], glyphs: &mut Vec<u32>, feature_tags: &[u32]) {
+    let table = match LayoutTable::parse(data) {
+        Some(table) => table,
+        None => return,
+    };
+    for lookup_index in table.feature_lookup_indices(feature_tags) {
+        let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+            Some(lookup) => lookup,
+            None => continue,
+        };
+        if lookup.lookup_type != LOOKUP_TYPE_LIGATURE {
+            continue;
+        }
+
+        let mut index = 0;
+        while index < glyphs.len() {
+            // The first subtable that has a ligature for the glyphs at this position wins.
+            if let Some((ligature_glyph, component_count)) = lookup
+                .subtables
+                .iter()
+                .filter_map(|subtable| ligature(subtable, &glyphs[index..]))
+                .next()
+            {
+                glyphs.splice(index..(index + component_count), Some(ligature_glyph));
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Applies the contextual and chaining contextual substitution lookups of the given features to
+/// `glyphs`, in lookup list order.
+///
+/// When a rule matches, the lookups that it names are applied to the matched glyphs, and matching
+/// resumes after them. Lookups of other types are skipped, as are lookup flags.
+pub(crate) fn substitute_contextually(data: &[u8], glyphs: &mut Vec<u32>, feature_tags: &[u32]) {
+    let table = match LayoutTable::parse(data) {
+        Some(table) => table,
+        None => return,
+    };
+    for lookup_index in table.feature_lookup_indices(feature_tags) {
+        let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+            Some(lookup) => lookup,
+            None => continue,
+        };
+        if lookup.lookup_type != LOOKUP_TYPE_CONTEXT
+            && lookup.lookup_type != LOOKUP_TYPE_CHAINED_CONTEXT
+        {
+            continue;
+        }
+
+        let mut index = 0;
+        while index < glyphs.len() {
+            index = match apply_contextual_lookup(&table, &lookup, glyphs, index, 0) {
+                Some(end) if end > index => end,
+                _ => index + 1,
+            };
+        }
+    }
+}
+
+/// Returns the `(input, output)` glyph pairs that the single and alternate substitution lookups
+/// of the given feature can produce, sorted and without duplicates.
+///
+/// Every alternate of an alternate substitution is listed. Lookups of other types are skipped.
+pub(crate) fn feature_substitutions(data: &[u8], feature_tag: u32) -> Vec<(u32, u32)> {
+    let table = match LayoutTable::parse(data) {
+        Some(table) => table,
+        None => return vec![],
+    };
+    let mut substitutions = vec![];
+    for lookup_index in table.feature_lookup_indices(&[feature_tag]) {
+        let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+            Some(lookup) => lookup,
+            None => continue,
+        };
+        let add_substitutions = match lookup.lookup_type {
+            LOOKUP_TYPE_SINGLE => single_substitutions,
+            LOOKUP_TYPE_ALTERNATE => alternate_substitutions,
+            _ => continue,
+        };
+        for subtable in &lookup.subtables {
+            // A malformed subtable contributes the substitutions read before the error.
+            let _ = add_substitutions(subtable, &mut substitutions);
+        }
+    }
+    substitutions.retain(|&(input, output)| input != output);
+    substitutions.sort_unstable();
+    substitutions.dedup();
+    substitutions
+}
+
+/// Returns the alternates of the given glyph, in the order the font lists them and without
+/// duplicates.
+///
+/// These come from the single and alternate substitution lookups of the `aalt` feature, or, if
+/// the font has no such feature, from every alternate substitution lookup.
+pub(crate) fn glyph_alternates(data: &[u8], glyph_id: u32) -> Vec<u32> {
+    let (table, glyph_id) = match (LayoutTable::parse(data), u16::try_from(glyph_id)) {
+        (Some(table), Ok(glyph_id)) => (table, glyph_id),
+        _ => return vec![],
+    };
+    let mut lookup_indices = table.feature_lookup_indices(&[ACCESS_ALL_ALTERNATES_FEATURE]);
+    let lookup_types: &[u16] = if lookup_indices.is_empty() {
+        lookup_indices = (0..table.lookup_count()).collect();
+        &[LOOKUP_TYPE_ALTERNATE]
+    } else {
+        &[LOOKUP_TYPE_SINGLE, LOOKUP_TYPE_ALTERNATE]
+    };
+
+    let mut alternates = vec![];
+    for lookup_index in lookup_indices {
+        let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+            Some(lookup) if lookup_types.contains(&lookup.lookup_type) => lookup,
+            _ => continue,
+        };
+        for subtable in &lookup.subtables {
+            let outputs = if lookup.lookup_type == LOOKUP_TYPE_SINGLE {
+                single_substitute(subtable, glyph_id).into_iter().collect()
+            } else {
+                alternate_set(subtable, glyph_id).unwrap_or_default()
+            };
+            for output in outputs {
+                let output = output as u32;
+                if output != glyph_id as u32 && !alternates.contains(&output) {
+                    alternates.push(output);
+                }
+            }
+        }
+    }
+    alternates
+}
+
+/// Returns the glyph that a `SingleSubst` subtable substitutes for the given one, if any.
+fn single_substitute(subtable: &[u8], glyph_id: u16) -> Option<u16> {
+    let mut reader = subtable;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, glyph_id)?;
+    match format {
+        1 => {
+            let delta = reader.read_i16::<BigEndian>().ok()?;
+            Some(glyph_id.wrapping_add(delta as u16))
+        }
+        2 => {
+            let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+            if coverage_index >= glyph_count {
+                return None;
+            }
+            let mut reader = reader.get((coverage_index as usize * 2)..)?;
+            reader.read_u16::<BigEndian>().ok()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the alternates that an `AlternateSubst` subtable offers for the given glyph, if any.
+fn alternate_set(subtable: &[u8], glyph_id: u16) -> Option<Vec<u16>> {
+    let mut reader = subtable;
+    let _format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let alternate_set_count = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, glyph_id)?;
+    if coverage_index >= alternate_set_count {
+        return None;
+    }
+    let mut reader = reader.get((coverage_index as usize * 2)..)?;
+    let mut reader = subtable.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+    let alternate_count = reader.read_u16::<BigEndian>().ok()?;
+    (0..alternate_count)
+        .map(|_| reader.read_u16::<BigEndian>().ok())
+        .collect()
+}
+
+/// Adds the substitutions of a `SingleSubst` subtable to `substitutions`.
+fn single_substitutions(subtable: &[u8], substitutions: &mut Vec<(u32, u32)>) -> Option<()> {
+    let mut reader = subtable;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let covered_glyphs = layout::covered_glyphs(subtable.get(coverage_offset..)?)?;
+    match format {
+        1 => {
+            // Glyph IDs wrap around modulo 65536.
+            let delta = reader.read_i16::<BigEndian>().ok()?;
+            for glyph in covered_glyphs {
+                substitutions.push((glyph as u32, glyph.wrapping_add(delta as u16) as u32));
+            }
+        }
+        2 => {
+            let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+            for &glyph in covered_glyphs.iter().take(glyph_count as usize) {
+                substitutions.push((glyph as u32, reader.read_u16::<BigEndian>().ok()? as u32));
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Adds the substitutions of an `AlternateSubst` subtable to `substitutions`.
+fn alternate_substitutions(subtable: &[u8], substitutions: &mut Vec<(u32, u32)>) -> Option<()> {
+    let mut reader = subtable;
+    let _format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let alternate_set_count = reader.read_u16::<BigEndian>().ok()?;
+    let covered_glyphs = layout::covered_glyphs(subtable.get(coverage_offset..)?)?;
+    for &glyph in covered_glyphs.iter().take(alternate_set_count as usize) {
+        let alternate_set = subtable.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+        let mut alternate_reader = alternate_set;
+        let alternate_count = alternate_reader.read_u16::<BigEndian>().ok()?;
+        for _ in 0..alternate_count {
+            let alternate = alternate_reader.read_u16::<BigEndian>().ok()?;
+            substitutions.push((glyph as u32, alternate as u32));
+        }
+    }
+    Some(())
+}
+
+/// Finds a ligature in a `LigatureSubst` subtable that starts the given glyphs, returning the
+/// ligature glyph and the number of glyphs it replaces.
+fn ligature(subtable: &[u8], glyphs: &[u32]) -> Option<(u32, usize)> {
+    let first_glyph_id = u16::try_from(glyphs[0]).ok()?;
+    let mut reader = subtable;
+    let _format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let ligature_set_count = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, first_glyph_id)?;
+    if coverage_index >= ligature_set_count {
+        return None;
+    }
+    let mut reader = reader.get((coverage_index as usize * 2)..)?;
+    let ligature_set = subtable.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+
+    // Ligatures are listed in order of preference, so the first one that matches wins.
+    let mut reader = ligature_set;
+    let ligature_count = reader.read_u16::<BigEndian>().ok()?;
+    for _ in 0..ligature_count {
+        let ligature_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let mut ligature_reader = ligature_set.get(ligature_offset..)?;
+        let ligature_glyph = ligature_reader.read_u16::<BigEndian>().ok()?;
+        let component_count = ligature_reader.read_u16::<BigEndian>().ok()? as usize;
+        if component_count == 0 || component_count > glyphs.len() {
+            continue;
+        }
+        let mut matches = true;
+        for &glyph in &glyphs[1..component_count] {
+            if ligature_reader.read_u16::<BigEndian>().ok()? as u32 != glyph {
+                matches = false;
+                break;
+            }
+        }
+        if matches {
+            return Some((ligature_glyph as u32, component_count));
+        }
+    }
+    None
+}
+
+/// Returns the glyphs that a `MultipleSubst` subtable substitutes for the given one, if any.
+fn multiple_substitute(subtable: &[u8], glyph_id: u16) -> Option<Vec<u16>> {
+    let mut reader = subtable;
+    let _format = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let sequence_count = reader.read_u16::<BigEndian>().ok()?;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, glyph_id)?;
+    if coverage_index >= sequence_count {
+        return None;
+    }
+    let mut reader = reader.get((coverage_index as usize * 2)..)?;
+    let mut reader = subtable.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+    let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+    (0..glyph_count)
+        .map(|_| reader.read_u16::<BigEndian>().ok())
+        .collect()
+}
+
+/// Tries the subtables of a contextual or chaining contextual lookup at `glyphs[index]`, and
+/// applies the lookups of the first rule that matches there.
+///
+/// Returns the index just past the matched glyphs, as the nested lookups left them, or `None` if
+/// no rule matched.
+fn apply_contextual_lookup(
+    table: &LayoutTable,
+    lookup: &Lookup,
+    glyphs: &mut Vec<u32>,
+    index: usize,
+    depth: u32,
+) -> Option<usize> {
+    let rule = lookup.subtables.iter().find_map(|subtable| {
+        if lookup.lookup_type == LOOKUP_TYPE_CONTEXT {
+            context_rule(subtable, glyphs, index)
+        } else {
+            chained_context_rule(subtable, glyphs, index)
+        }
+    })?;
+
+    let mut end = index + rule.input_length();
+    let mut reader = rule.lookup_records;
+    for _ in 0..rule.lookup_record_count {
+        let sequence_index = reader.read_u16::<BigEndian>().ok()? as usize;
+        let lookup_index = reader.read_u16::<BigEndian>().ok()?;
+        let position = index + sequence_index;
+        if position >= end {
+            continue;
+        }
+        let glyph_count = glyphs.len();
+        apply_nested_lookup(table, lookup_index, glyphs, position, end, depth + 1);
+        // Nested contextual lookups can take glyphs past the end of this rule's input.
+        end = (end + glyphs.len())
+            .saturating_sub(glyph_count)
+            .min(glyphs.len());
+    }
+    Some(end)
+}
+
+/// Applies the lookup with the given index, as a contextual rule invokes it, to the glyph at
+/// `position`. Ligatures may only take glyphs before `end`.
+fn apply_nested_lookup(
+    table: &LayoutTable,
+    lookup_index: u16,
+    glyphs: &mut Vec<u32>,
+    position: usize,
+    end: usize,
+    depth: u32,
+) {
+    let lookup = match table.lookup(lookup_index, LOOKUP_TYPE_EXTENSION) {
+        Some(lookup) if depth <= MAX_NESTING_DEPTH => lookup,
+        _ => return,
+    };
+    if lookup.lookup_type == LOOKUP_TYPE_CONTEXT
+        || lookup.lookup_type == LOOKUP_TYPE_CHAINED_CONTEXT
+    {
+        apply_contextual_lookup(table, &lookup, glyphs, position, depth);
+        return;
+    }
+    if lookup.lookup_type == LOOKUP_TYPE_LIGATURE {
+        if let Some((ligature_glyph, component_count)) = lookup
+            .subtables
+            .iter()
+            .find_map(|subtable| ligature(subtable, &glyphs[position..end]))
+        {
+            glyphs.splice(position..(position + component_count), Some(ligature_glyph));
+        }
+        return;
+    }
+
+    let glyph_id = match u16::try_from(glyphs[position]) {
+        Ok(glyph_id) => glyph_id,
+        Err(_) => return,
+    };
+    // Alternate substitutions pick the first alternate, as they do when a feature is simply on.
+    let substitutes = lookup
+        .subtables
+        .iter()
+        .find_map(|subtable| match lookup.lookup_type {
+            LOOKUP_TYPE_SINGLE => single_substitute(subtable, glyph_id).map(|glyph| vec![glyph]),
+            LOOKUP_TYPE_MULTIPLE => multiple_substitute(subtable, glyph_id),
+            LOOKUP_TYPE_ALTERNATE => alternate_set(subtable, glyph_id)?
+                .get(..1)
+                .map(<[u16]>::to_vec),
+            _ => None,
+        });
+    if let Some(substitutes) = substitutes {
+        let substitutes = substitutes.into_iter().map(|glyph| glyph as u32);
+        glyphs.splice(position..(position + 1), substitutes);
+    }
+}
+
+/// Finds the first rule of a `ContextSubst` subtable that matches at `glyphs[index]`.
+fn context_rule<'a>(subtable: &'a [u8], glyphs: &[u32], index: usize) -> Option<Rule<'a>> {
+    let first_glyph_id = u16::try_from(glyphs[index]).ok()?;
+    let mut reader = subtable;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    if format == 3 {
+        let glyph_count = reader.read_u16::<BigEndian>().ok()? as usize;
+        let lookup_record_count = reader.read_u16::<BigEndian>().ok()?;
+        let rule = Rule {
+            backtrack: Sequence::EMPTY,
+            input: Sequence::read(&mut reader, glyph_count, Matcher::Coverages(subtable))?,
+            input_offset: 0,
+            lookahead: Sequence::EMPTY,
+            lookup_records: reader,
+            lookup_record_count,
+        };
+        return Some(rule).filter(|rule| rule.matches(glyphs, index));
+    }
+
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, first_glyph_id)?;
+    let (matcher, rule_set_index) = match format {
+        1 => (Matcher::Glyphs, coverage_index),
+        2 => {
+            let class_def_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            let class_def = subtable.get(class_def_offset..)?;
+            (
+                Matcher::Classes(class_def),
+                layout::glyph_class(class_def, first_glyph_id),
+            )
+        }
+        _ => return None,
+    };
+    let rule_set = rule_set(subtable, reader, rule_set_index)?;
+
+    let mut reader = rule_set;
+    let rule_count = reader.read_u16::<BigEndian>().ok()?;
+    for _ in 0..rule_count {
+        let mut rule_reader = rule_set.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+        let glyph_count = rule_reader.read_u16::<BigEndian>().ok()? as usize;
+        let lookup_record_count = rule_reader.read_u16::<BigEndian>().ok()?;
+        let input_count = glyph_count.checked_sub(1)?;
+        let rule = Rule {
+            backtrack: Sequence::EMPTY,
+            input: Sequence::read(&mut rule_reader, input_count, matcher)?,
+            input_offset: 1,
+            lookahead: Sequence::EMPTY,
+            lookup_records: rule_reader,
+            lookup_record_count,
+        };
+        if rule.matches(glyphs, index) {
+            return Some(rule);
+        }
+    }
+    None
+}
+
+/// Finds the first rule of a `ChainContextSubst` subtable that matches at `glyphs[index]`.
+fn chained_context_rule<'a>(subtable: &'a [u8], glyphs: &[u32], index: usize) -> Option<Rule<'a>> {
+    let first_glyph_id = u16::try_from(glyphs[index]).ok()?;
+    let mut reader = subtable;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    if format == 3 {
+        let matcher = Matcher::Coverages(subtable);
+        let backtrack = Sequence::read_counted(&mut reader, 0, matcher)?;
+        let input = Sequence::read_counted(&mut reader, 0, matcher)?;
+        let lookahead = Sequence::read_counted(&mut reader, 0, matcher)?;
+        let lookup_record_count = reader.read_u16::<BigEndian>().ok()?;
+        let rule = Rule {
+            backtrack,
+            input,
+            input_offset: 0,
+            lookahead,
+            lookup_records: reader,
+            lookup_record_count,
+        };
+        return Some(rule).filter(|rule| rule.matches(glyphs, index));
+    }
+
+    let coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+    let coverage_index = layout::coverage_index(subtable.get(coverage_offset..)?, first_glyph_id)?;
+    let (matchers, rule_set_index) = match format {
+        1 => ([Matcher::Glyphs; 3], coverage_index),
+        2 => {
+            let mut class_defs = [&[][..]; 3];
+            for class_def in &mut class_defs {
+                let class_def_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+                *class_def = subtable.get(class_def_offset..)?;
+            }
+            (
+                [
+                    Matcher::Classes(class_defs[0]),
+                    Matcher::Classes(class_defs[1]),
+                    Matcher::Classes(class_defs[2]),
+                ],
+                layout::glyph_class(class_defs[1], first_glyph_id),
+            )
+        }
+        _ => return None,
+    };
+    let rule_set = rule_set(subtable, reader, rule_set_index)?;
+
+    let mut reader = rule_set;
+    let rule_count = reader.read_u16::<BigEndian>().ok()?;
+    for _ in 0..rule_count {
+        let mut rule_reader = rule_set.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+        let backtrack = Sequence::read_counted(&mut rule_reader, 0, matchers[0])?;
+        let input = Sequence::read_counted(&mut rule_reader, 1, matchers[1])?;
+        let lookahead = Sequence::read_counted(&mut rule_reader, 0, matchers[2])?;
+        let lookup_record_count = rule_reader.read_u16::<BigEndian>().ok()?;
+        let rule = Rule {
+            backtrack,
+            input,
+            input_offset: 1,
+            lookahead,
+            lookup_records: rule_reader,
+            lookup_record_count,
+        };
+        if rule.matches(glyphs, index) {
+            return Some(rule);
+        }
+    }
+    None
+}
+
+/// Returns the rule set with the given index, from the array of rule set offsets that `reader`
+/// starts at. Rule sets with a null offset have no rules.
+fn rule_set<'a>(subtable: &'a [u8], mut reader: &[u8], rule_set_index: u16) -> Option<&'a [u8]> {
+    let rule_set_count = reader.read_u16::<BigEndian>().ok()?;
+    if rule_set_index >= rule_set_count {
+        return None;
+    }
+    let mut reader = reader.get((rule_set_index as usize * 2)..)?;
+    match reader.read_u16::<BigEndian>().ok()? {
+        0 => None,
+        rule_set_offset => subtable.get((rule_set_offset as usize)..),
+    }
+}
+
+/// A rule of a contextual subtable: the glyphs that it matches, and the lookups that it applies
+/// to them.
+#[derive(Clone, Copy)]
+struct Rule<'a> {
+    /// The glyphs before the input, nearest first.
+    backtrack: Sequence<'a>,
+    /// The input glyphs, after the first one if `input_offset` is 1.
+    input: Sequence<'a>,
+    /// 1 if the first input glyph is matched by the subtable's coverage table rather than by
+    /// `input`, or 0 otherwise.
+    input_offset: usize,
+    /// The glyphs after the input.
+    lookahead: Sequence<'a>,
+    /// The `(sequenceIndex, lookupListIndex)` records of the lookups to apply.
+    lookup_records: &'a [u8],
+    lookup_record_count: u16,
+}
+
+impl<'a> Rule<'a> {
+    fn input_length(&self) -> usize {
+        self.input_offset + self.input.count
+    }
+
+    fn matches(&self, glyphs: &[u32], index: usize) -> bool {
+        let input_end = index + self.input_length();
+        input_end <= glyphs.len()
+            && self.backtrack.matches(glyphs[..index].iter().rev())
+            && self
+                .input
+                .matches(glyphs[(index + self.input_offset)..input_end].iter())
+            && self.lookahead.matches(glyphs[input_end..].iter())
+    }
+}
+
+/// A sequence of glyphs that a rule matches, as an array of glyph IDs, classes, or coverage table
+/// offsets.
+#[derive(Clone, Copy)]
+struct Sequence<'a> {
+    values: &'a [u8],
+    count: usize,
+    matcher: Matcher<'a>,
+}
+
+/// How the values of a sequence describe glyphs.
+#[derive(Clone, Copy)]
+enum Matcher<'a> {
+    /// Each value is a glyph ID.
+    Glyphs,
+    /// Each value is a class of the given class definition table.
+    Classes(&'a [u8]),
+    /// Each value is the offset of a coverage table from the start of the given subtable.
+    Coverages(&'a [u8]),
+}
+
+impl<'a> Sequence<'a> {
+    const EMPTY: Sequence<'static> = Sequence {
+        values: &[],
+        count: 0,
+        matcher: Matcher::Glyphs,
+    };
+
+    /// Reads a sequence of `count` values, advancing `reader` past it.
+    fn read(reader: &mut &'a [u8], count: usize, matcher: Matcher<'a>) -> Option<Sequence<'a>> {
+        let values = reader.get(..(count * 2))?;
+        *reader = &reader[(count * 2)..];
+        Some(Sequence {
+            values,
+            count,
+            matcher,
+        })
+    }
+
+    /// Reads a sequence that is preceded by its length, advancing `reader` past it. The length
+    /// counts `implied` glyphs that aren't in the array.
+    fn read_counted(
+        reader: &mut &'a [u8],
+        implied: usize,
+        matcher: Matcher<'a>,
+    ) -> Option<Sequence<'a>> {
+        let count = reader.read_u16::<BigEndian>().ok()? as usize;
+        Sequence::read(reader, count.checked_sub(implied)?, matcher)
+    }
+
+    fn matches<'g, I>(&self, mut glyphs: I) -> bool
+    where
+        I: Iterator<Item = &'g u32>,
+    {
+        let mut values = self.values;
+        for _ in 0..self.count {
+            let (value, glyph_id) = match (values.read_u16::<BigEndian>(), glyphs.next()) {
+                (Ok(value), Some(&glyph_id)) => (value, glyph_id),
+                _ => return false,
+            };
+            let glyph_id = match u16::try_from(glyph_id) {
+                Ok(glyph_id) => glyph_id,
+                Err(_) => return false,
+            };
+            let matches = match self.matcher {
+                Matcher::Glyphs => value == glyph_id,
+                Matcher::Classes(class_def) => layout::glyph_class(class_def, glyph_id) == value,
+                Matcher::Coverages(subtable) => subtable
+                    .get((value as usize)..)
+                    .and_then(|coverage| layout::coverage_index(coverage, glyph_id))
+                    .is_some(),
+            };
+            if !matches {
+                return false;
+            }
+        }
+        true
+    }
+}
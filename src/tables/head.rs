@@ -0,0 +1,28 @@
+// font-kit/src/tables/head.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The font header table, `head`, which holds global information about the font.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/head
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"head");
+
+const UNITS_PER_EM_OFFSET: usize = 18;
+
+/// Returns the `unitsPerEm` field of the `head` table.
+pub(crate) fn units_per_em(data: &[u8]) -> Option<u16> {
+    data.get(UNITS_PER_EM_OFFSET..)?
+        .read_u16::<BigEndian>()
+        .ok()
+}
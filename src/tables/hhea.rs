@@ -0,0 +1,47 @@
+// font-kit/src/tables/hhea.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The horizontal header table, `hhea`, which holds the font-wide metrics for horizontal layout.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/hhea
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"hhea");
+
+const ASCENDER_OFFSET: usize = 4;
+const CARET_SLOPE_RISE_OFFSET: usize = 18;
+const NUMBER_OF_H_METRICS_OFFSET: usize = 34;
+
+/// Returns the `ascender`, `descender`, and `lineGap` fields of the `hhea` table.
+pub(crate) fn line_metrics(data: &[u8]) -> Option<(i16, i16, i16)> {
+    let mut reader = data.get(ASCENDER_OFFSET..)?;
+    let ascender = reader.read_i16::<BigEndian>().ok()?;
+    let descender = reader.read_i16::<BigEndian>().ok()?;
+    let line_gap = reader.read_i16::<BigEndian>().ok()?;
+    Some((ascender, descender, line_gap))
+}
+
+/// Returns the `caretSlopeRise` and `caretSlopeRun` fields of the `hhea` table.
+pub(crate) fn caret_slope(data: &[u8]) -> Option<(i16, i16)> {
+    let mut reader = data.get(CARET_SLOPE_RISE_OFFSET..)?;
+    let rise = reader.read_i16::<BigEndian>().ok()?;
+    let run = reader.read_i16::<BigEndian>().ok()?;
+    Some((rise, run))
+}
+
+/// Returns the `numberOfHMetrics` field of the `hhea` table, the number of long metrics in `hmtx`.
+pub(crate) fn long_metric_count(data: &[u8]) -> Option<u16> {
+    data.get(NUMBER_OF_H_METRICS_OFFSET..)?
+        .read_u16::<BigEndian>()
+        .ok()
+}
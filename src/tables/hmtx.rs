@@ -0,0 +1,27 @@
+// font-kit/src/tables/hmtx.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The horizontal metrics table, `hmtx`, which holds each glyph's advance width and left side
+//! bearing.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/hmtx
+
+use crate::tables::{self, vmtx};
+
+pub(crate) const TAG: u32 = tables::tag(b"hmtx");
+
+/// Returns the advance width and left side bearing of a glyph, given the number of long metrics
+/// from the `hhea` table.
+///
+/// The table is laid out exactly like `vmtx`.
+#[inline]
+pub(crate) fn metric(data: &[u8], long_metric_count: u16, glyph_id: u32) -> Option<(u16, i16)> {
+    vmtx::metric(data, long_metric_count, glyph_id)
+}
@@ -0,0 +1,105 @@
+// font-kit/src/tables/kern.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The legacy `kern` table, in both its Microsoft and Apple variants.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/kern
+//! https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kern.html
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"kern");
+
+const MICROSOFT_COVERAGE_HORIZONTAL: u16 = 0x0001;
+const MICROSOFT_COVERAGE_MINIMUM: u16 = 0x0002;
+const MICROSOFT_COVERAGE_CROSS_STREAM: u16 = 0x0004;
+const MICROSOFT_COVERAGE_OVERRIDE: u16 = 0x0008;
+
+const APPLE_COVERAGE_VERTICAL: u16 = 0x8000;
+const APPLE_COVERAGE_CROSS_STREAM: u16 = 0x4000;
+const APPLE_COVERAGE_VARIATION: u16 = 0x2000;
+
+const KERNING_PAIR_SIZE: usize = 6;
+
+/// Returns the horizontal kerning of the given pair in font units, summed over all format 0
+/// subtables that apply to horizontal text.
+pub(crate) fn kerning(data: &[u8], left_glyph_id: u16, right_glyph_id: u16) -> Option<i16> {
+    let mut reader = data;
+    let version = reader.read_u16::<BigEndian>().ok()?;
+    let is_apple = version == 1;
+    let table_count = if is_apple {
+        let _version_low = reader.read_u16::<BigEndian>().ok()?;
+        reader.read_u32::<BigEndian>().ok()?
+    } else {
+        reader.read_u16::<BigEndian>().ok()? as u32
+    };
+
+    let mut total = 0i16;
+    for _ in 0..table_count {
+        let subtable = reader;
+        let (length, format, applies, overrides) = if is_apple {
+            let length = reader.read_u32::<BigEndian>().ok()? as usize;
+            let coverage = reader.read_u16::<BigEndian>().ok()?;
+            let _tuple_index = reader.read_u16::<BigEndian>().ok()?;
+            let excluded =
+                APPLE_COVERAGE_VERTICAL | APPLE_COVERAGE_CROSS_STREAM | APPLE_COVERAGE_VARIATION;
+            (length, coverage & 0xff, (coverage & excluded) == 0, false)
+        } else {
+            let _version = reader.read_u16::<BigEndian>().ok()?;
+            let length = reader.read_u16::<BigEndian>().ok()? as usize;
+            let coverage = reader.read_u16::<BigEndian>().ok()?;
+            let excluded = MICROSOFT_COVERAGE_MINIMUM | MICROSOFT_COVERAGE_CROSS_STREAM;
+            let applies =
+                (coverage & MICROSOFT_COVERAGE_HORIZONTAL) != 0 && (coverage & excluded) == 0;
+            let overrides = (coverage & MICROSOFT_COVERAGE_OVERRIDE) != 0;
+            (length, coverage >> 8, applies, overrides)
+        };
+        if applies && format == 0 {
+            if let Some(value) = format_0_value(reader, left_glyph_id, right_glyph_id) {
+                total = if overrides {
+                    value
+                } else {
+                    total.wrapping_add(value)
+                };
+            }
+        }
+        // Some fonts overflow the 16-bit length of the Microsoft variant, so stop gracefully if
+        // the next subtable would be out of bounds.
+        reader = match subtable.get(length..) {
+            Some(next) if length > 0 => next,
+            _ => break,
+        };
+    }
+    Some(total)
+}
+
+fn format_0_value(mut reader: &[u8], left_glyph_id: u16, right_glyph_id: u16) -> Option<i16> {
+    let pair_count = reader.read_u16::<BigEndian>().ok()? as usize;
+    let reader = reader.get(6..)?;
+    let key = ((left_glyph_id as u32) << 16) | right_glyph_id as u32;
+
+    // Pairs are sorted by the left and right glyph IDs, taken together.
+    let (mut low, mut high) = (0, pair_count);
+    while low < high {
+        let mid = (low + high) / 2;
+        let mut reader = reader.get((mid * KERNING_PAIR_SIZE)..)?;
+        let pair_key = reader.read_u32::<BigEndian>().ok()?;
+        if key < pair_key {
+            high = mid
+        } else if key > pair_key {
+            low = mid + 1
+        } else {
+            return reader.read_i16::<BigEndian>().ok();
+        }
+    }
+    None
+}
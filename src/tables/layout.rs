@@ -0,0 +1,308 @@
+// font-kit/src/tables/layout.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The structures shared by the OpenType layout tables, `GSUB` and `GPOS`.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+/// A view of the header of a `GSUB` or `GPOS` table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LayoutTable<'a> {
+    data: &'a [u8],
+    script_list_offset: usize,
+    feature_list_offset: usize,
+    lookup_list_offset: usize,
+}
+
+/// A lookup, with its subtables.
+#[derive(Clone, Debug)]
+pub(crate) struct Lookup<'a> {
+    /// The type of the lookup. For extension lookups, this is the type of the wrapped subtables.
+    pub(crate) lookup_type: u16,
+    pub(crate) subtables: Vec<&'a [u8]>,
+}
+
+impl<'a> LayoutTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<LayoutTable<'a>> {
+        let mut reader = data;
+        let major_version = reader.read_u16::<BigEndian>().ok()?;
+        let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+        if major_version != 1 {
+            return None;
+        }
+        Some(LayoutTable {
+            data,
+            script_list_offset: reader.read_u16::<BigEndian>().ok()? as usize,
+            feature_list_offset: reader.read_u16::<BigEndian>().ok()? as usize,
+            lookup_list_offset: reader.read_u16::<BigEndian>().ok()? as usize,
+        })
+    }
+
+    /// Returns the indices of the lookups referenced by every feature with one of the given tags,
+    /// in lookup list order and without duplicates.
+    ///
+    /// Features are gathered across all scripts and language systems.
+    pub(crate) fn feature_lookup_indices(&self, feature_tags: &[u32]) -> Vec<u16> {
+        let mut lookup_indices = vec![];
+        let _ = self.for_each_feature(|tag, feature| {
+            if feature_tags.contains(&tag) {
+                let mut reader = feature;
+                let _feature_params_offset = reader.read_u16::<BigEndian>().ok()?;
+                let lookup_index_count = reader.read_u16::<BigEndian>().ok()?;
+                for _ in 0..lookup_index_count {
+                    lookup_indices.push(reader.read_u16::<BigEndian>().ok()?);
+                }
+            }
+            Some(())
+        });
+        lookup_indices.sort_unstable();
+        lookup_indices.dedup();
+        lookup_indices
+    }
+
+    fn for_each_feature<F>(&self, mut callback: F) -> Option<()>
+    where
+        F: FnMut(u32, &'a [u8]) -> Option<()>,
+    {
+        let feature_list = self.data.get(self.feature_list_offset..)?;
+        let mut reader = feature_list;
+        let feature_count = reader.read_u16::<BigEndian>().ok()?;
+        for _ in 0..feature_count {
+            let feature_tag = reader.read_u32::<BigEndian>().ok()?;
+            let feature_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            callback(feature_tag, feature_list.get(feature_offset..)?)?;
+        }
+        Some(())
+    }
+
+    /// Returns the tags of the features of every language system in the script list, as
+    /// `(script tag, language tag, feature tags)` triples.
+    ///
+    /// The language tag of each script's default language system is `None`. Feature tags are
+    /// listed in feature list order, the required feature included, and indices that fall outside
+    /// the feature list are skipped.
+    pub(crate) fn language_systems(&self) -> Vec<(u32, Option<u32>, Vec<u32>)> {
+        let mut feature_tags = vec![];
+        let _ = self.for_each_feature(|tag, _| {
+            feature_tags.push(tag);
+            Some(())
+        });
+
+        let mut language_systems = vec![];
+        let _ = self.for_each_language_system(|script_tag, language_tag, lang_sys| {
+            let mut reader = lang_sys;
+            let _lookup_order_offset = reader.read_u16::<BigEndian>().ok()?;
+            let required_feature_index = reader.read_u16::<BigEndian>().ok()?;
+            let feature_index_count = reader.read_u16::<BigEndian>().ok()?;
+            let mut feature_indices = Vec::with_capacity(feature_index_count as usize + 1);
+            if required_feature_index != 0xffff {
+                feature_indices.push(required_feature_index);
+            }
+            for _ in 0..feature_index_count {
+                feature_indices.push(reader.read_u16::<BigEndian>().ok()?);
+            }
+            feature_indices.sort_unstable();
+            feature_indices.dedup();
+
+            let tags = feature_indices
+                .into_iter()
+                .filter_map(|index| feature_tags.get(index as usize).cloned())
+                .collect();
+            language_systems.push((script_tag, language_tag, tags));
+            Some(())
+        });
+        language_systems
+    }
+
+    fn for_each_language_system<F>(&self, mut callback: F) -> Option<()>
+    where
+        F: FnMut(u32, Option<u32>, &'a [u8]) -> Option<()>,
+    {
+        let script_list = self.data.get(self.script_list_offset..)?;
+        let mut reader = script_list;
+        let script_count = reader.read_u16::<BigEndian>().ok()?;
+        for _ in 0..script_count {
+            let script_tag = reader.read_u32::<BigEndian>().ok()?;
+            let script = script_list.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+
+            let mut script_reader = script;
+            let default_lang_sys_offset = script_reader.read_u16::<BigEndian>().ok()? as usize;
+            if default_lang_sys_offset != 0 {
+                callback(script_tag, None, script.get(default_lang_sys_offset..)?)?;
+            }
+            let lang_sys_count = script_reader.read_u16::<BigEndian>().ok()?;
+            for _ in 0..lang_sys_count {
+                let language_tag = script_reader.read_u32::<BigEndian>().ok()?;
+                let lang_sys_offset = script_reader.read_u16::<BigEndian>().ok()? as usize;
+                callback(
+                    script_tag,
+                    Some(language_tag),
+                    script.get(lang_sys_offset..)?,
+                )?;
+            }
+        }
+        Some(())
+    }
+
+    /// Returns the number of lookups in the lookup list.
+    pub(crate) fn lookup_count(&self) -> u16 {
+        self.data
+            .get(self.lookup_list_offset..)
+            .and_then(|mut reader| reader.read_u16::<BigEndian>().ok())
+            .unwrap_or(0)
+    }
+
+    /// Returns the lookup at the given index.
+    ///
+    /// Subtables of extension lookups, whose type is `extension_lookup_type`, are unwrapped.
+    pub(crate) fn lookup(
+        &self,
+        lookup_index: u16,
+        extension_lookup_type: u16,
+    ) -> Option<Lookup<'a>> {
+        let lookup_list = self.data.get(self.lookup_list_offset..)?;
+        let mut reader = lookup_list;
+        let lookup_count = reader.read_u16::<BigEndian>().ok()?;
+        if lookup_index >= lookup_count {
+            return None;
+        }
+        let mut reader = reader.get((lookup_index as usize * 2)..)?;
+        let lookup_data = lookup_list.get((reader.read_u16::<BigEndian>().ok()? as usize)..)?;
+
+        let mut reader = lookup_data;
+        let mut lookup_type = reader.read_u16::<BigEndian>().ok()?;
+        let _lookup_flag = reader.read_u16::<BigEndian>().ok()?;
+        let subtable_count = reader.read_u16::<BigEndian>().ok()?;
+        let mut subtables = Vec::with_capacity(subtable_count as usize);
+        for _ in 0..subtable_count {
+            let subtable_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            subtables.push(lookup_data.get(subtable_offset..)?);
+        }
+
+        if lookup_type == extension_lookup_type {
+            for subtable in &mut subtables {
+                let mut reader = *subtable;
+                let _format = reader.read_u16::<BigEndian>().ok()?;
+                lookup_type = reader.read_u16::<BigEndian>().ok()?;
+                let extension_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+                *subtable = subtable.get(extension_offset..)?;
+            }
+        }
+
+        Some(Lookup {
+            lookup_type,
+            subtables,
+        })
+    }
+}
+
+/// Returns the coverage index of the glyph in the given coverage table, if the glyph is covered.
+pub(crate) fn coverage_index(coverage: &[u8], glyph_id: u16) -> Option<u16> {
+    let mut reader = coverage;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    let count = reader.read_u16::<BigEndian>().ok()? as usize;
+    match format {
+        1 => {
+            let (mut low, mut high) = (0, count);
+            while low < high {
+                let mid = (low + high) / 2;
+                let covered_glyph_id = reader.get((mid * 2)..)?.read_u16::<BigEndian>().ok()?;
+                if glyph_id < covered_glyph_id {
+                    high = mid
+                } else if glyph_id > covered_glyph_id {
+                    low = mid + 1
+                } else {
+                    return Some(mid as u16);
+                }
+            }
+            None
+        }
+        2 => {
+            let range = range_record(reader, count, glyph_id)?;
+            Some(range.2 + (glyph_id - range.0))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the glyphs in the given coverage table, in coverage index order.
+pub(crate) fn covered_glyphs(coverage: &[u8]) -> Option<Vec<u16>> {
+    let mut reader = coverage;
+    let format = reader.read_u16::<BigEndian>().ok()?;
+    let count = reader.read_u16::<BigEndian>().ok()?;
+    let mut glyphs = vec![];
+    match format {
+        1 => {
+            for _ in 0..count {
+                glyphs.push(reader.read_u16::<BigEndian>().ok()?);
+            }
+        }
+        2 => {
+            // Ranges are sorted by start glyph ID, so their coverage indices ascend too.
+            for _ in 0..count {
+                let start_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+                let end_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+                let _start_coverage_index = reader.read_u16::<BigEndian>().ok()?;
+                glyphs.extend(start_glyph_id..=end_glyph_id);
+            }
+        }
+        _ => return None,
+    }
+    Some(glyphs)
+}
+
+/// Returns the class of the glyph in the given class definition table.
+///
+/// Glyphs that the table doesn't mention are in class 0.
+pub(crate) fn glyph_class(class_def: &[u8], glyph_id: u16) -> u16 {
+    glyph_class_opt(class_def, glyph_id).unwrap_or(0)
+}
+
+fn glyph_class_opt(class_def: &[u8], glyph_id: u16) -> Option<u16> {
+    let mut reader = class_def;
+    match reader.read_u16::<BigEndian>().ok()? {
+        1 => {
+            let start_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+            let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+            if glyph_id < start_glyph_id || glyph_id - start_glyph_id >= glyph_count {
+                return None;
+            }
+            let mut reader = reader.get(((glyph_id - start_glyph_id) as usize * 2)..)?;
+            reader.read_u16::<BigEndian>().ok()
+        }
+        2 => {
+            let count = reader.read_u16::<BigEndian>().ok()? as usize;
+            Some(range_record(reader, count, glyph_id)?.2)
+        }
+        _ => None,
+    }
+}
+
+/// Binary searches an array of `(start, end, value)` range records for the given glyph.
+fn range_record(records: &[u8], count: usize, glyph_id: u16) -> Option<(u16, u16, u16)> {
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = (low + high) / 2;
+        let mut reader = records.get((mid * 6)..)?;
+        let start_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+        let end_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+        if glyph_id < start_glyph_id {
+            high = mid
+        } else if glyph_id > end_glyph_id {
+            low = mid + 1
+        } else {
+            let value = reader.read_u16::<BigEndian>().ok()?;
+            return Some((start_glyph_id, end_glyph_id, value));
+        }
+    }
+    None
+}
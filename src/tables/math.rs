@@ -0,0 +1,165 @@
+// font-kit/src/tables/math.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `MATH` table, which holds the constants and glyph information for math layout.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/math
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::math::{GlyphVariant, MathConstants, StretchDirection};
+use crate::tables;
+use crate::tables::layout;
+
+pub(crate) const TAG: u32 = tables::tag(b"MATH");
+
+/// A view of the header of a `MATH` table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MathTable<'a> {
+    data: &'a [u8],
+    math_constants_offset: usize,
+    math_variants_offset: usize,
+}
+
+impl<'a> MathTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<MathTable<'a>> {
+        let mut reader = data;
+        let major_version = reader.read_u16::<BigEndian>().ok()?;
+        let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+        if major_version != 1 {
+            return None;
+        }
+        let math_constants_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let _math_glyph_info_offset = reader.read_u16::<BigEndian>().ok()?;
+        Some(MathTable {
+            data,
+            math_constants_offset,
+            math_variants_offset: reader.read_u16::<BigEndian>().ok()? as usize,
+        })
+    }
+
+    pub(crate) fn constants(&self) -> Option<MathConstants> {
+        if self.math_constants_offset == 0 {
+            return None;
+        }
+        let mut reader = self.data.get(self.math_constants_offset..)?;
+        Some(MathConstants {
+            script_percent_scale_down: reader.read_i16::<BigEndian>().ok()?,
+            script_script_percent_scale_down: reader.read_i16::<BigEndian>().ok()?,
+            delimited_sub_formula_min_height: reader.read_u16::<BigEndian>().ok()? as f32,
+            display_operator_min_height: reader.read_u16::<BigEndian>().ok()? as f32,
+            math_leading: math_value(&mut reader)?,
+            axis_height: math_value(&mut reader)?,
+            accent_base_height: math_value(&mut reader)?,
+            flattened_accent_base_height: math_value(&mut reader)?,
+            subscript_shift_down: math_value(&mut reader)?,
+            subscript_top_max: math_value(&mut reader)?,
+            subscript_baseline_drop_min: math_value(&mut reader)?,
+            superscript_shift_up: math_value(&mut reader)?,
+            superscript_shift_up_cramped: math_value(&mut reader)?,
+            superscript_bottom_min: math_value(&mut reader)?,
+            superscript_baseline_drop_max: math_value(&mut reader)?,
+            sub_superscript_gap_min: math_value(&mut reader)?,
+            superscript_bottom_max_with_subscript: math_value(&mut reader)?,
+            space_after_script: math_value(&mut reader)?,
+            upper_limit_gap_min: math_value(&mut reader)?,
+            upper_limit_baseline_rise_min: math_value(&mut reader)?,
+            lower_limit_gap_min: math_value(&mut reader)?,
+            lower_limit_baseline_drop_min: math_value(&mut reader)?,
+            stack_top_shift_up: math_value(&mut reader)?,
+            stack_top_display_style_shift_up: math_value(&mut reader)?,
+            stack_bottom_shift_down: math_value(&mut reader)?,
+            stack_bottom_display_style_shift_down: math_value(&mut reader)?,
+            stack_gap_min: math_value(&mut reader)?,
+            stack_display_style_gap_min: math_value(&mut reader)?,
+            stretch_stack_top_shift_up: math_value(&mut reader)?,
+            stretch_stack_bottom_shift_down: math_value(&mut reader)?,
+            stretch_stack_gap_above_min: math_value(&mut reader)?,
+            stretch_stack_gap_below_min: math_value(&mut reader)?,
+            fraction_numerator_shift_up: math_value(&mut reader)?,
+            fraction_numerator_display_style_shift_up: math_value(&mut reader)?,
+            fraction_denominator_shift_down: math_value(&mut reader)?,
+            fraction_denominator_display_style_shift_down: math_value(&mut reader)?,
+            fraction_numerator_gap_min: math_value(&mut reader)?,
+            fraction_num_display_style_gap_min: math_value(&mut reader)?,
+            fraction_rule_thickness: math_value(&mut reader)?,
+            fraction_denominator_gap_min: math_value(&mut reader)?,
+            fraction_denom_display_style_gap_min: math_value(&mut reader)?,
+            skewed_fraction_horizontal_gap: math_value(&mut reader)?,
+            skewed_fraction_vertical_gap: math_value(&mut reader)?,
+            overbar_vertical_gap: math_value(&mut reader)?,
+            overbar_rule_thickness: math_value(&mut reader)?,
+            overbar_extra_ascender: math_value(&mut reader)?,
+            underbar_vertical_gap: math_value(&mut reader)?,
+            underbar_rule_thickness: math_value(&mut reader)?,
+            underbar_extra_descender: math_value(&mut reader)?,
+            radical_vertical_gap: math_value(&mut reader)?,
+            radical_display_style_vertical_gap: math_value(&mut reader)?,
+            radical_rule_thickness: math_value(&mut reader)?,
+            radical_extra_ascender: math_value(&mut reader)?,
+            radical_kern_before_degree: math_value(&mut reader)?,
+            radical_kern_after_degree: math_value(&mut reader)?,
+            radical_degree_bottom_raise_percent: reader.read_i16::<BigEndian>().ok()?,
+        })
+    }
+
+    /// Returns the prebuilt variants of the given glyph in the given direction, from smallest to
+    /// largest.
+    ///
+    /// The glyph itself is usually the first variant.
+    pub(crate) fn glyph_variants(
+        &self,
+        glyph_id: u16,
+        direction: StretchDirection,
+    ) -> Option<Vec<GlyphVariant>> {
+        if self.math_variants_offset == 0 {
+            return None;
+        }
+        let math_variants = self.data.get(self.math_variants_offset..)?;
+        let mut reader = math_variants;
+        let _min_connector_overlap = reader.read_u16::<BigEndian>().ok()?;
+        let vert_glyph_coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let horiz_glyph_coverage_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let vert_glyph_count = reader.read_u16::<BigEndian>().ok()? as usize;
+        let _horiz_glyph_count = reader.read_u16::<BigEndian>().ok()?;
+
+        // The construction offsets of the horizontal glyphs follow those of the vertical ones.
+        let (coverage_offset, first_construction) = match direction {
+            StretchDirection::Vertical => (vert_glyph_coverage_offset, 0),
+            StretchDirection::Horizontal => (horiz_glyph_coverage_offset, vert_glyph_count),
+        };
+        if coverage_offset == 0 {
+            return None;
+        }
+        let coverage_index =
+            layout::coverage_index(math_variants.get(coverage_offset..)?, glyph_id)? as usize;
+        let mut reader = reader.get(((first_construction + coverage_index) * 2)..)?;
+        let construction_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+
+        let mut reader = math_variants.get(construction_offset..)?;
+        let _glyph_assembly_offset = reader.read_u16::<BigEndian>().ok()?;
+        let variant_count = reader.read_u16::<BigEndian>().ok()?;
+        let mut variants = Vec::with_capacity(variant_count as usize);
+        for _ in 0..variant_count {
+            variants.push(GlyphVariant {
+                glyph_id: reader.read_u16::<BigEndian>().ok()? as u32,
+                advance: reader.read_u16::<BigEndian>().ok()? as f32,
+            });
+        }
+        Some(variants)
+    }
+}
+
+/// Reads a `MathValueRecord`, ignoring its device table.
+fn math_value(reader: &mut &[u8]) -> Option<f32> {
+    let value = reader.read_i16::<BigEndian>().ok()?;
+    let _device_offset = reader.read_u16::<BigEndian>().ok()?;
+    Some(value as f32)
+}
@@ -0,0 +1,51 @@
+// font-kit/src/tables/maxp.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The maximum profile table, `maxp`, which holds the number of glyphs and, for TrueType
+//! outlines, the limits that the glyphs stay within.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/maxp
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::metrics::MaxpLimits;
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"maxp");
+
+/// The version of the table that has the limits. Version 0.5, used by fonts with CFF outlines,
+/// only has the number of glyphs.
+const VERSION_1_0: u32 = 0x0001_0000;
+
+const MAX_POINTS_OFFSET: usize = 6;
+const MAX_COMPONENT_ELEMENTS_OFFSET: usize = 28;
+
+/// Returns the outline limits of a version 1.0 table, or `None` for version 0.5.
+pub(crate) fn limits(data: &[u8]) -> Option<MaxpLimits> {
+    if data.get(0..4)?.read_u32::<BigEndian>().ok()? != VERSION_1_0 {
+        return None;
+    }
+    let mut reader = data.get(MAX_POINTS_OFFSET..)?;
+    let max_points = reader.read_u16::<BigEndian>().ok()?;
+    let max_contours = reader.read_u16::<BigEndian>().ok()?;
+    let max_composite_points = reader.read_u16::<BigEndian>().ok()?;
+    let max_composite_contours = reader.read_u16::<BigEndian>().ok()?;
+    let mut reader = data.get(MAX_COMPONENT_ELEMENTS_OFFSET..)?;
+    let max_component_elements = reader.read_u16::<BigEndian>().ok()?;
+    let max_component_depth = reader.read_u16::<BigEndian>().ok()?;
+    Some(MaxpLimits {
+        max_points,
+        max_contours,
+        max_composite_points,
+        max_composite_contours,
+        max_component_elements,
+        max_component_depth,
+    })
+}
@@ -0,0 +1,59 @@
+// font-kit/src/tables/mod.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Loader-independent parsers for OpenType tables.
+//!
+//! These operate on the raw table data returned by `Loader::load_font_table()`, so that features
+//! the native APIs don't expose uniformly work the same way on every backend.
+
+pub(crate) mod base;
+pub(crate) mod cbdt;
+pub(crate) mod cblc;
+pub(crate) mod cff;
+pub(crate) mod cmap;
+pub(crate) mod colr;
+pub(crate) mod cpal;
+pub(crate) mod dsig;
+pub(crate) mod fvar;
+pub(crate) mod gdef;
+pub(crate) mod glyf;
+pub(crate) mod gpos;
+pub(crate) mod gsub;
+pub(crate) mod head;
+pub(crate) mod hhea;
+pub(crate) mod hmtx;
+pub(crate) mod kern;
+pub(crate) mod layout;
+pub(crate) mod math;
+pub(crate) mod maxp;
+pub(crate) mod name;
+pub(crate) mod os_2;
+pub(crate) mod post;
+pub(crate) mod sbix;
+pub(crate) mod svg;
+pub(crate) mod vhea;
+pub(crate) mod vmtx;
+pub(crate) mod vorg;
+
+/// Packs a four-character OpenType tag into the `u32` form that `load_font_table()` expects.
+#[inline]
+pub(crate) const fn tag(bytes: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*bytes)
+}
+
+#[inline]
+pub(crate) fn fixed_16_16_to_f32(fixed: i32) -> f32 {
+    fixed as f32 / 65536.0
+}
+
+/// Unpacks a four-character OpenType tag, for display.
+pub(crate) fn tag_to_string(tag: u32) -> String {
+    tag.to_be_bytes().iter().map(|&byte| byte as char).collect()
+}
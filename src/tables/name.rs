@@ -0,0 +1,369 @@
+// font-kit/src/tables/name.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `name` table, which holds the human-readable strings of a font.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/name
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"name");
+
+pub(crate) const PLATFORM_UNICODE: u16 = 0;
+pub(crate) const PLATFORM_MACINTOSH: u16 = 1;
+pub(crate) const PLATFORM_WINDOWS: u16 = 3;
+
+pub(crate) const FAMILY_NAME_ID: u16 = 1;
+pub(crate) const FULL_NAME_ID: u16 = 4;
+pub(crate) const TYPOGRAPHIC_FAMILY_NAME_ID: u16 = 16;
+
+const MACINTOSH_ENCODING_ROMAN: u16 = 0;
+const MACINTOSH_LANGUAGE_ENGLISH: u16 = 0;
+const WINDOWS_LANGUAGE_ENGLISH_US: u16 = 0x0409;
+
+const RECORD_SIZE: usize = 12;
+
+/// A single record of the `name` table.
+#[derive(Clone, Debug)]
+pub(crate) struct NameRecord {
+    pub(crate) platform_id: u16,
+    pub(crate) encoding_id: u16,
+    pub(crate) language_id: u16,
+    pub(crate) name_id: u16,
+    /// The decoded string, or `None` if the record uses an encoding we can't decode.
+    pub(crate) string: Option<String>,
+}
+
+/// The parsed contents of a `name` table.
+#[derive(Clone, Debug)]
+pub(crate) struct NameTable {
+    pub(crate) records: Vec<NameRecord>,
+}
+
+impl NameTable {
+    pub(crate) fn parse(data: &[u8]) -> Option<NameTable> {
+        let mut reader = data;
+        let _format = reader.read_u16::<BigEndian>().ok()?;
+        let count = reader.read_u16::<BigEndian>().ok()? as usize;
+        let string_offset = reader.read_u16::<BigEndian>().ok()? as usize;
+        let storage = data.get(string_offset..)?;
+
+        let mut records = Vec::with_capacity(count);
+        for record_index in 0..count {
+            let mut reader = data.get((6 + record_index * RECORD_SIZE)..)?;
+            let platform_id = reader.read_u16::<BigEndian>().ok()?;
+            let encoding_id = reader.read_u16::<BigEndian>().ok()?;
+            let language_id = reader.read_u16::<BigEndian>().ok()?;
+            let name_id = reader.read_u16::<BigEndian>().ok()?;
+            let length = reader.read_u16::<BigEndian>().ok()? as usize;
+            let offset = reader.read_u16::<BigEndian>().ok()? as usize;
+            let string = storage
+                .get(offset..(offset + length))
+                .and_then(|bytes| decode(platform_id, encoding_id, bytes));
+            records.push(NameRecord {
+                platform_id,
+                encoding_id,
+                language_id,
+                name_id,
+                string,
+            });
+        }
+
+        Some(NameTable { records })
+    }
+
+    /// Returns every decodable string with the given name ID, in any language.
+    pub(crate) fn names(&self, name_id: u16) -> impl Iterator<Item = &str> {
+        self.records
+            .iter()
+            .filter(move |record| record.name_id == name_id)
+            .filter_map(|record| record.string.as_deref())
+    }
+
+    /// Returns the string with the given name ID, preferring US English.
+    ///
+    /// If there is no English record, the first decodable record with that ID is returned.
+    pub(crate) fn english_name(&self, name_id: u16) -> Option<String> {
+        let candidates = || {
+            self.records
+                .iter()
+                .filter(move |record| record.name_id == name_id && record.string.is_some())
+        };
+        candidates()
+            .find(|record| {
+                record.platform_id == PLATFORM_WINDOWS
+                    && record.language_id == WINDOWS_LANGUAGE_ENGLISH_US
+            })
+            .or_else(|| candidates().find(|record| record.platform_id == PLATFORM_UNICODE))
+            .or_else(|| {
+                candidates().find(|record| {
+                    record.platform_id == PLATFORM_MACINTOSH
+                        && record.language_id == MACINTOSH_LANGUAGE_ENGLISH
+                })
+            })
+            .or_else(|| candidates().next())
+            .and_then(|record| record.string.clone())
+    }
+
+    /// Returns the string with the given name ID in the language of a BCP 47 locale, such as
+    /// `ja` or `zh-Hant-TW`, or `None` if the table has no record in that language.
+    ///
+    /// Windows records for the exact locale are preferred, then Windows records for any region
+    /// of the same language, and then Macintosh records.
+    pub(crate) fn localized_name(&self, name_id: u16, locale: &str) -> Option<String> {
+        let keys = locale_keys(locale);
+        let windows_language_ids: Vec<u16> = keys
+            .iter()
+            .filter_map(|key| lookup_language_id(WINDOWS_LANGUAGE_IDS, key))
+            .collect();
+        let macintosh_language_id = keys
+            .iter()
+            .find_map(|key| lookup_language_id(MACINTOSH_LANGUAGE_IDS, key));
+
+        let candidates = || {
+            self.records
+                .iter()
+                .filter(move |record| record.name_id == name_id && record.string.is_some())
+        };
+        let windows_record = |language_id: u16| {
+            candidates().find(|record| {
+                record.platform_id == PLATFORM_WINDOWS && record.language_id == language_id
+            })
+        };
+        windows_language_ids
+            .iter()
+            .find_map(|&language_id| windows_record(language_id))
+            .or_else(|| {
+                // The low 10 bits of a Windows language ID are the primary language.
+                let primary_language_id = windows_language_ids.last()? & 0x3ff;
+                candidates().find(|record| {
+                    record.platform_id == PLATFORM_WINDOWS
+                        && record.language_id & 0x3ff == primary_language_id
+                })
+            })
+            .or_else(|| {
+                let language_id = macintosh_language_id?;
+                candidates().find(|record| {
+                    record.platform_id == PLATFORM_MACINTOSH && record.language_id == language_id
+                })
+            })
+            .and_then(|record| record.string.clone())
+    }
+}
+
+/// Returns the lookup keys for a BCP 47 locale, most specific first: language and region, then
+/// language and script, then the language alone.
+///
+/// For example, `zh-hant_tw` yields `zh-TW`, `zh-Hant`, and `zh`.
+fn locale_keys(locale: &str) -> Vec<String> {
+    let mut subtags = locale.split(['-', '_']);
+    let language = match subtags.next() {
+        Some(language) if !language.is_empty() => language.to_ascii_lowercase(),
+        _ => return vec![],
+    };
+    let (mut script, mut region) = (None, None);
+    for subtag in subtags {
+        match subtag.len() {
+            4 if script.is_none() && region.is_none() => {
+                let (first, rest) = subtag.split_at(1);
+                script = Some(first.to_ascii_uppercase() + &rest.to_ascii_lowercase());
+            }
+            2 | 3 if region.is_none() => region = Some(subtag.to_ascii_uppercase()),
+            _ => {}
+        }
+    }
+
+    let mut keys = vec![];
+    if let Some(region) = region {
+        keys.push(format!("{}-{}", language, region));
+    }
+    if let Some(script) = script {
+        keys.push(format!("{}-{}", language, script));
+    }
+    keys.push(language);
+    keys
+}
+
+fn lookup_language_id(table: &[(&str, u16)], key: &str) -> Option<u16> {
+    table
+        .iter()
+        .find(|&&(locale, _)| locale == key)
+        .map(|&(_, language_id)| language_id)
+}
+
+fn decode(platform_id: u16, encoding_id: u16, mut bytes: &[u8]) -> Option<String> {
+    match (platform_id, encoding_id) {
+        (PLATFORM_UNICODE, _) | (PLATFORM_WINDOWS, _) => {
+            let mut utf16 = Vec::with_capacity(bytes.len() / 2);
+            while let Ok(unit) = bytes.read_u16::<BigEndian>() {
+                utf16.push(unit)
+            }
+            String::from_utf16(&utf16).ok()
+        }
+        (PLATFORM_MACINTOSH, MACINTOSH_ENCODING_ROMAN) => Some(
+            bytes
+                .iter()
+                .map(|&byte| {
+                    if byte < 0x80 {
+                        byte as char
+                    } else {
+                        MAC_ROMAN_HIGH_HALF[(byte - 0x80) as usize]
+                    }
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns the Mac Roman code of a character, or `None` if Mac Roman has no code for it.
+#[allow(dead_code)]
+pub(crate) fn encode_mac_roman(character: char) -> Option<u8> {
+    if (character as u32) < 0x80 {
+        return Some(character as u8);
+    }
+    MAC_ROMAN_HIGH_HALF
+        .iter()
+        .position(|&high_character| high_character == character)
+        .map(|index| index as u8 + 0x80)
+}
+
+static MAC_ROMAN_HIGH_HALF: [char; 128] = [
+    'Ä', 'Å', 'Ç', 'É', 'Ñ', 'Ö', 'Ü', 'á', 'à', 'â', 'ä', 'ã', 'å', 'ç', 'é', 'è', 'ê', 'ë', 'í',
+    'ì', 'î', 'ï', 'ñ', 'ó', 'ò', 'ô', 'ö', 'õ', 'ú', 'ù', 'û', 'ü', '†', '°', '¢', '£', '§', '•',
+    '¶', 'ß', '®', '©', '™', '´', '¨', '≠', 'Æ', 'Ø', '∞', '±', '≤', '≥', '¥', 'µ', '∂', '∑', '∏',
+    'π', '∫', 'ª', 'º', 'Ω', 'æ', 'ø', '¿', '¡', '¬', '√', 'ƒ', '≈', '∆', '«', '»', '…', '\u{a0}',
+    'À', 'Ã', 'Õ', 'Œ', 'œ', '–', '—', '“', '”', '‘', '’', '÷', '◊', 'ÿ', 'Ÿ', '⁄', '€', '‹', '›',
+    'ﬁ', 'ﬂ', '‡', '·', '‚', '„', '‰', 'Â', 'Ê', 'Á', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', 'Ó', 'Ô',
+    '\u{f8ff}', 'Ò', 'Ú', 'Û', 'Ù', 'ı', 'ˆ', '˜', '¯', '˘', '˙', '˚', '¸', '˝', '˛', 'ˇ',
+];
+
+/// Windows language IDs (LCIDs) by BCP 47 locale.
+///
+/// Each bare language maps to the ID of the region most commonly used for it; the list is limited
+/// to languages that fonts commonly carry localized names for.
+static WINDOWS_LANGUAGE_IDS: &[(&str, u16)] = &[
+    ("ar", 0x0401),
+    ("bg", 0x0402),
+    ("ca", 0x0403),
+    ("zh-TW", 0x0404),
+    ("zh-Hant", 0x0404),
+    ("cs", 0x0405),
+    ("da", 0x0406),
+    ("de", 0x0407),
+    ("el", 0x0408),
+    ("en", 0x0409),
+    ("en-US", 0x0409),
+    ("fi", 0x040b),
+    ("fr", 0x040c),
+    ("he", 0x040d),
+    ("hu", 0x040e),
+    ("is", 0x040f),
+    ("it", 0x0410),
+    ("ja", 0x0411),
+    ("ko", 0x0412),
+    ("nl", 0x0413),
+    ("nb", 0x0414),
+    ("no", 0x0414),
+    ("pl", 0x0415),
+    ("pt", 0x0416),
+    ("pt-BR", 0x0416),
+    ("ro", 0x0418),
+    ("ru", 0x0419),
+    ("hr", 0x041a),
+    ("sk", 0x041b),
+    ("sq", 0x041c),
+    ("sv", 0x041d),
+    ("th", 0x041e),
+    ("tr", 0x041f),
+    ("ur", 0x0420),
+    ("id", 0x0421),
+    ("uk", 0x0422),
+    ("be", 0x0423),
+    ("sl", 0x0424),
+    ("et", 0x0425),
+    ("lv", 0x0426),
+    ("lt", 0x0427),
+    ("fa", 0x0429),
+    ("vi", 0x042a),
+    ("mk", 0x042f),
+    ("hi", 0x0439),
+    ("mt", 0x043a),
+    ("zh", 0x0804),
+    ("zh-CN", 0x0804),
+    ("zh-Hans", 0x0804),
+    ("de-CH", 0x0807),
+    ("en-GB", 0x0809),
+    ("es-MX", 0x080a),
+    ("pt-PT", 0x0816),
+    ("de-AT", 0x0c07),
+    ("en-AU", 0x0c09),
+    ("es", 0x0c0a),
+    ("es-ES", 0x0c0a),
+    ("fr-CA", 0x0c0c),
+    ("sr", 0x0c1a),
+    ("zh-HK", 0x0c04),
+    ("zh-SG", 0x1004),
+    ("en-CA", 0x1009),
+    ("zh-MO", 0x1404),
+];
+
+/// Macintosh language IDs by BCP 47 locale.
+static MACINTOSH_LANGUAGE_IDS: &[(&str, u16)] = &[
+    ("en", 0),
+    ("fr", 1),
+    ("de", 2),
+    ("it", 3),
+    ("nl", 4),
+    ("sv", 5),
+    ("es", 6),
+    ("da", 7),
+    ("pt", 8),
+    ("nb", 9),
+    ("no", 9),
+    ("he", 10),
+    ("ja", 11),
+    ("ar", 12),
+    ("fi", 13),
+    ("el", 14),
+    ("is", 15),
+    ("mt", 16),
+    ("tr", 17),
+    ("hr", 18),
+    ("zh-TW", 19),
+    ("zh-HK", 19),
+    ("zh-MO", 19),
+    ("zh-Hant", 19),
+    ("ur", 20),
+    ("hi", 21),
+    ("th", 22),
+    ("ko", 23),
+    ("lt", 24),
+    ("pl", 25),
+    ("hu", 26),
+    ("et", 27),
+    ("lv", 28),
+    ("fa", 31),
+    ("ru", 32),
+    ("zh", 33),
+    ("ro", 37),
+    ("cs", 38),
+    ("sk", 39),
+    ("sl", 40),
+    ("sr", 42),
+    ("mk", 43),
+    ("bg", 44),
+    ("uk", 45),
+    ("be", 46),
+    ("vi", 80),
+    ("id", 81),
+];
@@ -0,0 +1,84 @@
+// font-kit/src/tables/os_2.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `OS/2` table, which holds metrics and other metadata that Windows relies on.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/os2
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"OS/2");
+
+const FS_TYPE_OFFSET: usize = 8;
+const Y_STRIKEOUT_SIZE_OFFSET: usize = 26;
+const PANOSE_OFFSET: usize = 32;
+const ACH_VEND_ID_OFFSET: usize = 58;
+const FS_SELECTION_OFFSET: usize = 62;
+const S_TYPO_ASCENDER_OFFSET: usize = 68;
+const US_WIN_ASCENT_OFFSET: usize = 74;
+
+/// The bit of `fsSelection` that asks for the typographic metrics to be used for line layout.
+const FS_SELECTION_USE_TYPO_METRICS: u16 = 1 << 7;
+
+/// Returns the `fsType` field, which holds the font's embedding permissions.
+pub(crate) fn fs_type(data: &[u8]) -> Option<u16> {
+    data.get(FS_TYPE_OFFSET..)?.read_u16::<BigEndian>().ok()
+}
+
+/// Returns the `yStrikeoutSize` and `yStrikeoutPosition` fields.
+pub(crate) fn strikeout(data: &[u8]) -> Option<(i16, i16)> {
+    let mut reader = data.get(Y_STRIKEOUT_SIZE_OFFSET..)?;
+    let size = reader.read_i16::<BigEndian>().ok()?;
+    let position = reader.read_i16::<BigEndian>().ok()?;
+    Some((size, position))
+}
+
+/// Returns the ten bytes of the PANOSE classification.
+pub(crate) fn panose(data: &[u8]) -> Option<[u8; 10]> {
+    let mut panose = [0; 10];
+    panose.copy_from_slice(data.get(PANOSE_OFFSET..(PANOSE_OFFSET + 10))?);
+    Some(panose)
+}
+
+/// Returns the four bytes of the `achVendID` field.
+pub(crate) fn vendor_id(data: &[u8]) -> Option<[u8; 4]> {
+    let mut vendor_id = [0; 4];
+    vendor_id.copy_from_slice(data.get(ACH_VEND_ID_OFFSET..(ACH_VEND_ID_OFFSET + 4))?);
+    Some(vendor_id)
+}
+
+/// Returns the `sTypoAscender`, `sTypoDescender`, and `sTypoLineGap` fields.
+pub(crate) fn typo_metrics(data: &[u8]) -> Option<(i16, i16, i16)> {
+    let mut reader = data.get(S_TYPO_ASCENDER_OFFSET..)?;
+    let ascender = reader.read_i16::<BigEndian>().ok()?;
+    let descender = reader.read_i16::<BigEndian>().ok()?;
+    let line_gap = reader.read_i16::<BigEndian>().ok()?;
+    Some((ascender, descender, line_gap))
+}
+
+/// Returns the `usWinAscent` and `usWinDescent` fields.
+pub(crate) fn win_metrics(data: &[u8]) -> Option<(u16, u16)> {
+    let mut reader = data.get(US_WIN_ASCENT_OFFSET..)?;
+    let ascent = reader.read_u16::<BigEndian>().ok()?;
+    let descent = reader.read_u16::<BigEndian>().ok()?;
+    Some((ascent, descent))
+}
+
+/// Returns true if the `USE_TYPO_METRICS` bit of `fsSelection` is set.
+pub(crate) fn use_typo_metrics(data: &[u8]) -> bool {
+    match data.get(FS_SELECTION_OFFSET..) {
+        Some(mut reader) => reader
+            .read_u16::<BigEndian>()
+            .is_ok_and(|fs_selection| fs_selection & FS_SELECTION_USE_TYPO_METRICS != 0),
+        None => false,
+    }
+}
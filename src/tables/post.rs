@@ -0,0 +1,423 @@
+// font-kit/src/tables/post.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The PostScript table, `post`, and the glyph naming conventions that stand in for the names
+//! that format 3 tables leave out.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/post
+//! https://github.com/adobe-type-tools/agl-specification
+
+use byteorder::{BigEndian, ReadBytesExt};
+use std::convert::TryFrom;
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"post");
+
+const VERSION_1_0: u32 = 0x0001_0000;
+const VERSION_2_0: u32 = 0x0002_0000;
+const VERSION_2_5: u32 = 0x0002_5000;
+
+/// Returns true if the `post` table names the font's glyphs.
+///
+/// Format 3 tables, and tables in formats that aren't recognized, carry no names.
+pub(crate) fn has_glyph_names(data: &[u8]) -> bool {
+    match (&data[..]).read_u32::<BigEndian>() {
+        Ok(version) => version == VERSION_1_0 || version == VERSION_2_0 || version == VERSION_2_5,
+        Err(_) => false,
+    }
+}
+
+/// Returns the `italicAngle` field of the `post` table, in degrees.
+pub(crate) fn italic_angle(data: &[u8]) -> Option<f32> {
+    let mut reader = data.get(4..)?;
+    Some(reader.read_i32::<BigEndian>().ok()? as f32 / 65536.0)
+}
+
+/// Returns the `underlinePosition` and `underlineThickness` fields of the `post` table.
+#[allow(dead_code)]
+pub(crate) fn underline(data: &[u8]) -> Option<(i16, i16)> {
+    let mut reader = data.get(8..)?;
+    let position = reader.read_i16::<BigEndian>().ok()?;
+    let thickness = reader.read_i16::<BigEndian>().ok()?;
+    Some((position, thickness))
+}
+
+/// Returns the `isFixedPitch` field of the `post` table.
+pub(crate) fn is_fixed_pitch(data: &[u8]) -> Option<bool> {
+    let mut reader = data.get(12..)?;
+    Some(reader.read_u32::<BigEndian>().ok()? != 0)
+}
+
+/// Returns the names of the font's glyphs, indexed by glyph ID, if the `post` table names them.
+///
+/// Format 2.0 tables may name fewer glyphs than the font has, in which case the rest are left
+/// out.
+pub(crate) fn glyph_names(data: &[u8]) -> Option<Vec<String>> {
+    let mut reader = data;
+    match reader.read_u32::<BigEndian>().ok()? {
+        VERSION_1_0 => Some(
+            STANDARD_MAC_GLYPH_NAMES
+                .iter()
+                .map(|&(name, _)| name.to_owned())
+                .collect(),
+        ),
+        VERSION_2_0 => {
+            let mut reader = data.get(32..)?;
+            let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+            let mut name_indices = Vec::with_capacity(glyph_count as usize);
+            for _ in 0..glyph_count {
+                name_indices.push(reader.read_u16::<BigEndian>().ok()?);
+            }
+            // The names that aren't standard follow as Pascal strings, up to the end of the table.
+            let mut custom_names = vec![];
+            while let Some((&length, rest)) = reader.split_first() {
+                let name = rest.get(..length as usize)?;
+                custom_names.push(String::from_utf8_lossy(name).into_owned());
+                reader = &rest[length as usize..];
+            }
+            name_indices
+                .into_iter()
+                .map(|name_index| match name_index as usize {
+                    index if index < STANDARD_MAC_GLYPH_NAMES.len() => {
+                        Some(STANDARD_MAC_GLYPH_NAMES[index].0.to_owned())
+                    }
+                    index => custom_names
+                        .get(index - STANDARD_MAC_GLYPH_NAMES.len())
+                        .cloned(),
+                })
+                .collect()
+        }
+        // Format 2.5 gives each glyph the offset of its name in the standard Macintosh character
+        // set from its glyph ID.
+        VERSION_2_5 => {
+            let mut reader = data.get(32..)?;
+            let glyph_count = reader.read_u16::<BigEndian>().ok()?;
+            (0..glyph_count as i32)
+                .map(|glyph_id| {
+                    let index = glyph_id + reader.read_i8().ok()? as i32;
+                    let &(name, _) = STANDARD_MAC_GLYPH_NAMES.get(usize::try_from(index).ok()?)?;
+                    Some(name.to_owned())
+                })
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the character that the given glyph name stands for, if it follows a convention that
+/// identifies one.
+///
+/// The names recognized are those of the standard Macintosh character set (e.g. `A` or
+/// `germandbls`) and the Adobe Glyph List's `uniXXXX` and `uXXXX`–`uXXXXXX` forms. Names with
+/// suffixes, like `A.sc`, identify variant glyphs rather than characters and aren't recognized.
+pub(crate) fn char_for_glyph_name(name: &str) -> Option<char> {
+    if let Some(&(_, character)) = STANDARD_MAC_GLYPH_NAMES
+        .iter()
+        .find(|&&(standard_name, _)| standard_name == name)
+    {
+        return character;
+    }
+
+    let hex_digits = if name.starts_with("uni") && name.len() == 7 {
+        &name[3..]
+    } else if name.starts_with('u') && name.len() >= 5 && name.len() <= 7 {
+        &name[1..]
+    } else {
+        return None;
+    };
+    // The specification requires uppercase hexadecimal digits.
+    if !hex_digits
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || (b'A'..=b'F').contains(&byte))
+    {
+        return None;
+    }
+    // `char::from_u32()` rejects surrogates and values beyond U+10FFFF, which aren't characters.
+    char::from_u32(u32::from_str_radix(hex_digits, 16).ok()?)
+}
+
+/// Returns the name that identifies the given character by convention, which
+/// `char_for_glyph_name()` maps back to it: its name in the standard Macintosh character set if it
+/// has one, and otherwise the Adobe Glyph List's `uniXXXX` form, or `uXXXXX` beyond the Basic
+/// Multilingual Plane.
+pub(crate) fn glyph_name_for_char(character: char) -> String {
+    match STANDARD_MAC_GLYPH_NAMES
+        .iter()
+        .find(|&&(_, standard_character)| standard_character == Some(character))
+    {
+        Some(&(name, _)) => name.to_owned(),
+        None if (character as u32) <= 0xffff => format!("uni{:04X}", character as u32),
+        None => format!("u{:X}", character as u32),
+    }
+}
+
+/// The names of the 258 glyphs of the standard Macintosh character set, in order, with the
+/// characters that they stand for.
+const STANDARD_MAC_GLYPH_NAMES: [(&str, Option<char>); 258] = [
+    (".notdef", None),
+    (".null", Some('\u{0}')),
+    ("nonmarkingreturn", Some('\u{d}')),
+    ("space", Some(' ')),
+    ("exclam", Some('!')),
+    ("quotedbl", Some('"')),
+    ("numbersign", Some('#')),
+    ("dollar", Some('$')),
+    ("percent", Some('%')),
+    ("ampersand", Some('&')),
+    ("quotesingle", Some('\'')),
+    ("parenleft", Some('(')),
+    ("parenright", Some(')')),
+    ("asterisk", Some('*')),
+    ("plus", Some('+')),
+    ("comma", Some(',')),
+    ("hyphen", Some('-')),
+    ("period", Some('.')),
+    ("slash", Some('/')),
+    ("zero", Some('0')),
+    ("one", Some('1')),
+    ("two", Some('2')),
+    ("three", Some('3')),
+    ("four", Some('4')),
+    ("five", Some('5')),
+    ("six", Some('6')),
+    ("seven", Some('7')),
+    ("eight", Some('8')),
+    ("nine", Some('9')),
+    ("colon", Some(':')),
+    ("semicolon", Some(';')),
+    ("less", Some('<')),
+    ("equal", Some('=')),
+    ("greater", Some('>')),
+    ("question", Some('?')),
+    ("at", Some('@')),
+    ("A", Some('A')),
+    ("B", Some('B')),
+    ("C", Some('C')),
+    ("D", Some('D')),
+    ("E", Some('E')),
+    ("F", Some('F')),
+    ("G", Some('G')),
+    ("H", Some('H')),
+    ("I", Some('I')),
+    ("J", Some('J')),
+    ("K", Some('K')),
+    ("L", Some('L')),
+    ("M", Some('M')),
+    ("N", Some('N')),
+    ("O", Some('O')),
+    ("P", Some('P')),
+    ("Q", Some('Q')),
+    ("R", Some('R')),
+    ("S", Some('S')),
+    ("T", Some('T')),
+    ("U", Some('U')),
+    ("V", Some('V')),
+    ("W", Some('W')),
+    ("X", Some('X')),
+    ("Y", Some('Y')),
+    ("Z", Some('Z')),
+    ("bracketleft", Some('[')),
+    ("backslash", Some('\\')),
+    ("bracketright", Some(']')),
+    ("asciicircum", Some('^')),
+    ("underscore", Some('_')),
+    ("grave", Some('`')),
+    ("a", Some('a')),
+    ("b", Some('b')),
+    ("c", Some('c')),
+    ("d", Some('d')),
+    ("e", Some('e')),
+    ("f", Some('f')),
+    ("g", Some('g')),
+    ("h", Some('h')),
+    ("i", Some('i')),
+    ("j", Some('j')),
+    ("k", Some('k')),
+    ("l", Some('l')),
+    ("m", Some('m')),
+    ("n", Some('n')),
+    ("o", Some('o')),
+    ("p", Some('p')),
+    ("q", Some('q')),
+    ("r", Some('r')),
+    ("s", Some('s')),
+    ("t", Some('t')),
+    ("u", Some('u')),
+    ("v", Some('v')),
+    ("w", Some('w')),
+    ("x", Some('x')),
+    ("y", Some('y')),
+    ("z", Some('z')),
+    ("braceleft", Some('{')),
+    ("bar", Some('|')),
+    ("braceright", Some('}')),
+    ("asciitilde", Some('~')),
+    ("Adieresis", Some('\u{c4}')),
+    ("Aring", Some('\u{c5}')),
+    ("Ccedilla", Some('\u{c7}')),
+    ("Eacute", Some('\u{c9}')),
+    ("Ntilde", Some('\u{d1}')),
+    ("Odieresis", Some('\u{d6}')),
+    ("Udieresis", Some('\u{dc}')),
+    ("aacute", Some('\u{e1}')),
+    ("agrave", Some('\u{e0}')),
+    ("acircumflex", Some('\u{e2}')),
+    ("adieresis", Some('\u{e4}')),
+    ("atilde", Some('\u{e3}')),
+    ("aring", Some('\u{e5}')),
+    ("ccedilla", Some('\u{e7}')),
+    ("eacute", Some('\u{e9}')),
+    ("egrave", Some('\u{e8}')),
+    ("ecircumflex", Some('\u{ea}')),
+    ("edieresis", Some('\u{eb}')),
+    ("iacute", Some('\u{ed}')),
+    ("igrave", Some('\u{ec}')),
+    ("icircumflex", Some('\u{ee}')),
+    ("idieresis", Some('\u{ef}')),
+    ("ntilde", Some('\u{f1}')),
+    ("oacute", Some('\u{f3}')),
+    ("ograve", Some('\u{f2}')),
+    ("ocircumflex", Some('\u{f4}')),
+    ("odieresis", Some('\u{f6}')),
+    ("otilde", Some('\u{f5}')),
+    ("uacute", Some('\u{fa}')),
+    ("ugrave", Some('\u{f9}')),
+    ("ucircumflex", Some('\u{fb}')),
+    ("udieresis", Some('\u{fc}')),
+    ("dagger", Some('\u{2020}')),
+    ("degree", Some('\u{b0}')),
+    ("cent", Some('\u{a2}')),
+    ("sterling", Some('\u{a3}')),
+    ("section", Some('\u{a7}')),
+    ("bullet", Some('\u{2022}')),
+    ("paragraph", Some('\u{b6}')),
+    ("germandbls", Some('\u{df}')),
+    ("registered", Some('\u{ae}')),
+    ("copyright", Some('\u{a9}')),
+    ("trademark", Some('\u{2122}')),
+    ("acute", Some('\u{b4}')),
+    ("dieresis", Some('\u{a8}')),
+    ("notequal", Some('\u{2260}')),
+    ("AE", Some('\u{c6}')),
+    ("Oslash", Some('\u{d8}')),
+    ("infinity", Some('\u{221e}')),
+    ("plusminus", Some('\u{b1}')),
+    ("lessequal", Some('\u{2264}')),
+    ("greaterequal", Some('\u{2265}')),
+    ("yen", Some('\u{a5}')),
+    ("mu", Some('\u{b5}')),
+    ("partialdiff", Some('\u{2202}')),
+    ("summation", Some('\u{2211}')),
+    ("product", Some('\u{220f}')),
+    ("pi", Some('\u{3c0}')),
+    ("integral", Some('\u{222b}')),
+    ("ordfeminine", Some('\u{aa}')),
+    ("ordmasculine", Some('\u{ba}')),
+    ("Omega", Some('\u{3a9}')),
+    ("ae", Some('\u{e6}')),
+    ("oslash", Some('\u{f8}')),
+    ("questiondown", Some('\u{bf}')),
+    ("exclamdown", Some('\u{a1}')),
+    ("logicalnot", Some('\u{ac}')),
+    ("radical", Some('\u{221a}')),
+    ("florin", Some('\u{192}')),
+    ("approxequal", Some('\u{2248}')),
+    ("Delta", Some('\u{2206}')),
+    ("guillemotleft", Some('\u{ab}')),
+    ("guillemotright", Some('\u{bb}')),
+    ("ellipsis", Some('\u{2026}')),
+    ("nonbreakingspace", Some('\u{a0}')),
+    ("Agrave", Some('\u{c0}')),
+    ("Atilde", Some('\u{c3}')),
+    ("Otilde", Some('\u{d5}')),
+    ("OE", Some('\u{152}')),
+    ("oe", Some('\u{153}')),
+    ("endash", Some('\u{2013}')),
+    ("emdash", Some('\u{2014}')),
+    ("quotedblleft", Some('\u{201c}')),
+    ("quotedblright", Some('\u{201d}')),
+    ("quoteleft", Some('\u{2018}')),
+    ("quoteright", Some('\u{2019}')),
+    ("divide", Some('\u{f7}')),
+    ("lozenge", Some('\u{25ca}')),
+    ("ydieresis", Some('\u{ff}')),
+    ("Ydieresis", Some('\u{178}')),
+    ("fraction", Some('\u{2044}')),
+    ("currency", Some('\u{a4}')),
+    ("guilsinglleft", Some('\u{2039}')),
+    ("guilsinglright", Some('\u{203a}')),
+    ("fi", Some('\u{fb01}')),
+    ("fl", Some('\u{fb02}')),
+    ("daggerdbl", Some('\u{2021}')),
+    ("periodcentered", Some('\u{b7}')),
+    ("quotesinglbase", Some('\u{201a}')),
+    ("quotedblbase", Some('\u{201e}')),
+    ("perthousand", Some('\u{2030}')),
+    ("Acircumflex", Some('\u{c2}')),
+    ("Ecircumflex", Some('\u{ca}')),
+    ("Aacute", Some('\u{c1}')),
+    ("Edieresis", Some('\u{cb}')),
+    ("Egrave", Some('\u{c8}')),
+    ("Iacute", Some('\u{cd}')),
+    ("Icircumflex", Some('\u{ce}')),
+    ("Idieresis", Some('\u{cf}')),
+    ("Igrave", Some('\u{cc}')),
+    ("Oacute", Some('\u{d3}')),
+    ("Ocircumflex", Some('\u{d4}')),
+    ("apple", Some('\u{f8ff}')),
+    ("Ograve", Some('\u{d2}')),
+    ("Uacute", Some('\u{da}')),
+    ("Ucircumflex", Some('\u{db}')),
+    ("Ugrave", Some('\u{d9}')),
+    ("dotlessi", Some('\u{131}')),
+    ("circumflex", Some('\u{2c6}')),
+    ("tilde", Some('\u{2dc}')),
+    ("macron", Some('\u{af}')),
+    ("breve", Some('\u{2d8}')),
+    ("dotaccent", Some('\u{2d9}')),
+    ("ring", Some('\u{2da}')),
+    ("cedilla", Some('\u{b8}')),
+    ("hungarumlaut", Some('\u{2dd}')),
+    ("ogonek", Some('\u{2db}')),
+    ("caron", Some('\u{2c7}')),
+    ("Lslash", Some('\u{141}')),
+    ("lslash", Some('\u{142}')),
+    ("Scaron", Some('\u{160}')),
+    ("scaron", Some('\u{161}')),
+    ("Zcaron", Some('\u{17d}')),
+    ("zcaron", Some('\u{17e}')),
+    ("brokenbar", Some('\u{a6}')),
+    ("Eth", Some('\u{d0}')),
+    ("eth", Some('\u{f0}')),
+    ("Yacute", Some('\u{dd}')),
+    ("yacute", Some('\u{fd}')),
+    ("Thorn", Some('\u{de}')),
+    ("thorn", Some('\u{fe}')),
+    ("minus", Some('\u{2212}')),
+    ("multiply", Some('\u{d7}')),
+    ("onesuperior", Some('\u{b9}')),
+    ("twosuperior", Some('\u{b2}')),
+    ("threesuperior", Some('\u{b3}')),
+    ("onehalf", Some('\u{bd}')),
+    ("onequarter", Some('\u{bc}')),
+    ("threequarters", Some('\u{be}')),
+    ("franc", Some('\u{20a3}')),
+    ("Gbreve", Some('\u{11e}')),
+    ("gbreve", Some('\u{11f}')),
+    ("Idotaccent", Some('\u{130}')),
+    ("Scedilla", Some('\u{15e}')),
+    ("scedilla", Some('\u{15f}')),
+    ("Cacute", Some('\u{106}')),
+    ("cacute", Some('\u{107}')),
+    ("Ccaron", Some('\u{10c}')),
+    ("ccaron", Some('\u{10d}')),
+    ("dcroat", Some('\u{111}')),
+];
@@ -0,0 +1,110 @@
+// font-kit/src/tables/sbix.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `sbix` table, which holds Apple-style embedded bitmap (typically PNG) strikes.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/sbix
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"sbix");
+
+pub(crate) const GRAPHIC_TYPE_PNG: u32 = tables::tag(b"png ");
+const GRAPHIC_TYPE_DUPE: u32 = tables::tag(b"dupe");
+
+/// A single strike: a set of bitmaps for one pixels-per-em size.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Strike {
+    pub(crate) ppem: u16,
+    offset: usize,
+}
+
+/// The bitmap data for one glyph in a strike.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct GlyphData<'a> {
+    /// The horizontal offset of the left edge of the bitmap from the glyph origin, in pixels.
+    pub(crate) origin_offset_x: i16,
+    /// The vertical offset of the bottom edge of the bitmap from the glyph origin, in pixels,
+    /// with y pointing up.
+    pub(crate) origin_offset_y: i16,
+    pub(crate) graphic_type: u32,
+    pub(crate) data: &'a [u8],
+}
+
+/// A view of an `sbix` table.
+#[derive(Clone, Debug)]
+pub(crate) struct SbixTable<'a> {
+    data: &'a [u8],
+    num_glyphs: usize,
+    pub(crate) strikes: Vec<Strike>,
+}
+
+impl<'a> SbixTable<'a> {
+    /// Parses the table. `num_glyphs` is the number of glyphs in the font, from `maxp`.
+    pub(crate) fn parse(data: &'a [u8], num_glyphs: u32) -> Option<SbixTable<'a>> {
+        let mut reader = data;
+        let _version = reader.read_u16::<BigEndian>().ok()?;
+        let _flags = reader.read_u16::<BigEndian>().ok()?;
+        let num_strikes = reader.read_u32::<BigEndian>().ok()?;
+        let mut strikes = vec![];
+        for _ in 0..num_strikes {
+            let offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            let ppem = data.get(offset..)?.read_u16::<BigEndian>().ok()?;
+            strikes.push(Strike { ppem, offset });
+        }
+        Some(SbixTable {
+            data,
+            num_glyphs: num_glyphs as usize,
+            strikes,
+        })
+    }
+
+    /// Returns the bitmap for the given glyph in the given strike, if there is one.
+    ///
+    /// `dupe` records are followed to the glyph that they duplicate.
+    pub(crate) fn glyph(&self, strike: &Strike, glyph_id: u16) -> Option<GlyphData<'a>> {
+        let glyph_data = self.glyph_data(strike, glyph_id)?;
+        if glyph_data.graphic_type != GRAPHIC_TYPE_DUPE {
+            return Some(glyph_data);
+        }
+        let original_glyph_id = (&glyph_data.data[..]).read_u16::<BigEndian>().ok()?;
+        let original_glyph_data = self.glyph_data(strike, original_glyph_id)?;
+        if original_glyph_data.graphic_type == GRAPHIC_TYPE_DUPE {
+            return None;
+        }
+        Some(original_glyph_data)
+    }
+
+    fn glyph_data(&self, strike: &Strike, glyph_id: u16) -> Option<GlyphData<'a>> {
+        if glyph_id as usize >= self.num_glyphs {
+            return None;
+        }
+        let mut reader = self
+            .data
+            .get((strike.offset + 4 + glyph_id as usize * 4)..)?;
+        let start = reader.read_u32::<BigEndian>().ok()? as usize;
+        let end = reader.read_u32::<BigEndian>().ok()? as usize;
+        // Glyphs without bitmaps have zero-length records.
+        if end < start + 8 {
+            return None;
+        }
+        let mut reader = self
+            .data
+            .get((strike.offset + start)..(strike.offset + end))?;
+        Some(GlyphData {
+            origin_offset_x: reader.read_i16::<BigEndian>().ok()?,
+            origin_offset_y: reader.read_i16::<BigEndian>().ok()?,
+            graphic_type: reader.read_u32::<BigEndian>().ok()?,
+            data: reader,
+        })
+    }
+}
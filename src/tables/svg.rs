@@ -0,0 +1,53 @@
+// font-kit/src/tables/svg.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `SVG ` table, which holds SVG documents describing color glyphs.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/svg
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"SVG ");
+
+/// The first two bytes of gzip-compressed documents.
+pub(crate) const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+const DOCUMENT_RECORD_SIZE: usize = 12;
+
+/// Returns the (possibly gzip-compressed) SVG document that covers the given glyph, if any.
+pub(crate) fn document(data: &[u8], glyph_id: u16) -> Option<&[u8]> {
+    let mut reader = data;
+    let _version = reader.read_u16::<BigEndian>().ok()?;
+    let document_list_offset = reader.read_u32::<BigEndian>().ok()? as usize;
+    let document_list = data.get(document_list_offset..)?;
+    let mut reader = document_list;
+    let num_entries = reader.read_u16::<BigEndian>().ok()? as usize;
+
+    // Document records are sorted by glyph ID, and their ranges don't overlap.
+    let (mut low, mut high) = (0, num_entries);
+    while low < high {
+        let mid = (low + high) / 2;
+        let mut reader = document_list.get((2 + mid * DOCUMENT_RECORD_SIZE)..)?;
+        let start_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+        let end_glyph_id = reader.read_u16::<BigEndian>().ok()?;
+        if glyph_id < start_glyph_id {
+            high = mid
+        } else if glyph_id > end_glyph_id {
+            low = mid + 1
+        } else {
+            let offset = reader.read_u32::<BigEndian>().ok()? as usize;
+            let length = reader.read_u32::<BigEndian>().ok()? as usize;
+            return document_list.get(offset..(offset + length));
+        }
+    }
+    None
+}
@@ -0,0 +1,51 @@
+// font-kit/src/tables/vhea.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The vertical header table, `vhea`, which holds the font-wide metrics for vertical layout.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/vhea
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"vhea");
+
+/// The offset of `numOfLongVerMetrics`, which is at the same place as its `hhea` counterpart.
+pub(crate) const NUMBER_OF_LONG_METRICS_OFFSET: usize = 34;
+
+/// The parsed contents of a `vhea` table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VheaTable {
+    /// The distance from the vertical centerline to the right edge of the design space.
+    pub(crate) ascent: i16,
+    /// The distance from the vertical centerline to the left edge of the design space.
+    pub(crate) descent: i16,
+    pub(crate) line_gap: i16,
+    /// The number of entries of the `vmtx` table that have their own advance height.
+    pub(crate) long_metric_count: u16,
+}
+
+impl VheaTable {
+    pub(crate) fn parse(data: &[u8]) -> Option<VheaTable> {
+        let mut reader = data;
+        let _version = reader.read_u32::<BigEndian>().ok()?;
+        let ascent = reader.read_i16::<BigEndian>().ok()?;
+        let descent = reader.read_i16::<BigEndian>().ok()?;
+        let line_gap = reader.read_i16::<BigEndian>().ok()?;
+        let mut reader = data.get(NUMBER_OF_LONG_METRICS_OFFSET..)?;
+        Some(VheaTable {
+            ascent,
+            descent,
+            line_gap,
+            long_metric_count: reader.read_u16::<BigEndian>().ok()?,
+        })
+    }
+}
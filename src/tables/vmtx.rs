@@ -0,0 +1,44 @@
+// font-kit/src/tables/vmtx.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The vertical metrics table, `vmtx`, which holds each glyph's advance height and top side
+//! bearing.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/vmtx
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"vmtx");
+
+/// Returns the advance height and top side bearing of a glyph, given the number of long metrics
+/// from the `vhea` table.
+///
+/// Glyphs past the last long metric share its advance height.
+pub(crate) fn metric(data: &[u8], long_metric_count: u16, glyph_id: u32) -> Option<(u16, i16)> {
+    let long_metric_count = long_metric_count as usize;
+    if long_metric_count == 0 {
+        return None;
+    }
+    let glyph_id = glyph_id as usize;
+    let advance_offset = glyph_id.min(long_metric_count - 1) * 4;
+    let advance = data.get(advance_offset..)?.read_u16::<BigEndian>().ok()?;
+    let side_bearing_offset = if glyph_id < long_metric_count {
+        glyph_id * 4 + 2
+    } else {
+        long_metric_count * 4 + (glyph_id - long_metric_count) * 2
+    };
+    let side_bearing = data
+        .get(side_bearing_offset..)?
+        .read_i16::<BigEndian>()
+        .ok()?;
+    Some((advance, side_bearing))
+}
@@ -0,0 +1,48 @@
+// font-kit/src/tables/vorg.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The vertical origin table, `VORG`, which CFF fonts use to place the vertical origin of each
+//! glyph.
+//!
+//! https://docs.microsoft.com/en-us/typography/opentype/spec/vorg
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::tables;
+
+pub(crate) const TAG: u32 = tables::tag(b"VORG");
+
+/// Returns the y coordinate of the vertical origin of the glyph.
+pub(crate) fn vert_origin_y(data: &[u8], glyph_id: u32) -> Option<i16> {
+    let mut reader = data;
+    let major_version = reader.read_u16::<BigEndian>().ok()?;
+    let _minor_version = reader.read_u16::<BigEndian>().ok()?;
+    if major_version != 1 {
+        return None;
+    }
+    let default_vert_origin_y = reader.read_i16::<BigEndian>().ok()?;
+    let metric_count = reader.read_u16::<BigEndian>().ok()? as usize;
+
+    // The metrics are sorted by glyph ID.
+    let (mut low, mut high) = (0, metric_count);
+    while low < high {
+        let mid = (low + high) / 2;
+        let mut metric = reader.get((mid * 4)..)?;
+        let metric_glyph_id = metric.read_u16::<BigEndian>().ok()? as u32;
+        if glyph_id < metric_glyph_id {
+            high = mid
+        } else if glyph_id > metric_glyph_id {
+            low = mid + 1
+        } else {
+            return metric.read_i16::<BigEndian>().ok();
+        }
+    }
+    Some(default_vert_origin_y)
+}
@@ -8,39 +8,90 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use euclid::default::{Point2D, Rect, Size2D, Vector2D};
-use euclid::point2;
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use euclid::{point2, vec2};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lyon_path::builder::{FlatPathBuilder, PathBuilder};
+use lyon_path::math::Point;
 use lyon_path::{Path, PathEvent};
+use std::convert::TryInto;
 use std::fs::File;
-use std::io::Read;
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
 
-use crate::canvas::{Canvas, Format, RasterizationOptions};
-use crate::family_name::FamilyName;
+use crate::bitmap::{BitmapFormat, BitmapSource};
+use crate::cache::GlyphCache;
+use crate::canvas::{
+    Canvas, Format, Gamma, LcdFilter, RasterizationAdjustments, RasterizationOptions,
+};
+use crate::color::{
+    ColorFormat, ColorLine, ColorPalette, ColorStop, ColorU, CompositeMode, Extend, Paint,
+};
+use crate::coverage::CodepointCoverage;
+use crate::embedding::{EmbeddingPermission, EmbeddingRestrictions};
+use crate::error::{FontLoadingError, GlyphLoadingError, SelectionError};
+use crate::family_name::{FamilyName, GenericFamilyConfig};
 use crate::file_type::FileType;
 use crate::font::Font;
+use crate::handle::Handle;
 use crate::hinting::HintingOptions;
+use crate::layout::{GlyphClass, LangTag, ScriptTag};
+#[cfg(all(feature = "mmap", not(target_family = "windows")))]
+use crate::loader::FontData;
 use crate::loader::FontTransform;
-use crate::properties::{Properties, Stretch, Weight};
-use crate::source::SystemSource;
+use crate::matching;
+use crate::math::{GlyphVariant, StretchDirection};
+use crate::metrics::MaxpLimits;
+use crate::names;
+use crate::outline::{Outline, Segment};
+use crate::outline_format::OutlineFormat;
+use crate::panose::Panose;
+use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::script::Script;
+use crate::sfnt;
+#[cfg(feature = "harfbuzz")]
+use crate::shaping::{Direction, Feature, ShapedGlyph};
+use crate::signature;
+use crate::source::{Source, SystemSource};
+use crate::sources::mem::{FontMetadata, MemSource};
+use crate::sources::multi::MultiSource;
+use crate::subset::FontSubset;
+use crate::svg_path::SvgPathBuilder;
+use crate::synthesis::Synthesis;
 use crate::utils;
+use crate::variations;
 
-static TEST_FONT_FILE_PATH: &'static str = "resources/tests/eb-garamond/EBGaramond12-Regular.otf";
-static TEST_FONT_POSTSCRIPT_NAME: &'static str = "EBGaramond12-Regular";
-static TEST_FONT_COLLECTION_FILE_PATH: &'static str =
-    "resources/tests/eb-garamond/EBGaramond12.otc";
-static TEST_FONT_COLLECTION_POSTSCRIPT_NAME: [&'static str; 2] =
+static TEST_FONT_FILE_PATH: &str = "resources/tests/eb-garamond/EBGaramond12-Regular.otf";
+static TEST_FONT_POSTSCRIPT_NAME: &str = "EBGaramond12-Regular";
+static TEST_FONT_COLLECTION_FILE_PATH: &str = "resources/tests/eb-garamond/EBGaramond12.otc";
+static TEST_FONT_COLLECTION_POSTSCRIPT_NAME: [&str; 2] =
     ["EBGaramond12-Regular", "EBGaramond12-Italic"];
 
-static FILE_PATH_EB_GARAMOND_TTF: &'static str =
-    "resources/tests/eb-garamond/EBGaramond12-Regular.ttf";
-static FILE_PATH_INCONSOLATA_TTF: &'static str =
-    "resources/tests/inconsolata/Inconsolata-Regular.ttf";
+static FILE_PATH_EB_GARAMOND_TTF: &str = "resources/tests/eb-garamond/EBGaramond12-Regular.ttf";
+static FILE_PATH_INCONSOLATA_TTF: &str = "resources/tests/inconsolata/Inconsolata-Regular.ttf";
+static FILE_PATH_VAZIRMATN_VAR_TTF: &str =
+    "resources/tests/font-test-data/vazirmatn_var_trimmed.ttf";
+static FILE_PATH_CANTARELL_VAR_TTF: &str =
+    "resources/tests/font-test-data/cantarell_vf_trimmed.ttf";
+static FILE_PATH_AVAR2_CHECKER_TTF: &str = "resources/tests/font-test-data/avar2checker.ttf";
+static FILE_PATH_COLR_1_TTF: &str = "resources/tests/font-test-data/test_glyphs-glyf_colr_1.ttf";
+static FILE_PATH_CBDT_TTF: &str = "resources/tests/font-test-data/cbdt.ttf";
+static FILE_PATH_AHEM_SVG_TTF: &str = "resources/tests/font-test-data/ahem_svg.ttf";
+static FILE_PATH_AHEM_KERN_TTF: &str = "resources/tests/font-test-data/ahem_kern.ttf";
+static FILE_PATH_GLYF_COMPONENTS_TTF: &str =
+    "resources/tests/font-test-data/glyf_components_point_matched.ttf";
+static FILE_PATH_INCONSOLATA_WOFF: &str = "resources/tests/inconsolata/Inconsolata-Regular.woff";
+static FILE_PATH_OPEN_SANS_WOFF2: &str = "resources/tests/open-sans/OpenSans-Regular.woff2";
 
 #[cfg(not(target_os = "linux"))]
-static KNOWN_SYSTEM_FONT_NAME: &'static str = "Arial";
+static KNOWN_SYSTEM_FONT_NAME: &str = "Arial";
 #[cfg(target_os = "linux")]
-static KNOWN_SYSTEM_FONT_NAME: &'static str = "DejaVu Sans";
+static KNOWN_SYSTEM_FONT_NAME: &str = "DejaVu Sans";
 
 #[test]
 pub fn get_font_full_name() {
@@ -55,6 +106,37 @@ pub fn get_font_full_name() {
     assert_eq!(font.full_name(), KNOWN_SYSTEM_FONT_NAME);
 }
 
+#[test]
+pub fn select_ranked_matches() {
+    let source = SystemSource::new();
+    let family_names = [FamilyName::Title(KNOWN_SYSTEM_FONT_NAME.to_string())];
+    let properties = Properties {
+        weight: Weight::BOLD,
+        ..Properties::new()
+    };
+    let matches = source
+        .select_matches(&family_names, &properties, 3)
+        .unwrap();
+    assert!(!matches.is_empty() && matches.len() <= 3);
+
+    // The best match is the one that `select_best_match()` picks.
+    let best_match = source
+        .select_best_match(&family_names, &properties)
+        .unwrap()
+        .load()
+        .unwrap();
+    let (ref first_handle, first_score) = matches[0];
+    assert_eq!(
+        first_handle.load().unwrap().postscript_name(),
+        best_match.postscript_name()
+    );
+    assert_eq!(first_score.family_index, 0);
+    assert_eq!(first_score.stretch_difference, 0.0);
+    assert_eq!(first_score.style_distance, 0);
+    assert_eq!(first_score.weight_difference, 0.0);
+    assert!(matches.iter().all(|(_, score)| score.family_index == 0));
+}
+
 #[test]
 pub fn load_font_from_file() {
     let mut file = File::open(TEST_FONT_FILE_PATH).unwrap();
@@ -71,6 +153,40 @@ pub fn load_font_from_memory() {
     assert_eq!(font.postscript_name().unwrap(), TEST_FONT_POSTSCRIPT_NAME);
 }
 
+#[cfg(feature = "serde")]
+#[test]
+pub fn serialize_and_deserialize_handles() {
+    let round_trip = |handle: &Handle| -> Handle {
+        serde_json::from_str(&serde_json::to_string(handle).unwrap()).unwrap()
+    };
+
+    let handle = Handle::from_path(TEST_FONT_COLLECTION_FILE_PATH.into(), 1);
+    match round_trip(&handle) {
+        Handle::Path { path, font_index } => {
+            assert_eq!(path, std::path::Path::new(TEST_FONT_COLLECTION_FILE_PATH));
+            assert_eq!(font_index, 1);
+        }
+        handle => panic!("expected a path handle, got {:?}", handle),
+    }
+
+    let font_data = Arc::new(std::fs::read(TEST_FONT_FILE_PATH).unwrap());
+    match round_trip(&Handle::from_memory(font_data.clone(), 0)) {
+        Handle::Memory { bytes, font_index } => {
+            assert_eq!(bytes, font_data);
+            assert_eq!(font_index, 0);
+        }
+        handle => panic!("expected a memory handle, got {:?}", handle),
+    }
+    assert_eq!(
+        round_trip(&handle)
+            .load()
+            .unwrap()
+            .postscript_name()
+            .unwrap(),
+        TEST_FONT_COLLECTION_POSTSCRIPT_NAME[1]
+    );
+}
+
 #[test]
 pub fn analyze_file() {
     let mut file = File::open(TEST_FONT_FILE_PATH).unwrap();
@@ -88,6 +204,43 @@ pub fn analyze_bytes() {
     );
 }
 
+#[test]
+pub fn get_all_fonts_asynchronously() {
+    fn paths(handles: Vec<Handle>) -> Vec<String> {
+        handles
+            .iter()
+            .map(|handle| match *handle {
+                Handle::Path {
+                    ref path,
+                    font_index,
+                } => format!("{}#{}", path.display(), font_index),
+                Handle::Memory { font_index, .. } => format!("memory#{}", font_index),
+            })
+            .collect()
+    }
+
+    let source = SystemSource::new();
+    let fonts = paths(source.all_fonts().unwrap());
+    assert_eq!(paths(source.all_fonts_async().wait().unwrap()), fonts);
+
+    // Poll the future the way an executor would, parking until the background thread wakes us.
+    struct ThreadWaker(thread::Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut future = source.all_fonts_async();
+    let result = loop {
+        match Pin::new(&mut future).poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(result) => break result,
+            Poll::Pending => thread::park(),
+        }
+    };
+    assert_eq!(paths(result.unwrap()), fonts);
+}
+
 #[test]
 pub fn get_glyph_for_char() {
     let font = SystemSource::new()
@@ -99,6 +252,87 @@ pub fn get_glyph_for_char() {
     assert_eq!(glyph, 68);
 }
 
+#[test]
+pub fn get_glyph_for_char_with_variation() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph = |c| font.glyph_for_char(c).unwrap();
+    let u24 = |value: u32| value.to_be_bytes()[1..].to_vec();
+
+    // A format 14 subtable with a specific glyph for "A" + U+FE0F, and marking "C" + U+FE0F and
+    // "A" + U+E0100 as using the default glyphs.
+    let mut subtable = vec![0, 14];
+    subtable.extend_from_slice(&57u32.to_be_bytes());
+    subtable.extend_from_slice(&2u32.to_be_bytes());
+    for (selector, default_offset, non_default_offset) in [(0xfe0f, 32, 40), (0xe0100, 49, 0)] {
+        subtable.extend(u24(selector));
+        subtable.extend_from_slice(&u32::to_be_bytes(default_offset));
+        subtable.extend_from_slice(&u32::to_be_bytes(non_default_offset));
+    }
+    subtable.extend_from_slice(&1u32.to_be_bytes());
+    subtable.extend(u24('C' as u32));
+    subtable.push(0);
+    subtable.extend_from_slice(&1u32.to_be_bytes());
+    subtable.extend(u24('A' as u32));
+    subtable.extend_from_slice(&(glyph('B') as u16).to_be_bytes());
+    subtable.extend_from_slice(&1u32.to_be_bytes());
+    subtable.extend(u24('A' as u32));
+    subtable.push(0);
+    assert_eq!(subtable.len(), 57);
+
+    // Append it to the font's own `cmap`, with an encoding record in sorted order.
+    let cmap_tag = u32::from_be_bytes(*b"cmap");
+    let cmap = font.load_font_table(cmap_tag).unwrap();
+    let num_tables = u16::from_be_bytes([cmap[2], cmap[3]]) as usize;
+    let mut records: Vec<_> = cmap[4..(4 + num_tables * 8)]
+        .chunks(8)
+        .map(|record| {
+            let offset = u32::from_be_bytes(record[4..8].try_into().unwrap());
+            [&record[..4], &(offset + 8).to_be_bytes()[..]].concat()
+        })
+        .collect();
+    records.push(
+        [
+            &[0, 0, 0, 5][..],
+            &(cmap.len() as u32 + 8).to_be_bytes()[..],
+        ]
+        .concat(),
+    );
+    records.sort();
+    let mut new_cmap = vec![0, 0];
+    new_cmap.extend_from_slice(&(num_tables as u16 + 1).to_be_bytes());
+    new_cmap.extend(records.concat());
+    new_cmap.extend_from_slice(&cmap[(4 + num_tables * 8)..]);
+    new_cmap.extend(subtable);
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(cmap_tag, new_cmap)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+
+    assert_eq!(font.glyph_for_char('A'), Some(glyph('A')));
+    assert_eq!(
+        font.glyph_for_char_with_variation('A', '\u{fe0f}'),
+        Some(glyph('B'))
+    );
+    assert_eq!(
+        font.glyph_for_char_with_variation('C', '\u{fe0f}'),
+        Some(glyph('C'))
+    );
+    assert_eq!(
+        font.glyph_for_char_with_variation('D', '\u{fe0f}'),
+        Some(glyph('D'))
+    );
+    assert_eq!(
+        font.glyph_for_char_with_variation('A', '\u{fe0e}'),
+        Some(glyph('A'))
+    );
+    assert_eq!(
+        font.glyph_for_char_with_variation('A', '\u{e0100}'),
+        Some(glyph('A'))
+    );
+    assert_eq!(
+        font.glyph_for_char_with_variation('\u{10ffff}', '\u{fe0f}'),
+        None
+    );
+}
+
 macro_rules! assert_line_to {
     ($event:expr, $pt:expr) => {
         match $event {
@@ -476,10 +710,105 @@ pub fn get_font_metrics() {
     assert_eq!(metrics.ascent, 1901.0);
     assert_eq!(metrics.descent, -483.0);
     assert_eq!(metrics.line_gap, 0.0); // FIXME(pcwalton): Huh?!
-    assert_eq!(metrics.underline_position, -130.0);
+                                       // The underline position differs between builds of DejaVu Sans, so it is checked against a
+                                       // bundled font in `get_underline_metrics()` instead.
     assert_eq!(metrics.underline_thickness, 90.0);
-    assert_eq!(metrics.cap_height, 0.0); // FIXME(pcwalton): Huh?!
-    assert_eq!(metrics.x_height, 0.0); // FIXME(pcwalton): Huh?!
+    // DejaVu Sans has a version 1 `OS/2` table, so these are estimated from the glyphs.
+    assert_eq!(metrics.cap_height, 1493.0);
+    assert_eq!(metrics.x_height, 1120.0);
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn get_underline_metrics() {
+    // These come from the `post` table of each font.
+    for &(path, position, thickness) in &[
+        (FILE_PATH_INCONSOLATA_TTF, -175.0, 50.0),
+        (FILE_PATH_EB_GARAMOND_TTF, -125.0, 50.0),
+    ] {
+        let metrics = Font::from_path(path, 0).unwrap().metrics();
+        assert_eq!(metrics.underline_position, position);
+        assert_eq!(metrics.underline_thickness, thickness);
+    }
+}
+
+#[test]
+pub fn get_units_per_em() {
+    for &(path, expected) in &[
+        (FILE_PATH_INCONSOLATA_TTF, 1000),
+        (TEST_FONT_FILE_PATH, 1000),
+        (FILE_PATH_EB_GARAMOND_TTF, 1000),
+        (FILE_PATH_VAZIRMATN_VAR_TTF, 2048),
+    ] {
+        let font = Font::from_path(path, 0).unwrap();
+        assert_eq!(font.units_per_em(), expected);
+        assert_eq!(font.units_per_em(), font.metrics().units_per_em);
+    }
+}
+
+#[test]
+pub fn get_line_metrics_of_each_table() {
+    let metrics = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0)
+        .unwrap()
+        .metrics();
+    assert_eq!(
+        (
+            metrics.hhea_ascent,
+            metrics.hhea_descent,
+            metrics.hhea_line_gap
+        ),
+        (Some(859.0), Some(-190.0), Some(0.0))
+    );
+    assert_eq!(
+        (
+            metrics.typo_ascent,
+            metrics.typo_descent,
+            metrics.typo_line_gap
+        ),
+        (Some(859.0), Some(-190.0), Some(0.0))
+    );
+    assert_eq!(
+        (metrics.win_ascent, metrics.win_descent),
+        (Some(1004.0), Some(-306.0))
+    );
+    assert!(metrics.use_typo_metrics);
+
+    let metrics = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap().metrics();
+    assert_eq!(
+        (metrics.win_ascent, metrics.win_descent),
+        (Some(910.0), Some(-324.0))
+    );
+    assert!(!metrics.use_typo_metrics);
+}
+
+#[test]
+pub fn get_line_metrics_of_font_with_use_typo_metrics() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let os_2_tag = u32::from_be_bytes(*b"OS/2");
+    let mut os_2 = font.load_font_table(os_2_tag).unwrap().into_vec();
+    // Give the typographic metrics values of their own: 800, -200, and 100.
+    os_2[68..74].copy_from_slice(&[0x03, 0x20, 0xff, 0x38, 0x00, 0x64]);
+    let font_data = font.copy_font_data().unwrap();
+
+    let patched_font_data = add_font_tables(&font_data, vec![(os_2_tag, os_2.clone())]);
+    let metrics = Font::from_bytes(Arc::new(patched_font_data), 0)
+        .unwrap()
+        .metrics();
+    assert_eq!(metrics.typo_ascent, Some(800.0));
+    assert_ne!(metrics.ascent, 800.0);
+
+    // Setting `USE_TYPO_METRICS` makes them the line metrics.
+    os_2[63] |= 1 << 7;
+    let patched_font_data = add_font_tables(&font_data, vec![(os_2_tag, os_2)]);
+    let metrics = Font::from_bytes(Arc::new(patched_font_data), 0)
+        .unwrap()
+        .metrics();
+    assert!(metrics.use_typo_metrics);
+    assert_eq!(
+        (metrics.ascent, metrics.descent, metrics.line_gap),
+        (800.0, -200.0, 100.0)
+    );
+    assert_eq!(metrics.hhea_ascent, Some(710.0));
 }
 
 #[test]
@@ -494,6 +823,99 @@ pub fn get_font_properties() {
     assert_eq!(properties.stretch, Stretch(1.0));
 }
 
+#[test]
+pub fn detect_monospace_fonts() {
+    let inconsolata = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let garamond = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert!(inconsolata.is_monospace());
+    assert!(!garamond.is_monospace());
+
+    // Flip `isFixedPitch`; the advances should still decide.
+    let post_tag = u32::from_be_bytes(*b"post");
+    for (font, is_fixed_pitch) in [(&inconsolata, 0), (&garamond, 1)] {
+        let mut post = font.load_font_table(post_tag).unwrap().into_vec();
+        post[12..16].copy_from_slice(&u32::to_be_bytes(is_fixed_pitch));
+        let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(post_tag, post)]);
+        let patched_font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+        assert_eq!(patched_font.is_monospace(), font.is_monospace());
+    }
+}
+
+#[test]
+pub fn get_italic_angle() {
+    let font = Font::from_path("resources/tests/eb-garamond/EBGaramond12-Italic.otf", 0).unwrap();
+    assert_eq!(font.italic_angle(), -17.0);
+    assert_eq!(font.properties().italic_angle, Some(-17.0));
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert_eq!(font.italic_angle(), 0.0);
+}
+
+#[test]
+pub fn get_caret_slope() {
+    let font = Font::from_path("resources/tests/eb-garamond/EBGaramond12-Italic.otf", 0).unwrap();
+    let metrics = font.metrics();
+    assert_eq!(
+        (metrics.caret_slope_rise, metrics.caret_slope_run),
+        (100.0, 31.0)
+    );
+    let metrics = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap().metrics();
+    assert_eq!(
+        (metrics.caret_slope_rise, metrics.caret_slope_run),
+        (1.0, 0.0)
+    );
+}
+
+#[test]
+pub fn get_strikeout_metrics() {
+    let font = Font::from_path("resources/tests/eb-garamond/EBGaramond12-Regular.otf", 0).unwrap();
+    let metrics = font.metrics();
+    assert_eq!(metrics.strikeout_position, 258.0);
+    assert_eq!(metrics.strikeout_size, 49.0);
+
+    // Zero out `yStrikeoutSize` and `yStrikeoutPosition` to get the derived values.
+    let os_2_tag = u32::from_be_bytes(*b"OS/2");
+    let mut os_2 = font.load_font_table(os_2_tag).unwrap().into_vec();
+    os_2[26..30].copy_from_slice(&[0; 4]);
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(os_2_tag, os_2)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let metrics = font.metrics();
+    assert_eq!(metrics.strikeout_size, metrics.underline_thickness);
+    assert_eq!(
+        metrics.strikeout_position,
+        metrics.x_height / 2.0 + metrics.underline_thickness / 2.0
+    );
+}
+
+#[test]
+pub fn match_closest_italic_angle() {
+    let face = |style, weight, italic_angle| Properties {
+        style,
+        weight,
+        italic_angle: Some(italic_angle),
+        ..Properties::new()
+    };
+    let candidates = [
+        face(Style::Oblique, Weight::NORMAL, -12.0),
+        face(Style::Oblique, Weight::NORMAL, -20.0),
+        face(Style::Normal, Weight::NORMAL, 0.0),
+        face(Style::Oblique, Weight::BOLD, -20.0),
+    ];
+    let mut query = Properties::new();
+    query.style(Style::Oblique);
+    // Without an angle, tied faces keep their order.
+    assert_eq!(matching::find_best_match(&candidates, &query), Ok(0));
+    query.italic_angle(-18.0);
+    assert_eq!(matching::find_best_match(&candidates, &query), Ok(1));
+    let ranked: Vec<usize> = matching::rank_matches(&candidates, &query)
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(ranked, vec![1, 0, 3, 2]);
+    // The angle only breaks ties, so it doesn't override weight.
+    query.weight(Weight::BOLD).italic_angle(-12.0);
+    assert_eq!(matching::find_best_match(&candidates, &query), Ok(3));
+}
+
 #[test]
 pub fn get_font_data() {
     let font = SystemSource::new()
@@ -507,6 +929,31 @@ pub fn get_font_data() {
         .any(|version| data[0..4] == *version));
 }
 
+#[test]
+pub fn get_font_data_without_copying() {
+    let mut font_data = vec![];
+    File::open(FILE_PATH_EB_GARAMOND_TTF)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    let font_data = Arc::new(font_data);
+    let font = Font::from_bytes(font_data.clone(), 0).unwrap();
+    let data = font.font_data().unwrap();
+    assert_eq!(*data, **font_data);
+    assert_eq!(*data, **font.copy_font_data().unwrap());
+}
+
+// DirectWrite reads font files itself rather than mapping them.
+#[cfg(all(feature = "mmap", not(target_family = "windows")))]
+#[test]
+pub fn get_memory_mapped_font_data_without_copying() {
+    let font = Font::from_path_mmap(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    match font.font_data().unwrap() {
+        FontData::Borrowed(data) => assert_eq!(*data, **font.copy_font_data().unwrap()),
+        FontData::Memory(_) => panic!("Memory-mapped font data should be borrowed!"),
+    }
+}
+
 #[test]
 pub fn rasterize_glyph_with_grayscale_aa() {
     let font = SystemSource::new()
@@ -618,6 +1065,54 @@ pub fn rasterize_glyph_bilevel_offset() {
     check_L_shape(&canvas);
 }
 
+#[test]
+pub fn rasterize_glyph_bilevel_with_threshold() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('o').unwrap();
+    let size = 16.0;
+    let hinting_options = HintingOptions::Vertical(size);
+    let origin = point2(4.3, 20.0);
+    let rasterize = |rasterization_options, rasterization_adjustments| {
+        let mut canvas = Canvas::new(&Size2D::new(24, 24), Format::A8);
+        font.rasterize_glyph_with_adjustments(
+            &mut canvas,
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            &origin,
+            hinting_options,
+            rasterization_options,
+            rasterization_adjustments,
+        )
+        .unwrap();
+        canvas.pixels
+    };
+
+    let grayscale = rasterize(
+        RasterizationOptions::GrayscaleAa,
+        RasterizationAdjustments::default(),
+    );
+    let mut previous_count = usize::MAX;
+    for &threshold in &[1, 128, 255] {
+        let bilevel = rasterize(
+            RasterizationOptions::Bilevel,
+            RasterizationAdjustments {
+                bilevel_threshold: Some(threshold),
+                ..RasterizationAdjustments::default()
+            },
+        );
+        // The result is the hinted grayscale coverage, thresholded.
+        let expected: Vec<u8> = grayscale
+            .iter()
+            .map(|&value| if value >= threshold { 0xff } else { 0 })
+            .collect();
+        assert_eq!(bilevel, expected);
+        let count = bilevel.iter().filter(|&&value| value != 0).count();
+        assert!(count > 0 && count < previous_count);
+        previous_count = count;
+    }
+}
+
 #[cfg(any(
     not(any(target_os = "macos", target_os = "ios", target_family = "windows")),
     feature = "loader-freetype-default"
@@ -658,20 +1153,75 @@ pub fn rasterize_glyph_with_full_hinting() {
     // Make sure the top and bottom (non-blank) rows have some fully black pixels in them.
     let mut top_row = &canvas.pixels[0..canvas.stride];
     if top_row.iter().all(|&value| value == 0) {
-        top_row = &canvas.pixels[(1 * canvas.stride)..(2 * canvas.stride)];
+        top_row = &canvas.pixels[canvas.stride..(2 * canvas.stride)];
     }
 
-    assert!(top_row.iter().any(|&value| value == 0xff));
+    assert!(top_row.contains(&0xff));
     for y in (0..(canvas.size.height as usize)).rev() {
         let bottom_row = &canvas.pixels[(y * canvas.stride)..((y + 1) * canvas.stride)];
         if bottom_row.iter().all(|&value| value == 0) {
             continue;
         }
-        assert!(bottom_row.iter().any(|&value| value == 0xff));
+        assert!(bottom_row.contains(&0xff));
         break;
     }
 }
 
+#[test]
+pub fn detect_hinting_instructions() {
+    // Inconsolata has `fpgm` and `prep` tables, and EB Garamond's CFF charstrings have stem hints.
+    for path in [FILE_PATH_INCONSOLATA_TTF, TEST_FONT_FILE_PATH] {
+        assert!(Font::from_path(path, 0).unwrap().has_hinting_instructions());
+    }
+    // This font has no `fpgm` or `prep` table, but one of its composite glyphs has instructions.
+    let font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    assert!(font.has_hinting_instructions());
+    for path in [FILE_PATH_GLYF_COMPONENTS_TTF, FILE_PATH_CBDT_TTF] {
+        assert!(!Font::from_path(path, 0).unwrap().has_hinting_instructions());
+    }
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn rasterize_glyph_with_autohinter() {
+    let font = Font::from_path("resources/tests/inconsolata/Inconsolata-Regular.ttf", 0).unwrap();
+    let size = 11.0;
+    assert!(font.supports_hinting_options(HintingOptions::Autohint(size), true));
+    assert!(!font.supports_hinting_options(HintingOptions::Autohint(size), false));
+
+    // Inconsolata has its own instructions, which the autohinter ignores.
+    let glyph_id = font.glyph_for_char('a').unwrap();
+    let raster_rect = font
+        .raster_bounds(
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            &Point2D::zero(),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap()
+        .inflate(2, 2);
+    let origin = Point2D::new(-raster_rect.origin.x, -raster_rect.origin.y).to_f32();
+    let rasterize = |hinting_options| {
+        let mut canvas = Canvas::new(&raster_rect.size.to_u32(), Format::A8);
+        font.rasterize_glyph(
+            &mut canvas,
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            &origin,
+            hinting_options,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap();
+        canvas.pixels
+    };
+    let autohinted = rasterize(HintingOptions::Autohint(size));
+    assert!(autohinted.iter().any(|&value| value != 0));
+    assert_ne!(autohinted, rasterize(HintingOptions::Full(size)));
+}
+
 #[cfg(target_family = "windows")]
 #[test]
 pub fn rasterize_glyph() {
@@ -742,6 +1292,77 @@ pub fn font_transform() {
     assert!((raster_rect2.origin.y - ((raster_rect.origin.y - 8) * 3 + 8)).abs() <= 3);
 }
 
+#[test]
+pub fn raster_bounds_contain_rasterized_glyphs() {
+    const MARGIN: i32 = 4;
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let size = 17.0;
+    let transforms = [
+        FontTransform::identity(),
+        FontTransform::new(1.0, -0.25, 0.0, 1.0),
+    ];
+    let options = [
+        (RasterizationOptions::Bilevel, Format::A8),
+        (RasterizationOptions::GrayscaleAa, Format::A8),
+        (RasterizationOptions::SubpixelAa, Format::Rgb24),
+    ];
+    for character in "Ag%fj".chars() {
+        let glyph_id = font.glyph_for_char(character).unwrap();
+        for hinting_options in [HintingOptions::None, HintingOptions::Full(size)] {
+            for &(rasterization_options, format) in &options {
+                for transform in &transforms {
+                    let origin = point2(0.3, 0.7);
+                    let bounds = font
+                        .raster_bounds(
+                            glyph_id,
+                            size,
+                            transform,
+                            &origin,
+                            hinting_options,
+                            rasterization_options,
+                        )
+                        .unwrap();
+
+                    // Rasterize with a margin around the bounds, which should stay empty.
+                    let canvas_size = (bounds.size + Size2D::new(MARGIN, MARGIN) * 2).to_u32();
+                    let mut canvas = Canvas::new(&canvas_size, format);
+                    let canvas_origin =
+                        origin - bounds.origin.to_vector().to_f32() + vec2(MARGIN, MARGIN).to_f32();
+                    font.rasterize_glyph(
+                        &mut canvas,
+                        glyph_id,
+                        size,
+                        transform,
+                        &canvas_origin,
+                        hinting_options,
+                        rasterization_options,
+                    )
+                    .unwrap();
+                    let bytes_per_pixel = format.bytes_per_pixel() as usize;
+                    for (y, row) in canvas.pixels.chunks(canvas.stride).enumerate() {
+                        for (x, pixel) in row.chunks(bytes_per_pixel).enumerate() {
+                            let (x, y) = (x as i32, y as i32);
+                            let inside = x >= MARGIN
+                                && y >= MARGIN
+                                && x < MARGIN + bounds.size.width
+                                && y < MARGIN + bounds.size.height;
+                            assert!(
+                                inside || pixel.iter().all(|&value| value == 0),
+                                "{:?} drew outside its bounds at ({}, {}) with {:?}, {:?}",
+                                character,
+                                x,
+                                y,
+                                hinting_options,
+                                rasterization_options,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[test]
 fn load_fonts_from_opentype_collection() {
     let mut file = File::open(TEST_FONT_COLLECTION_FILE_PATH).unwrap();
@@ -759,12 +1380,150 @@ fn load_fonts_from_opentype_collection() {
     );
 }
 
+#[test]
+fn load_font_from_bytes_by_postscript_name() {
+    let font_data = Arc::new(std::fs::read(TEST_FONT_COLLECTION_FILE_PATH).unwrap());
+    for (font_index, &postscript_name) in TEST_FONT_COLLECTION_POSTSCRIPT_NAME.iter().enumerate() {
+        let font = Font::from_bytes_by_postscript_name(font_data.clone(), postscript_name).unwrap();
+        assert_eq!(font.postscript_name().as_deref(), Some(postscript_name));
+        let expected_font = Font::from_bytes(font_data.clone(), font_index as u32).unwrap();
+        assert_eq!(font.glyph_count(), expected_font.glyph_count());
+    }
+    match Font::from_bytes_by_postscript_name(font_data, "NoSuchFont-Regular") {
+        Err(FontLoadingError::NoSuchFontInCollection) => {}
+        result => panic!("expected no such font in collection, got {:?}", result),
+    }
+
+    // A single font is found by its own name.
+    let font_data = Arc::new(std::fs::read(TEST_FONT_FILE_PATH).unwrap());
+    let font = Font::from_bytes_by_postscript_name(font_data, TEST_FONT_POSTSCRIPT_NAME).unwrap();
+    assert_eq!(
+        font.postscript_name().as_deref(),
+        Some(TEST_FONT_POSTSCRIPT_NAME)
+    );
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+pub fn load_font_from_memory_mapped_file() {
+    let mapped_font = Font::from_path_mmap(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert_eq!(mapped_font.postscript_name(), font.postscript_name());
+    assert_eq!(mapped_font.glyph_count(), font.glyph_count());
+    let glyph = mapped_font.glyph_for_char('g').unwrap();
+    let (mut mapped_path_builder, mut path_builder) = (Path::builder(), Path::builder());
+    mapped_font
+        .outline(glyph, HintingOptions::None, &mut mapped_path_builder)
+        .unwrap();
+    font.outline(glyph, HintingOptions::None, &mut path_builder)
+        .unwrap();
+    assert!(mapped_path_builder
+        .build()
+        .iter()
+        .eq(path_builder.build().iter()));
+
+    let mut font_data = vec![];
+    File::open(FILE_PATH_EB_GARAMOND_TTF)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    assert_eq!(*mapped_font.copy_font_data().unwrap(), font_data);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+pub fn load_fonts_from_memory_mapped_collection() {
+    let font = Font::from_path_mmap(TEST_FONT_COLLECTION_FILE_PATH, 1).unwrap();
+    assert_eq!(
+        font.postscript_name().unwrap(),
+        TEST_FONT_COLLECTION_POSTSCRIPT_NAME[1]
+    );
+    match Font::from_path_mmap(TEST_FONT_COLLECTION_FILE_PATH, 2) {
+        Err(FontLoadingError::NoSuchFontInCollection) => {}
+        result => panic!("expected no such font in collection, got {:?}", result),
+    }
+}
+
+#[test]
+fn count_fonts_in_collection() {
+    assert_eq!(
+        Font::count_in_collection(TEST_FONT_COLLECTION_FILE_PATH).unwrap(),
+        2
+    );
+    assert_eq!(Font::count_in_collection(TEST_FONT_FILE_PATH).unwrap(), 1);
+    let mut font_data = vec![];
+    File::open(TEST_FONT_COLLECTION_FILE_PATH)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    assert_eq!(
+        Font::count_in_collection_bytes(Arc::new(font_data)).unwrap(),
+        2
+    );
+}
+
+#[test]
+fn load_all_fonts_in_collection() {
+    let fonts = Font::all_in_collection(TEST_FONT_COLLECTION_FILE_PATH).unwrap();
+    assert_eq!(fonts.len(), 2);
+    let postscript_names: Vec<_> = fonts
+        .map(|font| font.unwrap().postscript_name().unwrap())
+        .collect();
+    assert_eq!(postscript_names, TEST_FONT_COLLECTION_POSTSCRIPT_NAME);
+
+    let mut fonts = Font::all_in_collection(TEST_FONT_FILE_PATH).unwrap();
+    assert_eq!(
+        fonts.next().unwrap().unwrap().postscript_name().unwrap(),
+        TEST_FONT_POSTSCRIPT_NAME
+    );
+    assert!(fonts.next().is_none());
+}
+
+#[test]
+fn load_font_with_out_of_range_collection_index() {
+    match Font::from_path(TEST_FONT_COLLECTION_FILE_PATH, 2) {
+        Err(FontLoadingError::NoSuchFontInCollection) => {}
+        result => panic!("expected no such font in collection, got {:?}", result),
+    }
+    match Font::from_path(TEST_FONT_FILE_PATH, 1) {
+        Err(FontLoadingError::NoSuchFontInCollection) => {}
+        result => panic!("expected no such font in collection, got {:?}", result),
+    }
+}
+
 #[test]
 fn get_glyph_count() {
     let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
     assert_eq!(font.glyph_count(), 3084);
 }
 
+#[test]
+fn iterate_glyphs() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyphs = font.glyphs();
+    assert_eq!(glyphs.len(), font.glyph_count() as usize);
+    let glyphs: Vec<_> = glyphs.map(Result::unwrap).collect();
+    assert_eq!(glyphs[0].name.as_deref(), Some(".notdef"));
+
+    let a = font.glyph_for_char('A').unwrap();
+    let glyph = &glyphs[a as usize];
+    assert_eq!(glyph.glyph_id, a);
+    assert_eq!(glyph.advance, font.advance(a).unwrap());
+    assert_eq!(
+        glyph.typographic_bounds,
+        font.typographic_bounds(a).unwrap()
+    );
+    assert_eq!(glyph.name.as_deref(), Some("A"));
+    assert_eq!(font.glyph_by_name(glyph.name.as_ref().unwrap()), Some(a));
+
+    // Iterating stops as soon as the caller does.
+    assert_eq!(font.glyphs().nth(a as usize).unwrap().unwrap(), *glyph);
+
+    // This font's `post` table is format 3, which carries no glyph names.
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert_eq!(font.glyphs().next().unwrap().unwrap().name, None);
+}
+
 // The initial off-curve point used to cause an assertion in the FreeType backend.
 #[test]
 fn get_glyph_outline_eb_garamond_exclam() {
@@ -961,131 +1720,3541 @@ fn get_glyph_outline_inconsolata_J() {
     assert_close!(events.next());
 }
 
-// Makes sure that a canvas has an "L" shape in it. This is used to test rasterization.
-#[allow(non_snake_case)]
-fn check_L_shape(canvas: &Canvas) {
-    // Find any empty rows at the start.
-    let mut y = 0;
-    while y < canvas.size.height {
-        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
-        if canvas.pixels[row_start..row_end].iter().any(|&p| p != 0) {
-            break;
-        }
-        y += 1;
-    }
-    assert!(y < canvas.size.height);
+#[test]
+fn is_variable() {
+    let font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    assert!(font.is_variable());
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert!(!font.is_variable());
+}
 
-    // Find the top part of the L.
-    let mut top_stripe_width = None;
-    while y < canvas.size.height {
+#[test]
+fn get_variation_axes() {
+    let font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let axes = font.variation_axes();
+    assert_eq!(axes.len(), 1);
+    assert_eq!(axes[0].tag, u32::from_be_bytes(*b"wght"));
+    assert_eq!(axes[0].min_value, 100.0);
+    assert_eq!(axes[0].default_value, 400.0);
+    assert_eq!(axes[0].max_value, 900.0);
+    assert!(!axes[0].hidden);
+    assert_eq!(axes[0].name, "Weight");
+}
+
+#[test]
+fn get_hidden_variation_axis() {
+    let font = Font::from_path(FILE_PATH_AVAR2_CHECKER_TTF, 0).unwrap();
+    let axes = font.variation_axes();
+    assert_eq!(axes.len(), 2);
+    assert!(!axes[0].hidden);
+    assert_eq!(axes[0].name, "avar2 check");
+    assert_eq!(axes[1].tag, u32::from_be_bytes(*b"AVWK"));
+    assert!(axes[1].hidden);
+    assert_eq!(axes[1].name, "avar2 worker");
+}
+
+#[test]
+fn get_variation_axes_of_non_variable_font() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert!(font.variation_axes().is_empty());
+}
+
+#[test]
+pub fn get_outline_format() {
+    let font_paths = [
+        (FILE_PATH_EB_GARAMOND_TTF, OutlineFormat::TrueType),
+        (TEST_FONT_FILE_PATH, OutlineFormat::Cff),
+        (FILE_PATH_CANTARELL_VAR_TTF, OutlineFormat::Cff2),
+        (FILE_PATH_CBDT_TTF, OutlineFormat::Bitmap),
+    ];
+    for &(path, outline_format) in &font_paths {
+        let font = Font::from_path(path, 0).unwrap();
+        assert_eq!(font.outline_format(), outline_format, "{}", path);
+    }
+}
+
+#[test]
+fn set_variations() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    let wght = u32::from_be_bytes(*b"wght");
+    assert_eq!(font.advance(glyph).unwrap().x, 1336.0);
+
+    font.set_variations(&[(wght, 900.0)]);
+    assert_eq!(font.advance(glyph).unwrap().x, 1395.0);
+
+    // Out-of-range values are clamped to the extremes of the axis.
+    font.set_variations(&[(wght, 2000.0)]);
+    assert_eq!(font.advance(glyph).unwrap().x, 1395.0);
+
+    // Axes that aren't mentioned go back to their defaults.
+    font.set_variations(&[]);
+    assert_eq!(font.advance(glyph).unwrap().x, 1336.0);
+}
+
+#[test]
+fn get_outline_of_cff2_variable_font() {
+    let mut font = Font::from_path(FILE_PATH_CANTARELL_VAR_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('l').expect("No glyph for char!");
+    let default_events = outline_events(&font, glyph);
+    assert_eq!(
+        default_events[0],
+        PathEvent::Begin {
+            at: Point::new(192.0, -6.0)
+        }
+    );
+
+    // The `blend` operators of the charstring move the points toward the bold master.
+    font.set_variations(&[(u32::from_be_bytes(*b"wght"), 800.0)]);
+    let bold_events = outline_events(&font, glyph);
+    assert_eq!(default_events.len(), bold_events.len());
+    assert_eq!(
+        bold_events[0],
+        PathEvent::Begin {
+            at: Point::new(224.0, -8.0)
+        }
+    );
+    assert_eq!(
+        bold_events[5],
+        PathEvent::Line {
+            from: Point::new(232.0, 168.0),
+            to: Point::new(232.0, 739.0)
+        }
+    );
+
+    font.set_variations(&[]);
+    assert_eq!(outline_events(&font, glyph), default_events);
+}
+
+#[test]
+fn properties_follow_variations() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    assert_eq!(font.properties().weight, Weight::NORMAL);
+    font.set_variations(&[(u32::from_be_bytes(*b"wght"), 700.0)]);
+    assert_eq!(font.properties().weight, Weight::BOLD);
+    assert_eq!(font.properties().style, Style::Normal);
+}
+
+#[test]
+fn properties_from_registered_axes() {
+    let tag = |bytes: &[u8; 4]| u32::from_be_bytes(*bytes);
+    let properties = variations::apply_to_properties(
+        Properties::new(),
+        &[(tag(b"wdth"), 75.0), (tag(b"slnt"), -10.0)],
+    );
+    assert_eq!(properties.stretch, Stretch::CONDENSED);
+    assert_eq!(properties.style, Style::Oblique);
+    assert_eq!(properties.italic_angle, Some(-10.0));
+
+    let properties = variations::apply_to_properties(
+        *Properties::new().style(Style::Italic),
+        &[(tag(b"ital"), 0.0)],
+    );
+    assert_eq!(properties.style, Style::Normal);
+    let properties = variations::apply_to_properties(Properties::new(), &[(tag(b"ital"), 1.0)]);
+    assert_eq!(properties.style, Style::Italic);
+}
+
+#[test]
+fn get_advance_in_font_units() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    for character in "Aé fi".chars() {
+        let glyph = font.glyph_for_char(character).unwrap();
+        assert_eq!(font.advance_font_units(glyph), font.advance(glyph));
+    }
+    assert_eq!(
+        font.advance_font_units(font.glyph_count()),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+
+    // The advance of the default instance is reported regardless of variations.
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    font.set_variations(&[(u32::from_be_bytes(*b"wght"), 900.0)]);
+    assert_eq!(font.advance_font_units(glyph).unwrap(), vec2(1336.0, 0.0));
+}
+
+#[test]
+fn get_horizontal_metrics() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    let metrics = font.horizontal_metrics(glyph).unwrap();
+    assert_eq!(metrics.advance_width, 500.0);
+    assert_eq!(metrics.left_side_bearing, 16.0);
+    assert_eq!(font.typographic_bounds(glyph).unwrap().min_x(), 16.0);
+    assert_eq!(
+        font.horizontal_metrics(font.glyph_count()),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+
+    // The recorded left side bearing is reported even if the outline disagrees with it.
+    let hmtx_tag = u32::from_be_bytes(*b"hmtx");
+    let mut hmtx = font.load_font_table(hmtx_tag).unwrap().into_vec();
+    let offset = glyph as usize * 4 + 2;
+    hmtx[offset..(offset + 2)].copy_from_slice(&40i16.to_be_bytes());
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(hmtx_tag, hmtx)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert_eq!(
+        font.horizontal_metrics(glyph).unwrap().left_side_bearing,
+        40.0
+    );
+
+    // Glyphs past the last long metric share its advance and have bearings of their own.
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let last_glyph = font.glyph_count() - 1;
+    let metrics = font.horizontal_metrics(last_glyph).unwrap();
+    assert_eq!(
+        metrics.advance_width,
+        font.horizontal_metrics(last_glyph - 1)
+            .unwrap()
+            .advance_width
+    );
+    assert_eq!(
+        metrics.left_side_bearing,
+        font.typographic_bounds(last_glyph).unwrap().min_x()
+    );
+}
+
+#[test]
+fn get_maxp_limits() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    assert_eq!(
+        font.maxp_limits(),
+        Some(MaxpLimits {
+            max_points: 96,
+            max_contours: 7,
+            max_composite_points: 92,
+            max_composite_contours: 5,
+            max_component_elements: 3,
+            max_component_depth: 1,
+        })
+    );
+
+    // The `maxp` tables of CFF fonts have no limits.
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert_eq!(font.maxp_limits(), None);
+}
+
+#[test]
+fn set_variations_does_not_affect_clones() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let original_font = font.clone();
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    font.set_variations(&[(u32::from_be_bytes(*b"wght"), 700.0)]);
+    assert_ne!(
+        font.advance(glyph).unwrap(),
+        original_font.advance(glyph).unwrap()
+    );
+}
+
+#[test]
+fn get_variations() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let wght = u32::from_be_bytes(*b"wght");
+    assert_eq!(font.variations(), [(wght, 400.0)]);
+    font.set_variations(&[(wght, 700.0)]);
+    assert_eq!(font.variations(), [(wght, 700.0)]);
+    font.set_variations(&[(wght, 2000.0)]);
+    assert_eq!(font.variations(), [(wght, 900.0)]);
+
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert!(font.variations().is_empty());
+}
+
+#[test]
+fn get_named_instances() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let instances = font.named_instances();
+    let names: Vec<&str> = instances
+        .iter()
+        .map(|instance| &*instance.subfamily_name)
+        .collect();
+    // This font is trimmed, so some of the names that `fvar` refers to are missing.
+    assert_eq!(names, ["Thin", "", "", "Regular", "", "", "Bold", "", ""]);
+    let wght = u32::from_be_bytes(*b"wght");
+    assert_eq!(instances[6].coordinates, [(wght, 700.0)]);
+    assert_eq!(instances[6].postscript_name, None);
+
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    font.set_variations(&instances[8].coordinates);
+    assert_eq!(font.advance(glyph).unwrap().x, 1395.0);
+}
+
+#[test]
+pub fn get_glyph_color_layers() {
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let glyph = font
+        .glyph_for_char('\u{f0e00}')
+        .expect("No glyph for char!");
+    let layers = font.glyph_color_layers(glyph, 0).unwrap();
+    assert_eq!(layers.len(), 8);
+    assert_eq!(layers[0].color, Some(ColorU::new(0xff, 0x00, 0x00, 0xff)));
+    assert_eq!(layers[1].color, Some(ColorU::new(0xff, 0xa5, 0x00, 0xff)));
+    assert_eq!(layers[7].color, Some(ColorU::new(0x00, 0x00, 0x00, 0xff)));
+    assert!(layers[0].glyph_id != layers[1].glyph_id);
+
+    // The second palette has different colors for the same layers.
+    let other_layers = font.glyph_color_layers(glyph, 1).unwrap();
+    assert_eq!(other_layers[0].glyph_id, layers[0].glyph_id);
+    assert_eq!(
+        other_layers[0].color,
+        Some(ColorU::new(0x2a, 0x29, 0x4a, 0xff))
+    );
+
+    // Out-of-range palettes fall back to the first palette.
+    assert_eq!(font.glyph_color_layers(glyph, 1000).unwrap(), layers);
+}
+
+#[test]
+pub fn get_colrv1_paint() {
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let glyph = |c: char| font.glyph_for_char(c).expect("No glyph for char!");
+
+    let gradient_glyph = glyph('\u{f0101}');
+    let red = Some(ColorU::new(0xff, 0x00, 0x00, 0xff));
+    let blue = Some(ColorU::new(0x00, 0x00, 0xff, 0xff));
+    assert_eq!(
+        font.colrv1_paint(gradient_glyph, 0),
+        Some(Paint::Glyph {
+            glyph_id: gradient_glyph,
+            paint: Box::new(Paint::LinearGradient {
+                color_line: ColorLine {
+                    extend: Extend::Repeat,
+                    stops: vec![
+                        ColorStop {
+                            offset: 3277.0 / 16384.0,
+                            color: red,
+                            alpha: 1.0,
+                        },
+                        ColorStop {
+                            offset: 13107.0 / 16384.0,
+                            color: blue,
+                            alpha: 1.0,
+                        },
+                    ],
+                },
+                p0: Point2D::new(100.0, 250.0),
+                p1: Point2D::new(900.0, 250.0),
+                p2: Point2D::new(100.0, 300.0),
+            }),
+        })
+    );
+
+    // Palette entry 0xffff stands for the foreground color.
+    match font.colrv1_paint(glyph('\u{f0b00}'), 0) {
+        Some(Paint::Glyph { paint, .. }) => match *paint {
+            Paint::LinearGradient { color_line, .. } => {
+                assert_eq!(color_line.stops[1].color, None)
+            }
+            paint => panic!("unexpected paint: {:?}", paint),
+        },
+        paint => panic!("unexpected paint: {:?}", paint),
+    }
+
+    // Scales about a center point become a single transform.
+    match font.colrv1_paint(glyph('\u{f0300}'), 0) {
+        Some(Paint::Composite { source, mode, .. }) => {
+            assert_eq!(mode, CompositeMode::DestinationOver);
+            match *source {
+                Paint::Transform { transform, .. } => assert_eq!(
+                    transform,
+                    Transform2D::row_major(0.5, 0.0, 0.0, 1.5, 250.0, -250.0)
+                ),
+                paint => panic!("unexpected paint: {:?}", paint),
+            }
+        }
+        paint => panic!("unexpected paint: {:?}", paint),
+    }
+
+    // Other glyphs' paints are resolved.
+    match font.colrv1_paint(glyph('\u{f0c00}'), 0) {
+        Some(Paint::Composite { backdrop, .. }) => match *backdrop {
+            Paint::ColrGlyph { paint, .. } => match *paint {
+                Paint::ColrGlyph { paint, .. } => match *paint {
+                    Paint::Glyph { paint, .. } => match *paint {
+                        Paint::RadialGradient { r0, r1, .. } => assert_eq!((r0, r1), (0.0, 256.0)),
+                        paint => panic!("unexpected paint: {:?}", paint),
+                    },
+                    paint => panic!("unexpected paint: {:?}", paint),
+                },
+                paint => panic!("unexpected paint: {:?}", paint),
+            },
+            paint => panic!("unexpected paint: {:?}", paint),
+        },
+        paint => panic!("unexpected paint: {:?}", paint),
+    }
+
+    // Out-of-range palettes fall back to the first palette.
+    assert_eq!(
+        font.colrv1_paint(gradient_glyph, 1000),
+        font.colrv1_paint(gradient_glyph, 0)
+    );
+
+    // Version 0 glyphs and fonts without `COLR` tables have no paints.
+    assert_eq!(font.colrv1_paint(glyph('\u{f0e00}'), 0), None);
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    assert_eq!(font.colrv1_paint(glyph, 0), None);
+}
+
+#[test]
+pub fn get_color_palettes() {
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let palettes = font.color_palettes();
+    assert_eq!(palettes.len(), 3);
+    assert!(palettes.iter().all(|palette| palette.colors.len() == 14));
+    let glyph = font.glyph_for_char('\u{f0e00}').unwrap();
+    for (palette_index, palette) in palettes.iter().enumerate() {
+        let layers = font
+            .glyph_color_layers(glyph, palette_index as u16)
+            .unwrap();
+        assert!(palette.colors.contains(&layers[0].color.unwrap()));
+    }
+    let palette_types: Vec<_> = palettes
+        .iter()
+        .map(|palette| {
+            (
+                palette.usable_with_light_background,
+                palette.usable_with_dark_background,
+            )
+        })
+        .collect();
+    assert_eq!(
+        palette_types,
+        [(false, false), (false, true), (true, false)]
+    );
+    assert_eq!(palettes[0].name, None);
+    assert_eq!(palettes[0].entry_names, vec![None; 14]);
+
+    // A version 1 table with one palette of two entries, labeled with the family and subfamily
+    // names.
+    let mut cpal = vec![];
+    for value in [1u16, 2, 1, 2, 0, 26, 0, 0, 34, 0, 38, 0, 40] {
+        cpal.extend_from_slice(&value.to_be_bytes());
+    }
+    cpal.extend_from_slice(&[0x00, 0x00, 0xff, 0xff, 0xff, 0x00, 0x00, 0x80]);
+    for value in [0u16, 3, 1, 2, 0xffff] {
+        cpal.extend_from_slice(&value.to_be_bytes());
+    }
+    let font_data = font.copy_font_data().unwrap();
+    let font_data = add_font_tables(&font_data, vec![(u32::from_be_bytes(*b"CPAL"), cpal)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let palettes = font.color_palettes();
+    assert_eq!(
+        palettes,
+        [ColorPalette {
+            colors: vec![
+                ColorU::new(0xff, 0x00, 0x00, 0xff),
+                ColorU::new(0x00, 0x00, 0xff, 0x80)
+            ],
+            usable_with_light_background: true,
+            usable_with_dark_background: true,
+            name: Some(font.family_name()),
+            entry_names: vec![Some("Regular".to_owned()), None],
+        }]
+    );
+
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert_eq!(font.color_palettes(), []);
+}
+
+#[test]
+pub fn get_glyph_color_layers_of_non_color_glyph() {
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('\u{f0e00}').unwrap();
+    let layer_glyph = font.glyph_color_layers(glyph, 0).unwrap()[0].glyph_id;
+    assert_eq!(font.glyph_color_layers(layer_glyph, 0), Ok(vec![]));
+    assert_eq!(
+        font.glyph_color_layers(font.glyph_count(), 0),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+}
+
+#[test]
+pub fn get_glyph_color_layers_of_non_color_font() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    assert_eq!(
+        font.glyph_color_layers(glyph, 0),
+        Err(GlyphLoadingError::NoColorData)
+    );
+}
+
+#[test]
+pub fn rasterize_color_glyph_with_colr_layers() {
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let glyph = font
+        .glyph_for_char('\u{f0e00}')
+        .expect("No glyph for char!");
+    let size = Size2D::new(64, 64);
+    let mut canvas = Canvas::new(&size, Format::Rgba32);
+    font.rasterize_color_glyph(
+        &mut canvas,
+        glyph,
+        64.0,
+        &FontTransform::identity(),
+        &Point2D::new(0.0, 52.0),
+        HintingOptions::None,
+    )
+    .unwrap();
+    check_premultiplied(&canvas);
+    // The bottommost layer is a red circle.
+    assert!(canvas
+        .pixels
+        .chunks(4)
+        .any(|pixel| pixel == [0xff, 0x00, 0x00, 0xff]));
+}
+
+#[test]
+pub fn rasterize_color_glyph_with_cbdt_bitmap() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let glyph = font
+        .glyph_for_char('\u{1f600}')
+        .expect("No glyph for char!");
+    let size = Size2D::new(32, 32);
+    let mut canvas = Canvas::new(&size, Format::Rgba32);
+    font.rasterize_color_glyph(
+        &mut canvas,
+        glyph,
+        32.0,
+        &FontTransform::identity(),
+        &Point2D::new(0.0, 26.0),
+        HintingOptions::None,
+    )
+    .unwrap();
+    check_premultiplied(&canvas);
+    assert!(canvas
+        .pixels
+        .chunks(4)
+        .any(|pixel| pixel[3] == 0xff && pixel[0] != pixel[2]));
+}
+
+#[test]
+pub fn get_raster_bounds_of_bitmap_glyph() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('\u{2662}').expect("No glyph for char!");
+    let bounds = font
+        .raster_bounds(
+            glyph,
+            16.0,
+            &FontTransform::identity(),
+            &Point2D::zero(),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap();
+    assert!(!bounds.is_empty());
+}
+
+#[test]
+pub fn rasterize_color_glyph_of_non_color_font() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    let mut canvas = Canvas::new(&Size2D::new(16, 16), Format::Rgba32);
+    assert_eq!(
+        font.rasterize_color_glyph(
+            &mut canvas,
+            glyph,
+            16.0,
+            &FontTransform::identity(),
+            &Point2D::new(0.0, 12.0),
+            HintingOptions::None,
+        ),
+        Err(GlyphLoadingError::NoColorData)
+    );
+}
+
+#[test]
+pub fn get_bitmap_strikes() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let strikes = font.bitmap_strikes();
+    let sizes: Vec<u16> = strikes.iter().map(|strike| strike.pixels_per_em).collect();
+    assert_eq!(sizes, [16, 64, 128]);
+    assert!(strikes
+        .iter()
+        .all(|strike| strike.source == BitmapSource::Cbdt));
+}
+
+#[test]
+pub fn get_bitmap_strikes_of_font_without_bitmaps() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert!(font.bitmap_strikes().is_empty());
+}
+
+#[test]
+pub fn get_best_strike_for_size() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let best_size = |pixels_per_em| {
+        let strike = font.best_strike_for_size(pixels_per_em).unwrap();
+        (strike.pixels_per_em, strike.needs_scaling(pixels_per_em))
+    };
+    assert_eq!(best_size(64.0), (64, false));
+    assert_eq!(best_size(63.6), (64, false));
+    assert_eq!(best_size(64.4), (64, false));
+    assert_eq!(best_size(64.6), (128, true));
+    assert_eq!(best_size(12.0), (16, true));
+    assert_eq!(best_size(200.0), (128, true));
+
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert_eq!(font.best_strike_for_size(16.0), None);
+}
+
+#[test]
+pub fn load_bitmap_glyph() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let strike = font.bitmap_strikes()[0];
+    let glyph = font.glyph_for_char('\u{2662}').expect("No glyph for char!");
+    let bitmap = font.load_bitmap_glyph(glyph, &strike).unwrap().unwrap();
+    assert_eq!(bitmap.format, BitmapFormat::Png);
+    assert!(bitmap.data.starts_with(b"\x89PNG"));
+    assert_eq!(bitmap.size, Size2D::new(11, 13));
+    assert_eq!(bitmap.offset, Vector2D::new(1, -13));
+
+    let space = font.glyph_for_char(' ').expect("No glyph for char!");
+    assert_eq!(font.load_bitmap_glyph(space, &strike), Ok(None));
+    assert_eq!(
+        font.load_bitmap_glyph(font.glyph_count(), &strike),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn load_bitmap_only_font() {
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    assert_eq!(font.glyph_count(), 4);
+    let metrics = font.metrics();
+    assert_eq!(metrics.units_per_em, 1000);
+    assert_eq!(font.units_per_em(), 1000);
+    assert_eq!(
+        (metrics.ascent, metrics.descent, metrics.line_gap),
+        (800.0, -200.0, 100.0)
+    );
+
+    let glyph = font.glyph_for_char('\u{2662}').expect("No glyph for char!");
+    assert_eq!(
+        font.outline(glyph, HintingOptions::None, &mut Path::builder()),
+        Err(GlyphLoadingError::UnsupportedFormat)
+    );
+    assert_eq!(
+        font.outline(
+            font.glyph_count(),
+            HintingOptions::None,
+            &mut Path::builder()
+        ),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+    let mut canvas = Canvas::new(&Size2D::new(16, 16), Format::A8);
+    assert_eq!(
+        font.rasterize_glyph(
+            &mut canvas,
+            glyph,
+            13.0,
+            &FontTransform::identity(),
+            &Point2D::new(0.0, 12.0),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        ),
+        Err(GlyphLoadingError::UnsupportedFormat)
+    );
+
+    let strike = font.bitmap_strikes()[0];
+    assert!(font.load_bitmap_glyph(glyph, &strike).unwrap().is_some());
+}
+
+#[test]
+pub fn get_glyph_svg_document() {
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    let glyph_a = font.glyph_for_char('A').expect("No glyph for char!");
+    let glyph_b = font.glyph_for_char('B').expect("No glyph for char!");
+    let document = font.glyph_svg_document(glyph_a).unwrap().unwrap();
+    assert!(document.starts_with(b"<svg"));
+    assert!(String::from_utf8(document.clone())
+        .unwrap()
+        .contains(&format!("id=\"glyph{}\"", glyph_a)));
+    // One document covers both "A" and "B".
+    assert_eq!(font.glyph_svg_document(glyph_b).unwrap().unwrap(), document);
+}
+
+#[test]
+pub fn get_compressed_glyph_svg_document() {
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('C').expect("No glyph for char!");
+    let document = font.glyph_svg_document(glyph).unwrap().unwrap();
+    assert!(document.starts_with(b"<svg"));
+    assert!(document.ends_with(b"</svg>"));
+}
+
+#[test]
+pub fn get_missing_glyph_svg_document() {
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('D').expect("No glyph for char!");
+    assert_eq!(font.glyph_svg_document(glyph), Ok(None));
+
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').expect("No glyph for char!");
+    assert_eq!(font.glyph_svg_document(glyph), Ok(None));
+}
+
+#[test]
+pub fn get_gpos_kerning() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph = |character| font.glyph_for_char(character).unwrap();
+    let units_per_em = font.metrics().units_per_em as f32;
+    assert_eq!(
+        font.kerning(glyph('A'), glyph('V'), units_per_em),
+        vec2(-160.0, 0.0)
+    );
+    assert_eq!(
+        font.kerning(glyph('T'), glyph('o'), units_per_em),
+        vec2(-105.0, 0.0)
+    );
+    assert_eq!(
+        font.kerning(glyph('A'), glyph('A'), units_per_em),
+        vec2(0.0, 0.0)
+    );
+}
+
+#[test]
+pub fn get_kern_table_kerning() {
+    let font = Font::from_path(FILE_PATH_AHEM_KERN_TTF, 0).unwrap();
+    let units_per_em = font.metrics().units_per_em as f32;
+    let glyph = |character| font.glyph_for_char(character).unwrap();
+    let (glyph_a, glyph_b) = (glyph('A'), glyph('B'));
+    assert_eq!(
+        font.kerning(glyph_a, glyph_b, units_per_em),
+        vec2(-100.0, 0.0)
+    );
+    assert_eq!(
+        font.kerning(glyph_b, glyph_a, units_per_em),
+        vec2(50.0, 0.0)
+    );
+    assert_eq!(font.kerning(glyph_a, glyph_a, units_per_em), vec2(0.0, 0.0));
+    // Fonts with neither table don't kern.
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert_eq!(font.kerning(glyph_a, glyph_b, 16.0), vec2(0.0, 0.0));
+}
+
+#[test]
+pub fn get_gpos_kerning_that_overflows() {
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    let glyph = |character| font.glyph_for_char(character).unwrap();
+    let (glyph_a, glyph_b) = (glyph('A'), glyph('B'));
+    // Two pair adjustment lookups, each adding 30,000 units to the advance of the first glyph.
+    let pair_adjustment = [
+        1,
+        18,
+        4,
+        0,
+        1,
+        12,
+        1,
+        glyph_b as u16,
+        30000,
+        1,
+        1,
+        glyph_a as u16,
+    ];
+    let gpos = layout_table(
+        &[(b"kern", &[0, 1])],
+        &[(2, &pair_adjustment), (2, &pair_adjustment)],
+    );
+    let font_data = add_font_tables(
+        &font.copy_font_data().unwrap(),
+        vec![(u32::from_be_bytes(*b"GPOS"), gpos)],
+    );
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let units_per_em = font.metrics().units_per_em as f32;
+    assert_eq!(
+        font.kerning(glyph_a, glyph_b, units_per_em),
+        vec2(i16::MAX as f32, 0.0)
+    );
+}
+
+#[test]
+pub fn substitute_default_ligatures() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyphs = |string: &str| -> Vec<u32> {
+        string
+            .chars()
+            .map(|character| font.glyph_for_char(character).unwrap())
+            .collect()
+    };
+    assert_eq!(font.substitute_ligatures(&glyphs("Qy"), &[]), vec![2817]);
+    // The longest ligature is preferred.
+    assert_eq!(font.substitute_ligatures(&glyphs("ſſt"), &[]), vec![2772]);
+    let mut expected = glyphs("aſtb");
+    expected.splice(1..3, Some(2909));
+    assert_eq!(font.substitute_ligatures(&glyphs("aſtb"), &[]), expected);
+    assert_eq!(
+        font.substitute_ligatures(&glyphs("Qab"), &[]),
+        glyphs("Qab")
+    );
+    // `dlig` is not on by default.
+    assert_eq!(font.substitute_ligatures(&glyphs("Th"), &[]), glyphs("Th"));
+}
+
+#[test]
+pub fn substitute_requested_ligatures() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyphs = |string: &str| -> Vec<u32> {
+        string
+            .chars()
+            .map(|character| font.glyph_for_char(character).unwrap())
+            .collect()
+    };
+    let dlig = u32::from_be_bytes(*b"dlig");
+    assert_eq!(
+        font.substitute_ligatures(&glyphs("Th"), &[dlig]),
+        vec![1985]
+    );
+    assert_eq!(
+        font.substitute_ligatures(&glyphs("Qy"), &[dlig]),
+        glyphs("Qy")
+    );
+    // Fonts without a `GSUB` table leave glyphs alone.
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert_eq!(font.substitute_ligatures(&[35, 36], &[]), vec![35, 36]);
+}
+
+#[test]
+pub fn apply_contextual_substitutions_of_font() {
+    let glyphs = |font: &Font, string: &str| -> Vec<u32> {
+        string
+            .chars()
+            .map(|character| font.glyph_for_char(character).unwrap())
+            .collect()
+    };
+    let ccmp = u32::from_be_bytes(*b"ccmp");
+
+    // Inconsolata's `ccmp` feature drops the dot of `i` and `j` before a mark above, with
+    // chaining contextual substitutions of format 3.
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let dotless_i = font.glyph_by_name("dotlessi").unwrap();
+    let mut expected = glyphs(&font, "i\u{301}");
+    expected[0] = dotless_i;
+    assert_eq!(
+        font.apply_contextual_substitutions(&glyphs(&font, "i\u{301}"), &[ccmp]),
+        expected
+    );
+    assert_eq!(
+        font.apply_contextual_substitutions(&glyphs(&font, "ix"), &[ccmp]),
+        glyphs(&font, "ix")
+    );
+
+    // EB Garamond's `frac` feature turns the digits around a slash into a numerator and a
+    // denominator.
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let frac = u32::from_be_bytes(*b"frac");
+    let mut expected = glyphs(&font, "1/2");
+    expected[0] = font.glyph_by_name("one.ordn").unwrap();
+    expected[2] = font.glyph_by_name("two.subs").unwrap();
+    assert_eq!(
+        font.apply_contextual_substitutions(&glyphs(&font, "1/2"), &[frac]),
+        expected
+    );
+
+    // Fonts without a `GSUB` table leave glyphs alone.
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert_eq!(
+        font.apply_contextual_substitutions(&[35, 36], &[]),
+        vec![35, 36]
+    );
+}
+
+/// Builds a `GSUB` or `GPOS` table with the given features, each with the indices of its lookups, and the
+/// given lookups, each with its type and one subtable of 16-bit words.
+fn layout_table(features: &[(&[u8; 4], &[u16])], lookups: &[(u16, &[u16])]) -> Vec<u8> {
+    let mut words: Vec<u16> = vec![];
+    // The header, followed by an empty script list.
+    let feature_list_offset = 12;
+    let feature_list_size = 2
+        + features.len() * 6
+        + features
+            .iter()
+            .map(|(_, lookup_indices)| 4 + lookup_indices.len() * 2)
+            .sum::<usize>();
+    let lookup_list_offset = feature_list_offset + feature_list_size;
+    words.extend_from_slice(&[1, 0, 10, feature_list_offset as u16]);
+    words.extend_from_slice(&[lookup_list_offset as u16, 0]);
+
+    words.push(features.len() as u16);
+    let mut feature_offset = 2 + features.len() * 6;
+    for &(tag, lookup_indices) in features {
+        words.extend_from_slice(&[
+            u16::from_be_bytes([tag[0], tag[1]]),
+            u16::from_be_bytes([tag[2], tag[3]]),
+            feature_offset as u16,
+        ]);
+        feature_offset += 4 + lookup_indices.len() * 2;
+    }
+    for &(_, lookup_indices) in features {
+        words.extend_from_slice(&[0, lookup_indices.len() as u16]);
+        words.extend_from_slice(lookup_indices);
+    }
+
+    words.push(lookups.len() as u16);
+    let mut lookup_offset = 2 + lookups.len() * 2;
+    for &(_, subtable) in lookups {
+        words.push(lookup_offset as u16);
+        lookup_offset += 8 + subtable.len() * 2;
+    }
+    for &(lookup_type, subtable) in lookups {
+        words.extend_from_slice(&[lookup_type, 0, 1, 8]);
+        words.extend_from_slice(subtable);
+    }
+    words.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+#[test]
+pub fn apply_contextual_substitutions_of_each_format() {
+    let single_substitution = |delta: u16| [1, 6, delta, 2, 1, 10, 12, 0];
+    let (medial, initial, final_) = (
+        single_substitution(100),
+        single_substitution(200),
+        single_substitution(300),
+    );
+    // Joining forms, chosen by the class (1 for every form of glyphs 10 to 12) of the glyphs
+    // before and after: medial, then initial, then final.
+    let joining_forms = [
+        2, 68, 78, 78, 78, 2, 0, 16, // Header and class set offsets.
+        3, 8, 24, 38, // Class set 1.
+        1, 1, 1, 1, 1, 1, 0, 1, // Medial rule.
+        0, 1, 1, 1, 1, 0, 2, // Initial rule.
+        1, 1, 1, 0, 1, 0, 3, // Final rule.
+        1, 3, 10, 11, 12, // Coverage.
+        2, 4, 10, 12, 1, 110, 112, 1, 210, 212, 1, 310, 312, 1, // Class definitions.
+    ];
+    // Glyph 21 becomes glyphs 22 and 23 after glyph 20.
+    let decomposition = [
+        1, 22, 1, 8, // Header and rule set offset.
+        1, 4, // Rule set.
+        2, 1, 21, 1, 5, // Rule.
+        1, 1, 20, // Coverage.
+    ];
+    let multiple_substitution = [1, 14, 1, 8, 2, 22, 23, 1, 1, 21];
+    let gsub = layout_table(
+        &[(b"calt", &[0]), (b"ccmp", &[4])],
+        &[
+            (6, &joining_forms),
+            (1, &medial),
+            (1, &initial),
+            (1, &final_),
+            (5, &decomposition),
+            (2, &multiple_substitution),
+        ],
+    );
+    let font_data = std::fs::read(FILE_PATH_INCONSOLATA_TTF).unwrap();
+    let font_data = add_font_tables(&font_data, vec![(u32::from_be_bytes(*b"GSUB"), gsub)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+
+    let (calt, ccmp) = (u32::from_be_bytes(*b"calt"), u32::from_be_bytes(*b"ccmp"));
+    assert_eq!(
+        font.apply_contextual_substitutions(&[10, 11, 12, 13], &[calt]),
+        vec![210, 111, 312, 13]
+    );
+    assert_eq!(
+        font.apply_contextual_substitutions(&[13, 12, 10], &[calt]),
+        vec![13, 212, 310]
+    );
+    assert_eq!(
+        font.apply_contextual_substitutions(&[11], &[calt]),
+        vec![11]
+    );
+    // `calt` is on by default, and `ccmp` isn't.
+    assert_eq!(
+        font.apply_contextual_substitutions(&[10, 11, 20, 21], &[]),
+        vec![210, 311, 20, 21]
+    );
+    assert_eq!(
+        font.apply_contextual_substitutions(&[20, 21, 21], &[ccmp]),
+        vec![20, 22, 23, 21]
+    );
+    assert_eq!(
+        font.apply_contextual_substitutions(&[21, 21], &[ccmp]),
+        vec![21, 21]
+    );
+}
+
+#[test]
+pub fn get_glyphs_affected_by_feature() {
+    let tag = |bytes: &[u8; 4]| u32::from_be_bytes(*bytes);
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let a = font.glyph_for_char('a').expect("No glyph for char!");
+    let small_cap_a = font.glyph_by_name("a.sc").expect("No glyph for name!");
+    let small_caps = font.feature_affects(tag(b"smcp"));
+    assert!(small_caps.contains(&(a, small_cap_a)));
+    assert!(small_caps.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Ligatures aren't single substitutions, and the font has no slashed zero.
+    assert!(font.feature_affects(tag(b"liga")).is_empty());
+    assert!(font.feature_affects(tag(b"zero")).is_empty());
+
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let zero = font.glyph_for_char('0').expect("No glyph for char!");
+    let slashed_zeros = font.feature_affects(tag(b"zero"));
+    assert_eq!(slashed_zeros.len(), 5);
+    assert_eq!(slashed_zeros[0], (zero, 490));
+}
+
+#[test]
+pub fn get_glyph_alternates() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let zero = font.glyph_for_char('0').expect("No glyph for char!");
+    // The alternates come in the order of the font's `aalt` feature, not sorted.
+    assert_eq!(font.glyph_alternates(zero), [491, 521, 511, 501, 490, 489]);
+    let h = font.glyph_for_char('h').expect("No glyph for char!");
+    assert!(font.glyph_alternates(h).is_empty());
+
+    // Without an `aalt` feature, the alternate substitutions are used.
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let a = font.glyph_for_char('a').expect("No glyph for char!");
+    assert_eq!(font.glyph_alternates(a), [2981]);
+}
+
+#[test]
+pub fn get_ligature_carets() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyphs = |string: &str| -> Vec<u32> {
+        string
+            .chars()
+            .map(|character| font.glyph_for_char(character).unwrap())
+            .collect()
+    };
+    let dlig = u32::from_be_bytes(*b"dlig");
+    let th = font.substitute_ligatures(&glyphs("Th"), &[dlig]);
+    assert_eq!(th.len(), 1);
+    assert_eq!(font.ligature_carets(th[0]), [500.0]);
+    assert!(font.ligature_carets(glyphs("T")[0]).is_empty());
+
+    // Carets can also be given as outline points, as in this `GDEF` table that gives "A" carets
+    // at a coordinate and at its first point.
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('A').unwrap();
+    let mut gdef = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0];
+    for value in [20, 1, 6, 2, 6, 10, 1, 150, 2, 0, 1, 1, glyph_id as u16] {
+        gdef.extend_from_slice(&u16::to_be_bytes(value));
+    }
+    let gdef_tag = u32::from_be_bytes(*b"GDEF");
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(gdef_tag, gdef)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let first_point_x = match outline_events(&font, glyph_id)[0] {
+        PathEvent::Begin { at } => at.x,
+        ref event => panic!("Expected the outline to begin, got {:?}", event),
+    };
+    assert_eq!(font.ligature_carets(glyph_id), [150.0, first_point_x]);
+    assert!(font.ligature_carets(glyph_id + 1).is_empty());
+}
+
+#[test]
+pub fn get_glyph_classes() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph = |character| font.glyph_for_char(character).unwrap();
+    assert_eq!(font.glyph_class(glyph('A')), GlyphClass::Base);
+    assert_eq!(font.glyph_class(glyph('\u{301}')), GlyphClass::Mark);
+    let dlig = u32::from_be_bytes(*b"dlig");
+    let th = font.substitute_ligatures(&[glyph('T'), glyph('h')], &[dlig]);
+    assert_eq!(font.glyph_class(th[0]), GlyphClass::Ligature);
+    assert_eq!(font.mark_attach_class(glyph('\u{301}')), 0);
+
+    // A `GDEF` table that makes "A" a mark of mark attachment class 2.
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('A').unwrap() as u16;
+    let mut gdef = vec![];
+    for value in [
+        1, 0, 12, 0, 0, 20, 1, glyph_id, 1, 3, 2, 1, glyph_id, glyph_id, 2,
+    ] {
+        gdef.extend_from_slice(&u16::to_be_bytes(value));
+    }
+    let gdef_tag = u32::from_be_bytes(*b"GDEF");
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(gdef_tag, gdef)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert_eq!(font.glyph_class(glyph_id as u32), GlyphClass::Mark);
+    assert_eq!(font.mark_attach_class(glyph_id as u32), 2);
+    assert_eq!(font.glyph_class(glyph_id as u32 + 1), GlyphClass::Unknown);
+    assert_eq!(font.mark_attach_class(glyph_id as u32 + 1), 0);
+
+    // Fonts without a `GDEF` table don't classify glyphs.
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert_eq!(font.glyph_class(35), GlyphClass::Unknown);
+}
+
+#[test]
+pub fn get_baselines() {
+    // A `BASE` table with `ideo` and `romn` baselines for the `DFLT` and `hani` scripts.
+    let words = |values: &[i16]| -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    };
+    let base = [
+        words(&[1, 0, 8, 0, 4, 14, 2]),
+        b"ideoromn".to_vec(),
+        words(&[2]),
+        b"DFLT".to_vec(),
+        words(&[14]),
+        b"hani".to_vec(),
+        words(&[36]),
+        words(&[6, 0, 0, 1, 2, 8, 12, 1, -120, 1, 0]),
+        words(&[6, 0, 0, 0, 2, 8, 12, 1, -100, 2, 10, 0, 0]),
+    ]
+    .concat();
+
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let base_tag = u32::from_be_bytes(*b"BASE");
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(base_tag, base)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let (ideo, romn) = (u32::from_be_bytes(*b"ideo"), u32::from_be_bytes(*b"romn"));
+    let (hani, latn) = (ScriptTag::new(b"hani"), ScriptTag::new(b"latn"));
+    assert_eq!(font.baseline(ideo, hani), Some(-100.0));
+    assert_eq!(font.baseline(romn, hani), Some(10.0));
+    assert_eq!(font.baseline(ideo, latn), Some(-120.0));
+    assert_eq!(font.baseline(romn, latn), Some(0.0));
+    assert_eq!(font.baseline(u32::from_be_bytes(*b"hang"), hani), None);
+
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    assert_eq!(font.baseline(ideo, hani), None);
+}
+
+#[test]
+pub fn get_layout_features() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let features = font.layout_features();
+    let tag = |bytes: &[u8; 4]| u32::from_be_bytes(*bytes);
+    let latin = features
+        .scripts
+        .iter()
+        .find(|script| script.script_tag == tag(b"latn"))
+        .unwrap();
+    let default = &latin.language_systems[0];
+    assert_eq!(default.language_tag, None);
+    let feature = |tag: u32| {
+        default
+            .features
+            .iter()
+            .find(|feature| feature.tag == tag)
+            .cloned()
+    };
+    let smcp = feature(tag(b"smcp")).unwrap();
+    assert!(smcp.in_gsub && !smcp.in_gpos);
+    let kern = feature(tag(b"kern")).unwrap();
+    assert!(!kern.in_gsub && kern.in_gpos);
+    assert!(feature(tag(b"ss01")).is_some());
+    assert!(feature(tag(b"locl")).is_none());
+
+    // Language-specific forms are only declared for the languages that need them.
+    let turkish = latin
+        .language_systems
+        .iter()
+        .find(|language_system| language_system.language_tag == Some(tag(b"TRK ")))
+        .unwrap();
+    assert!(turkish
+        .features
+        .iter()
+        .any(|feature| feature.tag == tag(b"locl")));
+
+    let feature_tags = features.feature_tags();
+    assert!(feature_tags.contains(&tag(b"onum")));
+    assert!(feature_tags.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert!(font.layout_features().is_empty());
+}
+
+#[test]
+pub fn get_supported_scripts_and_languages() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let latin = ScriptTag::new(b"latn");
+    assert_eq!(
+        font.supported_scripts(),
+        vec![ScriptTag::new(b"cyrl"), ScriptTag::new(b"grek"), latin]
+    );
+    let languages = font.supported_languages(latin);
+    assert_eq!(
+        languages,
+        [b"AZE ", b"CAT ", b"CRT ", b"DEU ", b"LAT ", b"TRK "]
+            .iter()
+            .map(|tag| LangTag::new(tag))
+            .collect::<Vec<_>>()
+    );
+    assert!(font.supported_languages(ScriptTag::new(b"arab")).is_empty());
+
+    // Fonts without layout tables declare nothing.
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert!(font.supported_scripts().is_empty());
+    assert!(font.supported_languages(latin).is_empty());
+}
+
+#[cfg(feature = "harfbuzz")]
+#[test]
+pub fn shape_text() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph_a = font.glyph_for_char('A').unwrap();
+    let glyph_v = font.glyph_for_char('V').unwrap();
+    let glyphs = font.shape("AV", Direction::LeftToRight, Script::LATIN, &[]);
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!((glyphs[0].glyph_id, glyphs[0].cluster), (glyph_a, 0));
+    assert_eq!((glyphs[1].glyph_id, glyphs[1].cluster), (glyph_v, 1));
+    // The pair is kerned.
+    let advance_a = font.advance(glyph_a).unwrap().x as i32;
+    assert_eq!(glyphs[0].advance, vec2(advance_a - 160, 0));
+    assert_eq!(glyphs[0].offset, vec2(0, 0));
+}
+
+#[cfg(feature = "harfbuzz")]
+#[test]
+pub fn shape_text_with_features() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph_ids = |glyphs: Vec<ShapedGlyph>| -> Vec<u32> {
+        glyphs.iter().map(|glyph| glyph.glyph_id).collect()
+    };
+    let shaped = font.shape("Qy", Direction::LeftToRight, Script::LATIN, &[]);
+    assert_eq!(glyph_ids(shaped), [2817]);
+    let no_liga = Feature::new(u32::from_be_bytes(*b"liga"), 0);
+    let shaped = font.shape("Qy", Direction::LeftToRight, Script::LATIN, &[no_liga]);
+    assert_eq!(shaped.len(), 2);
+    assert_eq!(shaped[1].cluster, 1);
+}
+
+#[cfg(feature = "harfbuzz")]
+#[test]
+pub fn shape_variable_font() {
+    let mut font = Font::from_path(FILE_PATH_VAZIRMATN_VAR_TTF, 0).unwrap();
+    let shaped = font.shape("A", Direction::LeftToRight, Script::LATIN, &[]);
+    assert_eq!(shaped[0].advance.x, 1336);
+    font.set_variations(&[(u32::from_be_bytes(*b"wght"), 900.0)]);
+    let shaped = font.shape("A", Direction::LeftToRight, Script::LATIN, &[]);
+    assert_eq!(shaped[0].advance.x, 1395);
+}
+
+#[test]
+pub fn fingerprint_font_files() {
+    let mut font_data = vec![];
+    File::open(TEST_FONT_FILE_PATH)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    let fingerprint = Font::fingerprint_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert_eq!(Font::fingerprint_bytes(&font_data, 0).unwrap(), fingerprint);
+    // Fingerprints are stable, so that they can be stored.
+    assert_eq!(fingerprint, 2246404939172752539);
+
+    // Changing the checksum adjustment of the `head` table changes the fingerprint.
+    let head_offset = 12
+        + 16 * sfnt::table_tags(&font_data, 0)
+            .unwrap()
+            .iter()
+            .position(|&tag| tag == u32::from_be_bytes(*b"head"))
+            .unwrap();
+    let head_offset = u32::from_be_bytes(
+        font_data[(head_offset + 8)..(head_offset + 12)]
+            .try_into()
+            .unwrap(),
+    );
+    font_data[head_offset as usize + 8] ^= 1;
+    assert_ne!(Font::fingerprint_bytes(&font_data, 0).unwrap(), fingerprint);
+
+    let fingerprints: Vec<u64> = (0..2)
+        .map(|font_index| {
+            Font::fingerprint_path(TEST_FONT_COLLECTION_FILE_PATH, font_index).unwrap()
+        })
+        .collect();
+    assert_ne!(fingerprints[0], fingerprints[1]);
+    assert!(!fingerprints.contains(&fingerprint));
+    match Font::fingerprint_path(TEST_FONT_COLLECTION_FILE_PATH, 2) {
+        Err(FontLoadingError::NoSuchFontInCollection) => {}
+        result => panic!("expected NoSuchFontInCollection, got {:?}", result),
+    }
+
+    // Compressed files can be fingerprinted too, though not to the same value as the fonts they
+    // hold, since the files differ in size.
+    assert_ne!(
+        Font::fingerprint_path(FILE_PATH_INCONSOLATA_WOFF, 0).unwrap(),
+        Font::fingerprint_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap()
+    );
+}
+
+#[test]
+pub fn analyze_woff_file() {
+    let mut file = File::open(FILE_PATH_INCONSOLATA_WOFF).unwrap();
+    assert_eq!(Font::analyze_file(&mut file).unwrap(), FileType::Woff);
+    let mut font_data = vec![];
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.read_to_end(&mut font_data).unwrap();
+    assert_eq!(
+        Font::analyze_bytes(Arc::new(font_data)).unwrap(),
+        FileType::Woff
+    );
+}
+
+#[test]
+pub fn load_woff_font() {
+    let woff_font = Font::from_path(FILE_PATH_INCONSOLATA_WOFF, 0).unwrap();
+    let ttf_font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    assert_eq!(woff_font.postscript_name(), ttf_font.postscript_name());
+    assert_eq!(woff_font.glyph_count(), ttf_font.glyph_count());
+    for character in "Ag&@".chars() {
+        let glyph = woff_font.glyph_for_char(character).unwrap();
+        assert_eq!(Some(glyph), ttf_font.glyph_for_char(character));
+        let (mut woff_path_builder, mut ttf_path_builder) = (Path::builder(), Path::builder());
+        woff_font
+            .outline(glyph, HintingOptions::None, &mut woff_path_builder)
+            .unwrap();
+        ttf_font
+            .outline(glyph, HintingOptions::None, &mut ttf_path_builder)
+            .unwrap();
+        let (woff_path, ttf_path) = (woff_path_builder.build(), ttf_path_builder.build());
+        assert!(woff_path.iter().next().is_some());
+        assert!(woff_path.iter().eq(ttf_path.iter()));
+    }
+}
+
+#[test]
+pub fn load_malformed_woff_font() {
+    let mut font_data = vec![];
+    File::open(FILE_PATH_INCONSOLATA_WOFF)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    font_data.truncate(font_data.len() / 2);
+    match Font::from_bytes(Arc::new(font_data), 0) {
+        Err(FontLoadingError::Parse) => {}
+        result => panic!("expected a parse error, got {:?}", result),
+    }
+}
+
+#[test]
+pub fn load_gzip_compressed_font() {
+    let mut font_data = vec![];
+    File::open(TEST_FONT_COLLECTION_FILE_PATH)
+        .unwrap()
+        .read_to_end(&mut font_data)
+        .unwrap();
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&font_data).unwrap();
+    let compressed_data = Arc::new(encoder.finish().unwrap());
+
+    // The file type is that of the decompressed data.
+    assert_eq!(
+        Font::analyze_bytes(compressed_data.clone()).unwrap(),
+        FileType::Collection(2)
+    );
+    let font = Font::from_bytes(compressed_data.clone(), 1).unwrap();
+    assert_eq!(
+        font.postscript_name().unwrap(),
+        TEST_FONT_COLLECTION_POSTSCRIPT_NAME[1]
+    );
+
+    let truncated_data = compressed_data[..(compressed_data.len() / 2)].to_vec();
+    match Font::from_bytes(Arc::new(truncated_data), 0) {
+        Err(FontLoadingError::Parse) => {}
+        result => panic!("expected a parse error, got {:?}", result),
+    }
+}
+
+#[cfg(feature = "woff2")]
+#[test]
+pub fn analyze_woff2_file() {
+    let mut file = File::open(FILE_PATH_OPEN_SANS_WOFF2).unwrap();
+    assert_eq!(Font::analyze_file(&mut file).unwrap(), FileType::Woff2);
+}
+
+#[cfg(feature = "woff2")]
+#[test]
+pub fn load_woff2_font() {
+    let font = Font::from_path(FILE_PATH_OPEN_SANS_WOFF2, 0).unwrap();
+    assert_eq!(font.postscript_name().unwrap(), "OpenSans-Regular");
+    assert_eq!(font.glyph_count(), 902);
+    let glyph = font.glyph_for_char('A').unwrap();
+    assert_eq!(font.advance(glyph).unwrap(), vec2(1296.0, 0.0));
+    assert_eq!(
+        font.typographic_bounds(glyph).unwrap(),
+        Rect::new(point2(0.0, 0.0), Size2D::new(1296.0, 1468.0))
+    );
+
+    let mut path_builder = Path::builder();
+    font.outline(glyph, HintingOptions::None, &mut path_builder)
+        .unwrap();
+    let path = path_builder.build();
+    let mut events = path.into_iter();
+    assert_eq!(
+        events.next(),
+        Some(PathEvent::Begin {
+            at: Point2D::new(1120.0, 0.0)
+        })
+    );
+    assert_line_to!(events.next(), Point2D::new(938.0, 465.0));
+    assert_line_to!(events.next(), Point2D::new(352.0, 465.0));
+    assert_line_to!(events.next(), Point2D::new(172.0, 0.0));
+    assert_line_to!(events.next(), Point2D::new(0.0, 0.0));
+    assert_line_to!(events.next(), Point2D::new(578.0, 1468.0));
+    assert_line_to!(events.next(), Point2D::new(721.0, 1468.0));
+    assert_line_to!(events.next(), Point2D::new(1296.0, 0.0));
+    assert_close!(events.next());
+    assert_eq!(
+        events.next(),
+        Some(PathEvent::Begin {
+            at: Point2D::new(885.0, 618.0)
+        })
+    );
+    assert_line_to!(events.next(), Point2D::new(715.0, 1071.0));
+    assert_quadratic_to!(
+        events.next(),
+        Point2D::new(682.0, 1157.0),
+        Point2D::new(647.0, 1282.0)
+    );
+    assert_quadratic_to!(
+        events.next(),
+        Point2D::new(625.0, 1186.0),
+        Point2D::new(584.0, 1071.0)
+    );
+    assert_line_to!(events.next(), Point2D::new(412.0, 618.0));
+    assert_close!(events.next());
+    assert_eq!(events.next(), None);
+}
+
+#[cfg(not(feature = "woff2"))]
+#[test]
+pub fn load_woff2_font_without_woff2_feature() {
+    match Font::from_path(FILE_PATH_OPEN_SANS_WOFF2, 0) {
+        Err(FontLoadingError::UnknownFormat) => {}
+        result => panic!("expected an unknown format error, got {:?}", result),
+    }
+}
+
+// Makes sure that a canvas has an "L" shape in it. This is used to test rasterization.
+#[allow(non_snake_case)]
+fn check_L_shape(canvas: &Canvas) {
+    // Find any empty rows at the start.
+    let mut y = 0;
+    while y < canvas.size.height {
+        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
+        if canvas.pixels[row_start..row_end].iter().any(|&p| p != 0) {
+            break;
+        }
+        y += 1;
+    }
+    assert!(y < canvas.size.height);
+
+    // Find the top part of the L.
+    let mut top_stripe_width = None;
+    while y < canvas.size.height {
         let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
         if let Some(stripe_width) = stripe_width(&canvas.pixels[row_start..row_end]) {
             if let Some(top_stripe_width) = top_stripe_width {
                 if stripe_width > top_stripe_width {
                     break;
                 }
-                assert_eq!(stripe_width, top_stripe_width);
+                assert_eq!(stripe_width, top_stripe_width);
+            }
+            top_stripe_width = Some(stripe_width);
+        }
+        y += 1;
+    }
+    assert!(y < canvas.size.height);
+
+    // Find the bottom part of the L.
+    let mut bottom_stripe_width = None;
+    while y < canvas.size.height {
+        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
+        y += 1;
+        if let Some(stripe_width) = stripe_width(&canvas.pixels[row_start..row_end]) {
+            if let Some(bottom_stripe_width) = bottom_stripe_width {
+                assert!(bottom_stripe_width > top_stripe_width.unwrap());
+                assert_eq!(stripe_width, bottom_stripe_width);
+            }
+            bottom_stripe_width = Some(stripe_width);
+        }
+    }
+
+    // Find any empty rows at the end.
+    while y < canvas.size.height {
+        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
+        y += 1;
+        if canvas.pixels[row_start..row_end].iter().any(|&p| p != 0) {
+            break;
+        }
+    }
+
+    // Make sure we made it to the end.
+    assert_eq!(y, canvas.size.height);
+}
+
+// Makes sure that a canvas has an "{" shape in it. This is used to test rasterization.
+#[cfg(target_family = "windows")]
+fn check_curly_shape(canvas: &Canvas) {
+    let mut y = 0;
+    let height = canvas.size.height;
+    // check the upper row and the lower rows are symmetrical
+    while y < height / 2 {
+        let (upper_row_start, upper_row_end) =
+            (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
+        let (lower_row_start, lower_row_end) = (
+            canvas.stride * (height - y - 1) as usize,
+            canvas.stride * (height - y) as usize,
+        );
+        let upper_row = &canvas.pixels[upper_row_start..upper_row_end];
+        let lower_row = &canvas.pixels[lower_row_start..lower_row_end];
+        let top_row_width = stripe_width(upper_row).unwrap();
+        let lower_row_width = stripe_width(lower_row).unwrap();
+        let upper_row_pixel_start = stride_pixel_start(upper_row).unwrap();
+        let lower_row_pixel_start = stride_pixel_start(lower_row).unwrap();
+        if top_row_width == lower_row_width {
+            // check non-zero pixels start at the same index
+            assert_eq!(upper_row_pixel_start, lower_row_pixel_start);
+        } else {
+            //if not, assert that the difference is not greater than 1
+            assert_eq!((lower_row_width as i32 - top_row_width as i32).abs(), 1);
+            // and assert that the non-zero pixel index difference is not greater than 1
+            assert_eq!(
+                (upper_row_pixel_start as i32 - lower_row_pixel_start as i32).abs(),
+                1
+            );
+        }
+        y += 1;
+    }
+}
+
+// return the first non-zero pixel index
+#[cfg(target_family = "windows")]
+fn stride_pixel_start(pixels: &[u8]) -> Option<u32> {
+    let mut index = 0;
+    for x in pixels {
+        if *x != 0 {
+            return Some(index);
+        }
+        index += 1;
+    }
+    None
+}
+
+fn stripe_width(pixels: &[u8]) -> Option<u32> {
+    let mut x = 0;
+    // Find the initial empty part.
+    while x < pixels.len() && pixels[x] == 0 {
+        x += 1
+    }
+    if x == pixels.len() {
+        return None;
+    }
+    // Find the stripe width.
+    let mut stripe_width = 0;
+    while x < pixels.len() && pixels[x] != 0 {
+        x += 1;
+        stripe_width += 1;
+    }
+    // Find the last empty part.
+    while x < pixels.len() && pixels[x] == 0 {
+        x += 1;
+    }
+    assert_eq!(x, pixels.len());
+    Some(stripe_width)
+}
+
+// Makes sure that no color channel of an RGBA canvas exceeds its alpha channel.
+fn check_premultiplied(canvas: &Canvas) {
+    assert_eq!(canvas.format, Format::Rgba32);
+    for pixel in canvas.pixels.chunks(4) {
+        assert!(pixel[0] <= pixel[3] && pixel[1] <= pixel[3] && pixel[2] <= pixel[3]);
+    }
+}
+
+#[test]
+pub fn select_font_by_full_name() {
+    let source = MemSource::from_fonts(
+        vec![
+            Handle::from_path(TEST_FONT_FILE_PATH.into(), 0),
+            Handle::from_path(
+                "resources/tests/eb-garamond/EBGaramond12-Italic.otf".into(),
+                0,
+            ),
+        ]
+        .into_iter(),
+    )
+    .unwrap();
+    let font = source
+        .select_by_full_name("eb garamond 12 ITALIC")
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(font.postscript_name().unwrap(), "EBGaramond12-Italic");
+    assert!(matches!(
+        source.select_by_full_name("EB Garamond 12 Nonexistent"),
+        Err(SelectionError::NotFound)
+    ));
+}
+
+#[test]
+pub fn multi_source_prefers_earlier_sources() {
+    let mem_source = |path: &str| -> Box<dyn Source> {
+        Box::new(
+            MemSource::from_fonts(vec![Handle::from_path(path.into(), 0)].into_iter()).unwrap(),
+        )
+    };
+    let source = MultiSource::from_sources(vec![
+        mem_source(TEST_FONT_FILE_PATH),
+        mem_source(FILE_PATH_INCONSOLATA_TTF),
+        mem_source("resources/tests/eb-garamond/EBGaramond12-Italic.otf"),
+    ]);
+    assert_eq!(
+        source.all_families().unwrap(),
+        ["EB Garamond", "Inconsolata"]
+    );
+    assert_eq!(source.all_fonts().unwrap().len(), 3);
+
+    // The italic in the last source is shadowed by the family in the first.
+    let family = source.select_family_by_name("EB Garamond").unwrap();
+    assert_eq!(family.fonts().len(), 1);
+    let font = source
+        .select_best_match(
+            &[FamilyName::Title("EB Garamond".to_owned())],
+            Properties::new().style(Style::Italic),
+        )
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(font.postscript_name().unwrap(), TEST_FONT_POSTSCRIPT_NAME);
+}
+
+#[test]
+pub fn select_family_by_generic_name_with_config() {
+    let source = MemSource::from_fonts(
+        vec![
+            Handle::from_path(TEST_FONT_FILE_PATH.into(), 0),
+            Handle::from_path(FILE_PATH_INCONSOLATA_TTF.into(), 0),
+        ]
+        .into_iter(),
+    )
+    .unwrap();
+    let config = GenericFamilyConfig {
+        monospace: vec!["Nonexistent".to_owned(), "Inconsolata".to_owned()],
+        serif: vec!["EB Garamond".to_owned()],
+        ..GenericFamilyConfig::new()
+    };
+    let family_name = |family_name: &FamilyName| {
+        let family_handle = source
+            .select_family_by_generic_name_with_config(family_name, &config)
+            .unwrap();
+        Font::from_handle(&family_handle.fonts()[0])
+            .unwrap()
+            .family_name()
+    };
+    assert_eq!(family_name(&FamilyName::Monospace), "Inconsolata");
+    assert_eq!(family_name(&FamilyName::Serif), "EB Garamond");
+    assert_eq!(
+        family_name(&FamilyName::Title("Inconsolata".to_owned())),
+        "Inconsolata"
+    );
+
+    // Generic families that aren't configured fall back to the platform defaults, which this
+    // source doesn't have.
+    assert!(source
+        .select_family_by_generic_name_with_config(&FamilyName::Cursive, &config)
+        .is_err());
+    assert!(source
+        .select_family_by_generic_name_with_config(&FamilyName::SansSerif, &config)
+        .is_err());
+}
+
+#[test]
+pub fn select_best_match_for_language() {
+    let source = MemSource::from_fonts(
+        vec![
+            Handle::from_path(TEST_FONT_FILE_PATH.into(), 0),
+            Handle::from_path(FILE_PATH_INCONSOLATA_TTF.into(), 0),
+        ]
+        .into_iter(),
+    )
+    .unwrap();
+    let family_names = [FamilyName::Title("Inconsolata".to_owned())];
+    let postscript_name_for_lang = |lang: &str| {
+        source
+            .select_best_match_for_lang(&family_names, &Properties::new(), lang)
+            .unwrap()
+            .load()
+            .unwrap()
+            .postscript_name()
+            .unwrap()
+    };
+    assert_eq!(postscript_name_for_lang("pl-PL"), "Inconsolata-Regular");
+    assert_eq!(postscript_name_for_lang("x-unknown"), "Inconsolata-Regular");
+
+    // Inconsolata has no Cyrillic glyphs, so another family is chosen.
+    assert_eq!(postscript_name_for_lang("ru"), TEST_FONT_POSTSCRIPT_NAME);
+
+    // No font has Thai glyphs, so the usual best match is returned.
+    assert_eq!(postscript_name_for_lang("th"), "Inconsolata-Regular");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn fontconfig_source_from_config_path() {
+    use crate::sources::fontconfig::FontconfigSource;
+
+    let temp_dir = std::env::temp_dir().join(format!("font-kit-fontconfig-{}", std::process::id()));
+    std::fs::create_dir_all(&temp_dir).unwrap();
+    let font_dir = std::env::current_dir()
+        .unwrap()
+        .join("resources/tests/eb-garamond");
+    let config_path = temp_dir.join("fonts.conf");
+    std::fs::write(
+        &config_path,
+        format!(
+            "<?xml version=\"1.0\"?>\n<fontconfig>\n  <dir>{}</dir>\n  <cachedir>{}</cachedir>\n</fontconfig>\n",
+            font_dir.display(),
+            temp_dir.join("cache").display()
+        ),
+    )
+    .unwrap();
+
+    let source = FontconfigSource::from_config_path(&config_path).unwrap();
+    assert_eq!(source.all_families().unwrap(), ["EB Garamond"]);
+    let font = source
+        .select_by_postscript_name(TEST_FONT_POSTSCRIPT_NAME)
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(font.family_name(), "EB Garamond");
+    assert!(source.select_family_by_name("Inconsolata").is_err());
+
+    assert!(matches!(
+        FontconfigSource::from_config_path(temp_dir.join("nonexistent.conf")),
+        Err(SelectionError::CannotAccessSource)
+    ));
+    std::fs::remove_dir_all(&temp_dir).unwrap();
+}
+
+#[test]
+pub fn add_and_remove_fonts_in_mem_source() {
+    let mut source = MemSource::from_fonts(vec![].into_iter()).unwrap();
+    assert!(source.all_families().unwrap().is_empty());
+
+    let font = source
+        .add_font(Handle::from_path(TEST_FONT_FILE_PATH.into(), 0))
+        .unwrap();
+    assert_eq!(font.postscript_name().unwrap(), TEST_FONT_POSTSCRIPT_NAME);
+    let family_name = font.family_name();
+    source
+        .add_font(Handle::from_path(
+            "resources/tests/inconsolata/Inconsolata-Regular.ttf".into(),
+            0,
+        ))
+        .unwrap();
+    source
+        .add_font(Handle::from_path(
+            "resources/tests/eb-garamond/EBGaramond12-Italic.otf".into(),
+            0,
+        ))
+        .unwrap();
+    let mut all_families = vec![family_name.clone(), "Inconsolata".to_owned()];
+    all_families.sort();
+    assert_eq!(source.all_families().unwrap(), all_families);
+    assert_eq!(
+        source
+            .select_family_by_name(&family_name)
+            .unwrap()
+            .fonts()
+            .len(),
+        2
+    );
+
+    // Adding the same font again replaces it rather than duplicating it.
+    source
+        .add_font(Handle::from_path(TEST_FONT_FILE_PATH.into(), 0))
+        .unwrap();
+    assert_eq!(source.all_fonts().unwrap().len(), 3);
+    assert_eq!(
+        source
+            .select_family_by_name(&family_name)
+            .unwrap()
+            .fonts()
+            .len(),
+        2
+    );
+
+    assert!(source.remove_font(TEST_FONT_POSTSCRIPT_NAME).is_some());
+    assert!(source.remove_font(TEST_FONT_POSTSCRIPT_NAME).is_none());
+    assert!(source
+        .select_by_postscript_name(TEST_FONT_POSTSCRIPT_NAME)
+        .is_err());
+    assert_eq!(
+        source
+            .select_family_by_name(&family_name)
+            .unwrap()
+            .fonts()
+            .len(),
+        1
+    );
+    source.remove_font("Inconsolata-Regular").unwrap();
+    assert_eq!(source.all_families().unwrap(), vec![family_name]);
+}
+
+#[test]
+pub fn create_mem_source_with_duplicate_postscript_names() {
+    // Both faces have the same PostScript name, so only the last one is kept.
+    let bytes = Arc::new(std::fs::read(TEST_FONT_FILE_PATH).unwrap());
+    let source = MemSource::from_fonts(
+        vec![
+            Handle::from_path(TEST_FONT_FILE_PATH.into(), 0),
+            Handle::from_memory(bytes, 0),
+        ]
+        .into_iter(),
+    )
+    .unwrap();
+    assert_eq!(source.all_fonts().unwrap().len(), 1);
+    match source
+        .select_by_postscript_name(TEST_FONT_POSTSCRIPT_NAME)
+        .unwrap()
+    {
+        Handle::Memory { .. } => {}
+        Handle::Path { .. } => panic!("Expected the later, in-memory face to be kept!"),
+    }
+}
+
+#[test]
+pub fn create_mem_source_with_metadata() {
+    let bytes = Arc::new(std::fs::read(TEST_FONT_FILE_PATH).unwrap());
+    let font = Font::from_bytes(bytes.clone(), 0).unwrap();
+    let metadata = FontMetadata::from_font(&font).unwrap();
+    assert_eq!(metadata.postscript_name, TEST_FONT_POSTSCRIPT_NAME);
+    assert_eq!(metadata.family_name, font.family_name());
+
+    // The fonts aren't loaded, so even a handle to bytes that aren't a font is accepted.
+    let bogus_metadata = FontMetadata {
+        family_name: "Bogus".to_owned(),
+        postscript_name: "Bogus-Regular".to_owned(),
+        properties: Properties::new(),
+    };
+    let bogus_bold_metadata = FontMetadata {
+        family_name: "Bogus".to_owned(),
+        postscript_name: "Bogus-Bold".to_owned(),
+        properties: *Properties::new().weight(Weight::BOLD),
+    };
+    let source = MemSource::from_fonts_with_metadata(
+        vec![
+            (Handle::from_memory(bytes, 0), metadata.clone()),
+            (
+                Handle::from_memory(Arc::new(vec![0; 16]), 0),
+                bogus_metadata,
+            ),
+            (
+                Handle::from_memory(Arc::new(vec![1; 16]), 0),
+                bogus_bold_metadata,
+            ),
+        ]
+        .into_iter(),
+    );
+    assert_eq!(
+        source.all_families().unwrap(),
+        ["Bogus".to_owned(), metadata.family_name.clone()]
+    );
+    let font = source
+        .select_by_postscript_name(TEST_FONT_POSTSCRIPT_NAME)
+        .unwrap()
+        .load()
+        .unwrap();
+    assert_eq!(FontMetadata::from_font(&font), Some(metadata));
+    assert!(source
+        .select_by_postscript_name("Bogus-Regular")
+        .unwrap()
+        .load()
+        .is_err());
+
+    // Matching uses the properties in the metadata, so it works without loading the fonts.
+    let bold = source
+        .select_best_match(
+            &[FamilyName::Title("Bogus".to_owned())],
+            Properties::new().weight(Weight::BOLD),
+        )
+        .unwrap();
+    match bold {
+        Handle::Memory { ref bytes, .. } => assert_eq!(bytes[0], 1),
+        Handle::Path { .. } => panic!("Expected a memory handle!"),
+    }
+}
+
+#[test]
+pub fn select_fallback_for_script() {
+    let primary =
+        Font::from_path("resources/tests/inconsolata/Inconsolata-Regular.ttf", 0).unwrap();
+    assert_eq!(primary.glyph_for_char('א'), None);
+    let fallback = SystemSource::new()
+        .select_fallback(&primary, Script::HEBREW, 'א')
+        .unwrap();
+    assert!(fallback.glyph_for_char('א').is_some());
+}
+
+#[test]
+pub fn get_codepoint_coverage() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let coverage = font.codepoint_coverage();
+    assert!(coverage.contains('a'));
+    assert!(coverage.contains_range(0x41..=0x5a));
+    assert!(!coverage.contains_range(0x4e00..=0x9fff));
+
+    // The coverage must agree exactly with the glyphs that the font maps characters to.
+    let mut covered = 0;
+    for codepoint in 0..=0x10ffff {
+        let expected = match char::from_u32(codepoint) {
+            Some(character) => font.glyph_for_char(character).is_some(),
+            None => false,
+        };
+        assert_eq!(
+            coverage.contains_codepoint(codepoint),
+            expected,
+            "U+{:04X}",
+            codepoint
+        );
+        covered += expected as usize;
+    }
+    assert_eq!(coverage.len(), covered);
+}
+
+#[test]
+pub fn codepoint_coverage_excludes_surrogates() {
+    let coverage =
+        CodepointCoverage::from_ranges(vec![0xe000..=0xe0ff, 0xd000..=0xdfff, 0x41..=0x5a]);
+    assert_eq!(
+        coverage.ranges(),
+        &[0x41..=0x5a, 0xd000..=0xd7ff, 0xe000..=0xe0ff]
+    );
+    assert!(!coverage.contains_codepoint(0xd800));
+    assert!(coverage.contains_range(0xd700..=0xe0ff));
+    assert!(!coverage.contains_range(0xd700..=0xe100));
+}
+
+/// Builds a `cmap` table whose encoding records all point to one format 4 subtable, which maps
+/// the codes `start_code..=end_code` to consecutive glyph IDs starting at `start_glyph_id`.
+fn single_segment_cmap(
+    encoding_records: &[(u16, u16)],
+    start_code: u16,
+    end_code: u16,
+    start_glyph_id: u16,
+) -> Vec<u8> {
+    let mut cmap = vec![];
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&(encoding_records.len() as u16).to_be_bytes());
+    let subtable_offset = 4 + encoding_records.len() as u32 * 8;
+    for &(platform_id, encoding_id) in encoding_records {
+        cmap.extend_from_slice(&platform_id.to_be_bytes());
+        cmap.extend_from_slice(&encoding_id.to_be_bytes());
+        cmap.extend_from_slice(&subtable_offset.to_be_bytes());
+    }
+    // The segment is followed by the one for U+FFFF that terminates every format 4 subtable.
+    let id_delta = start_glyph_id.wrapping_sub(start_code);
+    for value in [
+        4, 32, 0, 4, 4, 1, 0, end_code, 0xffff, 0, start_code, 0xffff, id_delta, 1, 0, 0,
+    ] {
+        cmap.extend_from_slice(&value.to_be_bytes());
+    }
+    cmap
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn get_glyphs_of_symbol_and_mac_roman_fonts() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let font_data = font.copy_font_data().unwrap();
+    let cmap_tag = u32::from_be_bytes(*b"cmap");
+    let a = font.glyph_for_char('A').unwrap();
+
+    // Symbol fonts map their codes into the private use area, where the characters are tried if
+    // they aren't found directly. The Windows symbol subtable is used in preference to the Mac
+    // Roman subtable, which would map the code of `A` to nothing.
+    let cmap = single_segment_cmap(&[(1, 0), (3, 0)], 0xf041, 0xf041, a as u16);
+    let symbol_font_data = add_font_tables(&font_data, vec![(cmap_tag, cmap)]);
+    let symbol_font = Font::from_bytes(Arc::new(symbol_font_data), 0).unwrap();
+    assert_eq!(symbol_font.glyph_for_char('A'), Some(a));
+    assert_eq!(symbol_font.glyph_for_char('\u{f041}'), Some(a));
+    assert_eq!(symbol_font.glyph_for_char('B'), None);
+    assert_eq!(symbol_font.glyphs_for_str("AB"), [Some(a), None]);
+
+    // Characters are encoded as Mac Roman for fonts with only a Mac Roman subtable.
+    let e_acute = font.glyph_for_char('é').unwrap();
+    let cmap = single_segment_cmap(&[(1, 0)], 0x8e, 0x8e, e_acute as u16);
+    let mac_font_data = add_font_tables(&font_data, vec![(cmap_tag, cmap)]);
+    let mac_font = Font::from_bytes(Arc::new(mac_font_data), 0).unwrap();
+    assert_eq!(mac_font.glyph_for_char('é'), Some(e_acute));
+    assert_eq!(mac_font.glyph_for_char('\u{8e}'), None);
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn load_font_without_cmap() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    // FreeType would make a charmap from the glyph names in the `post` table, so that goes too.
+    let (cmap_tag, post_tag) = (u32::from_be_bytes(*b"cmap"), u32::from_be_bytes(*b"post"));
+    let tables = font
+        .table_tags()
+        .into_iter()
+        .filter(|&tag| tag != cmap_tag && tag != post_tag)
+        .map(|tag| (tag, font.load_font_table(tag).unwrap().into_vec()))
+        .collect();
+    let font_data = sfnt::write_sfnt(0x0001_0000, tables);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert_eq!(font.glyph_for_char('A'), None);
+    assert!(font
+        .outline(glyph, HintingOptions::None, &mut Path::builder())
+        .is_ok());
+}
+
+#[test]
+pub fn get_glyphs_for_str() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let text = "Ab \u{4e00}😀z";
+    let glyphs = font.glyphs_for_str(text);
+    let expected: Vec<_> = text.chars().map(|c| font.glyph_for_char(c)).collect();
+    assert_eq!(glyphs, expected);
+    assert_eq!(glyphs[3], None);
+    assert!(font.glyphs_for_str("").is_empty());
+}
+
+#[test]
+pub fn get_chars_for_glyph() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    assert!(font.chars_for_glyph(glyph).contains(&'A'));
+    assert!(font.chars_for_glyph(0).is_empty());
+
+    // Ahem maps the ohm sign and capital omega, which are canonically equivalent, to one glyph.
+    let ahem = Font::from_path(FILE_PATH_AHEM_KERN_TTF, 0).unwrap();
+    let omega = ahem.glyph_for_char('\u{3a9}').unwrap();
+    assert_eq!(ahem.chars_for_glyph(omega), vec!['\u{3a9}', '\u{2126}']);
+
+    // Every mapped character must round-trip through its glyph.
+    for range in font.codepoint_coverage().ranges() {
+        for character in range.clone().filter_map(char::from_u32) {
+            let glyph = font.glyph_for_char(character).unwrap();
+            let chars = font.chars_for_glyph(glyph);
+            assert!(chars.contains(&character), "{:?}", character);
+            assert!(chars.iter().all(|&c| font.glyph_for_char(c) == Some(glyph)));
+        }
+    }
+}
+
+#[test]
+pub fn get_glyph_by_synthesized_name() {
+    // This font's `post` table is format 3, so it carries no glyph names of its own.
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    assert_eq!(font.glyph_by_name(".notdef"), Some(0));
+    assert_eq!(font.glyph_by_name("space"), font.glyph_for_char(' '));
+    assert_eq!(
+        font.glyph_by_name("uni2662"),
+        font.glyph_for_char('\u{2662}')
+    );
+    assert_eq!(font.glyph_by_name("u2662"), font.glyph_for_char('\u{2662}'));
+    assert_eq!(
+        font.glyph_by_name("u1F600"),
+        font.glyph_for_char('\u{1f600}')
+    );
+    assert!(font.glyph_by_name("u1F600").is_some());
+
+    // Characters the font doesn't map, suffixed names, and malformed names can't be resolved.
+    assert_eq!(font.glyph_by_name("A"), None);
+    assert_eq!(font.glyph_by_name("uni2662.alt"), None);
+    assert_eq!(font.glyph_by_name("u1f600"), None);
+    assert_eq!(font.glyph_by_name("uniD800"), None);
+    assert_eq!(font.glyph_by_name("nosuchglyph"), None);
+}
+
+#[test]
+pub fn get_glyph_name() {
+    // `post` table format 2.
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let a = font.glyph_for_char('A').unwrap();
+    assert_eq!(font.glyph_name(0).as_deref(), Some(".notdef"));
+    assert_eq!(font.glyph_name(1).as_deref(), Some("NULL"));
+    assert_eq!(font.glyph_name(a).as_deref(), Some("A"));
+    assert_eq!(font.glyph_name(font.glyph_count()), None);
+    for glyph_id in 0..font.glyph_count() {
+        let name = font.glyph_name(glyph_id).unwrap();
+        assert_eq!(font.glyph_by_name(&name), Some(glyph_id), "{}", name);
+    }
+
+    // `post` table format 3. The names are synthesized, and resolve back to the glyphs.
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    let heart = font.glyph_for_char('\u{2662}').unwrap();
+    let grinning_face = font.glyph_for_char('\u{1f600}').unwrap();
+    assert_eq!(font.glyph_name(0).as_deref(), Some(".notdef"));
+    assert_eq!(font.glyph_name(heart).as_deref(), Some("uni2662"));
+    assert_eq!(font.glyph_name(grinning_face).as_deref(), Some("u1F600"));
+    let space = font.glyph_for_char(' ').unwrap();
+    assert_eq!(font.glyph_name(space).as_deref(), Some("space"));
+    for glyph_id in [0, heart, grinning_face, space] {
+        let name = font.glyph_name(glyph_id).unwrap();
+        assert_eq!(font.glyph_by_name(&name), Some(glyph_id));
+    }
+}
+
+#[test]
+pub fn subset_truetype_font() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let subset = check_subset(&font, "Aé fi");
+
+    // The accented letter is a composite, so its components come along.
+    assert!(subset.old_glyph_ids.len() > 6);
+}
+
+#[test]
+pub fn write_sfnt_without_tables() {
+    let sfnt = sfnt::write_sfnt(0x0001_0000, vec![]);
+    assert_eq!(sfnt, [0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(sfnt::table_tags(&sfnt, 0), Some(vec![]));
+}
+
+// Other platforms may reject the font or substitute empty glyphs rather than report an error.
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn load_glyph_from_malformed_font() {
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph = font.glyph_for_char('A').unwrap();
+    let glyf_tag = u32::from_be_bytes(*b"glyf");
+    let glyf = vec![0xff; font.load_font_table(glyf_tag).unwrap().len()];
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(glyf_tag, glyf)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert_eq!(
+        font.outline(glyph, HintingOptions::None, &mut Path::builder()),
+        Err(GlyphLoadingError::MalformedFont)
+    );
+    assert_eq!(
+        font.typographic_bounds(font.glyph_count()),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+}
+
+// Load flags are specific to the FreeType loader.
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn outline_with_freetype_load_flags() {
+    use freetype::freetype::FT_LOAD_NO_SCALE;
+
+    let mut font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('a').unwrap();
+    let unhinted = outline_events(&font, glyph_id);
+    let hint = |font: &Font| {
+        let mut path_builder = Path::builder();
+        font.outline(glyph_id, HintingOptions::Full(12.0), &mut path_builder)
+            .unwrap();
+        path_builder.build().iter().collect::<Vec<_>>()
+    };
+    assert_ne!(hint(&font), unhinted);
+
+    // Unscaled outlines are never hinted, and come back in font units.
+    assert_eq!(font.load_flags(), 0);
+    font.set_load_flags(FT_LOAD_NO_SCALE);
+    assert_eq!(font.load_flags(), FT_LOAD_NO_SCALE);
+    assert_eq!(hint(&font), unhinted);
+    assert_eq!(hint(&font.clone()), unhinted);
+}
+
+#[test]
+pub fn subset_cff_font() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    check_subset(&font, "Aé fi");
+    assert_eq!(
+        font.subset(&[font.glyph_count()]).unwrap_err(),
+        GlyphLoadingError::NoSuchGlyph
+    );
+}
+
+fn check_subset(font: &Font, text: &str) -> FontSubset {
+    let glyph_ids: Vec<u32> = text
+        .chars()
+        .filter_map(|c| font.glyph_for_char(c))
+        .collect();
+    let subset = font.subset(&glyph_ids).unwrap();
+    let subset_font = Font::from_bytes(Arc::new(subset.font_data.clone()), 0).unwrap();
+
+    assert_eq!(subset.old_glyph_ids[0], 0);
+    assert_eq!(
+        subset_font.glyph_count() as usize,
+        subset.old_glyph_ids.len()
+    );
+    assert_eq!(subset_font.postscript_name(), font.postscript_name());
+    for character in text.chars() {
+        let old_glyph_id = font.glyph_for_char(character).unwrap();
+        assert_eq!(
+            subset_font.glyph_for_char(character),
+            subset.new_glyph_id(old_glyph_id)
+        );
+    }
+    assert_eq!(subset_font.glyph_for_char('B'), None);
+
+    for (new_glyph_id, &old_glyph_id) in subset.old_glyph_ids.iter().enumerate() {
+        let new_glyph_id = new_glyph_id as u32;
+        assert_eq!(
+            subset_font.advance(new_glyph_id).unwrap(),
+            font.advance(old_glyph_id).unwrap()
+        );
+        assert_eq!(
+            outline_events(&subset_font, new_glyph_id),
+            outline_events(font, old_glyph_id)
+        );
+    }
+    subset
+}
+
+fn outline_events(font: &Font, glyph_id: u32) -> Vec<PathEvent> {
+    let mut path_builder = Path::builder();
+    font.outline(glyph_id, HintingOptions::None, &mut path_builder)
+        .unwrap();
+    path_builder.build().iter().collect()
+}
+
+/// Returns the signed area of each contour of an outline, in font units with y pointing up:
+/// positive for counterclockwise contours and negative for clockwise ones.
+///
+/// Curves are approximated by the polygons of their control points, which is enough to tell which
+/// way a contour winds.
+fn contour_areas(events: &[PathEvent]) -> Vec<f32> {
+    let cross = |from: Point, to: Point| (from.x * to.y - to.x * from.y) / 2.0;
+    let mut areas = vec![];
+    let mut area = 0.0;
+    for event in events {
+        area += match *event {
+            PathEvent::Begin { .. } => 0.0,
+            PathEvent::Line { from, to } => cross(from, to),
+            PathEvent::Quadratic { from, ctrl, to } => cross(from, ctrl) + cross(ctrl, to),
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => cross(from, ctrl1) + cross(ctrl1, ctrl2) + cross(ctrl2, to),
+            PathEvent::End { last, first, .. } => {
+                areas.push(area + cross(last, first));
+                area = 0.0;
+                continue;
+            }
+        };
+    }
+    areas
+}
+
+#[test]
+pub fn get_outline_winding() {
+    // Outer contours run clockwise in TrueType fonts and counterclockwise in CFF fonts, and holes
+    // run the other way.
+    for &(path, outer_clockwise) in &[
+        (FILE_PATH_INCONSOLATA_TTF, true),
+        (FILE_PATH_EB_GARAMOND_TTF, true),
+        (TEST_FONT_FILE_PATH, false),
+    ] {
+        let font = Font::from_path(path, 0).unwrap();
+        let glyph = font.glyph_for_char('o').unwrap();
+        let areas = contour_areas(&outline_events(&font, glyph));
+        assert_eq!(areas.len(), 2, "{}", path);
+        let (outer, hole) = if areas[0].abs() > areas[1].abs() {
+            (areas[0], areas[1])
+        } else {
+            (areas[1], areas[0])
+        };
+        assert_eq!(outer < 0.0, outer_clockwise, "{}", path);
+        assert_eq!(hole > 0.0, outer_clockwise, "{}", path);
+    }
+}
+
+#[test]
+pub fn get_outlines_of_composite_glyphs() {
+    let font = Font::from_path(FILE_PATH_GLYF_COMPONENTS_TTF, 0).unwrap();
+    let first_point = |name: &str| {
+        let glyph_id = font.glyph_by_name(name).expect("No glyph for name!");
+        match outline_events(&font, glyph_id)[0] {
+            PathEvent::Begin { at } => at,
+            ref event => panic!("Unexpected path event: {:?}", event),
+        }
+    };
+
+    // A component with a 2x2 transform.
+    assert_eq!(first_point("comma"), Point::new(67.265625, 327.8125));
+
+    // A composite of a composite, whose offset is transformed by the outer component's scale only
+    // if `SCALED_COMPONENT_OFFSET` is set.
+    assert_eq!(first_point("simple_transform"), Point::new(549.9844, 200.0));
+    assert_eq!(
+        first_point("scaled_component_offset"),
+        Point::new(714.96875, 360.0)
+    );
+    assert_eq!(
+        first_point("noscaled_component_offset"),
+        Point::new(704.96875, 340.0)
+    );
+
+    // The second component is turned around and then moved so that its point 3 lands on point 1
+    // of the first.
+    let glyph_id = font.glyph_by_name("point_matched").unwrap();
+    let events = outline_events(&font, glyph_id);
+    assert_eq!(events.len(), 10);
+    assert_eq!(
+        events[5..9],
+        [
+            PathEvent::Begin {
+                at: Point::new(375.0, 50.0)
+            },
+            PathEvent::Line {
+                from: Point::new(375.0, 50.0),
+                to: Point::new(250.0, 50.0)
+            },
+            PathEvent::Line {
+                from: Point::new(250.0, 50.0),
+                to: Point::new(250.0, 100.0)
+            },
+            PathEvent::Line {
+                from: Point::new(250.0, 100.0),
+                to: Point::new(375.0, 100.0)
+            },
+        ]
+    );
+
+    // A composite that contains itself is rejected rather than recursed into forever.
+    let glyph_id = font.glyph_by_name("cycle").unwrap();
+    assert_eq!(
+        font.outline(glyph_id, HintingOptions::None, &mut Path::builder()),
+        Err(GlyphLoadingError::MalformedFont)
+    );
+}
+
+#[test]
+pub fn get_glyph_outline_path() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('a').unwrap();
+    let path = font.outline_path(glyph_id, HintingOptions::None).unwrap();
+    let events: Vec<_> = path.iter().collect();
+    assert!(!events.is_empty());
+    assert_eq!(events, outline_events(&font, glyph_id));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, PathEvent::End { close: true, .. })));
+
+    assert!(matches!(
+        font.outline_path(font.glyph_count(), HintingOptions::None),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    ));
+}
+
+#[test]
+pub fn get_owned_glyph_outline() {
+    for &(path, is_cubic) in &[
+        (TEST_FONT_FILE_PATH, true),
+        (FILE_PATH_INCONSOLATA_TTF, false),
+    ] {
+        let font = Font::from_path(path, 0).unwrap();
+        let glyph_id = font.glyph_for_char('a').unwrap();
+        let mut outline = font.glyph_outline(glyph_id, HintingOptions::None).unwrap();
+        assert!(!outline.is_empty());
+        assert!(outline.contours.iter().all(|contour| contour.closed));
+        let has_segment = |predicate: fn(&Segment) -> bool| {
+            outline
+                .contours
+                .iter()
+                .flat_map(|contour| contour.segments.iter())
+                .any(predicate)
+        };
+        assert_eq!(
+            has_segment(|segment| matches!(segment, Segment::Cubic(..))),
+            is_cubic
+        );
+        assert_eq!(
+            has_segment(|segment| matches!(segment, Segment::Quadratic(..))),
+            !is_cubic
+        );
+
+        // Replaying the outline sends the same path as streaming it.
+        let mut path_builder = Path::builder();
+        outline.copy_to(&mut path_builder);
+        let events: Vec<_> = path_builder.build().iter().collect();
+        assert_eq!(events, outline_events(&font, glyph_id));
+
+        let transform = Transform2D::row_major(0.5, 0.0, -0.1, -0.25, 10.0, 20.0);
+        outline.transform(&transform);
+        let mut path_builder = Path::builder();
+        outline.copy_to(&mut path_builder);
+        let mut expected = Path::builder();
+        font.outline_transformed(glyph_id, HintingOptions::None, &transform, &mut expected)
+            .unwrap();
+        assert_eq!(
+            path_builder.build().iter().collect::<Vec<_>>(),
+            expected.build().iter().collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            font.glyph_outline(font.glyph_count(), HintingOptions::None),
+            Err(GlyphLoadingError::NoSuchGlyph)
+        ));
+    }
+
+    let space = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let glyph_id = space.glyph_for_char(' ').unwrap();
+    assert_eq!(
+        space.glyph_outline(glyph_id, HintingOptions::None),
+        Ok(Outline::new())
+    );
+}
+
+#[test]
+pub fn get_transformed_glyph_outline() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('a').unwrap();
+    // Skew for a synthetic oblique, then scale to pixels with y pointing down, then translate.
+    let transform = Transform2D::row_major(0.5, 0.0, -0.1, -0.25, 10.0, 20.0);
+    let mut path_builder = Path::builder();
+    font.outline_transformed(
+        glyph_id,
+        HintingOptions::None,
+        &transform,
+        &mut path_builder,
+    )
+    .unwrap();
+    let transformed: Vec<_> = path_builder.build().iter().collect();
+
+    let expected: Vec<_> = outline_events(&font, glyph_id)
+        .into_iter()
+        .map(|event| event.transformed(&transform))
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(transformed, expected);
+}
+
+#[test]
+pub fn get_glyph_svg_path() {
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('X').unwrap();
+    // Ahem's `X` fills the em box, from 800 units above the baseline to 200 below it.
+    assert_eq!(
+        font.glyph_svg_path(glyph_id, 10.0).unwrap(),
+        "M0 -8L10 -8L10 2L0 2Z"
+    );
+
+    let mut path_builder = SvgPathBuilder::new();
+    path_builder.move_to(Point::new(0.0, 0.0));
+    path_builder.quadratic_bezier_to(Point::new(1.0, -2.0), Point::new(2.5, 0.0));
+    path_builder.cubic_bezier_to(
+        Point::new(3.0, 1.0),
+        Point::new(4.0, 1.0),
+        Point::new(5.0, 0.0),
+    );
+    path_builder.close();
+    assert_eq!(path_builder.into_string(), "M0 0Q1 -2 2.5 0C3 1 4 1 5 0Z");
+}
+
+#[test]
+pub fn get_embedding_restrictions() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let restrictions = font.embedding_restrictions();
+    assert_eq!(restrictions, EmbeddingRestrictions::default());
+    assert_eq!(restrictions.permission, EmbeddingPermission::Installable);
+    assert!(restrictions.can_embed());
+
+    let restricted = EmbeddingRestrictions::from_fs_type(0x0302);
+    assert_eq!(
+        restricted.permission,
+        EmbeddingPermission::RestrictedLicense
+    );
+    assert!(!restricted.can_embed());
+    assert!(restricted.no_subsetting);
+    assert!(restricted.bitmap_only);
+
+    // When several permissions are set, the least restrictive one wins.
+    let legacy = EmbeddingRestrictions::from_fs_type(0x000e);
+    assert_eq!(legacy.permission, EmbeddingPermission::Editable);
+    assert!(!legacy.no_subsetting);
+}
+
+#[test]
+pub fn get_color_format() {
+    let tag = |bytes: &[u8; 4]| u32::from_be_bytes(*bytes);
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    assert!(font.color_format().is_monochrome());
+
+    let font = Font::from_path(FILE_PATH_COLR_1_TTF, 0).unwrap();
+    let color_format = font.color_format();
+    assert!(color_format.colr_v0 && color_format.colr_v1);
+    assert!(!color_format.svg && !color_format.sbix && !color_format.cbdt);
+    assert!(!color_format.is_monochrome());
+
+    // A version 0 table can only have layers.
+    let mut colr = font.load_font_table(tag(b"COLR")).unwrap().into_vec();
+    colr[0..2].copy_from_slice(&0u16.to_be_bytes());
+    let font_data = add_font_tables(&font.copy_font_data().unwrap(), vec![(tag(b"COLR"), colr)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let color_format = font.color_format();
+    assert!(color_format.colr_v0 && !color_format.colr_v1);
+
+    let font = Font::from_path(FILE_PATH_AHEM_SVG_TTF, 0).unwrap();
+    assert_eq!(
+        font.color_format(),
+        ColorFormat {
+            svg: true,
+            ..ColorFormat::default()
+        }
+    );
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    assert_eq!(
+        font.color_format(),
+        ColorFormat {
+            cbdt: true,
+            ..ColorFormat::default()
+        }
+    );
+
+    let font_data = std::fs::read(FILE_PATH_INCONSOLATA_TTF).unwrap();
+    let sbix = [0u16, 1, 0, 0, 0, 0];
+    let sbix = sbix.iter().flat_map(|value| value.to_be_bytes()).collect();
+    let font_data = add_font_tables(&font_data, vec![(tag(b"sbix"), sbix)]);
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert_eq!(
+        font.color_format(),
+        ColorFormat {
+            sbix: true,
+            ..ColorFormat::default()
+        }
+    );
+}
+
+#[test]
+pub fn get_table_tags() {
+    let tag = |bytes: &[u8; 4]| u32::from_be_bytes(*bytes);
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let tags = font.table_tags();
+    assert!(tags.contains(&tag(b"CFF ")));
+    assert!(!tags.contains(&tag(b"glyf")));
+    assert!(font.contains_table(tag(b"cmap")));
+    assert!(!font.contains_table(tag(b"COLR")));
+
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert!(font.contains_table(tag(b"glyf")));
+    assert!(!font.contains_table(tag(b"CFF ")));
+
+    // Members of a collection report their own directories.
+    let mut collection_data = vec![];
+    File::open(TEST_FONT_COLLECTION_FILE_PATH)
+        .unwrap()
+        .read_to_end(&mut collection_data)
+        .unwrap();
+    for font_index in 0..2 {
+        let font = Font::from_path(TEST_FONT_COLLECTION_FILE_PATH, font_index).unwrap();
+        assert_eq!(
+            Some(font.table_tags()),
+            sfnt::table_tags(&collection_data, font_index)
+        );
+        assert!(!font.table_tags().is_empty());
+    }
+}
+
+#[test]
+pub fn get_digital_signature() {
+    // EB Garamond has no `DSIG` table, and Inconsolata has a placeholder one with no signatures.
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert_eq!(font.digital_signature(), None);
+    assert!(!font.has_digital_signature());
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    let signature_info = font.digital_signature().unwrap();
+    assert_eq!(signature_info.signature_count(), 0);
+    assert!(!font.has_digital_signature());
+
+    let mut dsig = vec![0, 0, 0, 1, 0, 1, 0, 1];
+    let signature_block = [0, 0, 0, 0, 0, 0, 0, 4, 0xde, 0xad, 0xbe, 0xef];
+    for field in &[signature::FORMAT_PKCS7, signature_block.len() as u32, 20] {
+        dsig.extend_from_slice(&field.to_be_bytes());
+    }
+    dsig.extend_from_slice(&signature_block);
+    let font_data = add_font_tables(
+        &font.copy_font_data().unwrap(),
+        vec![(u32::from_be_bytes(*b"DSIG"), dsig)],
+    );
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    assert!(font.has_digital_signature());
+    let signature_info = font.digital_signature().unwrap();
+    assert_eq!(signature_info.formats, vec![signature::FORMAT_PKCS7]);
+    assert!(signature_info.cannot_be_resigned());
+}
+
+#[test]
+pub fn get_name_records() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let records = font.name_records();
+    let find = |platform_id: u16, language_id: u16, name_id: u16| {
+        records
+            .iter()
+            .find(|record| {
+                record.platform_id == platform_id
+                    && record.language_id == language_id
+                    && record.name_id == name_id
+            })
+            .map(|record| record.string.as_str())
+    };
+    // Mac Roman and UTF-16BE records both decode.
+    assert_eq!(
+        find(names::PLATFORM_MACINTOSH, 0, names::FULL_NAME),
+        Some("EB Garamond 12 Regular")
+    );
+    assert_eq!(
+        find(names::PLATFORM_WINDOWS, 0x0409, names::LICENSE_URL),
+        Some("http://scripts.sil.org/OFL")
+    );
+    // Localized names are kept.
+    assert_eq!(
+        find(names::PLATFORM_WINDOWS, 0x0407, 257),
+        Some("Kyrillische alternative de, el und elj")
+    );
+    assert!(records
+        .iter()
+        .filter(|record| record.platform_id == names::PLATFORM_WINDOWS)
+        .all(|record| record.encoding_id == 1));
+    assert_eq!(records.len(), 28);
+}
+
+#[test]
+pub fn get_localized_family_name() {
+    // (platform ID, language ID, name ID, string), with Windows strings in UTF-16BE and Macintosh
+    // ones in Mac Roman.
+    let records: Vec<(u16, u16, u16, Vec<u8>)> = vec![
+        (1, 2, 1, b"Pr\x9ff".to_vec()),
+        (3, 0x0404, 16, utf16_be("測試")),
+        (3, 0x0409, 1, utf16_be("Test Sans")),
+        (3, 0x040c, 1, utf16_be("Essai")),
+        (3, 0x0411, 1, utf16_be("テスト")),
+    ];
+    let mut name_table = vec![0, 0];
+    name_table.extend_from_slice(&(records.len() as u16).to_be_bytes());
+    name_table.extend_from_slice(&(6 + records.len() as u16 * 12).to_be_bytes());
+    let mut storage = vec![];
+    for (platform_id, language_id, name_id, string) in &records {
+        let encoding_id: u16 = if *platform_id == 3 { 1 } else { 0 };
+        for field in &[
+            *platform_id,
+            encoding_id,
+            *language_id,
+            *name_id,
+            string.len() as u16,
+            storage.len() as u16,
+        ] {
+            name_table.extend_from_slice(&field.to_be_bytes());
+        }
+        storage.extend_from_slice(string);
+    }
+    name_table.extend_from_slice(&storage);
+
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let font_data = add_font_tables(
+        &font.copy_font_data().unwrap(),
+        vec![(u32::from_be_bytes(*b"name"), name_table)],
+    );
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+    let name = |locale: &str| font.localized_family_name(locale).unwrap();
+    assert_eq!(name("ja"), "テスト");
+    assert_eq!(name("ja-JP"), "テスト");
+    // The typographic family name is preferred.
+    assert_eq!(name("zh-Hant"), "測試");
+    assert_eq!(name("zh_tw"), "測試");
+    // Other regions of the same language match, and then Macintosh records.
+    assert_eq!(name("fr-CA"), "Essai");
+    assert_eq!(name("de-DE"), "Prüf");
+    // Unknown languages fall back to English, even over a typographic family name.
+    assert_eq!(name("ko"), "Test Sans");
+    assert_eq!(name(""), "Test Sans");
+}
+
+#[test]
+pub fn get_math_constants_and_glyph_variants() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    assert_eq!(font.math_constants(), None);
+    let (paren, brace) = (
+        font.glyph_for_char('(').unwrap(),
+        font.glyph_for_char('{').unwrap(),
+    );
+    assert!(font
+        .math_glyph_variants(paren, StretchDirection::Vertical)
+        .is_empty());
+
+    // Build a `MATH` table whose constants count up from 1, with two vertical variants of `(`
+    // and one horizontal variant of `{`.
+    let mut constants = vec![];
+    constants.extend_from_slice(&70i16.to_be_bytes());
+    constants.extend_from_slice(&50i16.to_be_bytes());
+    constants.extend_from_slice(&1u16.to_be_bytes());
+    constants.extend_from_slice(&2u16.to_be_bytes());
+    for value in 0..51i16 {
+        constants.extend_from_slice(&(value + 3).to_be_bytes());
+        constants.extend_from_slice(&0u16.to_be_bytes());
+    }
+    constants.extend_from_slice(&60i16.to_be_bytes());
+
+    let coverage = |glyph_id: u32| {
+        let glyph_id = (glyph_id as u16).to_be_bytes();
+        [0, 1, 0, 1, glyph_id[0], glyph_id[1]]
+    };
+    let mut variants: Vec<u8> = vec![];
+    let u16s = |values: &[u16]| -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes().to_vec())
+            .collect()
+    };
+    // minConnectorOverlap, both coverage offsets, both glyph counts, and both construction
+    // offsets come first, in 14 bytes; then the two coverage tables, in 12.
+    variants.extend(u16s(&[10, 14, 20, 1, 1, 26, 38]));
+    variants.extend_from_slice(&coverage(paren));
+    variants.extend_from_slice(&coverage(brace));
+    variants.extend(u16s(&[0, 2, paren as u16, 800, 500, 1200]));
+    variants.extend(u16s(&[0, 1, 501, 2000]));
+
+    let mut math = u16s(&[1, 0, 10, 0, 10 + constants.len() as u16]);
+    math.extend_from_slice(&constants);
+    math.extend_from_slice(&variants);
+    let font_data = add_font_tables(
+        &font.copy_font_data().unwrap(),
+        vec![(u32::from_be_bytes(*b"MATH"), math)],
+    );
+    let font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+
+    let constants = font.math_constants().unwrap();
+    assert_eq!(constants.script_percent_scale_down, 70);
+    assert_eq!(constants.script_script_percent_scale_down, 50);
+    assert_eq!(constants.display_operator_min_height, 2.0);
+    assert_eq!(constants.math_leading, 3.0);
+    assert_eq!(constants.axis_height, 4.0);
+    assert_eq!(constants.fraction_rule_thickness, 37.0);
+    assert_eq!(constants.radical_kern_after_degree, 53.0);
+    assert_eq!(constants.radical_degree_bottom_raise_percent, 60);
+
+    assert_eq!(
+        font.math_glyph_variants(paren, StretchDirection::Vertical),
+        vec![
+            GlyphVariant {
+                glyph_id: paren,
+                advance: 800.0
+            },
+            GlyphVariant {
+                glyph_id: 500,
+                advance: 1200.0
+            },
+        ]
+    );
+    assert_eq!(
+        font.math_glyph_variants(brace, StretchDirection::Horizontal),
+        vec![GlyphVariant {
+            glyph_id: 501,
+            advance: 2000.0
+        }]
+    );
+    assert!(font
+        .math_glyph_variants(paren, StretchDirection::Horizontal)
+        .is_empty());
+    assert!(font
+        .math_glyph_variants(brace, StretchDirection::Vertical)
+        .is_empty());
+}
+
+fn utf16_be(string: &str) -> Vec<u8> {
+    string
+        .encode_utf16()
+        .flat_map(|unit| unit.to_be_bytes().to_vec())
+        .collect()
+}
+
+#[test]
+pub fn get_panose() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let panose = font.panose().unwrap();
+    assert_eq!(panose.to_bytes(), [2, 2, 5, 2, 6, 2, 6, 2, 4, 3]);
+    assert_eq!(panose.family_type, 2);
+    assert_eq!(panose.serif_style, 2);
+    assert_eq!(panose.weight, 5);
+    assert_eq!(panose.x_height, 3);
+    assert_eq!(Panose::from_bytes(panose.to_bytes()), panose);
+}
+
+#[test]
+pub fn get_vendor_id_and_version_string() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert_eq!(font.vendor_id(), Some(*b"PfEd"));
+    assert_eq!(font.version_string().as_deref(), Some("Version 0.016 "));
+
+    let font = Font::from_path(FILE_PATH_INCONSOLATA_TTF, 0).unwrap();
+    assert_eq!(font.vendor_id(), Some(*b"CYRE"));
+    assert_eq!(font.version_string().as_deref(), Some("Version 2.001"));
+
+    // This font has no version record.
+    let font = Font::from_path(FILE_PATH_CBDT_TTF, 0).unwrap();
+    assert_eq!(font.version_string(), None);
+}
+
+#[test]
+pub fn get_vertical_metrics() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph_count = font.glyph_count() as usize;
+    let glyph = font.glyph_for_char('A').unwrap();
+    assert!(glyph > 1);
+
+    // Without vertical tables, the origin falls back to the horizontal metrics.
+    assert_eq!(
+        font.vertical_advance(glyph),
+        Err(GlyphLoadingError::NoVerticalMetrics)
+    );
+    assert_eq!(font.metrics().vertical_ascent, None);
+    let half_advance = font.advance(glyph).unwrap().x / 2.0;
+    assert_eq!(
+        font.vertical_origin(glyph).unwrap(),
+        vec2(half_advance, font.metrics().ascent)
+    );
+
+    // Give glyphs 0 and 1 their own advances, and every other glyph a top side bearing of 80.
+    let mut vhea = vec![0; 36];
+    vhea[0..4].copy_from_slice(&0x0001_1000u32.to_be_bytes());
+    vhea[4..6].copy_from_slice(&500i16.to_be_bytes());
+    vhea[6..8].copy_from_slice(&(-500i16).to_be_bytes());
+    vhea[34..36].copy_from_slice(&2u16.to_be_bytes());
+    let mut vmtx = vec![0x03, 0xe8, 0x00, 0x64, 0x03, 0x84, 0x00, 0x32];
+    for _ in 2..glyph_count {
+        vmtx.extend_from_slice(&80i16.to_be_bytes());
+    }
+    let vertical_font_data = add_font_tables(
+        &font.copy_font_data().unwrap(),
+        vec![
+            (u32::from_be_bytes(*b"vhea"), vhea),
+            (u32::from_be_bytes(*b"vmtx"), vmtx),
+        ],
+    );
+    let vertical_font = Font::from_bytes(Arc::new(vertical_font_data.clone()), 0).unwrap();
+    let metrics = vertical_font.metrics();
+    assert_eq!(metrics.vertical_ascent, Some(500.0));
+    assert_eq!(metrics.vertical_descent, Some(-500.0));
+    assert_eq!(metrics.vertical_line_gap, Some(0.0));
+    assert_eq!(vertical_font.vertical_advance(0), Ok(1000.0));
+    assert_eq!(vertical_font.vertical_advance(1), Ok(900.0));
+    assert_eq!(vertical_font.vertical_advance(glyph), Ok(900.0));
+    assert_eq!(
+        vertical_font.vertical_advance(glyph_count as u32),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+    let top = vertical_font.typographic_bounds(glyph).unwrap().max_y();
+    assert_eq!(
+        vertical_font.vertical_origin(glyph).unwrap(),
+        vec2(half_advance, top + 80.0)
+    );
+
+    // `VORG` takes precedence, with a default for glyphs that it doesn't list.
+    let mut vorg = vec![0x00, 0x01, 0x00, 0x00, 0x03, 0x70, 0x00, 0x01];
+    vorg.extend_from_slice(&(glyph as u16).to_be_bytes());
+    vorg.extend_from_slice(&700i16.to_be_bytes());
+    let vorg_font_data = add_font_tables(
+        &vertical_font_data,
+        vec![(u32::from_be_bytes(*b"VORG"), vorg)],
+    );
+    let vorg_font = Font::from_bytes(Arc::new(vorg_font_data), 0).unwrap();
+    assert_eq!(vorg_font.vertical_origin(glyph).unwrap().y, 700.0);
+    assert_eq!(vorg_font.vertical_origin(1).unwrap().y, 880.0);
+}
+
+/// Rebuilds an sfnt with some tables added, replacing any existing tables with the same tags.
+fn add_font_tables(font_data: &[u8], extra_tables: Vec<(u32, Vec<u8>)>) -> Vec<u8> {
+    let read_u32 =
+        |offset: usize| u32::from_be_bytes(font_data[offset..(offset + 4)].try_into().unwrap());
+    let flavor = read_u32(0);
+    let table_count = u16::from_be_bytes([font_data[4], font_data[5]]) as usize;
+    let mut tables = extra_tables;
+    for record in 0..table_count {
+        let record_offset = 12 + record * 16;
+        let (offset, length) = (
+            read_u32(record_offset + 8) as usize,
+            read_u32(record_offset + 12) as usize,
+        );
+        let tag = read_u32(record_offset);
+        if tables.iter().all(|&(extra_tag, _)| extra_tag != tag) {
+            tables.push((tag, font_data[offset..(offset + length)].to_vec()));
+        }
+    }
+    sfnt::write_sfnt(flavor, tables)
+}
+
+#[test]
+pub fn get_typographic_bounds_batch() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_ids: Vec<u32> = "Hello, world!"
+        .chars()
+        .map(|c| font.glyph_for_char(c).unwrap())
+        .collect();
+    let bounds = font.typographic_bounds_batch(&glyph_ids).unwrap();
+    assert_eq!(bounds.len(), glyph_ids.len());
+    for (&glyph_id, bounds) in glyph_ids.iter().zip(bounds.iter()) {
+        assert_eq!(*bounds, font.typographic_bounds(glyph_id).unwrap());
+    }
+    assert!(font.typographic_bounds_batch(&[]).unwrap().is_empty());
+    assert_eq!(
+        font.typographic_bounds_batch(&[glyph_ids[0], font.glyph_count()]),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+}
+
+#[test]
+pub fn get_glyph_advances() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_ids: Vec<u32> = "Hello, world!"
+        .chars()
+        .map(|c| font.glyph_for_char(c).unwrap())
+        .collect();
+    let advances = font.advances(&glyph_ids).unwrap();
+    assert_eq!(advances.len(), glyph_ids.len());
+    for (&glyph_id, advance) in glyph_ids.iter().zip(advances.iter()) {
+        assert_eq!(*advance, font.advance(glyph_id).unwrap());
+    }
+    assert!(font.advances(&[]).unwrap().is_empty());
+    assert_eq!(
+        font.advances(&[glyph_ids[0], font.glyph_count()]),
+        Err(GlyphLoadingError::NoSuchGlyph)
+    );
+}
+
+#[test]
+pub fn rasterize_glyph_with_gamma() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('e').unwrap();
+    let rasterize = |adjustments| {
+        rasterize_glyph_with_options(
+            &font,
+            glyph_id,
+            RasterizationOptions::GrayscaleAa,
+            adjustments,
+        )
+    };
+    let plain = rasterize(RasterizationAdjustments::default());
+    let darkened = rasterize(RasterizationAdjustments {
+        gamma: 2.2,
+        ..RasterizationAdjustments::default()
+    });
+
+    // Partially covered pixels get darker; empty and fully covered ones stay as they were.
+    assert_eq!(plain.pixels.len(), darkened.pixels.len());
+    assert!(plain
+        .pixels
+        .iter()
+        .any(|&value| value != 0 && value != 0xff));
+    for (&before, &after) in plain.pixels.iter().zip(darkened.pixels.iter()) {
+        match before {
+            0 | 0xff => assert_eq!(after, before),
+            _ => assert!(after > before),
+        }
+    }
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn rasterize_glyph_with_stem_darkening() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('e').unwrap();
+    let adjustments = RasterizationAdjustments::default();
+    let coverage = |adjustments| -> u32 {
+        rasterize_glyph_with_options(
+            &font,
+            glyph_id,
+            RasterizationOptions::GrayscaleAa,
+            adjustments,
+        )
+        .pixels
+        .iter()
+        .map(|&value| value as u32)
+        .sum()
+    };
+    let darkened_adjustments = RasterizationAdjustments {
+        stem_darkening: true,
+        ..adjustments
+    };
+    assert!(coverage(darkened_adjustments) > coverage(adjustments));
+
+    // Darkening must not stick to the face once the glyph is rendered.
+    let plain = coverage(adjustments);
+    coverage(darkened_adjustments);
+    assert_eq!(coverage(adjustments), plain);
+}
+
+#[test]
+pub fn rasterize_glyph_with_subpixel_aa() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('l').unwrap();
+    let canvas = rasterize_glyph_with_options(
+        &font,
+        glyph_id,
+        RasterizationOptions::SubpixelAa,
+        RasterizationAdjustments::default(),
+    );
+
+    // The stem's edges should cover the subpixels of some pixels unequally.
+    assert!(canvas
+        .pixels
+        .chunks(3)
+        .any(|pixel| pixel[0] != pixel[1] || pixel[1] != pixel[2]));
+}
+
+#[test]
+pub fn rasterize_glyph_with_each_antialiasing_strategy() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('e').unwrap();
+    let rasterize = |options, adjustments| {
+        rasterize_glyph_with_options(&font, glyph_id, options, adjustments).pixels
+    };
+    let plain = RasterizationAdjustments::default();
+    let thresholded = RasterizationAdjustments {
+        bilevel_threshold: Some(0x80),
+        ..plain
+    };
+
+    // Bilevel rendering leaves no pixel partly covered, whether the rasterizer or a threshold
+    // decides which pixels are on.
+    for &adjustments in &[plain, thresholded] {
+        let bilevel = rasterize(RasterizationOptions::Bilevel, adjustments);
+        assert!(bilevel.contains(&0xff));
+        assert!(bilevel.iter().all(|&value| value == 0 || value == 0xff));
+    }
+
+    // Grayscale antialiasing does leave some, and ignores the threshold.
+    let grayscale = rasterize(RasterizationOptions::GrayscaleAa, plain);
+    assert!(grayscale.iter().any(|&value| value != 0 && value != 0xff));
+    assert_eq!(
+        rasterize(RasterizationOptions::GrayscaleAa, thresholded),
+        grayscale
+    );
+
+    // Subpixel antialiasing covers the channels of some pixels unequally.
+    let subpixel = rasterize(RasterizationOptions::SubpixelAa, plain);
+    assert!(subpixel
+        .chunks(3)
+        .any(|pixel| pixel[0] != pixel[1] || pixel[1] != pixel[2]));
+    assert_eq!(
+        rasterize(RasterizationOptions::SubpixelAa, thresholded),
+        subpixel
+    );
+}
+
+#[cfg(not(any(target_family = "windows", target_os = "macos", target_os = "ios")))]
+#[test]
+pub fn rasterize_glyph_with_lcd_filter() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('l').unwrap();
+    let fringing = |lcd_filter| -> u32 {
+        let adjustments = RasterizationAdjustments {
+            lcd_filter,
+            ..RasterizationAdjustments::default()
+        };
+        rasterize_glyph_with_options(
+            &font,
+            glyph_id,
+            RasterizationOptions::SubpixelAa,
+            adjustments,
+        )
+        .pixels
+        .chunks(3)
+        .map(|pixel| (pixel[0] as i32 - pixel[2] as i32).unsigned_abs())
+        .sum()
+    };
+    assert_eq!(
+        LcdFilter::Default.weights(),
+        Some([0x08, 0x4d, 0x56, 0x4d, 0x08])
+    );
+    assert!(fringing(LcdFilter::Default) < fringing(LcdFilter::Light));
+    assert!(fringing(LcdFilter::Light) < fringing(LcdFilter::None));
+    assert_eq!(
+        fringing(LcdFilter::Custom([0x08, 0x4d, 0x56, 0x4d, 0x08])),
+        fringing(LcdFilter::Default)
+    );
+}
+
+#[test]
+pub fn blend_glyph_onto_background() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('e').unwrap();
+    let canvas = rasterize_glyph_with_options(
+        &font,
+        glyph_id,
+        RasterizationOptions::GrayscaleAa,
+        RasterizationAdjustments::default(),
+    );
+    let stride = canvas.size.width as usize * 4;
+    let blend = |color, gamma| {
+        let mut dst = vec![0xff; stride * canvas.size.height as usize];
+        canvas.blend_onto(&mut dst, stride, color, gamma);
+        dst
+    };
+    let black = ColorU {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0xff,
+    };
+    let (naive, linear) = (blend(black, Gamma::Srgb), blend(black, Gamma::Linear));
+
+    // Blending black in linear light leaves partially covered pixels lighter than naive
+    // blending does, and agrees with it elsewhere.
+    let mut lighter_pixel_count = 0;
+    for (coverage, (naive, linear)) in canvas
+        .pixels
+        .iter()
+        .zip(naive.chunks(4).zip(linear.chunks(4)))
+    {
+        assert_eq!(naive[3], 0xff);
+        assert_eq!(linear[3], 0xff);
+        match *coverage {
+            0 => assert_eq!(naive, [0xff; 4]),
+            0xff => assert_eq!(naive, [0, 0, 0, 0xff]),
+            coverage => {
+                assert_eq!(naive[0], 0xff - coverage);
+                if linear[0] > naive[0] {
+                    lighter_pixel_count += 1;
+                }
             }
-            top_stripe_width = Some(stripe_width);
         }
-        y += 1;
+        if *coverage == 0 || *coverage == 0xff {
+            assert_eq!(naive, linear);
+        } else {
+            assert!(linear[0] >= naive[0]);
+        }
     }
-    assert!(y < canvas.size.height);
+    assert!(lighter_pixel_count > 0);
+}
 
-    // Find the bottom part of the L.
-    let mut bottom_stripe_width = None;
-    while y < canvas.size.height {
-        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
-        y += 1;
-        if let Some(stripe_width) = stripe_width(&canvas.pixels[row_start..row_end]) {
-            if let Some(bottom_stripe_width) = bottom_stripe_width {
-                assert!(bottom_stripe_width > top_stripe_width.unwrap());
-                assert_eq!(stripe_width, bottom_stripe_width);
-            }
-            bottom_stripe_width = Some(stripe_width);
+#[test]
+pub fn convert_canvas_format() {
+    // Padded rows make sure that the stride of the source isn't confused with its width.
+    let mut coverage = Canvas::with_stride(&Size2D::new(2, 2), 5, Format::A8);
+    coverage.pixels = vec![0xff, 0x80, 0xaa, 0xaa, 0xaa, 0x00, 0x40, 0xaa, 0xaa, 0xaa];
+
+    let white = coverage.convert_to(Format::Rgb24, None);
+    assert_eq!((white.size, white.stride), (Size2D::new(2, 2), 6));
+    assert_eq!(
+        white.pixels,
+        [0xff, 0xff, 0xff, 0x80, 0x80, 0x80, 0x00, 0x00, 0x00, 0x40, 0x40, 0x40]
+    );
+    assert_eq!(
+        white.convert_to(Format::A8, None).pixels,
+        [0xff, 0x80, 0x00, 0x40]
+    );
+
+    let red = ColorU {
+        r: 0xff,
+        g: 0x00,
+        b: 0x00,
+        a: 0x80,
+    };
+    let rgba = coverage.convert_to(Format::Rgba32, Some(red));
+    assert_eq!(rgba.stride, 8);
+    assert_eq!(
+        rgba.pixels,
+        [
+            0x80, 0x00, 0x00, 0x80, 0x40, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00,
+            0x00, 0x20,
+        ]
+    );
+    assert_eq!(
+        rgba.convert_to(Format::A8, None).pixels,
+        [0x80, 0x40, 0x00, 0x20]
+    );
+    assert_eq!(
+        rgba.convert_to(Format::Rgb24, None).pixels,
+        [0x80, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00]
+    );
+
+    // Subpixel coverage averages to grayscale, and masks each channel of the color separately.
+    let mut subpixel = Canvas::new(&Size2D::new(1, 1), Format::Rgb24);
+    subpixel.pixels = vec![0xff, 0x80, 0x00];
+    assert_eq!(subpixel.convert_to(Format::A8, None).pixels, [0x80]);
+    let gray = ColorU {
+        r: 0x80,
+        g: 0x80,
+        b: 0x80,
+        a: 0xff,
+    };
+    assert_eq!(
+        subpixel.convert_to(Format::Rgba32, Some(gray)).pixels,
+        [0x80, 0x40, 0x00, 0xff]
+    );
+}
+
+#[test]
+pub fn cache_rasterized_glyphs() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let (e, o) = (
+        font.glyph_for_char('e').unwrap(),
+        font.glyph_for_char('o').unwrap(),
+    );
+    let rasterize = |cache: &mut GlyphCache<u32>, glyph_id, x| {
+        let glyph = cache
+            .rasterize(
+                0,
+                &font,
+                glyph_id,
+                16.0,
+                &Point2D::new(x, 0.0),
+                HintingOptions::None,
+                RasterizationOptions::GrayscaleAa,
+                RasterizationAdjustments::default(),
+                Format::A8,
+            )
+            .unwrap();
+        (glyph.canvas.pixels.clone(), glyph.offset)
+    };
+
+    let mut cache = GlyphCache::new(usize::MAX);
+    let (pixels, offset) = rasterize(&mut cache, e, 10.0);
+    assert!(pixels.iter().any(|&value| value != 0));
+
+    // Nearby positions in the same subpixel bucket share an entry.
+    assert_eq!(rasterize(&mut cache, e, 3.1), (pixels.clone(), offset));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.byte_count(), pixels.len());
+    rasterize(&mut cache, e, 10.5);
+    assert_eq!(cache.len(), 2);
+
+    // Shrinking the budget evicts the least recently used glyph.
+    rasterize(&mut cache, o, 0.0);
+    rasterize(&mut cache, e, 0.0);
+    cache.set_byte_budget(cache.byte_count() - 1);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(rasterize(&mut cache, e, 0.0), (pixels, offset));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.byte_count(), 0);
+}
+
+#[test]
+pub fn rasterize_glyph_with_synthesis() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    assert!(font.synthesis(&Properties::new()).is_none());
+    let oblique = font.synthesis(Properties::new().style(Style::Italic));
+    assert_eq!(oblique.embolden, 0.0);
+    assert!((oblique.skew - 14.0f32.to_radians().tan()).abs() < 0.0001);
+    let bold = font.synthesis(Properties::new().weight(Weight::BOLD));
+    assert_eq!((bold.skew, bold.embolden), (0.0, 1.0 / 24.0));
+
+    // A real italic isn't slanted again.
+    let italic_font =
+        Font::from_path("resources/tests/eb-garamond/EBGaramond12-Italic.otf", 0).unwrap();
+    assert!(italic_font
+        .synthesis(Properties::new().style(Style::Italic))
+        .is_none());
+
+    let glyph_id = font.glyph_for_char('l').unwrap();
+    let (size, origin) = (Size2D::new(32, 32), Point2D::new(8.0, 24.0));
+    let rasterize = |transform: &FontTransform, synthesis: Option<&Synthesis>| {
+        let mut canvas = Canvas::new(&size, Format::A8);
+        match synthesis {
+            None => font.rasterize_glyph(
+                &mut canvas,
+                glyph_id,
+                24.0,
+                transform,
+                &origin,
+                HintingOptions::None,
+                RasterizationOptions::GrayscaleAa,
+            ),
+            Some(synthesis) => font.rasterize_glyph_with_synthesis(
+                &mut canvas,
+                glyph_id,
+                24.0,
+                transform,
+                &origin,
+                HintingOptions::None,
+                RasterizationOptions::GrayscaleAa,
+                synthesis,
+            ),
         }
-    }
+        .unwrap();
+        canvas.pixels
+    };
+    let identity = FontTransform::identity();
+    let regular = rasterize(&identity, None);
 
-    // Find any empty rows at the end.
-    while y < canvas.size.height {
-        let (row_start, row_end) = (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
-        y += 1;
-        if canvas.pixels[row_start..row_end].iter().any(|&p| p != 0) {
-            break;
+    // The oblique is the regular glyph drawn with a skew.
+    let slanted = rasterize(&oblique.apply_to_font_transform(&identity), None);
+    assert_eq!(rasterize(&identity, Some(&oblique)), slanted);
+
+    // It leans to the right: the top of the `l` moves right, and its foot stays put.
+    let mean_x = |pixels: &[u8], rows: std::ops::Range<usize>| {
+        let (mut sum, mut weight) = (0.0, 0.0);
+        for y in rows {
+            for x in 0..32 {
+                let pixel = pixels[y * 32 + x] as f32;
+                sum += pixel * x as f32;
+                weight += pixel;
+            }
         }
-    }
+        sum / weight
+    };
+    assert!(mean_x(&slanted, 0..12) > mean_x(&regular, 0..12) + 2.0);
+    assert!((mean_x(&slanted, 20..24) - mean_x(&regular, 20..24)).abs() < 1.0);
 
-    // Make sure we made it to the end.
-    assert_eq!(y, canvas.size.height);
+    // The bold covers at least everything that the regular glyph does, and more.
+    let bold_pixels = rasterize(&identity, Some(&bold));
+    assert!(regular.iter().zip(&bold_pixels).all(|(a, b)| a <= b));
+    let total = |pixels: &[u8]| pixels.iter().map(|&pixel| pixel as u32).sum::<u32>();
+    assert!(total(&bold_pixels) > total(&regular));
 }
 
-// Makes sure that a canvas has an "{" shape in it. This is used to test rasterization.
-#[cfg(target_family = "windows")]
-fn check_curly_shape(canvas: &Canvas) {
-    let mut y = 0;
-    let height = canvas.size.height;
-    // check the upper row and the lower rows are symmetrical
-    while y < height / 2 {
-        let (upper_row_start, upper_row_end) =
-            (canvas.stride * y as usize, canvas.stride * (y + 1) as usize);
-        let (lower_row_start, lower_row_end) = (
-            canvas.stride * (height - y - 1) as usize,
-            canvas.stride * (height - y) as usize,
-        );
-        let upper_row = &canvas.pixels[upper_row_start..upper_row_end];
-        let lower_row = &canvas.pixels[lower_row_start..lower_row_end];
-        let top_row_width = stripe_width(upper_row).unwrap();
-        let lower_row_width = stripe_width(lower_row).unwrap();
-        let upper_row_pixel_start = stride_pixel_start(upper_row).unwrap();
-        let lower_row_pixel_start = stride_pixel_start(lower_row).unwrap();
-        if top_row_width == lower_row_width {
-            // check non-zero pixels start at the same index
-            assert_eq!(upper_row_pixel_start, lower_row_pixel_start);
-        } else {
-            //if not, assert that the difference is not greater than 1
-            assert_eq!((lower_row_width as i32 - top_row_width as i32).abs(), 1);
-            // and assert that the non-zero pixel index difference is not greater than 1
-            assert_eq!(
-                (upper_row_pixel_start as i32 - lower_row_pixel_start as i32).abs(),
-                1
-            );
+#[test]
+pub fn rasterize_string() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let size = Size2D::new(64, 32);
+    let rasterize_glyphs = |glyphs: &[(char, f32)]| {
+        let mut canvas = Canvas::new(&size, Format::A8);
+        for &(character, x) in glyphs {
+            let mut glyph_canvas = Canvas::new(&size, Format::A8);
+            font.rasterize_glyph(
+                &mut glyph_canvas,
+                font.glyph_for_char(character).unwrap(),
+                24.0,
+                &FontTransform::identity(),
+                &Point2D::new(x, 24.0),
+                HintingOptions::None,
+                RasterizationOptions::GrayscaleAa,
+            )
+            .unwrap();
+            for (dest, &src) in canvas.pixels.iter_mut().zip(glyph_canvas.pixels.iter()) {
+                *dest = (*dest).max(src);
+            }
         }
-        y += 1;
+        canvas.pixels
+    };
+    let rasterize_string = |text: &str, origin: Point2D<f32>| {
+        let mut canvas = Canvas::new(&size, Format::A8);
+        font.rasterize_string(
+            &mut canvas,
+            text,
+            24.0,
+            &origin,
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap();
+        canvas.pixels
+    };
+
+    // The second glyph lands a fractional advance after the first.
+    let advance = font.advance(font.glyph_for_char('l').unwrap()).unwrap().x * 24.0
+        / font.metrics().units_per_em as f32;
+    assert_ne!(advance.fract(), 0.0);
+    assert_eq!(
+        rasterize_string("ll", Point2D::new(2.5, 24.0)),
+        rasterize_glyphs(&[('l', 2.5), ('l', 2.5 + advance)])
+    );
+
+    // Glyphs hanging off the edges of the canvas are clipped.
+    let clipped = rasterize_string("ll", Point2D::new(-4.0, 24.0));
+    let unclipped = rasterize_string("ll", Point2D::new(4.0, 24.0));
+    for (clipped_row, unclipped_row) in clipped.chunks(64).zip(unclipped.chunks(64)) {
+        assert_eq!(clipped_row[..56], unclipped_row[8..]);
     }
+    assert!(rasterize_string("lll", Point2D::new(60.0, 24.0))
+        .iter()
+        .any(|&pixel| pixel != 0));
+    assert!(rasterize_string("lll", Point2D::new(80.0, 24.0))
+        .iter()
+        .all(|&pixel| pixel == 0));
 }
 
-// return the first non-zero pixel index
-#[cfg(target_family = "windows")]
-fn stride_pixel_start(pixels: &[u8]) -> Option<u32> {
-    let mut index = 0;
-    for x in pixels {
-        if *x != 0 {
-            return Some(index);
+#[test]
+pub fn rasterize_glyph_sdf() {
+    let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+    let glyph_id = font.glyph_for_char('o').unwrap();
+    let (size, spread) = (48.0, 4.0);
+    let raster_rect = font
+        .raster_bounds(
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            &Point2D::zero(),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap()
+        .inflate(spread as i32, spread as i32);
+    let origin = Point2D::new(-raster_rect.origin.x, -raster_rect.origin.y).to_f32();
+    let mut sdf = Canvas::new(&raster_rect.size.to_u32(), Format::A8);
+    font.rasterize_glyph_sdf(&mut sdf, glyph_id, size, &origin, spread)
+        .unwrap();
+    let mut coverage = Canvas::new(&raster_rect.size.to_u32(), Format::A8);
+    font.rasterize_glyph(
+        &mut coverage,
+        glyph_id,
+        size,
+        &FontTransform::identity(),
+        &origin,
+        HintingOptions::None,
+        RasterizationOptions::GrayscaleAa,
+    )
+    .unwrap();
+
+    // The margin around the glyph is a whole spread away from it, and so is the counter of the
+    // "o" at this size.
+    assert_eq!(sdf.pixels[0], 0);
+    let center = sdf.size.height as usize / 2 * sdf.stride + sdf.size.width as usize / 2;
+    assert_eq!(sdf.pixels[center], 0);
+
+    // Pixels more than a pixel inside the outline are covered, and pixels more than a pixel
+    // outside of it are not.
+    let mut inside_pixel_count = 0;
+    for (&distance, &coverage) in sdf.pixels.iter().zip(coverage.pixels.iter()) {
+        if distance >= 160 {
+            assert!(coverage >= 192);
+            inside_pixel_count += 1;
+        } else if distance <= 96 {
+            assert!(coverage <= 64);
         }
-        index += 1;
     }
-    None
+    assert!(inside_pixel_count > 0);
 }
 
-fn stripe_width(pixels: &[u8]) -> Option<u32> {
-    let mut x = 0;
-    // Find the initial empty part.
-    while x < pixels.len() && pixels[x] == 0 {
-        x += 1
-    }
-    if x == pixels.len() {
-        return None;
-    }
-    // Find the stripe width.
-    let mut stripe_width = 0;
-    while x < pixels.len() && pixels[x] != 0 {
-        x += 1;
-        stripe_width += 1;
+#[test]
+pub fn rasterize_glyph_at_subpixel_origins() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('l').unwrap();
+    let (size, canvas_size) = (16.0, Size2D::new(24, 24));
+    let rasterize = |origin: &Point2D<f32>, hinting_options| {
+        let mut canvas = Canvas::new(&canvas_size, Format::A8);
+        font.rasterize_glyph(
+            &mut canvas,
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            origin,
+            hinting_options,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap();
+        canvas
+    };
+    for &fraction in &[0.0, 0.25, 0.5, 0.75] {
+        let origin = Point2D::new(4.0 + fraction, 20.0 + fraction);
+
+        // Without hinting, the coverage matches the exact coverage at the fractional origin.
+        let coverage = font
+            .rasterize_glyph_f32(
+                &canvas_size,
+                glyph_id,
+                size,
+                &FontTransform::identity(),
+                &origin,
+                HintingOptions::None,
+            )
+            .unwrap();
+        let canvas = rasterize(&origin, HintingOptions::None);
+        for (&exact, &value) in coverage.iter().zip(canvas.pixels.iter()) {
+            assert!((exact - value as f32 / 255.0).abs() < 0.05);
+        }
+
+        // The raster bounds hold every pixel that the glyph covers, hinted or not.
+        for &hinting_options in &[HintingOptions::None, HintingOptions::Vertical(size)] {
+            let bounds = font
+                .raster_bounds(
+                    glyph_id,
+                    size,
+                    &FontTransform::identity(),
+                    &origin,
+                    hinting_options,
+                    RasterizationOptions::GrayscaleAa,
+                )
+                .unwrap();
+            let canvas = rasterize(&origin, hinting_options);
+            for (index, &value) in canvas.pixels.iter().enumerate() {
+                let pixel = point2(
+                    (index % canvas.stride) as i32,
+                    (index / canvas.stride) as i32,
+                );
+                assert!(value == 0 || bounds.contains(pixel));
+            }
+        }
     }
-    // Find the last empty part.
-    while x < pixels.len() && pixels[x] == 0 {
-        x += 1;
+}
+
+#[test]
+pub fn rasterize_glyph_f32() {
+    let font = Font::from_path(FILE_PATH_EB_GARAMOND_TTF, 0).unwrap();
+    let glyph_id = font.glyph_for_char('g').unwrap();
+    let size = 32.0;
+    let transform = FontTransform::new(1.0, 0.25, 0.0, 1.0);
+    let raster_rect = font
+        .raster_bounds(
+            glyph_id,
+            size,
+            &transform,
+            &Point2D::zero(),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap()
+        .inflate(1, 1);
+    let origin = Point2D::new(-raster_rect.origin.x, -raster_rect.origin.y).to_f32();
+    let raster_size = raster_rect.size.to_u32();
+    let coverage = font
+        .rasterize_glyph_f32(
+            &raster_size,
+            glyph_id,
+            size,
+            &transform,
+            &origin,
+            HintingOptions::None,
+        )
+        .unwrap();
+    let mut canvas = Canvas::new(&raster_size, Format::A8);
+    font.rasterize_glyph(
+        &mut canvas,
+        glyph_id,
+        size,
+        &transform,
+        &origin,
+        HintingOptions::None,
+        RasterizationOptions::GrayscaleAa,
+    )
+    .unwrap();
+
+    // The exact coverage agrees with the platform's 8-bit coverage to within rounding and
+    // curve flattening. (FreeType flattens cubic curves more coarsely, so this uses a TrueType
+    // font.)
+    assert_eq!(coverage.len(), canvas.pixels.len());
+    assert!(coverage.iter().all(|&value| (0.0..=1.0).contains(&value)));
+    assert!(coverage.contains(&1.0));
+    for (&value, &pixel) in coverage.iter().zip(canvas.pixels.iter()) {
+        assert!((value - pixel as f32 / 255.0).abs() < 0.05);
     }
-    assert_eq!(x, pixels.len());
-    Some(stripe_width)
+}
+
+/// Rasterizes a glyph at 11px with light hinting, which FreeType needs in order to darken stems.
+fn rasterize_glyph_with_options(
+    font: &Font,
+    glyph_id: u32,
+    rasterization_options: RasterizationOptions,
+    rasterization_adjustments: RasterizationAdjustments,
+) -> Canvas {
+    let size = 11.0;
+    let hinting_options = HintingOptions::Vertical(size);
+    let raster_rect = font
+        .raster_bounds(
+            glyph_id,
+            size,
+            &FontTransform::identity(),
+            &Point2D::zero(),
+            hinting_options,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .unwrap()
+        .inflate(1, 1);
+    let origin = Point2D::new(-raster_rect.origin.x, -raster_rect.origin.y).to_f32();
+    let format = match rasterization_options {
+        RasterizationOptions::SubpixelAa => Format::Rgb24,
+        _ => Format::A8,
+    };
+    let mut canvas = Canvas::new(&raster_rect.size.to_u32(), format);
+    font.rasterize_glyph_with_adjustments(
+        &mut canvas,
+        glyph_id,
+        size,
+        &FontTransform::identity(),
+        &origin,
+        hinting_options,
+        rasterization_options,
+        rasterization_adjustments,
+    )
+    .unwrap();
+    canvas
 }
@@ -0,0 +1,100 @@
+// font-kit/src/variations.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Information about the design space of variable (OpenType Font Variations) fonts.
+
+use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::tables;
+use crate::utils;
+
+/// A design axis of a variable font, as declared in the font's `fvar` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariationAxis {
+    /// The four-character tag of the axis (e.g. `wght`, `wdth`, or `opsz`), packed big-endian in
+    /// the same way as the tags passed to `load_font_table()`.
+    pub tag: u32,
+    /// The minimum coordinate value of the axis.
+    pub min_value: f32,
+    /// The coordinate value that the font uses when no variations are applied.
+    pub default_value: f32,
+    /// The maximum coordinate value of the axis.
+    pub max_value: f32,
+    /// True if the font designer has asked for this axis not to be shown in user interfaces.
+    pub hidden: bool,
+    /// The name of the axis from the `name` table, preferring English.
+    ///
+    /// If the font doesn't name the axis, this is the tag as a string.
+    pub name: String,
+}
+
+/// A named instance of a variable font (e.g. "SemiBold Condensed"), as declared in the font's
+/// `fvar` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedInstance {
+    /// The subfamily name of the instance from the `name` table, preferring English.
+    ///
+    /// If the font doesn't name the instance, this is empty.
+    pub subfamily_name: String,
+    /// The position of the instance in the design space, as one `(tag, value)` pair per axis.
+    ///
+    /// This can be passed directly to `set_variations()` to select the instance.
+    pub coordinates: Vec<(u32, f32)>,
+    /// The PostScript name of the instance, if the font supplies one.
+    pub postscript_name: Option<String>,
+}
+
+/// Resolves a set of `(tag, value)` pairs into one design coordinate per axis, in axis order.
+///
+/// Values are clamped to the range of their axis, and axes that aren't mentioned stay at their
+/// default values. If a tag is given more than once, the last value wins.
+pub(crate) fn design_coordinates(axes: &[VariationAxis], coords: &[(u32, f32)]) -> Vec<f32> {
+    axes.iter()
+        .map(
+            |axis| match coords.iter().rev().find(|&&(tag, _)| tag == axis.tag) {
+                None => axis.default_value,
+                Some(&(_, value)) => utils::clamp(value, axis.min_value, axis.max_value),
+            },
+        )
+        .collect()
+}
+
+/// Overrides the weight, stretch, and style of `properties` with the values of the registered
+/// `wght`, `wdth`, `slnt`, and `ital` axes at the given design coordinates.
+///
+/// Properties that have no corresponding axis among `coords` are left alone, so for fonts that
+/// aren't variable fonts this returns `properties` unchanged.
+pub(crate) fn apply_to_properties(mut properties: Properties, coords: &[(u32, f32)]) -> Properties {
+    let value_of = |axis_tag: &[u8; 4]| {
+        let axis_tag = tables::tag(axis_tag);
+        coords
+            .iter()
+            .find(|&&(tag, _)| tag == axis_tag)
+            .map(|&(_, value)| value)
+    };
+    if let Some(weight) = value_of(b"wght") {
+        properties.weight = Weight(weight);
+    }
+    // `wdth` is a percentage of the normal width.
+    if let Some(width) = value_of(b"wdth") {
+        properties.stretch = Stretch(width / 100.0);
+    }
+    // Like `italicAngle`, `slnt` is in degrees counterclockwise from vertical.
+    let slant = value_of(b"slnt");
+    if let Some(slant) = slant {
+        properties.italic_angle = Some(slant);
+    }
+    match (value_of(b"ital"), slant) {
+        (Some(italic), _) if italic >= 0.5 => properties.style = Style::Italic,
+        (_, Some(slant)) if slant != 0.0 => properties.style = Style::Oblique,
+        (Some(_), _) | (_, Some(_)) => properties.style = Style::Normal,
+        (None, None) => {}
+    }
+    properties
+}
@@ -0,0 +1,140 @@
+// font-kit/src/woff.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decompression of WOFF 1.0 web fonts into plain OpenType (sfnt) data, and detection of web
+//! fonts in general.
+//!
+//! https://www.w3.org/TR/WOFF/
+
+use byteorder::{BigEndian, ReadBytesExt};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::error::FontLoadingError;
+use crate::file_type::FileType;
+use crate::sfnt::{pad_to_4, write_sfnt_header, SFNT_HEADER_SIZE, SFNT_TABLE_RECORD_SIZE};
+
+const SIGNATURE: [u8; 4] = *b"wOFF";
+const WOFF2_SIGNATURE: [u8; 4] = *b"wOF2";
+
+const HEADER_SIZE: usize = 44;
+
+/// Returns `FileType::Woff` or `FileType::Woff2` if the data is a web font, or `None` otherwise.
+pub(crate) fn web_font_type(data: &[u8]) -> Option<FileType> {
+    if data.starts_with(&SIGNATURE) {
+        Some(FileType::Woff)
+    } else if data.starts_with(&WOFF2_SIGNATURE) {
+        Some(FileType::Woff2)
+    } else {
+        None
+    }
+}
+
+/// Returns the font data with its WOFF or WOFF2 wrapping, if any, removed.
+///
+/// WOFF2 fonts can only be decompressed with the `woff2` feature enabled; otherwise, they're
+/// reported as `FontLoadingError::UnknownFormat`.
+pub(crate) fn to_sfnt(font_data: Arc<Vec<u8>>) -> Result<Arc<Vec<u8>>, FontLoadingError> {
+    match web_font_type(&font_data) {
+        Some(FileType::Woff) => Ok(Arc::new(decode(&font_data)?)),
+        Some(FileType::Woff2) => Ok(Arc::new(decode_woff2(&font_data)?)),
+        _ => Ok(font_data),
+    }
+}
+
+#[cfg(feature = "woff2")]
+#[inline]
+fn decode_woff2(data: &[u8]) -> Result<Vec<u8>, FontLoadingError> {
+    crate::woff2::decode(data)
+}
+
+#[cfg(not(feature = "woff2"))]
+#[inline]
+fn decode_woff2(_: &[u8]) -> Result<Vec<u8>, FontLoadingError> {
+    Err(FontLoadingError::UnknownFormat)
+}
+
+/// Decompresses a WOFF 1.0 font into the sfnt that it wraps.
+fn decode(data: &[u8]) -> Result<Vec<u8>, FontLoadingError> {
+    decode_opt(data).ok_or(FontLoadingError::Parse)
+}
+
+struct TableDirectoryEntry {
+    tag: u32,
+    offset: usize,
+    comp_length: usize,
+    orig_length: usize,
+    orig_checksum: u32,
+}
+
+fn decode_opt(data: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = data;
+    if reader.read_u32::<BigEndian>().ok()?.to_be_bytes() != SIGNATURE {
+        return None;
+    }
+    let flavor = reader.read_u32::<BigEndian>().ok()?;
+    let _length = reader.read_u32::<BigEndian>().ok()?;
+    let num_tables = reader.read_u16::<BigEndian>().ok()?;
+    if num_tables == 0 {
+        return None;
+    }
+
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    let mut reader = data.get(HEADER_SIZE..)?;
+    for _ in 0..num_tables {
+        entries.push(TableDirectoryEntry {
+            tag: reader.read_u32::<BigEndian>().ok()?,
+            offset: reader.read_u32::<BigEndian>().ok()? as usize,
+            comp_length: reader.read_u32::<BigEndian>().ok()? as usize,
+            orig_length: reader.read_u32::<BigEndian>().ok()? as usize,
+            orig_checksum: reader.read_u32::<BigEndian>().ok()?,
+        });
+    }
+
+    // Lay the tables out in directory order, which the specification requires to be sorted by
+    // tag, just as an sfnt's table records are.
+    let mut table_offset = SFNT_HEADER_SIZE + SFNT_TABLE_RECORD_SIZE * entries.len();
+    let mut table_offsets = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        table_offsets.push(table_offset);
+        table_offset = table_offset.checked_add(pad_to_4(entry.orig_length))?;
+    }
+
+    // Don't trust the uncompressed lengths enough to reserve space for them up front.
+    let mut sfnt = Vec::with_capacity(table_offset.min(data.len() * 4));
+    write_sfnt_header(&mut sfnt, flavor, num_tables);
+    for (entry, &offset) in entries.iter().zip(table_offsets.iter()) {
+        sfnt.extend_from_slice(&entry.tag.to_be_bytes());
+        sfnt.extend_from_slice(&entry.orig_checksum.to_be_bytes());
+        sfnt.extend_from_slice(&(offset as u32).to_be_bytes());
+        sfnt.extend_from_slice(&(entry.orig_length as u32).to_be_bytes());
+    }
+
+    for entry in &entries {
+        let table = data.get(entry.offset..(entry.offset.checked_add(entry.comp_length)?))?;
+        if entry.comp_length == entry.orig_length {
+            sfnt.extend_from_slice(table);
+        } else if entry.comp_length < entry.orig_length {
+            let start = sfnt.len();
+            ZlibDecoder::new(table)
+                .take(entry.orig_length as u64)
+                .read_to_end(&mut sfnt)
+                .ok()?;
+            if sfnt.len() - start != entry.orig_length {
+                return None;
+            }
+        } else {
+            return None;
+        }
+        sfnt.resize(pad_to_4(sfnt.len()), 0);
+    }
+    Some(sfnt)
+}
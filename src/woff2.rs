@@ -0,0 +1,555 @@
+// font-kit/src/woff2.rs
+//
+// Copyright © 2018 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decompression of WOFF 2.0 web fonts into plain OpenType (sfnt) data.
+//!
+//! This module is only available with the `woff2` Cargo feature, which brings in the
+//! `brotli-decompressor` crate.
+//!
+//! https://www.w3.org/TR/WOFF2/
+
+use brotli_decompressor::Decompressor;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::Read;
+
+use crate::error::FontLoadingError;
+use crate::sfnt::{self, pad_to_4};
+use crate::tables;
+use crate::utils;
+
+const HEADER_SIZE: usize = 48;
+
+const COLLECTION_FLAVOR: u32 = tables::tag(b"ttcf");
+
+const GLYF_TAG: u32 = tables::tag(b"glyf");
+const HHEA_TAG: u32 = tables::tag(b"hhea");
+const HMTX_TAG: u32 = tables::tag(b"hmtx");
+const LOCA_TAG: u32 = tables::tag(b"loca");
+
+/// Refuse to decompress fonts that claim to be larger than this, rather than trusting the
+/// directory enough to allocate arbitrary amounts of memory.
+const MAX_DECOMPRESSED_SIZE: usize = 1 << 30;
+
+/// The tags that the low six bits of a table directory entry's flags can stand for.
+static KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+// Flags of simple glyph points in the `glyf` table.
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+const OVERLAP_SIMPLE: u8 = 0x40;
+
+// Flags of composite glyph components in the `glyf` table.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+/// The size of the buffer that the Brotli decoder reads compressed data into.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+/// Decompresses a WOFF 2.0 font into the sfnt that it wraps.
+///
+/// Font collections aren't supported and are reported as `FontLoadingError::UnknownFormat`.
+pub(crate) fn decode(data: &[u8]) -> Result<Vec<u8>, FontLoadingError> {
+    let mut reader = data.get(4..).ok_or(FontLoadingError::Parse)?;
+    if reader.read_u32::<BigEndian>().ok() == Some(COLLECTION_FLAVOR) {
+        return Err(FontLoadingError::UnknownFormat);
+    }
+    decode_opt(data).ok_or(FontLoadingError::Parse)
+}
+
+struct TableDirectoryEntry {
+    tag: u32,
+    orig_length: usize,
+    transformed: bool,
+    stream_offset: usize,
+    stream_length: usize,
+}
+
+fn decode_opt(data: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = data.get(4..)?;
+    let flavor = reader.read_u32::<BigEndian>().ok()?;
+    let _length = reader.read_u32::<BigEndian>().ok()?;
+    let num_tables = reader.read_u16::<BigEndian>().ok()?;
+    let _reserved = reader.read_u16::<BigEndian>().ok()?;
+    let _total_sfnt_size = reader.read_u32::<BigEndian>().ok()?;
+    let total_compressed_size = reader.read_u32::<BigEndian>().ok()? as usize;
+    if num_tables == 0 {
+        return None;
+    }
+
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    let mut reader = data.get(HEADER_SIZE..)?;
+    let mut stream_offset = 0usize;
+    for _ in 0..num_tables {
+        let flags = reader.read_u8().ok()?;
+        let tag = match flags & 0x3f {
+            0x3f => reader.read_u32::<BigEndian>().ok()?,
+            index => tables::tag(KNOWN_TAGS[index as usize]),
+        };
+        let orig_length = read_uint_base_128(&mut reader)? as usize;
+
+        // Version 0 means "transformed" for `glyf` and `loca`, but "not transformed" for
+        // everything else.
+        let transform_version = flags >> 6;
+        let transformed = if tag == GLYF_TAG || tag == LOCA_TAG {
+            transform_version == 0
+        } else {
+            transform_version != 0
+        };
+        let stream_length = if transformed {
+            read_uint_base_128(&mut reader)? as usize
+        } else {
+            orig_length
+        };
+
+        entries.push(TableDirectoryEntry {
+            tag,
+            orig_length,
+            transformed,
+            stream_offset,
+            stream_length,
+        });
+        stream_offset = stream_offset.checked_add(stream_length)?;
+    }
+    if stream_offset > MAX_DECOMPRESSED_SIZE {
+        return None;
+    }
+
+    let stream = brotli_decompress(reader.get(..total_compressed_size)?, stream_offset)?;
+    let stream_data = |entry: &TableDirectoryEntry| {
+        &stream[entry.stream_offset..(entry.stream_offset + entry.stream_length)]
+    };
+    let find_entry = |tag| entries.iter().find(|entry| entry.tag == tag);
+
+    // The transformed `glyf` table carries the data for `loca`, and the transformed `hmtx` table
+    // depends on the bounding boxes of the glyphs.
+    let glyf = match find_entry(GLYF_TAG) {
+        Some(glyf_entry) if glyf_entry.transformed => {
+            let loca_entry = find_entry(LOCA_TAG)?;
+            if !loca_entry.transformed || loca_entry.stream_length != 0 {
+                return None;
+            }
+            Some(reconstruct_glyf(
+                stream_data(glyf_entry),
+                loca_entry.orig_length,
+            )?)
+        }
+        _ => None,
+    };
+
+    let mut tables = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let table = if !entry.transformed {
+            stream_data(entry).to_vec()
+        } else if entry.tag == GLYF_TAG {
+            glyf.as_ref()?.glyf.clone()
+        } else if entry.tag == LOCA_TAG {
+            glyf.as_ref()?.loca.clone()
+        } else if entry.tag == HMTX_TAG {
+            let glyf = glyf.as_ref()?;
+            let mut hhea = stream_data(find_entry(HHEA_TAG)?).get(34..)?;
+            let num_h_metrics = hhea.read_u16::<BigEndian>().ok()? as usize;
+            reconstruct_hmtx(stream_data(entry), num_h_metrics, &glyf.x_mins)?
+        } else {
+            return None;
+        };
+        if table.len() != entry.orig_length && entry.tag != GLYF_TAG {
+            return None;
+        }
+        tables.push((entry.tag, table));
+    }
+    Some(sfnt::write_sfnt(flavor, tables))
+}
+
+/// The tables rebuilt from a transformed `glyf` table.
+struct Glyf {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    /// The minimum x coordinate of each glyph, which the transformed `hmtx` table may use in
+    /// place of left side bearings.
+    x_mins: Vec<i16>,
+}
+
+fn reconstruct_glyf(data: &[u8], loca_length: usize) -> Option<Glyf> {
+    let mut reader = data;
+    let _reserved = reader.read_u16::<BigEndian>().ok()?;
+    let option_flags = reader.read_u16::<BigEndian>().ok()?;
+    let num_glyphs = reader.read_u16::<BigEndian>().ok()? as usize;
+    let index_format = reader.read_u16::<BigEndian>().ok()?;
+
+    let mut streams = [&[][..]; 7];
+    let mut offset = 36usize;
+    for stream in &mut streams {
+        let size = reader.read_u32::<BigEndian>().ok()? as usize;
+        *stream = data.get(offset..(offset.checked_add(size)?))?;
+        offset += size;
+    }
+    let [mut n_contour_stream, mut n_points_stream, mut flag_stream, mut glyph_stream, mut composite_stream, bbox_stream, mut instruction_stream] =
+        streams;
+
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(data.get(offset..(offset + utils::div_round_up(num_glyphs, 8)))?)
+    } else {
+        None
+    };
+    let bbox_bitmap_length = ((num_glyphs + 31) >> 5) << 2;
+    let bbox_bitmap = bbox_stream.get(..bbox_bitmap_length)?;
+    let mut bbox_stream = &bbox_stream[bbox_bitmap_length..];
+    let bit_is_set = |bitmap: &[u8], index: usize| bitmap[index >> 3] & (0x80 >> (index & 7)) != 0;
+
+    let mut glyf = Vec::with_capacity(data.len());
+    let mut loca_offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins = Vec::with_capacity(num_glyphs);
+    for glyph_index in 0..num_glyphs {
+        loca_offsets.push(glyf.len());
+        let n_contours = n_contour_stream.read_i16::<BigEndian>().ok()?;
+        let has_bbox = bit_is_set(bbox_bitmap, glyph_index);
+        if n_contours == 0 {
+            if has_bbox {
+                return None;
+            }
+            x_mins.push(0);
+            continue;
+        }
+        let explicit_bbox = if has_bbox {
+            let mut bbox = [0; 4];
+            for value in &mut bbox {
+                *value = bbox_stream.read_i16::<BigEndian>().ok()?;
+            }
+            Some(bbox)
+        } else {
+            None
+        };
+
+        let bbox = if n_contours < 0 {
+            // Composite glyphs must have explicit bounding boxes.
+            let bbox = explicit_bbox?;
+            let (components, has_instructions) = read_composite_glyph(&mut composite_stream)?;
+            glyf.extend_from_slice(&n_contours.to_be_bytes());
+            write_bbox(&mut glyf, bbox);
+            glyf.extend_from_slice(components);
+            if has_instructions {
+                let instruction_length = read_255_u16(&mut glyph_stream)?;
+                let instructions = take(&mut instruction_stream, instruction_length as usize)?;
+                glyf.extend_from_slice(&instruction_length.to_be_bytes());
+                glyf.extend_from_slice(instructions);
+            }
+            bbox
+        } else {
+            let mut end_points = Vec::with_capacity(n_contours as usize);
+            let mut n_points = 0usize;
+            for _ in 0..n_contours {
+                n_points += read_255_u16(&mut n_points_stream)? as usize;
+                end_points.push(n_points.checked_sub(1)?);
+            }
+            if n_points > u16::MAX as usize {
+                return None;
+            }
+
+            let mut points = Vec::with_capacity(n_points);
+            let (mut x, mut y) = (0i32, 0i32);
+            for &flag in take(&mut flag_stream, n_points)? {
+                let (dx, dy) = read_triplet(flag, &mut glyph_stream)?;
+                x += dx;
+                y += dy;
+                if x < i16::MIN as i32
+                    || x > i16::MAX as i32
+                    || y < i16::MIN as i32
+                    || y > i16::MAX as i32
+                {
+                    return None;
+                }
+                points.push((x, y, flag & 0x80 == 0));
+            }
+            let instruction_length = read_255_u16(&mut glyph_stream)?;
+            let instructions = take(&mut instruction_stream, instruction_length as usize)?;
+
+            let bbox = explicit_bbox.unwrap_or_else(|| compute_bbox(&points));
+            let overlap = overlap_bitmap.is_some_and(|bitmap| bit_is_set(bitmap, glyph_index));
+            glyf.extend_from_slice(&n_contours.to_be_bytes());
+            write_bbox(&mut glyf, bbox);
+            for &end_point in &end_points {
+                glyf.extend_from_slice(&(end_point as u16).to_be_bytes());
+            }
+            glyf.extend_from_slice(&instruction_length.to_be_bytes());
+            glyf.extend_from_slice(instructions);
+            write_points(&mut glyf, &points, overlap);
+            bbox
+        };
+        x_mins.push(bbox[0]);
+
+        // Short `loca` offsets can only address even offsets.
+        glyf.resize(pad_to_4(glyf.len()), 0);
+    }
+    loca_offsets.push(glyf.len());
+
+    let loca = if index_format == 0 {
+        if glyf.len() / 2 > u16::MAX as usize {
+            return None;
+        }
+        loca_offsets
+            .iter()
+            .flat_map(|&offset| ((offset / 2) as u16).to_be_bytes().to_vec())
+            .collect::<Vec<_>>()
+    } else {
+        loca_offsets
+            .iter()
+            .flat_map(|&offset| (offset as u32).to_be_bytes().to_vec())
+            .collect()
+    };
+    if loca.len() != loca_length {
+        return None;
+    }
+    Some(Glyf { glyf, loca, x_mins })
+}
+
+/// Reads the records of a composite glyph as they appear in a plain `glyf` table, returning them
+/// along with whether the glyph has instructions.
+fn read_composite_glyph<'a>(stream: &mut &'a [u8]) -> Option<(&'a [u8], bool)> {
+    let start = *stream;
+    let mut has_instructions = false;
+    loop {
+        let flags = stream.read_u16::<BigEndian>().ok()?;
+        has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+        // The glyph index and the two arguments.
+        let mut length = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            6
+        } else {
+            4
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            length += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            length += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            length += 8;
+        }
+        take(stream, length)?;
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    Some((&start[..(start.len() - stream.len())], has_instructions))
+}
+
+/// Decodes the coordinate deltas of one point from the glyph stream, as described by the point's
+/// flag byte.
+fn read_triplet(flag: u8, glyph_stream: &mut &[u8]) -> Option<(i32, i32)> {
+    let flag = (flag & 0x7f) as i32;
+    let with_sign = |value: i32, bit: i32| if flag & bit != 0 { value } else { -value };
+    let mut read = || glyph_stream.read_u8().ok().map(|byte| byte as i32);
+    Some(if flag < 10 {
+        (0, with_sign(((flag & 14) << 7) + read()?, 1))
+    } else if flag < 20 {
+        (with_sign((((flag - 10) & 14) << 7) + read()?, 1), 0)
+    } else if flag < 84 {
+        let b0 = flag - 20;
+        let b1 = read()?;
+        (
+            with_sign(1 + (b0 & 0x30) + (b1 >> 4), 1),
+            with_sign(1 + ((b0 & 0x0c) << 2) + (b1 & 0x0f), 2),
+        )
+    } else if flag < 120 {
+        let b0 = flag - 84;
+        let (b1, b2) = (read()?, read()?);
+        (
+            with_sign(1 + ((b0 / 12) << 8) + b1, 1),
+            with_sign(1 + (((b0 % 12) >> 2) << 8) + b2, 2),
+        )
+    } else if flag < 124 {
+        let (b1, b2, b3) = (read()?, read()?, read()?);
+        (
+            with_sign((b1 << 4) + (b2 >> 4), 1),
+            with_sign(((b2 & 0x0f) << 8) + b3, 2),
+        )
+    } else {
+        let (b1, b2, b3, b4) = (read()?, read()?, read()?, read()?);
+        (with_sign((b1 << 8) + b2, 1), with_sign((b3 << 8) + b4, 2))
+    })
+}
+
+fn compute_bbox(points: &[(i32, i32, bool)]) -> [i16; 4] {
+    if points.is_empty() {
+        return [0; 4];
+    }
+    let mut bbox = [i32::MAX, i32::MAX, i32::MIN, i32::MIN];
+    for &(x, y, _) in points {
+        bbox = [
+            bbox[0].min(x),
+            bbox[1].min(y),
+            bbox[2].max(x),
+            bbox[3].max(y),
+        ];
+    }
+    [
+        bbox[0] as i16,
+        bbox[1] as i16,
+        bbox[2] as i16,
+        bbox[3] as i16,
+    ]
+}
+
+fn write_bbox(glyf: &mut Vec<u8>, bbox: [i16; 4]) {
+    for value in &bbox {
+        glyf.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// Writes the flags and coordinates of a simple glyph in the plain `glyf` encoding.
+fn write_points(glyf: &mut Vec<u8>, points: &[(i32, i32, bool)], overlap: bool) {
+    let mut flags = Vec::with_capacity(points.len());
+    let (mut x_coordinates, mut y_coordinates) = (vec![], vec![]);
+    let (mut last_x, mut last_y) = (0, 0);
+    for (point_index, &(x, y, on_curve)) in points.iter().enumerate() {
+        let mut flag = if on_curve { ON_CURVE_POINT } else { 0 };
+        if overlap && point_index == 0 {
+            flag |= OVERLAP_SIMPLE;
+        }
+        flag |= write_coordinate(
+            &mut x_coordinates,
+            x - last_x,
+            X_SHORT_VECTOR,
+            X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR,
+        );
+        flag |= write_coordinate(
+            &mut y_coordinates,
+            y - last_y,
+            Y_SHORT_VECTOR,
+            Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR,
+        );
+        flags.push(flag);
+        last_x = x;
+        last_y = y;
+    }
+    glyf.extend_from_slice(&flags);
+    glyf.extend_from_slice(&x_coordinates);
+    glyf.extend_from_slice(&y_coordinates);
+}
+
+/// Writes one coordinate delta, returning the flags that describe its encoding.
+fn write_coordinate(coordinates: &mut Vec<u8>, delta: i32, short_flag: u8, same_flag: u8) -> u8 {
+    if delta == 0 {
+        same_flag
+    } else if delta > -256 && delta < 256 {
+        coordinates.push(delta.unsigned_abs() as u8);
+        if delta > 0 {
+            short_flag | same_flag
+        } else {
+            short_flag
+        }
+    } else {
+        coordinates.extend_from_slice(&(delta as i16).to_be_bytes());
+        0
+    }
+}
+
+/// Rebuilds an `hmtx` table whose left side bearings were omitted because they equal the
+/// minimum x coordinates of the glyphs.
+fn reconstruct_hmtx(data: &[u8], num_h_metrics: usize, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let mut reader = data;
+    let flags = reader.read_u8().ok()?;
+    let num_glyphs = x_mins.len();
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return None;
+    }
+
+    let mut advances = Vec::with_capacity(num_h_metrics);
+    for _ in 0..num_h_metrics {
+        advances.push(reader.read_u16::<BigEndian>().ok()?);
+    }
+    let mut lsbs = Vec::with_capacity(num_glyphs);
+    for (glyph_index, &x_min) in x_mins.iter().enumerate() {
+        let lsb_omitted = if glyph_index < num_h_metrics {
+            flags & 1 != 0
+        } else {
+            flags & 2 != 0
+        };
+        lsbs.push(if lsb_omitted {
+            x_min
+        } else {
+            reader.read_i16::<BigEndian>().ok()?
+        });
+    }
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 2 + num_glyphs * 2);
+    for (glyph_index, lsb) in lsbs.iter().enumerate() {
+        if let Some(advance) = advances.get(glyph_index) {
+            hmtx.extend_from_slice(&advance.to_be_bytes());
+        }
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+    Some(hmtx)
+}
+
+fn read_uint_base_128(reader: &mut &[u8]) -> Option<u32> {
+    let mut value = 0u32;
+    for byte_index in 0..5 {
+        let byte = reader.read_u8().ok()?;
+        // Leading zeros and values that overflow 32 bits are invalid.
+        if (byte_index == 0 && byte == 0x80) || value & 0xfe00_0000 != 0 {
+            return None;
+        }
+        value = (value << 7) | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn read_255_u16(reader: &mut &[u8]) -> Option<u16> {
+    const WORD_CODE: u8 = 253;
+    const ONE_MORE_BYTE_CODE_2: u8 = 254;
+    const ONE_MORE_BYTE_CODE_1: u8 = 255;
+    const LOWEST_U_CODE: u16 = 253;
+    match reader.read_u8().ok()? {
+        WORD_CODE => reader.read_u16::<BigEndian>().ok(),
+        ONE_MORE_BYTE_CODE_1 => Some(reader.read_u8().ok()? as u16 + LOWEST_U_CODE),
+        ONE_MORE_BYTE_CODE_2 => Some(reader.read_u8().ok()? as u16 + LOWEST_U_CODE * 2),
+        code => Some(code as u16),
+    }
+}
+
+fn take<'a>(reader: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if reader.len() < length {
+        return None;
+    }
+    let (taken, rest) = reader.split_at(length);
+    *reader = rest;
+    Some(taken)
+}
+
+fn brotli_decompress(data: &[u8], decompressed_size: usize) -> Option<Vec<u8>> {
+    // Reading one byte past the expected size tells streams that are too long from ones that
+    // are just right.
+    let mut buffer = Vec::with_capacity(decompressed_size);
+    Decompressor::new(data, BROTLI_BUFFER_SIZE)
+        .take(decompressed_size as u64 + 1)
+        .read_to_end(&mut buffer)
+        .ok()?;
+    if buffer.len() != decompressed_size {
+        return None;
+    }
+    Some(buffer)
+}
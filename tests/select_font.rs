@@ -17,6 +17,7 @@ use font_kit::properties::Properties;
 use font_kit::source::SystemSource;
 use std::ffi::OsStr;
 
+#[allow(unused_macros)]
 macro_rules! match_handle {
     ($handle:expr, $path:expr, $index:expr) => {
         match $handle {
@@ -105,6 +106,7 @@ mod test {
                     style: font_kit::properties::Style::Normal,
                     weight: font_kit::properties::Weight::BOLD,
                     stretch: font_kit::properties::Stretch::NORMAL,
+                    italic_angle: None,
                 },
             )
             .unwrap();
@@ -197,6 +199,7 @@ mod test {
                     style: font_kit::properties::Style::Normal,
                     weight: font_kit::properties::Weight::BOLD,
                     stretch: font_kit::properties::Stretch::NORMAL,
+                    italic_angle: None,
                 },
             )
             .unwrap();
@@ -222,7 +225,7 @@ mod test {
         let family = SystemSource::new()
             .select_family_by_name("DejaVu Sans")
             .unwrap();
-        let mut filenames: Vec<String> = family
+        let filenames: Vec<String> = family
             .fonts()
             .iter()
             .map(|handle| match *handle {
@@ -239,16 +242,8 @@ mod test {
                 _ => panic!("Expected path handle!"),
             })
             .collect();
-        assert!(filenames
-            .iter()
-            .filter(|name| &**name == "DejaVuSans-Bold.ttf")
-            .next()
-            .is_some());
-        assert!(filenames
-            .iter()
-            .filter(|name| &**name == "DejaVuSans.ttf")
-            .next()
-            .is_some());
+        assert!(filenames.iter().any(|name| name == "DejaVuSans-Bold.ttf"));
+        assert!(filenames.iter().any(|name| name == "DejaVuSans.ttf"));
     }
 
     #[allow(non_snake_case)]
@@ -325,6 +320,7 @@ mod test {
                     style: font_kit::properties::Style::Normal,
                     weight: font_kit::properties::Weight::BOLD,
                     stretch: font_kit::properties::Stretch::NORMAL,
+                    italic_angle: None,
                 },
             )
             .unwrap();